@@ -0,0 +1,2 @@
+-- Remove role column from admins table
+ALTER TABLE admins DROP COLUMN role;
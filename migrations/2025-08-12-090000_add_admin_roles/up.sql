@@ -0,0 +1,5 @@
+-- Add role column to admins for permission checks
+-- Role values: 'superadmin', 'editor', 'translator', 'analyst'
+-- Existing admins keep full access
+ALTER TABLE admins ADD COLUMN role TEXT NOT NULL DEFAULT 'superadmin'
+    CHECK (role IN ('superadmin', 'editor', 'translator', 'analyst'));
@@ -0,0 +1,17 @@
+-- Audit trail of changes made through the admin interface and CLI
+-- The actor's username is copied so entries stay readable after renames
+CREATE TABLE admin_audit_log (
+    id TEXT PRIMARY KEY NOT NULL,
+    admin_id TEXT,
+    actor TEXT NOT NULL,
+    action TEXT NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT,
+    before_json TEXT,
+    after_json TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_admin_audit_log_created_at ON admin_audit_log(created_at);
+CREATE INDEX idx_admin_audit_log_entity ON admin_audit_log(entity_type, entity_id);
+CREATE INDEX idx_admin_audit_log_actor ON admin_audit_log(actor);
@@ -0,0 +1,12 @@
+-- Failed admin login tracking for backoff and temporary lockout
+-- One row per throttled username or client IP
+CREATE TABLE login_throttles (
+    scope TEXT NOT NULL CHECK (scope IN ('username', 'ip')),
+    subject TEXT NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMP NOT NULL,
+    locked_until TIMESTAMP,
+    PRIMARY KEY (scope, subject)
+);
+
+CREATE INDEX idx_login_throttles_locked_until ON login_throttles(locked_until);
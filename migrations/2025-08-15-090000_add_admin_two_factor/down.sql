@@ -0,0 +1,5 @@
+DROP TABLE settings;
+DROP TABLE admin_recovery_codes;
+ALTER TABLE admins DROP COLUMN totp_last_step;
+ALTER TABLE admins DROP COLUMN totp_enabled_at;
+ALTER TABLE admins DROP COLUMN totp_secret;
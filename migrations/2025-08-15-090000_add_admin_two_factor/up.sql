@@ -0,0 +1,24 @@
+-- Optional TOTP second factor for admins
+-- totp_secret is set while enrolling; totp_enabled_at marks a confirmed enrolment
+ALTER TABLE admins ADD COLUMN totp_secret TEXT;
+ALTER TABLE admins ADD COLUMN totp_enabled_at TIMESTAMP;
+-- Last accepted time step, so a code cannot be replayed
+ALTER TABLE admins ADD COLUMN totp_last_step BIGINT;
+
+-- One-time recovery codes, stored as SHA-256 hashes
+CREATE TABLE admin_recovery_codes (
+    id TEXT PRIMARY KEY NOT NULL,
+    admin_id TEXT NOT NULL REFERENCES admins(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_admin_recovery_codes_admin_id ON admin_recovery_codes(admin_id);
+
+-- Site-wide settings changed from the admin UI
+CREATE TABLE settings (
+    key TEXT PRIMARY KEY NOT NULL,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
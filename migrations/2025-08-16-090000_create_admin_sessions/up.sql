@@ -0,0 +1,14 @@
+-- Server-side admin sessions; the session cookie only carries the session id
+-- so a session can be revoked before its token expires
+CREATE TABLE admin_sessions (
+    id TEXT PRIMARY KEY NOT NULL,
+    admin_id TEXT NOT NULL REFERENCES admins(id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP,
+    ip_address TEXT,
+    user_agent TEXT
+);
+
+CREATE INDEX idx_admin_sessions_admin_id ON admin_sessions(admin_id);
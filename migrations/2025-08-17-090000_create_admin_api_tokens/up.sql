@@ -0,0 +1,15 @@
+-- Personal bearer tokens for scripts acting as an admin
+-- Only a SHA-256 hash of the token is stored; the scope is the role the token acts with
+CREATE TABLE admin_api_tokens (
+    id TEXT PRIMARY KEY NOT NULL,
+    admin_id TEXT NOT NULL REFERENCES admins(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    token_hint TEXT NOT NULL,
+    scope TEXT NOT NULL CHECK (scope IN ('superadmin', 'editor', 'translator', 'analyst')),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP,
+    revoked_at TIMESTAMP
+);
+
+CREATE INDEX idx_admin_api_tokens_admin_id ON admin_api_tokens(admin_id);
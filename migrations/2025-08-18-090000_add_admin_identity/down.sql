@@ -0,0 +1,4 @@
+DROP INDEX idx_admins_oidc_subject;
+DROP INDEX idx_admins_email;
+ALTER TABLE admins DROP COLUMN oidc_subject;
+ALTER TABLE admins DROP COLUMN email;
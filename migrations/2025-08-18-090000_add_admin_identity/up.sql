@@ -0,0 +1,7 @@
+-- Identity provider accounts that may log in as an admin
+-- email is matched against verified email claims, oidc_subject against the sub claim
+ALTER TABLE admins ADD COLUMN email TEXT;
+ALTER TABLE admins ADD COLUMN oidc_subject TEXT;
+
+CREATE UNIQUE INDEX idx_admins_email ON admins(email);
+CREATE UNIQUE INDEX idx_admins_oidc_subject ON admins(oidc_subject);
@@ -0,0 +1,17 @@
+-- Quiz sessions as records; quiz_attempts.session_id refers to them
+CREATE TABLE quiz_sessions (
+    id TEXT PRIMARY KEY NOT NULL,
+    rule_set_id TEXT NOT NULL REFERENCES rule_sets(id),
+    version_id TEXT NOT NULL REFERENCES versions(id),
+    language TEXT NOT NULL,
+    mode TEXT NOT NULL DEFAULT 'practice',
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ended_at TIMESTAMP
+);
+
+-- Sessions that so far only existed as ids on their attempts
+INSERT INTO quiz_sessions (id, rule_set_id, version_id, language, mode, started_at)
+SELECT a.session_id, q.rule_set_id, q.version_id, 'en', 'practice', MIN(a.created_at)
+FROM quiz_attempts a
+JOIN quiz_questions q ON q.id = a.question_id
+GROUP BY a.session_id;
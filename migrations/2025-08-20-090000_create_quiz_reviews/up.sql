@@ -0,0 +1,25 @@
+-- Spaced-repetition state per learner session and question (SM-2)
+CREATE TABLE quiz_reviews (
+    session_id TEXT NOT NULL REFERENCES quiz_sessions(id) ON DELETE CASCADE,
+    question_id TEXT NOT NULL REFERENCES quiz_questions(id) ON DELETE CASCADE,
+    repetitions INTEGER NOT NULL DEFAULT 0,
+    interval_days INTEGER NOT NULL DEFAULT 0,
+    ease_factor REAL NOT NULL DEFAULT 2.5,
+    due_at TIMESTAMP NOT NULL,
+    last_reviewed_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (session_id, question_id)
+);
+
+CREATE INDEX idx_quiz_reviews_due ON quiz_reviews(session_id, due_at);
+
+-- Questions answered before scheduling existed come up again a day after their last attempt
+INSERT INTO quiz_reviews (session_id, question_id, repetitions, interval_days, ease_factor, due_at, last_reviewed_at)
+SELECT a.session_id, a.question_id,
+       CASE WHEN a.is_correct THEN 1 ELSE 0 END,
+       1, 2.5, datetime(a.created_at, '+1 day'), a.created_at
+FROM quiz_attempts a
+WHERE a.created_at = (
+    SELECT MAX(b.created_at) FROM quiz_attempts b
+    WHERE b.session_id = a.session_id AND b.question_id = a.question_id
+)
+GROUP BY a.session_id, a.question_id;
@@ -0,0 +1,4 @@
+DROP INDEX idx_quiz_sessions_learner;
+ALTER TABLE quiz_sessions DROP COLUMN learner_id;
+DROP TABLE learner_login_links;
+DROP TABLE learners;
@@ -0,0 +1,22 @@
+-- Opt-in learner accounts that keep quiz progress across devices
+CREATE TABLE learners (
+    id TEXT PRIMARY KEY NOT NULL,
+    email TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_login_at TIMESTAMP
+);
+
+-- Emailed sign-in links; only a hash of the token is stored
+CREATE TABLE learner_login_links (
+    token_hash TEXT PRIMARY KEY NOT NULL,
+    email TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP
+);
+
+CREATE INDEX idx_learner_login_links_email ON learner_login_links(email, created_at);
+
+ALTER TABLE quiz_sessions ADD COLUMN learner_id TEXT REFERENCES learners(id);
+
+CREATE INDEX idx_quiz_sessions_learner ON quiz_sessions(learner_id);
@@ -0,0 +1,2 @@
+DROP INDEX idx_quiz_transfer_codes_session;
+DROP TABLE quiz_transfer_codes;
@@ -0,0 +1,11 @@
+-- Short-lived codes that open an anonymous quiz session on another device;
+-- only a hash of the code is stored
+CREATE TABLE quiz_transfer_codes (
+    code_hash TEXT PRIMARY KEY NOT NULL,
+    session_id TEXT NOT NULL REFERENCES quiz_sessions(id),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP
+);
+
+CREATE INDEX idx_quiz_transfer_codes_session ON quiz_transfer_codes(session_id);
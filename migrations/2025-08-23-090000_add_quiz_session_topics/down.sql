@@ -0,0 +1 @@
+ALTER TABLE quiz_sessions DROP COLUMN topic_rule_ids;
@@ -0,0 +1,2 @@
+-- Comma-separated ids of the rule subtrees a session is focused on; NULL for the whole rule set
+ALTER TABLE quiz_sessions ADD COLUMN topic_rule_ids TEXT;
@@ -0,0 +1,2 @@
+-- How a session picks question difficulties: all, a single level, progressive or a mix
+ALTER TABLE quiz_sessions ADD COLUMN difficulty_mode TEXT NOT NULL DEFAULT 'all';
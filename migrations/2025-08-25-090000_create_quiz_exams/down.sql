@@ -0,0 +1,2 @@
+DROP TABLE quiz_exam_questions;
+DROP TABLE quiz_exams;
@@ -0,0 +1,21 @@
+-- Timed exams, each run in its own quiz session with mode 'exam'
+CREATE TABLE quiz_exams (
+    session_id TEXT PRIMARY KEY NOT NULL REFERENCES quiz_sessions(id),
+    -- Practice session the visitor returns to once the exam is over
+    practice_session_id TEXT REFERENCES quiz_sessions(id),
+    question_count INTEGER NOT NULL,
+    pass_percent INTEGER NOT NULL,
+    started_at TIMESTAMP NOT NULL,
+    ends_at TIMESTAMP NOT NULL,
+    finished_at TIMESTAMP,
+    correct_count INTEGER,
+    passed BOOLEAN
+);
+
+-- The fixed question draw of an exam, in the order it is served
+CREATE TABLE quiz_exam_questions (
+    session_id TEXT NOT NULL REFERENCES quiz_exams(session_id),
+    position INTEGER NOT NULL,
+    question_id TEXT NOT NULL REFERENCES quiz_questions(id),
+    PRIMARY KEY (session_id, position)
+);
@@ -0,0 +1,14 @@
+-- Certificates for passed exams. Names and score are copied from the exam so the
+-- certificate reads the same even if the rule set or question pool changes later.
+CREATE TABLE quiz_certificates (
+    id TEXT PRIMARY KEY NOT NULL,
+    exam_session_id TEXT NOT NULL UNIQUE REFERENCES quiz_exams(session_id),
+    holder_name TEXT NOT NULL,
+    rule_set_name TEXT NOT NULL,
+    version_name TEXT NOT NULL,
+    score_percent INTEGER NOT NULL,
+    passed_at TIMESTAMP NOT NULL,
+    issued_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP,
+    revocation_reason TEXT
+);
@@ -0,0 +1,7 @@
+-- Bearer tokens for quiz sessions started through the JSON API; only a hash of the
+-- token is stored, so the session id itself never grants access
+CREATE TABLE quiz_api_tokens (
+    token_hash TEXT PRIMARY KEY NOT NULL,
+    session_id TEXT NOT NULL UNIQUE REFERENCES quiz_sessions(id),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,14 @@
+CREATE TABLE login_throttles_old (
+    scope TEXT NOT NULL CHECK (scope IN ('username', 'ip')),
+    subject TEXT NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMP NOT NULL,
+    locked_until TIMESTAMP,
+    PRIMARY KEY (scope, subject)
+);
+
+INSERT INTO login_throttles_old SELECT scope, subject, failed_attempts, last_failed_at, locked_until FROM login_throttles WHERE scope IN ('username', 'ip');
+DROP TABLE login_throttles;
+ALTER TABLE login_throttles_old RENAME TO login_throttles;
+
+CREATE INDEX idx_login_throttles_locked_until ON login_throttles(locked_until);
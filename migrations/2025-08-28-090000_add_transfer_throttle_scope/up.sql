@@ -0,0 +1,17 @@
+-- Failed transfer code redemptions are throttled per client IP too, counted
+-- apart from admin logins so one can't lock out the other.
+-- SQLite can't change a CHECK constraint, so the table is recreated.
+CREATE TABLE login_throttles_new (
+    scope TEXT NOT NULL CHECK (scope IN ('username', 'ip', 'transfer_ip')),
+    subject TEXT NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMP NOT NULL,
+    locked_until TIMESTAMP,
+    PRIMARY KEY (scope, subject)
+);
+
+INSERT INTO login_throttles_new SELECT scope, subject, failed_attempts, last_failed_at, locked_until FROM login_throttles;
+DROP TABLE login_throttles;
+ALTER TABLE login_throttles_new RENAME TO login_throttles;
+
+CREATE INDEX idx_login_throttles_locked_until ON login_throttles(locked_until);
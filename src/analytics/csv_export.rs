@@ -0,0 +1,87 @@
+use crate::models::quiz::QuestionExportData;
+use color_eyre::{Result, eyre::WrapErr};
+
+/// Writes question statistics as CSV, with one column group per answer position
+pub fn write_csv(questions: Vec<QuestionExportData>) -> Result<Vec<u8>> {
+    // Find maximum number of answers across all questions
+    let max_answers = questions.iter().map(|q| q.answers.len()).max().unwrap_or(0);
+
+    // Build dynamic headers
+    let mut headers = vec![
+        "question_id".to_string(),
+        "question_text".to_string(),
+        "explanation".to_string(),
+        "difficulty_level".to_string(),
+        "rule_references".to_string(),
+        "total_attempts".to_string(),
+        "correct_attempts".to_string(),
+        "success_rate_percent".to_string(),
+        "response_time_p50_ms".to_string(),
+        "response_time_p90_ms".to_string(),
+        "response_time_p95_ms".to_string(),
+    ];
+
+    for i in 1..=max_answers {
+        headers.push(format!("answer_{}_text", i));
+        headers.push(format!("answer_{}_correct", i));
+        headers.push(format!("answer_{}_selections", i));
+        headers.push(format!("answer_{}_percentage", i));
+    }
+    headers.extend_from_slice(&["created_at".to_string(), "updated_at".to_string()]);
+
+    // Write CSV with manual row construction
+    let mut csv_output = Vec::new();
+    {
+        let mut wtr = csv::Writer::from_writer(&mut csv_output);
+        wtr.write_record(&headers)
+            .wrap_err("Failed to write CSV headers")?;
+
+        for question in questions {
+            let mut row = vec![
+                question.question_id,
+                question.question_text,
+                question.explanation,
+                question.difficulty_level,
+                question.rule_references,
+                question.total_attempts.to_string(),
+                question.correct_attempts.to_string(),
+                format!("{:.1}", question.success_rate_percent),
+            ];
+
+            // Empty when no attempt has a measured response time
+            match question.response_times {
+                Some(times) => row.extend([
+                    times.p50_ms.to_string(),
+                    times.p90_ms.to_string(),
+                    times.p95_ms.to_string(),
+                ]),
+                None => row.extend([String::new(), String::new(), String::new()]),
+            }
+
+            // Add answers with padding for missing ones
+            for i in 0..max_answers {
+                if let Some(answer) = question.answers.get(i) {
+                    row.push(answer.text.clone());
+                    row.push(answer.is_correct.to_string());
+                    row.push(answer.selection_count.to_string());
+                    row.push(format!("{:.1}", answer.selection_percentage));
+                } else {
+                    row.extend_from_slice(&[
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                    ]);
+                }
+            }
+
+            row.push(question.created_at.format("%Y-%m-%d %H:%M:%S").to_string());
+            row.push(question.updated_at.format("%Y-%m-%d %H:%M:%S").to_string());
+
+            wtr.write_record(&row).wrap_err("Failed to write CSV row")?;
+        }
+        wtr.flush().wrap_err("Failed to flush CSV")?;
+    }
+
+    Ok(csv_output)
+}
@@ -1,3 +1,5 @@
+pub mod csv_export;
 pub mod parquet_export;
 
+pub use csv_export::write_csv;
 pub use parquet_export::{questions_to_record_batch, write_parquet};
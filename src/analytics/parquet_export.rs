@@ -31,6 +31,10 @@ pub fn create_builder_compatible_schema() -> Schema {
         Field::new("total_attempts", DataType::UInt64, false),
         Field::new("correct_attempts", DataType::UInt64, false),
         Field::new("success_rate_percent", DataType::Float64, false),
+        // Null when no attempt has a measured response time
+        Field::new("response_time_p50_ms", DataType::Int32, true),
+        Field::new("response_time_p90_ms", DataType::Int32, true),
+        Field::new("response_time_p95_ms", DataType::Int32, true),
         // The key difference: ListBuilder creates the inner "item" field with nullable: true
         Field::new(
             "answers",
@@ -56,6 +60,9 @@ pub struct QuizAnalyticsBuilders {
     total_attempts: UInt64Builder,
     correct_attempts: UInt64Builder,
     success_rate_percent: Float64Builder,
+    response_time_p50_ms: Int32Builder,
+    response_time_p90_ms: Int32Builder,
+    response_time_p95_ms: Int32Builder,
     answers: ListBuilder<arrow_array::builder::StructBuilder>,
     created_at: StringBuilder,
     updated_at: StringBuilder,
@@ -93,6 +100,9 @@ impl QuizAnalyticsBuilders {
             total_attempts: UInt64Builder::new(),
             correct_attempts: UInt64Builder::new(),
             success_rate_percent: Float64Builder::new(),
+            response_time_p50_ms: Int32Builder::new(),
+            response_time_p90_ms: Int32Builder::new(),
+            response_time_p95_ms: Int32Builder::new(),
             answers: ListBuilder::new(answer_struct_builder),
             created_at: StringBuilder::new(),
             updated_at: StringBuilder::new(),
@@ -113,6 +123,13 @@ impl QuizAnalyticsBuilders {
             .append_value(question.correct_attempts as u64);
         self.success_rate_percent
             .append_value(question.success_rate_percent);
+        let times = question.response_times;
+        self.response_time_p50_ms
+            .append_option(times.map(|t| t.p50_ms));
+        self.response_time_p90_ms
+            .append_option(times.map(|t| t.p90_ms));
+        self.response_time_p95_ms
+            .append_option(times.map(|t| t.p95_ms));
         self.created_at
             .append_value(question.created_at.format("%Y-%m-%d %H:%M:%S").to_string());
         self.updated_at
@@ -182,6 +199,9 @@ impl QuizAnalyticsBuilders {
             Arc::new(self.total_attempts.finish()),
             Arc::new(self.correct_attempts.finish()),
             Arc::new(self.success_rate_percent.finish()),
+            Arc::new(self.response_time_p50_ms.finish()),
+            Arc::new(self.response_time_p90_ms.finish()),
+            Arc::new(self.response_time_p95_ms.finish()),
             Arc::new(self.answers.finish()),
             Arc::new(self.created_at.finish()),
             Arc::new(self.updated_at.finish()),
@@ -0,0 +1,86 @@
+//! Personal API tokens that let scripts act as an admin.
+//!
+//! Tokens are sent as `Authorization: Bearer <token>`. They carry 256 bits of
+//! randomness, so only a SHA-256 hash is stored and looked up.
+
+use axum::http::{HeaderMap, header};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// Marks a string as a Regelator API token, e.g. for secret scanners
+pub const TOKEN_PREFIX: &str = "rgl_";
+
+/// Characters of the token shown in the admin UI to tell tokens apart
+const HINT_LENGTH: usize = 8;
+
+/// Generate a new token: the prefix followed by 64 hex characters
+pub fn generate_api_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("{TOKEN_PREFIX}{hex}")
+}
+
+/// Hash a token for storage and lookup
+pub fn hash_api_token(token: &str) -> String {
+    Sha256::digest(token.trim().as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Start of a token, safe to display next to its name
+pub fn token_hint(token: &str) -> String {
+    token
+        .chars()
+        .take(TOKEN_PREFIX.len() + HINT_LENGTH)
+        .collect()
+}
+
+/// Token from an `Authorization: Bearer` header, if the request has one
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_tokens_are_unique_and_hashed() {
+        let first = generate_api_token();
+        let second = generate_api_token();
+
+        assert!(first.starts_with(TOKEN_PREFIX));
+        assert_eq!(first.len(), TOKEN_PREFIX.len() + 64);
+        assert_ne!(first, second);
+        assert_ne!(hash_api_token(&first), hash_api_token(&second));
+        assert_eq!(
+            hash_api_token(&first),
+            hash_api_token(&format!(" {first}\n"))
+        );
+        assert_eq!(token_hint(&first), &first[..12]);
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, "Bearer rgl_abc".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Some("rgl_abc"));
+
+        headers.insert(header::AUTHORIZATION, "bearer  rgl_abc ".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Some("rgl_abc"));
+
+        headers.insert(header::AUTHORIZATION, "Basic dXNlcjpwYXNz".parse().unwrap());
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, "Bearer ".parse().unwrap());
+        assert_eq!(bearer_token(&headers), None);
+    }
+}
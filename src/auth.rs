@@ -1,29 +1,68 @@
+use argon2::password_hash::{SaltString, rand_core::OsRng};
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use axum::{
     extract::{FromRef, FromRequestParts},
-    http::request::Parts,
+    http::{StatusCode, request::Parts},
     response::{IntoResponse, Redirect, Response},
 };
 use axum_extra::extract::cookie::{Cookie, SameSite};
 use chrono::Utc;
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::models::AdminRole;
+use crate::oidc::PendingLogin;
+use crate::repository::RuleRepository;
 
 const COOKIE_NAME: &str = "admin_session";
 
+/// Minimum length for admin passwords
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Hash a password with Argon2 and a random salt
+pub fn hash_password(password: &str) -> color_eyre::Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| color_eyre::eyre::eyre!("Failed to hash password: {}", e))?;
+    Ok(hash.to_string())
+}
+
+/// Check a password against a stored Argon2 hash
+pub fn verify_password(password: &str, password_hash: &str) -> color_eyre::Result<bool> {
+    let parsed_hash = PasswordHash::new(password_hash)
+        .map_err(|e| color_eyre::eyre::eyre!("Failed to parse password hash: {}", e))?;
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok())
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AdminClaims {
+    /// Server-side session this token belongs to
+    pub sid: String,
     pub admin_id: String,
     pub username: String,
+    pub role: AdminRole,
     pub exp: i64,
     pub iat: i64,
 }
 
 impl AdminClaims {
-    pub fn new(admin_id: String, username: String, session_duration: chrono::Duration) -> Self {
+    pub fn new(
+        session_id: String,
+        admin_id: String,
+        username: String,
+        role: AdminRole,
+        session_duration: chrono::Duration,
+    ) -> Self {
         let now = Utc::now();
         Self {
+            sid: session_id,
             admin_id,
             username,
+            role,
             exp: (now + session_duration).timestamp(),
             iat: now.timestamp(),
         }
@@ -34,15 +73,17 @@ impl AdminClaims {
     }
 }
 
-/// Create a signed JWT cookie for admin authentication
+/// Create a signed JWT cookie for an admin session stored in `admin_sessions`
 /// Works on localhost (secure cookies allowed over HTTP on localhost)
 pub fn create_admin_cookie(
+    session_id: String,
     admin_id: String,
     username: String,
+    role: AdminRole,
     jwt_secret: &str,
     session_duration: chrono::Duration,
 ) -> Result<Cookie<'static>, jsonwebtoken::errors::Error> {
-    let claims = AdminClaims::new(admin_id, username, session_duration);
+    let claims = AdminClaims::new(session_id, admin_id, username, role, session_duration);
     let token = encode(
         &Header::default(),
         &claims,
@@ -93,11 +134,161 @@ pub fn clear_admin_cookie() -> Cookie<'static> {
 
 pub const ADMIN_COOKIE_NAME: &str = COOKIE_NAME;
 
+const TWO_FACTOR_COOKIE_NAME: &str = "admin_two_factor";
+
+/// How long the second login step may take after the password was accepted
+pub const TWO_FACTOR_PENDING_MINUTES: i64 = 5;
+
+/// Marks a JWT as a pending second factor rather than an admin session
+const TWO_FACTOR_PURPOSE: &str = "two_factor";
+
+/// Claims for an admin who passed the password check but still owes a second factor
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorClaims {
+    pub admin_id: String,
+    pub purpose: String,
+    pub exp: i64,
+    pub iat: i64,
+}
+
+/// Create a short-lived cookie that carries the admin between the password and code steps
+pub fn create_two_factor_cookie(
+    admin_id: String,
+    jwt_secret: &str,
+) -> Result<Cookie<'static>, jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let claims = TwoFactorClaims {
+        admin_id,
+        purpose: TWO_FACTOR_PURPOSE.to_string(),
+        exp: (now + chrono::Duration::minutes(TWO_FACTOR_PENDING_MINUTES)).timestamp(),
+        iat: now.timestamp(),
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret.as_ref()),
+    )?;
+
+    Ok(Cookie::build((TWO_FACTOR_COOKIE_NAME, token))
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Strict)
+        .max_age(time::Duration::minutes(TWO_FACTOR_PENDING_MINUTES))
+        .path("/admin")
+        .build())
+}
+
+/// Verify the pending second factor cookie from the request
+pub fn verify_two_factor_cookie(
+    jar: &axum_extra::extract::CookieJar,
+    jwt_secret: &str,
+) -> Result<TwoFactorClaims, AdminAuthError> {
+    let cookie = jar
+        .get(TWO_FACTOR_COOKIE_NAME)
+        .ok_or(AdminAuthError::Missing)?;
+    let claims = decode::<TwoFactorClaims>(
+        cookie.value(),
+        &DecodingKey::from_secret(jwt_secret.as_ref()),
+        &Validation::default(),
+    )?
+    .claims;
+
+    if claims.purpose != TWO_FACTOR_PURPOSE {
+        return Err(AdminAuthError::Missing);
+    }
+    if Utc::now().timestamp() > claims.exp {
+        return Err(AdminAuthError::Expired);
+    }
+
+    Ok(claims)
+}
+
+/// Create a cookie that clears a pending second factor
+pub fn clear_two_factor_cookie() -> Cookie<'static> {
+    Cookie::build((TWO_FACTOR_COOKIE_NAME, ""))
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Strict)
+        .max_age(time::Duration::seconds(0))
+        .path("/admin")
+        .build()
+}
+
+const OIDC_COOKIE_NAME: &str = "admin_oidc";
+
+/// How long the identity provider login may take
+const OIDC_PENDING_MINUTES: i64 = 10;
+
+/// Claims for a login waiting for the identity provider callback
+#[derive(Debug, Serialize, Deserialize)]
+struct OidcPendingClaims {
+    #[serde(flatten)]
+    pending: PendingLogin,
+    exp: i64,
+}
+
+/// Create a short-lived cookie holding the state, nonce and PKCE verifier of an OIDC login.
+/// SameSite=Lax, because the callback is a cross-site redirect from the provider.
+pub fn create_oidc_cookie(
+    pending: PendingLogin,
+    jwt_secret: &str,
+) -> Result<Cookie<'static>, jsonwebtoken::errors::Error> {
+    let claims = OidcPendingClaims {
+        pending,
+        exp: (Utc::now() + chrono::Duration::minutes(OIDC_PENDING_MINUTES)).timestamp(),
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret.as_ref()),
+    )?;
+
+    Ok(Cookie::build((OIDC_COOKIE_NAME, token))
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::minutes(OIDC_PENDING_MINUTES))
+        .path("/admin/login/oidc")
+        .build())
+}
+
+/// Read the pending OIDC login from the request
+pub fn verify_oidc_cookie(
+    jar: &axum_extra::extract::CookieJar,
+    jwt_secret: &str,
+) -> Result<PendingLogin, AdminAuthError> {
+    let cookie = jar.get(OIDC_COOKIE_NAME).ok_or(AdminAuthError::Missing)?;
+    let claims = decode::<OidcPendingClaims>(
+        cookie.value(),
+        &DecodingKey::from_secret(jwt_secret.as_ref()),
+        &Validation::default(),
+    )?
+    .claims;
+    Ok(claims.pending)
+}
+
+/// Create a cookie that clears a pending OIDC login
+pub fn clear_oidc_cookie() -> Cookie<'static> {
+    Cookie::build((OIDC_COOKIE_NAME, ""))
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::seconds(0))
+        .path("/admin/login/oidc")
+        .build()
+}
+
 #[derive(Debug)]
 pub enum AdminAuthError {
     InvalidToken(jsonwebtoken::errors::Error),
     Expired,
     Missing,
+    /// The session was logged out, revoked or its admin deactivated
+    Revoked,
+    /// The bearer token is unknown, revoked or exceeds its admin's role
+    InvalidApiToken,
+    Forbidden,
+    Database(color_eyre::Report),
 }
 
 impl From<jsonwebtoken::errors::Error> for AdminAuthError {
@@ -112,6 +303,10 @@ impl std::fmt::Display for AdminAuthError {
             AdminAuthError::InvalidToken(e) => write!(f, "Invalid token: {e}"),
             AdminAuthError::Expired => write!(f, "Token expired"),
             AdminAuthError::Missing => write!(f, "No authentication token"),
+            AdminAuthError::Revoked => write!(f, "Session is no longer valid"),
+            AdminAuthError::InvalidApiToken => write!(f, "Invalid API token"),
+            AdminAuthError::Database(e) => write!(f, "Failed to check session: {e}"),
+            AdminAuthError::Forbidden => write!(f, "Role does not permit this action"),
         }
     }
 }
@@ -120,40 +315,122 @@ impl std::error::Error for AdminAuthError {}
 
 impl IntoResponse for AdminAuthError {
     fn into_response(self) -> Response {
-        // For admin authentication failures, redirect to login page
-        Redirect::to("/admin/login").into_response()
+        match self {
+            // Logged in, but not allowed - sending them to the login page would loop
+            AdminAuthError::Forbidden => (
+                StatusCode::FORBIDDEN,
+                "Your admin role does not permit this action",
+            )
+                .into_response(),
+            // Scripts cannot follow a login redirect
+            AdminAuthError::InvalidApiToken => (
+                StatusCode::UNAUTHORIZED,
+                [(axum::http::header::WWW_AUTHENTICATE, "Bearer")],
+                "Invalid API token",
+            )
+                .into_response(),
+            AdminAuthError::Database(e) => {
+                tracing::error!("Failed to check admin session: {e:?}");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
+            }
+            // For admin authentication failures, redirect to login page
+            _ => Redirect::to("/admin/login").into_response(),
+        }
     }
 }
 
 /// AdminToken provides authenticated admin context to handlers
-/// Newtype wrapper around AdminClaims that can only be constructed via authentication
+/// Wraps AdminClaims and can only be constructed via authentication
 #[derive(Debug)]
-pub struct AdminToken(AdminClaims);
+pub struct AdminToken {
+    claims: AdminClaims,
+    api_token_id: Option<String>,
+}
 
 impl AdminToken {
     /// Get the admin ID
     pub fn admin_id(&self) -> &str {
-        &self.0.admin_id
+        &self.claims.admin_id
+    }
+
+    /// Get the server-side session ID
+    pub fn session_id(&self) -> &str {
+        &self.claims.sid
     }
 
     /// Get the username
     pub fn username(&self) -> &str {
-        &self.0.username
+        &self.claims.username
+    }
+
+    /// Get the admin's role
+    pub fn role(&self) -> AdminRole {
+        self.claims.role
+    }
+
+    /// Check whether the admin's role grants the required role
+    pub fn can(&self, required: AdminRole) -> bool {
+        self.claims.role.grants(required)
+    }
+
+    /// Get what the admin may do, for showing or hiding navigation
+    pub fn permissions(&self) -> Permissions {
+        Permissions::for_role(self.claims.role)
     }
 
     /// Get the expiration timestamp
     pub fn exp(&self) -> i64 {
-        self.0.exp
+        self.claims.exp
     }
 
     /// Get the issued at timestamp
     pub fn iat(&self) -> i64 {
-        self.0.iat
+        self.claims.iat
+    }
+
+    /// ID of the API token used for this request, if not a browser session
+    pub fn api_token_id(&self) -> Option<&str> {
+        self.api_token_id.as_deref()
     }
 
     /// Internal constructor - only used by the extractor
     fn from_verified_claims(claims: AdminClaims) -> Self {
-        Self(claims)
+        Self {
+            claims,
+            api_token_id: None,
+        }
+    }
+
+    /// Authenticate a personal API token; it acts with its scope, not the admin's full role
+    fn from_api_token(
+        repository: &RuleRepository,
+        token: &str,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Self, AdminAuthError> {
+        let (api_token, admin) = repository
+            .get_valid_api_token(&crate::api_token::hash_api_token(token))
+            .map_err(AdminAuthError::Database)?
+            .ok_or(AdminAuthError::InvalidApiToken)?;
+
+        // A demoted admin's tokens must not keep the old role
+        if !admin.role.grants(api_token.scope) {
+            return Err(AdminAuthError::InvalidApiToken);
+        }
+        repository
+            .touch_api_token(&api_token.id, now)
+            .map_err(AdminAuthError::Database)?;
+
+        Ok(Self {
+            claims: AdminClaims {
+                sid: String::new(),
+                admin_id: admin.id,
+                username: admin.username,
+                role: api_token.scope,
+                exp: i64::MAX,
+                iat: api_token.created_at.and_utc().timestamp(),
+            },
+            api_token_id: Some(api_token.id),
+        })
     }
 }
 
@@ -161,12 +438,20 @@ impl<S> FromRequestParts<S> for AdminToken
 where
     S: Send + Sync,
     crate::config::Config: axum::extract::FromRef<S>,
+    RuleRepository: axum::extract::FromRef<S>,
 {
     type Rejection = AdminAuthError;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         // Extract configuration to get JWT secret
         let config = crate::config::Config::from_ref(state);
+        let repository = RuleRepository::from_ref(state);
+        let now = Utc::now().naive_utc();
+
+        // Scripts authenticate with a personal API token; never fall back to cookies
+        if let Some(token) = crate::api_token::bearer_token(&parts.headers) {
+            return AdminToken::from_api_token(&repository, token, now);
+        }
 
         // Extract cookie jar
         let jar = axum_extra::extract::CookieJar::from_request_parts(parts, state)
@@ -177,8 +462,427 @@ where
         let cookie = jar.get(ADMIN_COOKIE_NAME).ok_or(AdminAuthError::Missing)?;
 
         // Verify cookie and get claims
-        let claims = verify_admin_cookie(cookie.value(), &config.security.jwt_secret)?;
+        let mut claims = verify_admin_cookie(cookie.value(), &config.security.jwt_secret)?;
+
+        // The signed token is only valid while its server-side session is
+        let (session, admin) = repository
+            .get_valid_admin_session(&claims.sid, now)
+            .map_err(AdminAuthError::Database)?
+            .ok_or(AdminAuthError::Revoked)?;
+        if session.admin_id != claims.admin_id {
+            return Err(AdminAuthError::Revoked);
+        }
+        repository
+            .touch_admin_session(&session.id, now)
+            .map_err(AdminAuthError::Database)?;
+
+        // Role and username changes apply without logging in again
+        claims.username = admin.username;
+        claims.role = admin.role;
 
         Ok(AdminToken::from_verified_claims(claims))
     }
 }
+
+/// Which admin areas a role can access, passed to templates to hide navigation
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Permissions {
+    pub superadmin: bool,
+    pub editor: bool,
+    pub translator: bool,
+    pub analyst: bool,
+}
+
+impl Permissions {
+    pub fn for_role(role: AdminRole) -> Self {
+        Self {
+            superadmin: role.grants(AdminRole::Superadmin),
+            editor: role.grants(AdminRole::Editor),
+            translator: role.grants(AdminRole::Translator),
+            analyst: role.grants(AdminRole::Analyst),
+        }
+    }
+}
+
+/// Marker for a role that `RequireRole` checks against
+pub trait RoleRequirement {
+    const ROLE: AdminRole;
+}
+
+/// Can manage admin accounts and everything else
+pub struct Superadmin;
+/// Can create, edit and delete quiz questions
+pub struct Editor;
+/// Can view and export statistics
+pub struct Analyst;
+
+impl RoleRequirement for Superadmin {
+    const ROLE: AdminRole = AdminRole::Superadmin;
+}
+
+impl RoleRequirement for Editor {
+    const ROLE: AdminRole = AdminRole::Editor;
+}
+
+impl RoleRequirement for Analyst {
+    const ROLE: AdminRole = AdminRole::Analyst;
+}
+
+/// Authenticated admin whose role grants `R`; rejects others with 403 Forbidden
+pub struct RequireRole<R: RoleRequirement>(pub AdminToken, PhantomData<R>);
+
+impl<R: RoleRequirement> std::ops::Deref for RequireRole<R> {
+    type Target = AdminToken;
+
+    fn deref(&self) -> &AdminToken {
+        &self.0
+    }
+}
+
+impl<R: RoleRequirement> std::fmt::Debug for RequireRole<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("RequireRole")
+            .field(&R::ROLE)
+            .field(&self.0)
+            .finish()
+    }
+}
+
+impl<S, R> FromRequestParts<S> for RequireRole<R>
+where
+    S: Send + Sync,
+    R: RoleRequirement,
+    crate::config::Config: axum::extract::FromRef<S>,
+    RuleRepository: axum::extract::FromRef<S>,
+{
+    type Rejection = AdminAuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let token = AdminToken::from_request_parts(parts, state).await?;
+
+        if !token.can(R::ROLE) {
+            tracing::warn!(
+                admin_username = %token.username(),
+                role = token.role().as_str(),
+                required = R::ROLE.as_str(),
+                "Admin lacks required role"
+            );
+            return Err(AdminAuthError::Forbidden);
+        }
+
+        Ok(RequireRole(token, PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn test_hash_and_verify_password() {
+        let hash = hash_password("correct horse").unwrap();
+
+        assert!(verify_password("correct horse", &hash).unwrap());
+        assert!(!verify_password("battery staple", &hash).unwrap());
+        assert!(verify_password("correct horse", "not a hash").is_err());
+    }
+
+    #[test]
+    fn test_role_survives_cookie_round_trip() {
+        let secret = "test-secret-that-is-long-enough-for-validation";
+        let cookie = create_admin_cookie(
+            "session-1".to_string(),
+            "admin-1".to_string(),
+            "translator".to_string(),
+            AdminRole::Translator,
+            secret,
+            chrono::Duration::hours(1),
+        )
+        .unwrap();
+
+        let claims = verify_admin_cookie(cookie.value(), secret).unwrap();
+
+        assert_eq!(claims.role, AdminRole::Translator);
+        let permissions = Permissions::for_role(claims.role);
+        assert!(permissions.translator);
+        assert!(!permissions.editor);
+        assert!(!permissions.analyst);
+    }
+
+    #[test]
+    fn test_two_factor_cookie_is_not_a_session() {
+        let secret = "test-secret-that-is-long-enough-for-validation";
+        let pending = create_two_factor_cookie("admin-1".to_string(), secret).unwrap();
+        let session = create_admin_cookie(
+            "session-1".to_string(),
+            "admin-1".to_string(),
+            "alice".to_string(),
+            AdminRole::Superadmin,
+            secret,
+            chrono::Duration::hours(1),
+        )
+        .unwrap();
+
+        let jar = axum_extra::extract::CookieJar::new().add(pending.clone());
+        assert_eq!(
+            verify_two_factor_cookie(&jar, secret).unwrap().admin_id,
+            "admin-1"
+        );
+        assert!(verify_admin_cookie(pending.value(), secret).is_err());
+
+        let jar = axum_extra::extract::CookieJar::new().add(Cookie::new(
+            TWO_FACTOR_COOKIE_NAME,
+            session.value().to_string(),
+        ));
+        assert!(verify_two_factor_cookie(&jar, secret).is_err());
+    }
+
+    #[derive(Clone, FromRef)]
+    struct TestState {
+        config: crate::config::Config,
+        repository: RuleRepository,
+    }
+
+    fn test_state() -> TestState {
+        TestState {
+            config: test_support::config(),
+            repository: test_support::repository(),
+        }
+    }
+
+    async fn extract_token(
+        state: &TestState,
+        cookie: &Cookie<'_>,
+    ) -> Result<AdminToken, AdminAuthError> {
+        let (mut parts, ()) = axum::http::Request::builder()
+            .header(
+                "cookie",
+                format!("{}={}", ADMIN_COOKIE_NAME, cookie.value()),
+            )
+            .body(())
+            .unwrap()
+            .into_parts();
+        AdminToken::from_request_parts(&mut parts, state).await
+    }
+
+    #[tokio::test]
+    async fn test_token_requires_live_session() {
+        use crate::models::{NewAdmin, NewAdminSession};
+
+        let state = test_state();
+        let repository = &state.repository;
+        let admin = repository
+            .create_admin(NewAdmin::new(
+                "alice".to_string(),
+                "hash".to_string(),
+                AdminRole::Editor,
+            ))
+            .unwrap();
+        let new_session = |admin_id: &str| {
+            repository
+                .create_admin_session(NewAdminSession::new(
+                    admin_id.to_string(),
+                    (Utc::now() + chrono::Duration::hours(1)).naive_utc(),
+                    None,
+                    None,
+                ))
+                .unwrap()
+        };
+        let cookie_for = |session_id: String| {
+            create_admin_cookie(
+                session_id,
+                admin.id.clone(),
+                admin.username.clone(),
+                admin.role,
+                &state.config.security.jwt_secret,
+                chrono::Duration::hours(1),
+            )
+            .unwrap()
+        };
+
+        // A role change applies to the existing session
+        let first = cookie_for(new_session(&admin.id).id);
+        repository
+            .set_admin_role(&admin.id, AdminRole::Analyst)
+            .unwrap();
+        let token = extract_token(&state, &first).await.unwrap();
+        assert_eq!(token.role(), AdminRole::Analyst);
+
+        // Logging out one session leaves the others alive
+        let second = cookie_for(new_session(&admin.id).id);
+        assert!(
+            repository
+                .revoke_admin_session(&admin.id, token.session_id())
+                .unwrap()
+        );
+        assert!(matches!(
+            extract_token(&state, &first).await,
+            Err(AdminAuthError::Revoked)
+        ));
+        assert!(extract_token(&state, &second).await.is_ok());
+
+        // A token signed for a session that does not exist is rejected
+        let unknown = cookie_for("no-such-session".to_string());
+        assert!(matches!(
+            extract_token(&state, &unknown).await,
+            Err(AdminAuthError::Revoked)
+        ));
+
+        // Deactivation ends every session
+        repository.set_admin_active(&admin.id, false).unwrap();
+        assert!(matches!(
+            extract_token(&state, &second).await,
+            Err(AdminAuthError::Revoked)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_translator_is_refused_editor_routes() {
+        use crate::models::{NewAdmin, NewAdminSession};
+
+        let state = test_state();
+        let repository = &state.repository;
+        let cookie_for = |username: &str, role: AdminRole| {
+            let admin = repository
+                .create_admin(NewAdmin::new(
+                    username.to_string(),
+                    "hash".to_string(),
+                    role,
+                ))
+                .unwrap();
+            let session = repository
+                .create_admin_session(NewAdminSession::new(
+                    admin.id.clone(),
+                    (Utc::now() + chrono::Duration::hours(1)).naive_utc(),
+                    None,
+                    None,
+                ))
+                .unwrap();
+            create_admin_cookie(
+                session.id,
+                admin.id,
+                admin.username,
+                admin.role,
+                &state.config.security.jwt_secret,
+                chrono::Duration::hours(1),
+            )
+            .unwrap()
+        };
+        let require_editor = |cookie: Cookie<'static>| {
+            let (mut parts, ()) = axum::http::Request::builder()
+                .header(
+                    "cookie",
+                    format!("{}={}", ADMIN_COOKIE_NAME, cookie.value()),
+                )
+                .body(())
+                .unwrap()
+                .into_parts();
+            let state = state.clone();
+            async move { RequireRole::<Editor>::from_request_parts(&mut parts, &state).await }
+        };
+
+        let translator = cookie_for("tina", AdminRole::Translator);
+        assert!(matches!(
+            require_editor(translator).await,
+            Err(AdminAuthError::Forbidden)
+        ));
+
+        let editor = cookie_for("erin", AdminRole::Editor);
+        assert_eq!(
+            require_editor(editor).await.unwrap().role(),
+            AdminRole::Editor
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_token_acts_with_its_scope() {
+        use crate::models::{NewAdmin, NewAdminApiToken, NewAdminSession};
+
+        let state = test_state();
+        let repository = &state.repository;
+        let admin = repository
+            .create_admin(NewAdmin::new(
+                "bot".to_string(),
+                "hash".to_string(),
+                AdminRole::Superadmin,
+            ))
+            .unwrap();
+        let token = crate::api_token::generate_api_token();
+        let created = repository
+            .create_api_token(NewAdminApiToken::new(
+                admin.id.clone(),
+                "nightly export".to_string(),
+                crate::api_token::hash_api_token(&token),
+                crate::api_token::token_hint(&token),
+                AdminRole::Analyst,
+            ))
+            .unwrap();
+        let session = repository
+            .create_admin_session(NewAdminSession::new(
+                admin.id.clone(),
+                (Utc::now() + chrono::Duration::hours(1)).naive_utc(),
+                None,
+                None,
+            ))
+            .unwrap();
+        let cookie = create_admin_cookie(
+            session.id,
+            admin.id.clone(),
+            admin.username.clone(),
+            admin.role,
+            &state.config.security.jwt_secret,
+            chrono::Duration::hours(1),
+        )
+        .unwrap();
+
+        let state = &state;
+        let cookie = cookie.value().to_string();
+        let extract = |bearer: &str| {
+            let (mut parts, ()) = axum::http::Request::builder()
+                .header("authorization", format!("Bearer {bearer}"))
+                .header("cookie", format!("{ADMIN_COOKIE_NAME}={cookie}"))
+                .body(())
+                .unwrap()
+                .into_parts();
+            async move { AdminToken::from_request_parts(&mut parts, state).await }
+        };
+
+        let authenticated = extract(&token).await.unwrap();
+        assert_eq!(authenticated.role(), AdminRole::Analyst);
+        assert_eq!(authenticated.api_token_id(), Some(created.id.as_str()));
+        assert!(!authenticated.can(AdminRole::Editor));
+        let used = repository.get_admin_api_tokens(&admin.id).unwrap();
+        assert!(used[0].last_used_at.is_some());
+
+        // A wrong bearer token does not fall back to the session cookie
+        assert!(matches!(
+            extract("rgl_wrong").await,
+            Err(AdminAuthError::InvalidApiToken)
+        ));
+
+        // Demoting the owner below the token's scope disables the token
+        repository
+            .set_admin_role(&admin.id, AdminRole::Editor)
+            .unwrap();
+        assert!(matches!(
+            extract(&token).await,
+            Err(AdminAuthError::InvalidApiToken)
+        ));
+        repository
+            .set_admin_role(&admin.id, AdminRole::Analyst)
+            .unwrap();
+        assert!(extract(&token).await.is_ok());
+
+        assert!(
+            repository
+                .revoke_api_token(&admin.id, &created.id)
+                .unwrap()
+                .is_some()
+        );
+        assert!(matches!(
+            extract(&token).await,
+            Err(AdminAuthError::InvalidApiToken)
+        ));
+    }
+}
@@ -0,0 +1,31 @@
+//! Telegram bot answering rule lookups and running quizzes.
+//!
+//! Configured by the `[telegram]` section; shares the database with the web server.
+
+use color_eyre::eyre::{WrapErr, eyre};
+use regelator::{config::Config, repository::RuleRepository, telegram::TelegramBot};
+use tracing::info;
+
+#[tokio::main]
+async fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+
+    let config = Config::load().map_err(|e| eyre!("Failed to load configuration: {}", e))?;
+    regelator::logging::init_tracing(&config.logging)?;
+
+    let telegram = config
+        .telegram
+        .clone()
+        .ok_or_else(|| eyre!("Missing [telegram] configuration"))?;
+    let pool = regelator::db::create_pool(&config.database.url)
+        .wrap_err("Failed to connect to database")?;
+    let bot = TelegramBot::new(telegram, RuleRepository::new(pool))?;
+
+    tokio::select! {
+        result = bot.run() => result,
+        _ = tokio::signal::ctrl_c() => {
+            info!("Received Ctrl+C signal, stopping the bot");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,604 @@
+//! Certificates for passed exams.
+//!
+//! A certificate copies the holder's name, score, rule set version and date from a
+//! passed exam. Its public id carries an HMAC of the certificate id, so ids can't be
+//! guessed or made up, and `/certificates/{id}` confirms it against the database,
+//! where admins can revoke it. The certificate itself is drawn as SVG and as a
+//! one-page PDF with a QR code linking to that page.
+
+use chrono::NaiveDateTime;
+use color_eyre::{Result, eyre::eyre};
+use hmac::{Hmac, Mac};
+use qrcode::{Color, QrCode};
+use sha2::Sha256;
+use tracing::info;
+
+use crate::{
+    config::SecurityConfig,
+    models::{QuizCertificate, QuizExam, QuizSessionRecord},
+    repository::RuleRepository,
+};
+
+/// Bytes of the HMAC kept in the public id
+const SIGNATURE_BYTES: usize = 10;
+
+/// Longest holder name accepted, in characters
+pub const MAX_HOLDER_NAME_CHARS: usize = 100;
+
+/// Signs and checks public certificate ids with `security.certificate_key`, or
+/// `jwt_secret` if no separate key is set
+#[derive(Clone)]
+pub struct CertificateSigner {
+    key: Vec<u8>,
+}
+
+impl CertificateSigner {
+    pub fn new(security: &SecurityConfig) -> Self {
+        let key = security
+            .certificate_key
+            .as_ref()
+            .unwrap_or(&security.jwt_secret);
+        Self {
+            key: key.as_bytes().to_vec(),
+        }
+    }
+
+    fn mac(&self, certificate_id: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(b"regelator certificate\0");
+        mac.update(certificate_id.as_bytes());
+        mac
+    }
+
+    /// Public id of a certificate: its id followed by a truncated signature
+    pub fn signed_id(&self, certificate_id: &str) -> String {
+        let signature = self.mac(certificate_id).finalize().into_bytes();
+        let hex: String = signature[..SIGNATURE_BYTES]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        format!("{certificate_id}-{hex}")
+    }
+
+    /// Certificate id of a public id, or `None` if its signature doesn't match
+    pub fn verify(&self, signed_id: &str) -> Option<String> {
+        let (certificate_id, hex) = signed_id.rsplit_once('-')?;
+        if hex.len() != SIGNATURE_BYTES * 2 {
+            return None;
+        }
+        let signature = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        self.mac(certificate_id)
+            .verify_truncated_left(&signature)
+            .ok()?;
+        Some(certificate_id.to_string())
+    }
+}
+
+/// Why a holder name can't go on a certificate, if it can't
+pub fn holder_name_error(name: &str) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        Some("Enter the name to put on the certificate".to_string())
+    } else if name.chars().count() > MAX_HOLDER_NAME_CHARS {
+        Some(format!(
+            "The name can be at most {MAX_HOLDER_NAME_CHARS} characters long"
+        ))
+    } else if name.chars().any(char::is_control) {
+        Some("The name contains characters that can't be printed".to_string())
+    } else {
+        None
+    }
+}
+
+/// The passed exam in this session, if the requester took it: in the exam session
+/// itself, from the practice session it was started from, or as the same learner
+pub fn claimable_exam(
+    repository: &RuleRepository,
+    exam_session_id: &str,
+    requester: &QuizSessionRecord,
+) -> Result<Option<QuizExam>> {
+    let Some(exam) = repository
+        .get_quiz_exam(exam_session_id)?
+        .filter(|exam| exam.passed == Some(true))
+    else {
+        return Ok(None);
+    };
+    let Some(session) = repository.get_quiz_session(exam_session_id)? else {
+        return Ok(None);
+    };
+
+    let taken_by_requester = exam.session_id == requester.id
+        || exam.practice_session_id.as_deref() == Some(requester.id.as_str())
+        || (session.learner_id.is_some() && session.learner_id == requester.learner_id);
+    Ok(taken_by_requester.then_some(exam))
+}
+
+/// Issue the certificate for a passed exam, or return the one issued before
+pub fn issue_certificate(
+    repository: &RuleRepository,
+    exam: &QuizExam,
+    holder_name: &str,
+    now: NaiveDateTime,
+) -> Result<QuizCertificate> {
+    if let Some(existing) = repository.get_exam_certificate(&exam.session_id)? {
+        return Ok(existing);
+    }
+    let session = repository
+        .get_quiz_session(&exam.session_id)?
+        .ok_or_else(|| eyre!("Exam session {} not found", exam.session_id))?;
+    let rule_set = repository
+        .get_rule_sets()?
+        .into_iter()
+        .find(|rule_set| rule_set.id == session.rule_set_id)
+        .ok_or_else(|| eyre!("Rule set {} not found", session.rule_set_id))?;
+    let version = repository
+        .get_versions_for_rule_set(&rule_set.id)?
+        .into_iter()
+        .find(|version| version.id == session.version_id)
+        .ok_or_else(|| eyre!("Version {} not found", session.version_id))?;
+
+    let certificate = QuizCertificate::new(
+        exam.session_id.clone(),
+        holder_name.trim().to_string(),
+        rule_set.name,
+        version.version_name,
+        exam.correct_count.unwrap_or(0) * 100 / exam.question_count.max(1),
+        exam.finished_at.unwrap_or(now),
+        now,
+    );
+    repository.create_quiz_certificate(&certificate)?;
+    info!(
+        "Issued certificate {} for exam {}",
+        certificate.id, exam.session_id
+    );
+    Ok(certificate)
+}
+
+// The certificate is laid out on an A4 landscape page in points, from the top left
+
+const PAGE_WIDTH: f32 = 842.0;
+const PAGE_HEIGHT: f32 = 595.0;
+const MARGIN: f32 = 60.0;
+const QR_SIZE: f32 = 90.0;
+
+struct Line {
+    text: String,
+    /// Baseline, from the top of the page
+    y: f32,
+    size: f32,
+    bold: bool,
+    centered: bool,
+}
+
+fn layout(certificate: &QuizCertificate, signed_id: &str, verify_url: &str) -> Vec<Line> {
+    let centered = |text: String, y, size, bold| Line {
+        text,
+        y,
+        size,
+        bold,
+        centered: true,
+    };
+    let footer = |text: String, y| Line {
+        text,
+        y,
+        size: 9.0,
+        bold: false,
+        centered: false,
+    };
+    vec![
+        centered(
+            "Certificate of Rules Knowledge".to_string(),
+            130.0,
+            30.0,
+            true,
+        ),
+        centered("This certifies that".to_string(), 185.0, 14.0, false),
+        centered(certificate.holder_name.clone(), 240.0, 28.0, true),
+        centered("passed the rules exam for".to_string(), 290.0, 14.0, false),
+        centered(
+            format!(
+                "{} ({})",
+                certificate.rule_set_name, certificate.version_name
+            ),
+            335.0,
+            18.0,
+            true,
+        ),
+        centered(
+            format!(
+                "with a score of {}% on {}",
+                certificate.score_percent,
+                certificate.passed_at.format("%Y-%m-%d")
+            ),
+            380.0,
+            14.0,
+            false,
+        ),
+        footer(format!("Certificate {signed_id}"), 520.0),
+        footer(format!("Verify at {verify_url}"), 535.0),
+    ]
+}
+
+/// Dark modules of the QR code for `data` as `(column, row)`, with the code's width
+fn qr_modules(data: &str) -> Result<(Vec<(usize, usize)>, usize)> {
+    let code = QrCode::new(data.as_bytes()).map_err(|e| eyre!("Failed to build QR code: {}", e))?;
+    let width = code.width();
+    let modules = code
+        .to_colors()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, color)| *color == Color::Dark)
+        .map(|(i, _)| (i % width, i / width))
+        .collect();
+    Ok((modules, width))
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// The certificate as a standalone SVG document
+pub fn certificate_svg(
+    certificate: &QuizCertificate,
+    signed_id: &str,
+    verify_url: &str,
+) -> Result<String> {
+    let mut svg = format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}" "#,
+            r##"font-family="Helvetica, Arial, sans-serif" fill="#1f2933">"##,
+            r##"<rect width="{w}" height="{h}" fill="#fff"/>"##,
+            r##"<rect x="24" y="24" width="{bw}" height="{bh}" fill="none" stroke="#1f3a5f" stroke-width="3"/>"##,
+        ),
+        w = PAGE_WIDTH,
+        h = PAGE_HEIGHT,
+        bw = PAGE_WIDTH - 48.0,
+        bh = PAGE_HEIGHT - 48.0,
+    );
+    for line in layout(certificate, signed_id, verify_url) {
+        let (x, anchor) = if line.centered {
+            (PAGE_WIDTH / 2.0, "middle")
+        } else {
+            (MARGIN, "start")
+        };
+        svg.push_str(&format!(
+            r#"<text x="{x}" y="{}" font-size="{}" font-weight="{}" text-anchor="{anchor}">{}</text>"#,
+            line.y,
+            line.size,
+            if line.bold { "bold" } else { "normal" },
+            escape_xml(&line.text)
+        ));
+    }
+
+    let (modules, width) = qr_modules(verify_url)?;
+    let path: String = modules
+        .iter()
+        .map(|(column, row)| format!("M{column} {row}h1v1h-1z"))
+        .collect();
+    svg.push_str(&format!(
+        r##"<path transform="translate({} {}) scale({})" fill="#000" d="{path}"/>"##,
+        PAGE_WIDTH - MARGIN - QR_SIZE,
+        PAGE_HEIGHT - MARGIN - QR_SIZE,
+        QR_SIZE / width as f32,
+    ));
+    svg.push_str("</svg>");
+    Ok(svg)
+}
+
+/// Advance widths of Helvetica for the printable ASCII characters, in 1/1000 em
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278,
+    278, // space to /
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, // 0 to ?
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, // @ to O
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, // P to _
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, // ` to o
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584, // p to ~
+];
+
+/// Approximate width of `text` in points; bold is taken as slightly wider
+fn text_width(text: &str, size: f32, bold: bool) -> f32 {
+    let units: u32 = text
+        .chars()
+        .map(|c| {
+            (c as usize)
+                .checked_sub(32)
+                .and_then(|i| HELVETICA_WIDTHS.get(i))
+                .map_or(556, |width| u32::from(*width))
+        })
+        .sum();
+    let scale = if bold { 1.06 } else { 1.0 };
+    units as f32 * size * scale / 1000.0
+}
+
+/// PDF string literal in WinAnsi encoding; characters outside Latin-1 become `?`
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut bytes = vec![b'('];
+    for c in text.chars() {
+        let byte = u8::try_from(u32::from(c))
+            .ok()
+            .filter(|byte| *byte >= 0x20 && !(0x7f..0xa0).contains(byte))
+            .unwrap_or(b'?');
+        if matches!(byte, b'(' | b')' | b'\\') {
+            bytes.push(b'\\');
+        }
+        bytes.push(byte);
+    }
+    bytes.push(b')');
+    bytes
+}
+
+/// The certificate as a one-page PDF using the standard Helvetica fonts
+pub fn certificate_pdf(
+    certificate: &QuizCertificate,
+    signed_id: &str,
+    verify_url: &str,
+) -> Result<Vec<u8>> {
+    let mut content = format!(
+        "0.12 0.23 0.37 RG 3 w 24 24 {} {} re S\n0.12 0.16 0.2 rg\n",
+        PAGE_WIDTH - 48.0,
+        PAGE_HEIGHT - 48.0
+    )
+    .into_bytes();
+    for line in layout(certificate, signed_id, verify_url) {
+        let x = if line.centered {
+            (PAGE_WIDTH - text_width(&line.text, line.size, line.bold)) / 2.0
+        } else {
+            MARGIN
+        };
+        let font = if line.bold { "F2" } else { "F1" };
+        content.extend_from_slice(
+            format!(
+                "BT /{font} {} Tf {x:.2} {:.2} Td ",
+                line.size,
+                PAGE_HEIGHT - line.y
+            )
+            .as_bytes(),
+        );
+        content.extend(pdf_string(&line.text));
+        content.extend_from_slice(b" Tj ET\n");
+    }
+
+    let (modules, width) = qr_modules(verify_url)?;
+    let module = QR_SIZE / width as f32;
+    let (left, bottom) = (PAGE_WIDTH - MARGIN - QR_SIZE, MARGIN);
+    content.extend_from_slice(b"0 g\n");
+    for (column, row) in modules {
+        content.extend_from_slice(
+            format!(
+                "{:.2} {:.2} {module:.2} {module:.2} re\n",
+                left + column as f32 * module,
+                bottom + QR_SIZE - (row + 1) as f32 * module
+            )
+            .as_bytes(),
+        );
+    }
+    content.extend_from_slice(b"f\n");
+
+    let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+    stream.extend(content);
+    stream.extend_from_slice(b"\nendstream");
+    let mut info = b"<< /Producer (Regelator) /Title ".to_vec();
+    info.extend(pdf_string(&format!(
+        "Certificate for {}",
+        certificate.holder_name
+    )));
+    info.extend_from_slice(b" >>");
+    let objects: [Vec<u8>; 7] = [
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+             /Resources << /Font << /F1 4 0 R /F2 5 0 R >> >> /Contents 6 0 R >>"
+        )
+        .into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        stream,
+        info,
+    ];
+
+    let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R /Info 7 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .as_bytes(),
+    );
+    Ok(pdf)
+}
+
+/// Link to the public verification page of a certificate under `site_url`
+pub fn verify_url(site_url: &str, signed_id: &str) -> String {
+    format!(
+        "{}/certificates/{signed_id}",
+        site_url.trim_end_matches('/')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::QuizMode;
+    use crate::test_support::{self, repository_with_current_version};
+
+    fn signer(key: &str) -> CertificateSigner {
+        CertificateSigner {
+            key: key.as_bytes().to_vec(),
+        }
+    }
+
+    fn certificate(holder_name: &str) -> QuizCertificate {
+        let at = chrono::NaiveDate::from_ymd_opt(2025, 8, 26)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap();
+        QuizCertificate::new(
+            "exam".to_string(),
+            holder_name.to_string(),
+            "Indoor".to_string(),
+            "2025".to_string(),
+            85,
+            at,
+            at,
+        )
+    }
+
+    #[test]
+    fn test_signed_ids_verify_only_with_the_same_key() {
+        let key = signer("certificate-key-certificate-key-!");
+        let signed = key.signed_id("0198e4c2-1111-7000-8000-000000000000");
+        assert_eq!(
+            key.verify(&signed).as_deref(),
+            Some("0198e4c2-1111-7000-8000-000000000000")
+        );
+
+        let mut tampered = signed.clone();
+        tampered.replace_range(0..1, "1");
+        assert_eq!(key.verify(&tampered), None);
+        assert_eq!(key.verify(&signed[..signed.len() - 2]), None);
+        assert_eq!(key.verify("no-signature"), None);
+        assert_eq!(signer("another-key").verify(&signed), None);
+    }
+
+    #[test]
+    fn test_holder_names_are_checked() {
+        assert!(holder_name_error("  Alex Example ").is_none());
+        assert!(holder_name_error("   ").is_some());
+        assert!(holder_name_error("Alex\nExample").is_some());
+        assert!(holder_name_error(&"x".repeat(MAX_HOLDER_NAME_CHARS + 1)).is_some());
+    }
+
+    #[test]
+    fn test_only_the_exam_taker_can_claim_a_passed_exam() {
+        let (repo, rule_set, version) = repository_with_current_version();
+        let session = |mode| test_support::quiz_session(&repo, &rule_set, &version, mode);
+        let practice = session(QuizMode::Practice);
+        let stranger = session(QuizMode::Practice);
+        let now = chrono::Utc::now().naive_utc();
+
+        let mut exams = Vec::new();
+        for passed in [true, false] {
+            let exam = QuizExam {
+                session_id: session(QuizMode::Exam).id,
+                practice_session_id: Some(practice.id.clone()),
+                question_count: 20,
+                pass_percent: 80,
+                started_at: now,
+                ends_at: now + chrono::Duration::minutes(30),
+                finished_at: None,
+                correct_count: None,
+                passed: None,
+            };
+            repo.create_quiz_exam(&exam, &[]).unwrap();
+            repo.finish_quiz_exam(&exam.session_id, now, if passed { 17 } else { 15 }, passed)
+                .unwrap();
+            exams.push(exam.session_id);
+        }
+
+        assert!(
+            claimable_exam(&repo, &exams[0], &stranger)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            claimable_exam(&repo, &exams[1], &practice)
+                .unwrap()
+                .is_none()
+        );
+        let exam = claimable_exam(&repo, &exams[0], &practice)
+            .unwrap()
+            .unwrap();
+
+        let issued = issue_certificate(&repo, &exam, " Alex Example ", now).unwrap();
+        assert_eq!(issued.holder_name, "Alex Example");
+        assert_eq!(issued.score_percent, 85);
+        assert_eq!(
+            (issued.rule_set_name.as_str(), issued.version_name.as_str()),
+            ("Indoor", "2025")
+        );
+        // Claiming again returns the same certificate
+        let again = issue_certificate(&repo, &exam, "Someone Else", now).unwrap();
+        assert_eq!(again.id, issued.id);
+
+        let revoked = repo
+            .revoke_quiz_certificate(&issued.id, now, Some("Cheated"))
+            .unwrap();
+        assert!(revoked.is_some_and(|before| !before.is_revoked()));
+        assert!(
+            repo.revoke_quiz_certificate(&issued.id, now, None)
+                .unwrap()
+                .is_none()
+        );
+        let stored = repo.get_quiz_certificate(&issued.id).unwrap().unwrap();
+        assert!(stored.is_revoked());
+        assert_eq!(stored.revocation_reason.as_deref(), Some("Cheated"));
+    }
+
+    #[test]
+    fn test_svg_escapes_the_holder_name() {
+        let svg = certificate_svg(
+            &certificate("<script>Bob & Co</script>"),
+            "id-sig",
+            "https://rules.example.org/certificates/id-sig",
+        )
+        .unwrap();
+        assert!(svg.contains("&lt;script&gt;Bob &amp; Co&lt;/script&gt;"));
+        assert!(!svg.contains("<script>"));
+    }
+
+    #[test]
+    fn test_pdf_cross_reference_points_at_objects() {
+        let pdf = certificate_pdf(
+            &certificate("Zoë (Captain)"),
+            "id-sig",
+            "https://rules.example.org/certificates/id-sig",
+        )
+        .unwrap();
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+        // Latin-1 text is kept, parentheses are escaped
+        let name = b"(Zo\xeb \\(Captain\\))";
+        assert!(pdf.windows(name.len()).any(|w| w == name));
+
+        let text = String::from_utf8_lossy(&pdf);
+        let xref: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(pdf[xref..].starts_with(b"xref\n"));
+        let entries = std::str::from_utf8(&pdf[xref..]).unwrap().lines().skip(3);
+        for (i, entry) in entries.take(7).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+}
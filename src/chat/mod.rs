@@ -0,0 +1,789 @@
+//! Chat commands independent of the chat network.
+//!
+//! [`ChatEngine`] turns a message or button press into a [`ChatResponse`] with
+//! Markdown text, buttons and the [`FollowUp`] the conversation expects next.
+//! Transports such as the Telegram bot only map their updates to [`ChatInput`],
+//! render the Markdown in their own formatting and remember the follow-up per
+//! conversation. [`test_adapter`] does the same in process for tests.
+
+pub mod test_adapter;
+
+use color_eyre::{Result, eyre::eyre};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    models::{QuizMode, Rule},
+    quiz_engine::QuizScope,
+    repository::RuleRepository,
+};
+
+const MAX_SEARCH_RESULTS: usize = 5;
+const SNIPPET_CHARS: usize = 100;
+
+/// Button actions; the part after the prefix identifies the target
+const RULE_ACTION: &str = "rule:";
+const ANSWER_ACTION: &str = "answer:";
+const NEXT_ACTION: &str = "next";
+
+const HELP_TEXT: &str = "\
+I look up rules and definitions and quiz you on them.
+
+- `/rule 17.5` shows a rule by number or slug
+- `/search stall count` finds rules mentioning all words
+- `/define pivot` shows a definition
+- `/quiz` or `/next` asks a question; reply with its number or press a button
+- `/stats` shows how you are doing";
+
+/// What a participant sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatInput {
+    /// A typed message, e.g. `/rule 17.5` or `2` as the answer to a question
+    Text(String),
+    /// The action of a [`ChatButton`] the participant pressed
+    Button(String),
+}
+
+/// Button offered with a response; transports send `action` back as [`ChatInput::Button`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatButton {
+    pub label: String,
+    pub action: String,
+}
+
+/// What the conversation expects after a response
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FollowUp {
+    #[default]
+    None,
+    /// A typed number answers the question with the answer at that position
+    AwaitingAnswer {
+        question_id: String,
+        answer_ids: Vec<String>,
+    },
+}
+
+/// Transport-neutral reply
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatResponse {
+    /// Markdown; rule text keeps its `rule:` and `definition:` links
+    pub text: String,
+    pub buttons: Vec<ChatButton>,
+    pub follow_up: FollowUp,
+}
+
+impl ChatResponse {
+    fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            buttons: Vec::new(),
+            follow_up: FollowUp::None,
+        }
+    }
+
+    fn with_buttons(mut self, buttons: Vec<ChatButton>) -> Self {
+        self.buttons = buttons;
+        self
+    }
+}
+
+/// Quiz session id for a conversation on a transport.
+///
+/// Derived rather than stored, so a conversation keeps its quiz progress across
+/// restarts of the transport.
+pub fn conversation_session_id(transport: &str, conversation_id: &str) -> String {
+    let digest = Sha256::digest(format!("{transport}-chat:{conversation_id}"));
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Uuid::new_v8(bytes).to_string()
+}
+
+/// Escape user-provided text for Markdown
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// First `max_chars` characters of the Markdown's text, for result lists
+pub fn markdown_snippet(markdown: &str, max_chars: usize) -> String {
+    use pulldown_cmark::{Event, Parser, TagEnd};
+
+    let mut text = String::new();
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Text(part) | Event::Code(part) => text.push_str(&part),
+            Event::SoftBreak | Event::HardBreak | Event::End(TagEnd::Paragraph) => text.push(' '),
+            _ => {}
+        }
+    }
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Split `/command@botname argument` (or `!command`) into the lowercased command and its argument
+fn parse_command(text: &str) -> Option<(String, &str)> {
+    let text = text.trim();
+    let text = text.strip_prefix('/').or_else(|| text.strip_prefix('!'))?;
+    let (command, argument) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let command = command.split('@').next().unwrap_or(command);
+    Some((command.to_lowercase(), argument.trim()))
+}
+
+/// `Pivot foot` -> `pivot-foot`, matching rule and glossary slugs
+fn slugify(term: &str) -> String {
+    term.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Answers chat commands from one rule set in one language
+#[derive(Clone)]
+pub struct ChatEngine {
+    repo: RuleRepository,
+    rule_set: String,
+    language: String,
+}
+
+impl ChatEngine {
+    pub fn new(repo: RuleRepository, rule_set: String, language: String) -> Self {
+        Self {
+            repo,
+            rule_set,
+            language,
+        }
+    }
+
+    pub fn rule_set(&self) -> &str {
+        &self.rule_set
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Respond to one input; `follow_up` is what the previous response asked for
+    pub fn handle(
+        &self,
+        session_id: &str,
+        input: &ChatInput,
+        follow_up: &FollowUp,
+    ) -> Result<ChatResponse> {
+        match input {
+            ChatInput::Button(action) => self.handle_button(session_id, action),
+            ChatInput::Text(text) => self.handle_text(session_id, text, follow_up),
+        }
+    }
+
+    fn handle_button(&self, session_id: &str, action: &str) -> Result<ChatResponse> {
+        if action == NEXT_ACTION {
+            return self.next_question(session_id);
+        }
+        if let Some(answer_id) = action.strip_prefix(ANSWER_ACTION) {
+            return self.answer(session_id, answer_id);
+        }
+        if let Some(number) = action.strip_prefix(RULE_ACTION) {
+            return self.rule(number);
+        }
+        Ok(ChatResponse::text("That button is no longer available."))
+    }
+
+    fn handle_text(
+        &self,
+        session_id: &str,
+        text: &str,
+        follow_up: &FollowUp,
+    ) -> Result<ChatResponse> {
+        if let FollowUp::AwaitingAnswer { answer_ids, .. } = follow_up
+            && let Ok(position) = text.trim().parse::<usize>()
+        {
+            return match position
+                .checked_sub(1)
+                .and_then(|index| answer_ids.get(index))
+            {
+                Some(answer_id) => self.answer(session_id, answer_id),
+                None => Ok(ChatResponse {
+                    follow_up: follow_up.clone(),
+                    ..ChatResponse::text(format!(
+                        "Reply with a number from 1 to {}.",
+                        answer_ids.len()
+                    ))
+                }),
+            };
+        }
+
+        let Some((command, argument)) = parse_command(text) else {
+            return Ok(ChatResponse::text(format!(
+                "I did not understand that.\n\n{HELP_TEXT}"
+            )));
+        };
+        match command.as_str() {
+            "rule" => self.rule(argument),
+            "search" => self.search(argument),
+            "define" => self.define(argument),
+            "quiz" | "next" => self.next_question(session_id),
+            "stats" => self.stats(session_id),
+            "start" | "help" => Ok(ChatResponse::text(HELP_TEXT)),
+            _ => Ok(ChatResponse::text(format!(
+                "Unknown command `{}`.\n\n{HELP_TEXT}",
+                escape_markdown(&command)
+            ))),
+        }
+    }
+
+    fn scope(&self) -> Result<QuizScope> {
+        QuizScope::current(&self.repo, &self.rule_set)?
+            .ok_or_else(|| eyre!("Rule set {} has no current version", self.rule_set))
+    }
+
+    /// Rule by number or slug, otherwise a keyword search
+    fn rule(&self, query: &str) -> Result<ChatResponse> {
+        if query.is_empty() {
+            return Ok(ChatResponse::text(
+                "Which rule? For example `/rule 17.5` or `/rule pivot`",
+            ));
+        }
+        let version_id = self.scope()?.version.id;
+
+        let rule = match self.repo.get_rule_by_number(query, &version_id)? {
+            Some(rule) => Some(rule),
+            None => self.repo.get_rule_by_slug(&slugify(query), &version_id)?,
+        };
+        match rule {
+            Some(rule) => self.format_rule(&rule),
+            None => self.search(query),
+        }
+    }
+
+    fn format_rule(&self, rule: &Rule) -> Result<ChatResponse> {
+        let content = self
+            .repo
+            .get_rule_content(&rule.id, &self.language)?
+            .map(|content| content.content_markdown)
+            .unwrap_or_default();
+        let mut text = format!("**Rule {}**\n\n{content}", escape_markdown(&rule.number));
+
+        let children = self.repo.get_child_rules(&rule.id)?;
+        if !children.is_empty() {
+            text.push('\n');
+            for child in &children {
+                let snippet = self
+                    .repo
+                    .get_rule_content(&child.id, &self.language)?
+                    .map(|content| markdown_snippet(&content.content_markdown, SNIPPET_CHARS))
+                    .unwrap_or_default();
+                text.push_str(&format!(
+                    "\n- **{}** {}",
+                    escape_markdown(&child.number),
+                    escape_markdown(&snippet)
+                ));
+            }
+        }
+        Ok(ChatResponse::text(text).with_buttons(rule_buttons(&children)))
+    }
+
+    /// Rules containing every word of the query
+    fn search(&self, query: &str) -> Result<ChatResponse> {
+        if query.is_empty() {
+            return Ok(ChatResponse::text(
+                "What should I look for? For example `/search stall count`",
+            ));
+        }
+        let version_id = self.scope()?.version.id;
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let matches: Vec<_> = self
+            .repo
+            .get_rules_with_content_for_version(&version_id, &self.language)?
+            .into_iter()
+            .filter(|(_, content)| {
+                let text = content.content_markdown.to_lowercase();
+                words.iter().all(|word| text.contains(word.as_str()))
+            })
+            .collect();
+
+        if matches.is_empty() {
+            return Ok(ChatResponse::text(format!(
+                "No rule mentions “{}”.",
+                escape_markdown(query)
+            )));
+        }
+
+        let mut text = format!(
+            "{} rule{} mention “{}”:\n",
+            matches.len(),
+            if matches.len() == 1 { "" } else { "s" },
+            escape_markdown(query)
+        );
+        let shown: Vec<_> = matches.iter().take(MAX_SEARCH_RESULTS).collect();
+        for (rule, content) in &shown {
+            text.push_str(&format!(
+                "\n- **{}** {}",
+                escape_markdown(&rule.number),
+                escape_markdown(&markdown_snippet(&content.content_markdown, SNIPPET_CHARS))
+            ));
+        }
+        if matches.len() > MAX_SEARCH_RESULTS {
+            text.push_str("\n\nAdd more words to narrow the search.");
+        }
+        let rules: Vec<Rule> = shown.into_iter().map(|(rule, _)| rule.clone()).collect();
+        Ok(ChatResponse::text(text).with_buttons(rule_buttons(&rules)))
+    }
+
+    /// Glossary term by slug or by its name in the engine's language
+    fn define(&self, term: &str) -> Result<ChatResponse> {
+        if term.is_empty() {
+            return Ok(ChatResponse::text(
+                "Which term? For example `/define pivot`",
+            ));
+        }
+        let scope = self.scope()?;
+
+        let glossary_term = match self.repo.find_glossary_term(
+            &scope.rule_set.id,
+            &scope.version.id,
+            &slugify(term),
+        )? {
+            Some(found) => Some(found),
+            None => self
+                .repo
+                .get_glossary_terms(&scope.rule_set.id, &scope.version.id, &self.language)?
+                .into_iter()
+                .find(|(_, content)| content.term.eq_ignore_ascii_case(term))
+                .map(|(found, _)| found),
+        };
+        let content = match &glossary_term {
+            Some(glossary_term) => match self
+                .repo
+                .find_glossary_content_for_language(&glossary_term.id, &self.language)?
+            {
+                Some(content) => Some(content),
+                None => self
+                    .repo
+                    .find_glossary_content_for_language(&glossary_term.id, "en")?,
+            },
+            None => None,
+        };
+        let Some(content) = content else {
+            return Ok(ChatResponse::text(format!(
+                "No definition for “{}”.",
+                escape_markdown(term)
+            )));
+        };
+        Ok(ChatResponse::text(format!(
+            "**{}**\n\n{}",
+            escape_markdown(&content.term),
+            content.definition_markdown
+        )))
+    }
+
+    fn next_question(&self, session_id: &str) -> Result<ChatResponse> {
+        let scope = self.scope()?;
+        scope.ensure_session(&self.repo, session_id, &self.language, QuizMode::Practice)?;
+        let Some(next) = scope.next_question(&self.repo, session_id)? else {
+            let stats = self.repo.get_session_statistics(session_id)?;
+            let mut text = format!(
+                "You have answered every question: {} of {} correct ({}%).",
+                stats.correct_answers, stats.total_questions, stats.accuracy_percentage
+            );
+            if let Some(due_at) = scope.next_review_due(&self.repo, session_id)? {
+                text.push_str(&format!(
+                    " Your next review is due {} UTC.",
+                    due_at.format("%Y-%m-%d %H:%M")
+                ));
+            }
+            return Ok(ChatResponse::text(text));
+        };
+
+        let mut text = next.question.question_text.clone();
+        text.push('\n');
+        for (position, answer) in next.answers.iter().enumerate() {
+            text.push_str(&format!(
+                "\n{}. {}",
+                position + 1,
+                escape_markdown(&answer.answer_text)
+            ));
+        }
+        Ok(ChatResponse {
+            text,
+            buttons: next
+                .answers
+                .iter()
+                .map(|answer| ChatButton {
+                    label: answer.answer_text.clone(),
+                    action: format!("{ANSWER_ACTION}{}", answer.id),
+                })
+                .collect(),
+            follow_up: FollowUp::AwaitingAnswer {
+                question_id: next.question.id,
+                answer_ids: next.answers.into_iter().map(|answer| answer.id).collect(),
+            },
+        })
+    }
+
+    fn answer(&self, session_id: &str, answer_id: &str) -> Result<ChatResponse> {
+        let outcome = match self.repo.get_quiz_answer_by_id(answer_id)? {
+            Some(answer) => self.scope()?.submit_answer(
+                &self.repo,
+                session_id,
+                &answer.question_id,
+                answer_id,
+                None,
+            )?,
+            None => None,
+        };
+        let Some(outcome) = outcome else {
+            return Ok(ChatResponse::text(
+                "That question is no longer part of the quiz. Send `/quiz` for a new one.",
+            ));
+        };
+
+        let verdict = if outcome.is_correct {
+            "✅ **Correct!**".to_string()
+        } else {
+            let correct: Vec<String> = outcome
+                .answers
+                .iter()
+                .filter(|answer| answer.is_correct)
+                .map(|answer| escape_markdown(&answer.answer_text))
+                .collect();
+            format!("❌ **Not quite.** Correct: {}", correct.join(", "))
+        };
+        Ok(
+            ChatResponse::text(format!("{verdict}\n\n{}", outcome.question.explanation))
+                .with_buttons(vec![ChatButton {
+                    label: "Next question".to_string(),
+                    action: NEXT_ACTION.to_string(),
+                }]),
+        )
+    }
+
+    fn stats(&self, session_id: &str) -> Result<ChatResponse> {
+        let stats = self.repo.get_session_statistics(session_id)?;
+        if stats.total_questions == 0 {
+            return Ok(ChatResponse::text(
+                "You have not answered any questions yet. Send `/quiz` to start.",
+            ));
+        }
+        let total = self.scope()?.total_questions(&self.repo)?;
+        let missed = self.repo.get_session_missed_questions(session_id)?.len();
+        Ok(ChatResponse::text(format!(
+            "**Your quiz so far**\n\n\
+             - Answered: {} of {total}\n\
+             - Correct: {} ({}%)\n\
+             - Current streak: {}\n\
+             - Missed: {missed}",
+            stats.questions_seen,
+            stats.correct_answers,
+            stats.accuracy_percentage,
+            stats.current_streak,
+        )))
+    }
+}
+
+fn rule_buttons(rules: &[Rule]) -> Vec<ChatButton> {
+    rules
+        .iter()
+        .map(|rule| ChatButton {
+            label: format!("Rule {}", rule.number),
+            action: format!("{RULE_ACTION}{}", rule.number),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::test_adapter::TestConversation;
+    use super::*;
+    use crate::models::{
+        NewGlossaryContent, NewGlossaryTerm, NewRule, NewRuleContent, QuizAnswerData,
+        QuizQuestionData,
+    };
+    use crate::test_support::repository_with_current_version;
+
+    /// Rule set `indoor` with section 17, rule 17.5, the term `pivot` and one question
+    pub(crate) fn seeded_repo() -> RuleRepository {
+        let (repo, rule_set, version) = repository_with_current_version();
+        let section = repo
+            .create_rule(NewRule::new(
+                "marking".to_string(),
+                rule_set.id.clone(),
+                version.id.clone(),
+                None,
+                "17".to_string(),
+            ))
+            .unwrap();
+        repo.create_rule_content(NewRuleContent::new(
+            section.id.clone(),
+            "en".to_string(),
+            "**Marking**".to_string(),
+            None,
+        ))
+        .unwrap();
+        let rule = repo
+            .create_rule(NewRule::new(
+                "stall-count".to_string(),
+                rule_set.id.clone(),
+                version.id.clone(),
+                Some(section.id),
+                "17.5".to_string(),
+            ))
+            .unwrap();
+        repo.create_rule_content(NewRuleContent::new(
+            rule.id,
+            "en".to_string(),
+            "The [marker](definition:marker) counts to ten & may not *straddle* the [pivot](definition:pivot)."
+                .to_string(),
+            None,
+        ))
+        .unwrap();
+
+        let term = repo
+            .create_glossary_term(NewGlossaryTerm::new(
+                rule_set.id.clone(),
+                version.id.clone(),
+                "pivot".to_string(),
+            ))
+            .unwrap();
+        repo.create_glossary_content(NewGlossaryContent::new(
+            term.id,
+            "en".to_string(),
+            "Pivot".to_string(),
+            "The foot the thrower keeps in place.".to_string(),
+        ))
+        .unwrap();
+
+        repo.create_quiz_question_complete(&QuizQuestionData {
+            rule_set_id: rule_set.id,
+            version_id: version.id,
+            question_text: "Who calls fouls?".to_string(),
+            explanation: "Players referee themselves.".to_string(),
+            difficulty_level: "beginner".to_string(),
+            answers: vec![
+                QuizAnswerData {
+                    answer_text: "The players".to_string(),
+                    is_correct: true,
+                },
+                QuizAnswerData {
+                    answer_text: "A referee".to_string(),
+                    is_correct: false,
+                },
+            ],
+            rule_ids: vec![],
+        })
+        .unwrap();
+
+        repo
+    }
+
+    fn conversation(repo: &RuleRepository) -> TestConversation {
+        TestConversation::new(
+            ChatEngine::new(repo.clone(), "indoor".to_string(), "en".to_string()),
+            "alice",
+        )
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            parse_command("/rule 17.5"),
+            Some(("rule".to_string(), "17.5"))
+        );
+        assert_eq!(
+            parse_command("/Define@RegelatorBot  pivot foot "),
+            Some(("define".to_string(), "pivot foot"))
+        );
+        assert_eq!(parse_command("!quiz"), Some(("quiz".to_string(), "")));
+        assert_eq!(parse_command("hello"), None);
+    }
+
+    #[test]
+    fn test_conversation_session_id_is_stable_per_conversation() {
+        let id = conversation_session_id("telegram", "42");
+        assert_eq!(id, conversation_session_id("telegram", "42"));
+        assert_ne!(id, conversation_session_id("telegram", "-42"));
+        assert_ne!(id, conversation_session_id("matrix", "42"));
+        assert!(uuid::Uuid::parse_str(&id).is_ok());
+    }
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("a *b* [c]"), "a \\*b\\* \\[c\\]");
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(
+            markdown_snippet(
+                "The [thrower](definition:thrower) may\n**pivot**.\n\nMore.",
+                100
+            ),
+            "The thrower may pivot. More."
+        );
+        assert_eq!(markdown_snippet("One two three", 7), "One two…");
+    }
+
+    #[test]
+    fn test_lookups() {
+        let repo = seeded_repo();
+        let mut chat = conversation(&repo);
+
+        assert_eq!(
+            chat.send("/rule 17.5").text,
+            "**Rule 17.5**\n\nThe [marker](definition:marker) counts to ten & may not *straddle* the [pivot](definition:pivot)."
+        );
+
+        // Sections list their subrules, which can be opened by button
+        let section = chat.send("/rule marking");
+        assert_eq!(
+            section.text,
+            "**Rule 17**\n\n**Marking**\n\n- **17.5** The marker counts to ten & may not straddle the pivot."
+        );
+        assert!(chat.press("Rule 17.5").text.starts_with("**Rule 17.5**"));
+
+        // Unknown rules fall back to a search
+        let results = chat.send("/rule straddle pivot");
+        assert!(results.text.starts_with("1 rule mention “straddle pivot”:"));
+        assert_eq!(results.buttons[0].action, "rule:17.5");
+        assert_eq!(chat.send("/search huck").text, "No rule mentions “huck”.");
+
+        assert_eq!(
+            chat.send("/define Pivot").text,
+            "**Pivot**\n\nThe foot the thrower keeps in place."
+        );
+        assert_eq!(
+            chat.send("/define *huck*").text,
+            "No definition for “\\*huck\\*”."
+        );
+
+        assert!(
+            chat.send("/frobnicate")
+                .text
+                .starts_with("Unknown command `frobnicate`.")
+        );
+        assert!(
+            chat.send("hello")
+                .text
+                .starts_with("I did not understand that.")
+        );
+    }
+
+    #[test]
+    fn test_quiz_conversation() {
+        let repo = seeded_repo();
+        let mut chat = conversation(&repo);
+
+        assert_eq!(
+            chat.send("/stats").text,
+            "You have not answered any questions yet. Send `/quiz` to start."
+        );
+
+        let question = chat.send("/quiz");
+        assert_eq!(
+            question.text,
+            "Who calls fouls?\n\n1. The players\n2. A referee"
+        );
+        assert!(matches!(
+            question.follow_up,
+            FollowUp::AwaitingAnswer { .. }
+        ));
+
+        // Out-of-range numbers keep the question open
+        assert_eq!(chat.send("3").text, "Reply with a number from 1 to 2.");
+        assert_eq!(
+            chat.send("2").text,
+            "❌ **Not quite.** Correct: The players\n\nPlayers referee themselves."
+        );
+        assert_eq!(chat.follow_up(), &FollowUp::None);
+
+        let attempts = repo.get_session_attempts(chat.session_id()).unwrap();
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].is_correct, Some(false));
+
+        // The missed question is scheduled for tomorrow
+        let review = repo
+            .get_quiz_review(chat.session_id(), &attempts[0].question_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!((review.repetitions, review.interval_days), (0, 1));
+        assert_eq!(
+            chat.press("Next question").text,
+            format!(
+                "You have answered every question: 0 of 1 correct (0%). Your next review is due {} UTC.",
+                review.due_at.format("%Y-%m-%d %H:%M")
+            )
+        );
+        // A number outside a question is not an answer
+        assert!(
+            chat.send("1")
+                .text
+                .starts_with("I did not understand that.")
+        );
+        assert_eq!(
+            chat.send("/stats").text,
+            "**Your quiz so far**\n\n- Answered: 1 of 1\n- Correct: 0 (0%)\n- Current streak: 0\n- Missed: 1"
+        );
+
+        // Another participant has their own session
+        let mut other = TestConversation::new(
+            ChatEngine::new(repo.clone(), "indoor".to_string(), "en".to_string()),
+            "bob",
+        );
+        other.send("/next");
+        assert_eq!(
+            other.press("The players").text,
+            "✅ **Correct!**\n\nPlayers referee themselves."
+        );
+        assert_eq!(
+            repo.get_session_attempts(chat.session_id()).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_due_reviews_come_back() {
+        let repo = seeded_repo();
+        let mut chat = conversation(&repo);
+        chat.send("/quiz");
+        chat.press("The players");
+
+        let scope = QuizScope::current(&repo, "indoor").unwrap().unwrap();
+        assert_eq!(scope.due_today(&repo, chat.session_id()).unwrap(), 0);
+
+        // Pretend a day has passed
+        let question_id = repo.get_session_attempts(chat.session_id()).unwrap()[0]
+            .question_id
+            .clone();
+        let mut review = repo
+            .get_quiz_review(chat.session_id(), &question_id)
+            .unwrap()
+            .unwrap();
+        review.due_at -= chrono::Duration::days(1);
+        repo.save_quiz_review(&review).unwrap();
+        assert_eq!(scope.due_today(&repo, chat.session_id()).unwrap(), 1);
+
+        let again = chat.send("/quiz");
+        assert!(again.text.starts_with("Who calls fouls?"));
+        chat.press("The players");
+
+        let review = repo
+            .get_quiz_review(chat.session_id(), &question_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!((review.repetitions, review.interval_days), (2, 6));
+        assert_eq!(scope.due_today(&repo, chat.session_id()).unwrap(), 0);
+        assert_eq!(
+            chat.send("/stats").text,
+            "**Your quiz so far**\n\n- Answered: 1 of 1\n- Correct: 2 (100%)\n- Current streak: 2\n- Missed: 0"
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! In-process transport for testing whole conversations.
+//!
+//! Keeps the follow-up state between messages like a real transport would and
+//! panics on errors, so tests read as a transcript.
+
+use super::{ChatEngine, ChatInput, ChatResponse, FollowUp, conversation_session_id};
+
+/// One participant talking to the engine
+pub struct TestConversation {
+    engine: ChatEngine,
+    session_id: String,
+    follow_up: FollowUp,
+    transcript: Vec<(ChatInput, ChatResponse)>,
+}
+
+impl TestConversation {
+    pub fn new(engine: ChatEngine, participant: &str) -> Self {
+        Self {
+            engine,
+            session_id: conversation_session_id("test", participant),
+            follow_up: FollowUp::None,
+            transcript: Vec::new(),
+        }
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// What the last response expects next
+    pub fn follow_up(&self) -> &FollowUp {
+        &self.follow_up
+    }
+
+    /// Every input with the response it got, oldest first
+    pub fn transcript(&self) -> &[(ChatInput, ChatResponse)] {
+        &self.transcript
+    }
+
+    /// Type a message
+    pub fn send(&mut self, text: &str) -> ChatResponse {
+        self.input(ChatInput::Text(text.to_string()))
+    }
+
+    /// Press the button with this label on the last response
+    pub fn press(&mut self, label: &str) -> ChatResponse {
+        let action = self
+            .transcript
+            .last()
+            .and_then(|(_, response)| response.buttons.iter().find(|button| button.label == label))
+            .unwrap_or_else(|| panic!("the last response has no button labelled {label:?}"))
+            .action
+            .clone();
+        self.input(ChatInput::Button(action))
+    }
+
+    fn input(&mut self, input: ChatInput) -> ChatResponse {
+        let response = self
+            .engine
+            .handle(&self.session_id, &input, &self.follow_up)
+            .unwrap_or_else(|error| panic!("{input:?} failed: {error:?}"));
+        self.follow_up = response.follow_up.clone();
+        self.transcript.push((input, response.clone()));
+        response
+    }
+}
@@ -0,0 +1,213 @@
+use color_eyre::{Result, eyre::WrapErr};
+use std::io::{self, BufRead};
+
+use super::AdminCommand;
+use regelator::auth::{MIN_PASSWORD_LENGTH, hash_password};
+use regelator::config::Config;
+use regelator::models::{Admin, AuditAction, NewAdmin, NewAuditLogEntry, ThrottleScope};
+use regelator::repository::RuleRepository;
+
+/// Read a new password from stdin or an interactive prompt
+fn read_new_password(from_stdin: bool) -> Result<String> {
+    let password = if from_stdin {
+        let mut line = String::new();
+        io::stdin()
+            .lock()
+            .read_line(&mut line)
+            .wrap_err("Failed to read password from stdin")?;
+        line.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        let password = rpassword::prompt_password("New password: ")?;
+        let confirmation = rpassword::prompt_password("Confirm password: ")?;
+        if password != confirmation {
+            return Err(color_eyre::eyre::eyre!("Passwords do not match"));
+        }
+        password
+    };
+
+    if password.len() < MIN_PASSWORD_LENGTH {
+        return Err(color_eyre::eyre::eyre!(
+            "Password must be at least {} characters",
+            MIN_PASSWORD_LENGTH
+        ));
+    }
+
+    Ok(password)
+}
+
+fn find_admin(repo: &RuleRepository, username: &str) -> Result<Admin> {
+    repo.get_admin_by_username(username)?
+        .ok_or_else(|| color_eyre::eyre::eyre!("Admin '{}' not found", username))
+}
+
+/// Record an action taken through the CLI in the audit log
+fn record_audit(
+    repo: &RuleRepository,
+    action: AuditAction,
+    admin_id: &str,
+    before: Option<&Admin>,
+    after: Option<&Admin>,
+) -> Result<()> {
+    let snapshot = |admin: &Admin| serde_json::to_value(admin).ok();
+    repo.record_audit_event(NewAuditLogEntry::new(
+        None,
+        "cli".to_string(),
+        action,
+        Some(admin_id.to_string()),
+        before.and_then(snapshot),
+        after.and_then(snapshot),
+    ))
+}
+
+pub fn run(command: AdminCommand, config: &Config) -> Result<()> {
+    let repo = RuleRepository::new(regelator::db::create_pool(&config.database.url)?);
+
+    match command {
+        AdminCommand::Create {
+            username,
+            role,
+            password_stdin,
+        } => {
+            if repo.get_admin_by_username(&username)?.is_some() {
+                return Err(color_eyre::eyre::eyre!(
+                    "Admin '{}' already exists",
+                    username
+                ));
+            }
+
+            let password_hash = hash_password(&read_new_password(password_stdin)?)?;
+            let admin = repo.create_admin(NewAdmin::new(username, password_hash, role))?;
+            record_audit(
+                &repo,
+                AuditAction::AdminCreated,
+                &admin.id,
+                None,
+                Some(&admin),
+            )?;
+            println!("Created {} '{}'", admin.role.as_str(), admin.username);
+        }
+        AdminCommand::List => {
+            println!(
+                "{:<24} {:<12} {:<8} LAST LOGIN",
+                "USERNAME", "ROLE", "ACTIVE"
+            );
+            for admin in repo.get_admins()? {
+                let last_login = admin
+                    .last_login
+                    .map(|login| login.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "never".to_string());
+                println!(
+                    "{:<24} {:<12} {:<8} {}",
+                    admin.username,
+                    admin.role.as_str(),
+                    if admin.is_active { "yes" } else { "no" },
+                    last_login
+                );
+            }
+        }
+        AdminCommand::SetRole { username, role } => {
+            let admin = find_admin(&repo, &username)?;
+            repo.set_admin_role(&admin.id, role)?;
+            let updated = repo.get_admin_by_id(&admin.id)?;
+            record_audit(
+                &repo,
+                AuditAction::AdminRoleChanged,
+                &admin.id,
+                Some(&admin),
+                updated.as_ref(),
+            )?;
+            println!("'{}' is now {}", admin.username, role.as_str());
+        }
+        AdminCommand::Deactivate { username } => {
+            let admin = find_admin(&repo, &username)?;
+            repo.set_admin_active(&admin.id, false)?;
+            repo.revoke_admin_sessions(&admin.id, None)?;
+            let updated = repo.get_admin_by_id(&admin.id)?;
+            record_audit(
+                &repo,
+                AuditAction::AdminDeactivated,
+                &admin.id,
+                Some(&admin),
+                updated.as_ref(),
+            )?;
+            println!("Deactivated admin '{}'", admin.username);
+        }
+        AdminCommand::Activate { username } => {
+            let admin = find_admin(&repo, &username)?;
+            repo.set_admin_active(&admin.id, true)?;
+            let updated = repo.get_admin_by_id(&admin.id)?;
+            record_audit(
+                &repo,
+                AuditAction::AdminActivated,
+                &admin.id,
+                Some(&admin),
+                updated.as_ref(),
+            )?;
+            println!("Activated admin '{}'", admin.username);
+        }
+        AdminCommand::ResetPassword {
+            username,
+            password_stdin,
+        } => {
+            let admin = find_admin(&repo, &username)?;
+            let password_hash = hash_password(&read_new_password(password_stdin)?)?;
+            repo.reset_admin_password(&admin.id, &password_hash)?;
+            repo.revoke_admin_sessions(&admin.id, None)?;
+            record_audit(
+                &repo,
+                AuditAction::AdminPasswordReset,
+                &admin.id,
+                None,
+                None,
+            )?;
+            println!("Password reset for '{}'", admin.username);
+        }
+        AdminCommand::Unlock { username } => {
+            let throttle = repo.get_login_throttle(ThrottleScope::Username, &username)?;
+            repo.clear_login_throttle(ThrottleScope::Username, &username)?;
+            match throttle {
+                Some(throttle) => {
+                    repo.record_audit_event(NewAuditLogEntry::new(
+                        None,
+                        "cli".to_string(),
+                        AuditAction::LoginUnlocked,
+                        Some(format!("{}:{}", ThrottleScope::Username.as_str(), username)),
+                        serde_json::to_value(&throttle).ok(),
+                        None,
+                    ))?;
+                    println!(
+                        "Cleared {} failed login(s) for '{}'",
+                        throttle.failed_attempts, username
+                    );
+                }
+                None => println!("No failed logins recorded for '{}'", username),
+            }
+        }
+        AdminCommand::ResetTwoFactor { username } => {
+            let admin = find_admin(&repo, &username)?;
+            repo.disable_admin_two_factor(&admin.id)?;
+            repo.revoke_admin_sessions(&admin.id, None)?;
+            record_audit(&repo, AuditAction::TwoFactorDisabled, &admin.id, None, None)?;
+            println!("Two-factor authentication reset for '{}'", admin.username);
+        }
+        AdminCommand::SetIdentity {
+            username,
+            email,
+            oidc_subject,
+        } => {
+            let admin = find_admin(&repo, &username)?;
+            repo.set_admin_identity(&admin.id, email.as_deref(), oidc_subject.as_deref())?;
+            let updated = repo.get_admin_by_id(&admin.id)?;
+            record_audit(
+                &repo,
+                AuditAction::AdminIdentityChanged,
+                &admin.id,
+                Some(&admin),
+                updated.as_ref(),
+            )?;
+            println!("Single sign-on updated for '{}'", admin.username);
+        }
+    }
+
+    Ok(())
+}
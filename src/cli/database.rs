@@ -0,0 +1,71 @@
+use color_eyre::{Result, eyre::WrapErr};
+use diesel::{RunQueryDsl, sql_query};
+use std::path::Path;
+use tracing::info;
+
+use regelator::config::Config;
+use regelator::db;
+use regelator::repository::RuleRepository;
+
+/// Apply all pending migrations to the configured database
+pub fn migrate(config: &Config) -> Result<()> {
+    let pool = db::create_pool(&config.database.url)?;
+
+    let applied = db::run_pending_migrations(&pool)?;
+    if applied.is_empty() {
+        info!("Database is up to date");
+    }
+    for version in applied {
+        info!("Applied migration {}", version);
+    }
+
+    Ok(())
+}
+
+/// Check that the server would be able to start and serve content
+pub fn check(config: &Config) -> Result<()> {
+    // Configuration was already loaded and validated by the caller
+    println!("ok: configuration loaded");
+
+    let pool = db::create_pool(&config.database.url)?;
+    let mut conn = pool.get().wrap_err("Failed to get database connection")?;
+    sql_query("SELECT 1=1")
+        .execute(&mut conn)
+        .wrap_err("Database is not reachable")?;
+    drop(conn);
+    println!("ok: database {} reachable", config.database.url);
+
+    let pending = db::pending_migrations(&pool)?;
+    if !pending.is_empty() {
+        return Err(color_eyre::eyre::eyre!(
+            "{} pending migrations, run `regelator migrate`: {}",
+            pending.len(),
+            pending.join(", ")
+        ));
+    }
+    println!("ok: no pending migrations");
+
+    let repo = RuleRepository::new(pool);
+    let rule_sets = repo.get_rule_sets()?;
+    if rule_sets.is_empty() {
+        println!("warning: no rule sets imported yet");
+    }
+    for rule_set in rule_sets {
+        match repo.get_current_version(&rule_set.slug)? {
+            Some(version) => println!(
+                "ok: rule set {} (current version {})",
+                rule_set.slug, version.version_name
+            ),
+            None => println!("warning: rule set {} has no current version", rule_set.slug),
+        }
+    }
+
+    if !Path::new("src/templates").is_dir() {
+        return Err(color_eyre::eyre::eyre!(
+            "Template directory src/templates not found; run from the project root"
+        ));
+    }
+    println!("ok: templates found");
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use color_eyre::{Result, eyre::WrapErr};
+use std::io::{self, Write};
+
+use super::{ExportArgs, ExportFormat};
+use regelator::analytics;
+use regelator::config::Config;
+use regelator::repository::RuleRepository;
+
+/// Write the same statistics export the admin dashboard offers for download
+pub fn run(args: ExportArgs, config: &Config) -> Result<()> {
+    let repo = RuleRepository::new(regelator::db::create_pool(&config.database.url)?);
+
+    let questions =
+        repo.get_questions_with_selection_data_for_export(args.start_date, args.end_date)?;
+
+    let data = match args.format {
+        ExportFormat::Csv => analytics::write_csv(questions)?,
+        ExportFormat::Parquet => {
+            analytics::write_parquet(analytics::questions_to_record_batch(questions)?)?
+        }
+    };
+
+    match args.file {
+        Some(path) => std::fs::write(&path, data)
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))?,
+        None => io::stdout()
+            .lock()
+            .write_all(&data)
+            .wrap_err("Failed to write export to stdout")?,
+    }
+
+    Ok(())
+}
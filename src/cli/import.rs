@@ -0,0 +1,67 @@
+use color_eyre::{Result, eyre::WrapErr};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use tracing::info;
+
+use super::{ImportCommand, ImportSource};
+use regelator::config::Config;
+use regelator::import::{self, ImportTarget, RuleImportOptions};
+use regelator::repository::RuleRepository;
+
+/// Open the import file, falling back to stdin
+fn open_input(file: Option<&Path>) -> Result<Box<dyn BufRead>> {
+    match file {
+        Some(path) => {
+            let file =
+                File::open(path).wrap_err_with(|| format!("Failed to open {}", path.display()))?;
+            Ok(Box::new(BufReader::new(file)))
+        }
+        None => Ok(Box::new(io::stdin().lock())),
+    }
+}
+
+fn target(source: &ImportSource, language: &str) -> ImportTarget {
+    ImportTarget {
+        rule_set_slug: source.rule_set.clone(),
+        version_name: source.version.clone(),
+        language: language.to_string(),
+    }
+}
+
+pub fn run(command: ImportCommand, config: &Config) -> Result<()> {
+    let repo = RuleRepository::new(regelator::db::create_pool(&config.database.url)?);
+
+    match command {
+        ImportCommand::Rules {
+            source,
+            language,
+            rule_set_name,
+            effective_date,
+        } => {
+            let rules = import::parse_rules(open_input(source.file.as_deref())?)?;
+            info!("Parsed {} rules", rules.len());
+
+            let options = RuleImportOptions {
+                rule_set_name,
+                effective_from: effective_date,
+            };
+            import::import_rules(&repo, &target(&source, &language), &options, rules)?;
+        }
+        ImportCommand::Definitions { source, language } => {
+            let definitions = import::parse_definitions(open_input(source.file.as_deref())?)?;
+            info!("Parsed {} definitions", definitions.len());
+
+            import::import_definitions(&repo, &target(&source, &language), definitions)?;
+        }
+        ImportCommand::Questions { source } => {
+            let questions = import::read_quiz_questions(open_input(source.file.as_deref())?)?;
+            info!("Parsed {} quiz questions", questions.len());
+
+            // Questions are not stored per language
+            import::import_quiz_questions(&repo, &target(&source, "en"), questions)?;
+        }
+    }
+
+    Ok(())
+}
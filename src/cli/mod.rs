@@ -0,0 +1,205 @@
+//! Command line interface for the `regelator` binary
+mod admin;
+mod database;
+mod export;
+mod import;
+
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use color_eyre::Result;
+use std::path::PathBuf;
+
+use regelator::config::Config;
+use regelator::models::AdminRole;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "regelator",
+    version,
+    about = "Ultimate rules reference and quiz server"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the web server (the default when no subcommand is given)
+    Serve,
+    /// Import rules, definitions or quiz questions from the text formats in import_docs/
+    #[command(subcommand)]
+    Import(ImportCommand),
+    /// Export quiz statistics
+    Export(ExportArgs),
+    /// Manage admin accounts
+    #[command(subcommand)]
+    Admin(AdminCommand),
+    /// Apply pending database migrations
+    Migrate,
+    /// Verify configuration, database and templates without starting the server
+    Check,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportCommand {
+    /// Import rules, creating the rule set and version if they don't exist
+    Rules {
+        #[command(flatten)]
+        source: ImportSource,
+        /// Language of the rule content
+        #[arg(long, default_value = "en")]
+        language: String,
+        /// Display name used when creating a new rule set
+        #[arg(long)]
+        rule_set_name: Option<String>,
+        /// Effective date (YYYY-MM-DD) used when creating a new version
+        #[arg(long)]
+        effective_date: Option<NaiveDate>,
+    },
+    /// Import glossary definitions into an existing version
+    Definitions {
+        #[command(flatten)]
+        source: ImportSource,
+        /// Language of the definitions
+        #[arg(long, default_value = "en")]
+        language: String,
+    },
+    /// Import quiz questions into an existing version
+    Questions {
+        #[command(flatten)]
+        source: ImportSource,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AdminCommand {
+    /// Create an admin account, prompting for the password
+    Create {
+        username: String,
+        /// One of superadmin, editor, translator, analyst
+        #[arg(long, default_value = "editor")]
+        role: AdminRole,
+        /// Read the password from the first line of stdin instead of prompting
+        #[arg(long)]
+        password_stdin: bool,
+    },
+    /// List admin accounts with their status and last login
+    List,
+    /// Change what an admin is allowed to do
+    SetRole { username: String, role: AdminRole },
+    /// Prevent an admin from logging in
+    Deactivate { username: String },
+    /// Allow a deactivated admin to log in again
+    Activate { username: String },
+    /// Set a new password for an admin
+    ResetPassword {
+        username: String,
+        /// Read the password from the first line of stdin instead of prompting
+        #[arg(long)]
+        password_stdin: bool,
+    },
+    /// Lift a login lockout caused by repeated failed attempts
+    Unlock { username: String },
+    /// Remove an admin's two-factor authentication, e.g. after a lost device
+    ResetTwoFactor { username: String },
+    /// Link an admin to an identity provider account for single sign-on
+    SetIdentity {
+        username: String,
+        /// Verified email claim to accept; omit to clear
+        #[arg(long)]
+        email: Option<String>,
+        /// Subject (`sub`) claim to accept; omit to clear
+        #[arg(long)]
+        oidc_subject: Option<String>,
+    },
+}
+
+/// Where imported data comes from and which rule set version it belongs to
+#[derive(Debug, Args)]
+pub struct ImportSource {
+    /// Rule set slug, e.g. wfdf-2025
+    #[arg(long)]
+    pub rule_set: String,
+    /// Version name, e.g. "2025 Edition"
+    #[arg(long)]
+    pub version: String,
+    /// Input file (reads stdin if omitted)
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value = "csv")]
+    pub format: ExportFormat,
+    /// Only include attempts on or after this date (YYYY-MM-DD)
+    #[arg(long)]
+    pub start_date: Option<NaiveDate>,
+    /// Only include attempts on or before this date (YYYY-MM-DD)
+    #[arg(long)]
+    pub end_date: Option<NaiveDate>,
+    /// Output file (writes stdout if omitted)
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+}
+
+/// Run a non-server subcommand
+pub fn run(command: Command, config: &Config) -> Result<()> {
+    match command {
+        Command::Serve => unreachable!("the server is started from main"),
+        Command::Import(import) => import::run(import, config),
+        Command::Export(args) => export::run(args, config),
+        Command::Admin(admin) => admin::run(admin, config),
+        Command::Migrate => database::migrate(config),
+        Command::Check => database::check(config),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_import_rules() {
+        let cli = Cli::try_parse_from([
+            "regelator",
+            "import",
+            "rules",
+            "--rule-set",
+            "wfdf-2025",
+            "--version",
+            "2025 Edition",
+            "--language",
+            "de",
+            "--file",
+            "rules_de.txt",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Some(Command::Import(ImportCommand::Rules {
+                source, language, ..
+            })) => {
+                assert_eq!(source.rule_set, "wfdf-2025");
+                assert_eq!(source.version, "2025 Edition");
+                assert_eq!(source.file, Some(PathBuf::from("rules_de.txt")));
+                assert_eq!(language, "de");
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+}
@@ -9,6 +9,16 @@ pub struct Config {
     pub database: DatabaseConfig,
     pub security: SecurityConfig,
     pub logging: LoggingConfig,
+    #[serde(default)]
+    pub learners: LearnerConfig,
+    #[serde(default)]
+    pub exam: ExamConfig,
+    /// SMTP server for learner sign-in links; without it the links are only logged
+    #[serde(default)]
+    pub email: Option<EmailConfig>,
+    /// Only read by the `regelator-telegram` binary
+    #[serde(default)]
+    pub telegram: Option<TelegramConfig>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -16,6 +26,9 @@ pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub shutdown_timeout_seconds: u64,
+    /// Take the client IP from the last `X-Forwarded-For` entry (only behind a trusted reverse proxy)
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -27,6 +40,227 @@ pub struct DatabaseConfig {
 pub struct SecurityConfig {
     pub session_duration_hours: u32,
     pub jwt_secret: String,
+    #[serde(default)]
+    pub login_throttle: LoginThrottleConfig,
+    #[serde(default)]
+    pub quiz_cookie: QuizCookieConfig,
+    /// Optional OpenID Connect login; password login stays available
+    #[serde(default)]
+    pub oidc: Option<OidcConfig>,
+    /// Secret signing certificate ids; `jwt_secret` is used if unset. Set it so that
+    /// changing `jwt_secret` leaves issued certificates verifiable.
+    #[serde(default)]
+    pub certificate_key: Option<String>,
+}
+
+/// Claim used to find the admin account for an OpenID Connect login
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OidcMatchClaim {
+    /// Verified `email` claim against `admins.email`
+    #[default]
+    Email,
+    /// `sub` claim against `admins.oidc_subject`
+    Subject,
+}
+
+/// OpenID Connect identity provider for admin logins
+#[derive(Debug, Deserialize, Clone)]
+pub struct OidcConfig {
+    /// Issuer URL; discovery is read from `/.well-known/openid-configuration` below it
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Secret for confidential clients; public clients rely on PKCE alone
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    /// Must point at `/admin/login/oidc/callback` and be registered with the provider
+    pub redirect_url: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: String,
+    #[serde(default)]
+    pub match_claim: OidcMatchClaim,
+    /// Shown on the login button
+    #[serde(default = "default_oidc_provider_name")]
+    pub provider_name: String,
+}
+
+fn default_oidc_scopes() -> String {
+    "openid email profile".to_string()
+}
+
+fn default_oidc_provider_name() -> String {
+    "Single Sign-On".to_string()
+}
+
+/// Backoff and lockout policy for failed admin logins
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LoginThrottleConfig {
+    /// Failed attempts for one username before it is locked out
+    pub max_failed_attempts: u32,
+    /// Failed attempts from one IP address before it is locked out
+    pub max_failed_attempts_per_ip: u32,
+    /// Delay after the first failure, doubled for each further failure
+    pub base_delay_seconds: u64,
+    /// Upper bound for the backoff delay
+    pub max_delay_seconds: u64,
+    pub lockout_minutes: u64,
+    /// Failures older than this are forgotten
+    pub reset_after_minutes: u64,
+}
+
+impl Default for LoginThrottleConfig {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: 5,
+            max_failed_attempts_per_ip: 20,
+            base_delay_seconds: 1,
+            max_delay_seconds: 60,
+            lockout_minutes: 15,
+            reset_after_minutes: 60,
+        }
+    }
+}
+
+/// Cookie naming a visitor's quiz session, encrypted with a key derived from `jwt_secret`
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct QuizCookieConfig {
+    /// Only send this and the CSRF cookie over HTTPS (browsers also accept it on `localhost`)
+    pub secure: bool,
+    /// How long an unused quiz session is remembered
+    pub max_age_hours: u32,
+}
+
+impl Default for QuizCookieConfig {
+    fn default() -> Self {
+        Self {
+            secure: true,
+            max_age_hours: 24,
+        }
+    }
+}
+
+/// Opt-in learner accounts that keep quiz progress across devices
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LearnerConfig {
+    /// Public base URL of the site, used in emailed sign-in links
+    pub site_url: String,
+    /// How long an emailed sign-in link stays valid
+    pub login_link_minutes: u32,
+    /// How long a browser stays signed in
+    pub session_days: u32,
+}
+
+/// Timed exams for observer and captain certification
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ExamConfig {
+    /// Questions drawn per exam, spread over difficulty levels and rule sections
+    pub question_count: u32,
+    pub time_limit_minutes: u32,
+    /// Share of correct answers needed to pass
+    pub pass_percent: u32,
+}
+
+impl Default for ExamConfig {
+    fn default() -> Self {
+        Self {
+            question_count: 20,
+            time_limit_minutes: 30,
+            pass_percent: 80,
+        }
+    }
+}
+
+impl Default for LearnerConfig {
+    fn default() -> Self {
+        Self {
+            site_url: "http://localhost:8000".to_string(),
+            login_link_minutes: 15,
+            session_days: 180,
+        }
+    }
+}
+
+/// How the connection to the SMTP server is secured
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Plain connection, e.g. to a relay on localhost
+    None,
+    /// Upgrade with `STARTTLS`, usually on port 587
+    #[default]
+    StartTls,
+    /// TLS from the start, usually on port 465
+    Tls,
+}
+
+/// Outgoing email over SMTP
+#[derive(Debug, Deserialize, Clone)]
+pub struct EmailConfig {
+    /// Sender, e.g. `Regelator <noreply@example.org>`
+    pub from: String,
+    pub smtp_host: String,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    #[serde(default)]
+    pub smtp_security: SmtpSecurity,
+    #[serde(default)]
+    pub smtp_username: Option<String>,
+    #[serde(default)]
+    pub smtp_password: Option<String>,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+/// Telegram bot answering rule lookups and running quizzes
+#[derive(Debug, Deserialize, Clone)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    /// Bot API server; point it at a local mock for testing
+    #[serde(default = "default_telegram_api_base_url")]
+    pub api_base_url: String,
+    /// Slug of the rule set the bot answers from
+    pub rule_set: String,
+    #[serde(default = "default_telegram_language")]
+    pub language: String,
+    /// Public base URL of the web app, used for rule and definition links
+    #[serde(default)]
+    pub site_url: Option<String>,
+    /// How long a `getUpdates` long poll waits for new updates
+    #[serde(default = "default_telegram_poll_timeout")]
+    pub poll_timeout_seconds: u64,
+    /// Receive updates by webhook instead of long polling
+    #[serde(default)]
+    pub webhook: Option<TelegramWebhookConfig>,
+}
+
+/// Webhook registered with the Bot API on startup
+#[derive(Debug, Deserialize, Clone)]
+pub struct TelegramWebhookConfig {
+    /// Public HTTPS URL Telegram posts updates to
+    pub url: String,
+    /// Local address the webhook listener binds to
+    pub listen: String,
+    /// Compared with the `X-Telegram-Bot-Api-Secret-Token` header of every update
+    #[serde(default)]
+    pub secret_token: Option<String>,
+}
+
+fn default_telegram_api_base_url() -> String {
+    "https://api.telegram.org".to_string()
+}
+
+fn default_telegram_language() -> String {
+    "en".to_string()
+}
+
+fn default_telegram_poll_timeout() -> u64 {
+    30
 }
 
 fn deserialize_level<'de, D>(deserializer: D) -> Result<Level, D::Error>
@@ -68,36 +302,6 @@ pub struct LoggingConfig {
     pub enable_colors: bool,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct ImportConfig {
-    pub rule_set_name: String,
-    pub rule_set_slug: String,
-    pub version_name: String,
-    pub version_effective_date: String,
-}
-
-impl ImportConfig {
-    /// Load configuration from TOML files and environment variables
-    pub fn load() -> Result<Self, ConfigError> {
-        // Load .env file if it exists (ignore errors if it doesn't exist)
-        let _ = dotenvy::dotenv();
-
-        let environment = env::var("REGELATOR_ENV").unwrap_or_else(|_| "local".to_string());
-
-        let config = ConfigBuilder::builder()
-            // Load shared configuration
-            .add_source(File::with_name("config/shared").required(false))
-            // Load environment-specific configuration
-            .add_source(File::with_name(&format!("config/{environment}")).required(false))
-            // Override with environment variables prefixed with REGELATOR__
-            .add_source(Environment::with_prefix("REGELATOR").separator("__"))
-            .build()?;
-
-        let loaded_config: ImportConfig = config.try_deserialize()?;
-
-        Ok(loaded_config)
-    }
-}
 impl Config {
     /// Load configuration from TOML files and environment variables
     pub fn load() -> Result<Self, ConfigError> {
@@ -137,6 +341,17 @@ impl Config {
             ));
         }
 
+        if self
+            .security
+            .certificate_key
+            .as_ref()
+            .is_some_and(|key| key.len() < 32)
+        {
+            return Err(ConfigError::Message(
+                "Certificate key must be at least 32 characters long".to_string(),
+            ));
+        }
+
         Ok(())
     }
 
@@ -167,6 +382,7 @@ mod tests {
                 host: "0.0.0.0".to_string(),
                 port: 3000,
                 shutdown_timeout_seconds: 30,
+                trust_forwarded_for: false,
             },
             database: DatabaseConfig {
                 url: "test.db".to_string(),
@@ -174,12 +390,20 @@ mod tests {
             security: SecurityConfig {
                 session_duration_hours: 2,
                 jwt_secret: "test-secret-that-is-long-enough-for-validation".to_string(),
+                login_throttle: LoginThrottleConfig::default(),
+                quiz_cookie: QuizCookieConfig::default(),
+                oidc: None,
+                certificate_key: None,
             },
             logging: LoggingConfig {
                 level: Level::INFO,
                 format: "tree".to_string(),
                 enable_colors: true,
             },
+            learners: LearnerConfig::default(),
+            exam: ExamConfig::default(),
+            email: None,
+            telegram: None,
         };
 
         assert_eq!(config.bind_address(), "0.0.0.0:3000");
@@ -192,6 +416,7 @@ mod tests {
                 host: "127.0.0.1".to_string(),
                 port: 8000,
                 shutdown_timeout_seconds: 30,
+                trust_forwarded_for: false,
             },
             database: DatabaseConfig {
                 url: "test.db".to_string(),
@@ -199,12 +424,20 @@ mod tests {
             security: SecurityConfig {
                 session_duration_hours: 4,
                 jwt_secret: "test-secret-that-is-long-enough-for-validation".to_string(),
+                login_throttle: LoginThrottleConfig::default(),
+                quiz_cookie: QuizCookieConfig::default(),
+                oidc: None,
+                certificate_key: None,
             },
             logging: LoggingConfig {
                 level: Level::INFO,
                 format: "tree".to_string(),
                 enable_colors: true,
             },
+            learners: LearnerConfig::default(),
+            exam: ExamConfig::default(),
+            email: None,
+            telegram: None,
         };
 
         assert_eq!(config.session_duration(), chrono::Duration::hours(4));
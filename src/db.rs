@@ -0,0 +1,43 @@
+use color_eyre::{Result, eyre::WrapErr};
+use diesel::migration::MigrationVersion;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
+
+pub type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+/// Migrations compiled into the binary so deployments don't need the diesel CLI
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+/// Create a connection pool for the configured SQLite database
+pub fn create_pool(database_url: &str) -> Result<DbPool> {
+    let manager = ConnectionManager::<SqliteConnection>::new(database_url);
+    Pool::builder()
+        .build(manager)
+        .wrap_err("Failed to create connection pool")
+}
+
+/// Apply all pending migrations and return the versions that were run
+pub fn run_pending_migrations(pool: &DbPool) -> Result<Vec<String>> {
+    let mut conn = pool.get().wrap_err("Failed to get database connection")?;
+
+    let applied = conn
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|e| color_eyre::eyre::eyre!("Failed to run migrations: {e}"))?;
+
+    Ok(applied.iter().map(MigrationVersion::to_string).collect())
+}
+
+/// List migrations that have not been applied to the database yet
+pub fn pending_migrations(pool: &DbPool) -> Result<Vec<String>> {
+    let mut conn = pool.get().wrap_err("Failed to get database connection")?;
+
+    let pending = conn
+        .pending_migrations(MIGRATIONS)
+        .map_err(|e| color_eyre::eyre::eyre!("Failed to list pending migrations: {e}"))?;
+
+    Ok(pending
+        .iter()
+        .map(|m| m.name().version().to_string())
+        .collect())
+}
@@ -0,0 +1,160 @@
+//! Difficulty modes: which level a session's next new question comes from.
+//!
+//! A single-level mode narrows the quiz itself, so only progressive sessions and
+//! mixes need to pick a level here. The policy is pure; the quiz engine passes in
+//! the session's answers and the levels that still have unanswered questions.
+
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::models::{Difficulty, DifficultyMode};
+
+/// Correct answers in a row at the current level before a progressive session moves up
+pub const PROGRESSIVE_STREAK: usize = 3;
+
+/// Level a progressive session has reached after these answers, oldest first.
+///
+/// Only answers at the current level count towards the streak; a wrong one resets it.
+pub fn progressive_level(answers: impl IntoIterator<Item = (Difficulty, bool)>) -> Difficulty {
+    let mut level = Difficulty::Beginner;
+    let mut streak = 0;
+    for (difficulty, is_correct) in answers {
+        if difficulty != level {
+            continue;
+        }
+        if !is_correct {
+            streak = 0;
+            continue;
+        }
+        streak += 1;
+        if streak >= PROGRESSIVE_STREAK
+            && let Some(next) = level.next()
+        {
+            level = next;
+            streak = 0;
+        }
+    }
+    level
+}
+
+/// Level to draw the next new question from, out of the levels that still have one.
+///
+/// A progressive session stays at `progressive` while it can, then moves on to harder
+/// and finally easier levels. A mix picks a level in proportion to its weights; levels
+/// weighted 0 only come up once the others are used up. Other modes pick no level.
+pub fn next_level<R: Rng + ?Sized>(
+    mode: DifficultyMode,
+    progressive: Difficulty,
+    available: &[Difficulty],
+    rng: &mut R,
+) -> Option<Difficulty> {
+    match mode {
+        DifficultyMode::Progressive => {
+            let harder = Difficulty::ALL
+                .into_iter()
+                .skip_while(|level| *level != progressive);
+            let easier = Difficulty::ALL
+                .into_iter()
+                .rev()
+                .skip_while(|level| *level != progressive);
+            harder.chain(easier).find(|level| available.contains(level))
+        }
+        DifficultyMode::Mix { .. } => available
+            .choose_weighted(rng, |level| mode.weight(*level))
+            .ok()
+            .or_else(|| available.choose(rng))
+            .copied(),
+        DifficultyMode::All | DifficultyMode::Single(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Difficulty::*;
+
+    #[test]
+    fn test_progressive_level_moves_up_after_a_streak() {
+        assert_eq!(progressive_level([]), Beginner);
+        assert_eq!(
+            progressive_level([(Beginner, true), (Beginner, true), (Beginner, false)]),
+            Beginner
+        );
+        assert_eq!(
+            progressive_level([
+                (Beginner, true),
+                (Beginner, true),
+                (Advanced, true),
+                (Beginner, true),
+            ]),
+            Intermediate
+        );
+        let climb = [(Beginner, true); 3]
+            .into_iter()
+            .chain([(Intermediate, true); 3])
+            .chain([(Advanced, true); 3]);
+        assert_eq!(progressive_level(climb), Advanced);
+    }
+
+    #[test]
+    fn test_progressive_falls_back_to_harder_then_easier_levels() {
+        let mut rng = rand::rng();
+        let mut next = |level, available: &[Difficulty]| {
+            next_level(DifficultyMode::Progressive, level, available, &mut rng)
+        };
+        assert_eq!(next(Intermediate, &Difficulty::ALL), Some(Intermediate));
+        assert_eq!(next(Intermediate, &[Beginner, Advanced]), Some(Advanced));
+        assert_eq!(
+            next(Advanced, &[Beginner, Intermediate]),
+            Some(Intermediate)
+        );
+        assert_eq!(next(Beginner, &[]), None);
+        assert_eq!(
+            next_level(
+                DifficultyMode::All,
+                Beginner,
+                &Difficulty::ALL,
+                &mut rand::rng()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_mix_follows_weights() {
+        let mut rng = rand::rng();
+        let only_advanced = DifficultyMode::Mix {
+            beginner: 0,
+            intermediate: 0,
+            advanced: 100,
+        };
+        for _ in 0..20 {
+            assert_eq!(
+                next_level(only_advanced, Beginner, &Difficulty::ALL, &mut rng),
+                Some(Advanced)
+            );
+        }
+        // Levels weighted 0 are used once nothing else is left
+        assert_eq!(
+            next_level(only_advanced, Beginner, &[Beginner], &mut rng),
+            Some(Beginner)
+        );
+    }
+
+    #[test]
+    fn test_modes_round_trip_through_strings() {
+        for mode in [
+            DifficultyMode::All,
+            DifficultyMode::Single(Intermediate),
+            DifficultyMode::Progressive,
+            DifficultyMode::Mix {
+                beginner: 50,
+                intermediate: 30,
+                advanced: 20,
+            },
+        ] {
+            assert_eq!(mode.to_string().parse::<DifficultyMode>(), Ok(mode));
+        }
+        assert!("mix:50-30".parse::<DifficultyMode>().is_err());
+        assert!("expert".parse::<DifficultyMode>().is_err());
+    }
+}
@@ -0,0 +1,223 @@
+//! Outgoing email, e.g. learner sign-in links.
+//!
+//! Senders are pluggable behind [`EmailSender`]: [`SmtpEmailSender`] delivers
+//! through the server in `[email]`, and [`LogEmailSender`] only logs messages so
+//! development setups work without a mail server.
+
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use tracing::{info, warn};
+
+use crate::config::{EmailConfig, SmtpSecurity};
+
+/// A plain-text email to one recipient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+pub type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// Delivers emails; shared by handlers as `Arc<dyn EmailSender>`
+pub trait EmailSender: Send + Sync {
+    fn send<'a>(&'a self, message: &'a EmailMessage) -> SendFuture<'a>;
+}
+
+/// Logs messages instead of sending them
+pub struct LogEmailSender;
+
+impl EmailSender for LogEmailSender {
+    fn send<'a>(&'a self, message: &'a EmailMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            info!(
+                to = %message.to,
+                subject = %message.subject,
+                "Email not sent (no [email] configured):\n{}",
+                message.body
+            );
+            Ok(())
+        })
+    }
+}
+
+/// Sends through an SMTP server
+pub struct SmtpEmailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpEmailSender {
+    pub fn new(config: &EmailConfig) -> Result<Self> {
+        let from = config
+            .from
+            .parse()
+            .wrap_err("Invalid sender address in [email] from")?;
+        let builder = match config.smtp_security {
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+            }
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+                    .wrap_err("Failed to set up STARTTLS for the SMTP server")?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)
+                .wrap_err("Failed to set up TLS for the SMTP server")?,
+        };
+        let mut builder = builder.port(config.smtp_port);
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+impl EmailSender for SmtpEmailSender {
+    fn send<'a>(&'a self, message: &'a EmailMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            let to: Mailbox = message
+                .to
+                .parse()
+                .map_err(|e| eyre!("Invalid recipient {}: {}", message.to, e))?;
+            let email = Message::builder()
+                .from(self.from.clone())
+                .to(to)
+                .subject(&message.subject)
+                .header(ContentType::TEXT_PLAIN)
+                .body(message.body.clone())
+                .wrap_err("Failed to build email")?;
+            self.transport
+                .send(email)
+                .await
+                .wrap_err("Failed to send email")?;
+            Ok(())
+        })
+    }
+}
+
+/// Sender for the configuration: SMTP if `[email]` is set, otherwise logging
+pub fn email_sender(config: Option<&EmailConfig>) -> Result<Arc<dyn EmailSender>> {
+    match config {
+        Some(config) => Ok(Arc::new(SmtpEmailSender::new(config)?)),
+        None => {
+            warn!("No [email] configured: learner sign-in links are written to the log");
+            Ok(Arc::new(LogEmailSender))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    /// Minimal SMTP server accepting one message; returns its port and the DATA it received
+    async fn smtp_stub() -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            writer.write_all(b"220 stub ESMTP\r\n").await.unwrap();
+
+            let mut data = String::new();
+            let mut in_data = false;
+            while let Some(line) = lines.next_line().await.unwrap() {
+                if in_data {
+                    if line == "." {
+                        in_data = false;
+                        writer.write_all(b"250 queued\r\n").await.unwrap();
+                    } else {
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    continue;
+                }
+                let command = line.to_ascii_uppercase();
+                if command.starts_with("DATA") {
+                    in_data = true;
+                    writer.write_all(b"354 go ahead\r\n").await.unwrap();
+                } else if command.starts_with("QUIT") {
+                    writer.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    writer.write_all(b"250 ok\r\n").await.unwrap();
+                }
+            }
+            data
+        });
+        (port, handle)
+    }
+
+    #[tokio::test]
+    async fn test_smtp_sender_delivers_to_server() {
+        let (port, received) = smtp_stub().await;
+        let sender = SmtpEmailSender::new(&EmailConfig {
+            from: "Regelator <noreply@example.org>".to_string(),
+            smtp_host: "127.0.0.1".to_string(),
+            smtp_port: port,
+            smtp_security: SmtpSecurity::None,
+            smtp_username: None,
+            smtp_password: None,
+        })
+        .unwrap();
+
+        sender
+            .send(&EmailMessage {
+                to: "player@example.org".to_string(),
+                subject: "Sign in".to_string(),
+                body: "https://rules.example.org/en/account/verify?token=abc".to_string(),
+            })
+            .await
+            .unwrap();
+        drop(sender);
+
+        let data = tokio::time::timeout(std::time::Duration::from_secs(5), received)
+            .await
+            .expect("the stub should see the connection close")
+            .unwrap();
+        assert!(data.contains("From: Regelator <noreply@example.org>"));
+        assert!(data.contains("To: player@example.org"));
+        assert!(data.contains("Subject: Sign in"));
+        assert!(data.contains("https://rules.example.org/en/account/verify?token=abc"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_recipient_is_an_error() {
+        let sender = SmtpEmailSender::new(&EmailConfig {
+            from: "noreply@example.org".to_string(),
+            smtp_host: "127.0.0.1".to_string(),
+            smtp_port: 1,
+            smtp_security: SmtpSecurity::None,
+            smtp_username: None,
+            smtp_password: None,
+        })
+        .unwrap();
+        let result = sender
+            .send(&EmailMessage {
+                to: "not an address".to_string(),
+                subject: "Sign in".to_string(),
+                body: String::new(),
+            })
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,621 @@
+//! Exams for observer and captain certification.
+//!
+//! An exam runs in its own quiz session with mode `exam`: a fixed number of
+//! questions is drawn up front, spread over difficulty levels and rule sections in
+//! proportion to the question pool, and served in order. Answers get no feedback
+//! and are only accepted until the time limit, which the server enforces. Once
+//! every question is answered or time is up the exam is scored against the pass
+//! mark, its session ends and the visitor returns to their practice session.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{Duration, NaiveDateTime};
+use color_eyre::Result;
+use rand::{
+    Rng,
+    seq::{IndexedRandom, SliceRandom},
+};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::{
+    config::ExamConfig,
+    models::{NewQuizAttempt, QuizExam, QuizExamQuestion, QuizMode, QuizSessionRecord},
+    quiz_engine::{QuestionWithAnswers, QuizScope},
+    repository::RuleRepository,
+};
+
+/// The exam question to answer next
+#[derive(Debug, Clone)]
+pub struct ExamQuestion {
+    pub question: QuestionWithAnswers,
+    /// From 1
+    pub position: usize,
+    pub question_count: usize,
+    pub seconds_left: i64,
+}
+
+/// Score of a finished exam with a breakdown and every question for review
+#[derive(Debug, Clone, Serialize)]
+pub struct ExamReport {
+    pub question_count: usize,
+    pub answered: usize,
+    pub correct: usize,
+    pub score_percent: u32,
+    pub pass_percent: u32,
+    pub passed: bool,
+    /// Time ran out before every question was answered
+    pub timed_out: bool,
+    pub started_at: NaiveDateTime,
+    pub finished_at: NaiveDateTime,
+    pub by_difficulty: Vec<ExamBreakdown>,
+    pub by_section: Vec<ExamBreakdown>,
+    pub questions: Vec<ExamReportQuestion>,
+}
+
+/// Correct answers among the exam questions of one difficulty level or section
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExamBreakdown {
+    pub label: String,
+    pub correct: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExamReportQuestion {
+    pub position: usize,
+    pub question_text: String,
+    pub difficulty_level: String,
+    /// `None` if the question was not answered in time
+    pub selected_answer: Option<String>,
+    pub correct_answers: Vec<String>,
+    pub is_correct: bool,
+    pub explanation: String,
+}
+
+/// How many questions to draw from each stratum: in proportion to its size, with
+/// the questions left over from rounding down going to the largest remainders
+pub fn allocate(sizes: &[usize], count: usize) -> Vec<usize> {
+    let total: usize = sizes.iter().sum();
+    if count >= total {
+        return sizes.to_vec();
+    }
+    let mut shares: Vec<usize> = sizes.iter().map(|size| size * count / total).collect();
+    let left = count - shares.iter().sum::<usize>();
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| {
+        (
+            std::cmp::Reverse(sizes[i] * count % total),
+            std::cmp::Reverse(sizes[i]),
+            i,
+        )
+    });
+    for &i in order.iter().take(left) {
+        shares[i] += 1;
+    }
+    shares
+}
+
+/// Draw `count` question ids spread over the strata, in random order
+pub fn draw<R: Rng + ?Sized>(strata: &[Vec<String>], count: usize, rng: &mut R) -> Vec<String> {
+    let sizes: Vec<usize> = strata.iter().map(Vec::len).collect();
+    let mut drawn: Vec<String> = strata
+        .iter()
+        .zip(allocate(&sizes, count))
+        .flat_map(|(stratum, share)| stratum.choose_multiple(rng, share).cloned())
+        .collect();
+    drawn.shuffle(rng);
+    drawn
+}
+
+/// Start an exam over the quiz in a new session, returning to `practice` afterwards.
+///
+/// Returns `None` if the quiz has no questions.
+pub fn start_exam(
+    repository: &RuleRepository,
+    scope: &QuizScope,
+    practice: &QuizSessionRecord,
+    config: &ExamConfig,
+    now: NaiveDateTime,
+) -> Result<Option<QuizSessionRecord>> {
+    let sections = scope.question_sections(repository)?;
+    let mut strata: BTreeMap<(String, Option<&String>), Vec<String>> = BTreeMap::new();
+    for question in repository.get_quiz_questions(&scope.rule_set.id, &scope.version.id)? {
+        strata
+            .entry((
+                question.difficulty_level.to_lowercase(),
+                sections.get(&question.id),
+            ))
+            .or_default()
+            .push(question.id);
+    }
+    let strata: Vec<Vec<String>> = strata.into_values().collect();
+    let question_ids = draw(&strata, config.question_count as usize, &mut rand::rng());
+    if question_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let session = scope.start_session(
+        repository,
+        &practice.language,
+        QuizMode::Exam,
+        practice.learner_id.as_deref(),
+    )?;
+    let exam = QuizExam {
+        session_id: session.id.clone(),
+        practice_session_id: (practice.mode == QuizMode::Practice).then(|| practice.id.clone()),
+        question_count: question_ids.len() as i32,
+        pass_percent: config.pass_percent as i32,
+        started_at: now,
+        ends_at: now + Duration::minutes(config.time_limit_minutes.into()),
+        finished_at: None,
+        correct_count: None,
+        passed: None,
+    };
+    let questions: Vec<QuizExamQuestion> = question_ids
+        .into_iter()
+        .enumerate()
+        .map(|(position, question_id)| QuizExamQuestion {
+            session_id: session.id.clone(),
+            position: position as i32,
+            question_id,
+        })
+        .collect();
+    repository.create_quiz_exam(&exam, &questions)?;
+    debug!(
+        "Started exam {} with {} questions",
+        session.id, exam.question_count
+    );
+    Ok(Some(session))
+}
+
+/// Ids of the exam questions answered so far
+fn answered_questions(repository: &RuleRepository, exam: &QuizExam) -> Result<HashSet<String>> {
+    Ok(repository
+        .get_session_attempts(&exam.session_id)?
+        .into_iter()
+        .map(|attempt| attempt.question_id)
+        .collect())
+}
+
+/// The first unanswered question, or `None` once the exam is over
+pub fn next_question(
+    repository: &RuleRepository,
+    exam: &QuizExam,
+    now: NaiveDateTime,
+) -> Result<Option<ExamQuestion>> {
+    if exam.finished_at.is_some() || now >= exam.ends_at {
+        return Ok(None);
+    }
+    let answered = answered_questions(repository, exam)?;
+    let questions = repository.get_quiz_exam_questions(&exam.session_id)?;
+    let Some((index, next)) = questions
+        .iter()
+        .enumerate()
+        .find(|(_, question)| !answered.contains(&question.question_id))
+    else {
+        return Ok(None);
+    };
+    let Some(question) = repository.get_quiz_question_by_id(&next.question_id)? else {
+        return Ok(None);
+    };
+    let answers = repository.get_quiz_answers(&question.id)?;
+
+    Ok(Some(ExamQuestion {
+        question: QuestionWithAnswers {
+            question,
+            answers,
+            is_review: false,
+        },
+        position: index + 1,
+        question_count: questions.len(),
+        seconds_left: (exam.ends_at - now).num_seconds(),
+    }))
+}
+
+/// Record an answer to an exam question.
+///
+/// Returns `false` without recording anything once time is up, or if the question
+/// is not part of the exam, was answered already or the answer is not one of its own.
+pub fn answer_question(
+    repository: &RuleRepository,
+    exam: &QuizExam,
+    question_id: &str,
+    answer_id: &str,
+    response_time_ms: Option<i32>,
+    now: NaiveDateTime,
+) -> Result<bool> {
+    if exam.finished_at.is_some() || now >= exam.ends_at {
+        return Ok(false);
+    }
+    let in_exam = repository
+        .get_quiz_exam_questions(&exam.session_id)?
+        .iter()
+        .any(|question| question.question_id == question_id);
+    if !in_exam || answered_questions(repository, exam)?.contains(question_id) {
+        return Ok(false);
+    }
+    let Some(is_correct) = repository
+        .get_quiz_answers(question_id)?
+        .into_iter()
+        .find(|answer| answer.id == answer_id)
+        .map(|answer| answer.is_correct)
+    else {
+        return Ok(false);
+    };
+
+    repository.create_quiz_attempt(&NewQuizAttempt::new(
+        exam.session_id.clone(),
+        question_id.to_string(),
+        Some(answer_id.to_string()),
+        Some(is_correct),
+        response_time_ms,
+    ))?;
+    Ok(true)
+}
+
+/// Score the exam and end its session; an exam finished before is returned as stored
+pub fn finish_exam(
+    repository: &RuleRepository,
+    exam: &QuizExam,
+    now: NaiveDateTime,
+) -> Result<QuizExam> {
+    if exam.finished_at.is_some() {
+        return Ok(exam.clone());
+    }
+    let question_ids: HashSet<String> = repository
+        .get_quiz_exam_questions(&exam.session_id)?
+        .into_iter()
+        .map(|question| question.question_id)
+        .collect();
+    let correct = repository
+        .get_session_attempts(&exam.session_id)?
+        .iter()
+        .filter(|attempt| {
+            question_ids.contains(&attempt.question_id) && attempt.is_correct == Some(true)
+        })
+        .count() as i32;
+    let passed = correct * 100 >= exam.pass_percent * exam.question_count;
+    if repository.finish_quiz_exam(&exam.session_id, now, correct, passed)? {
+        debug!(
+            "Finished exam {}: {}/{} correct",
+            exam.session_id, correct, exam.question_count
+        );
+    }
+
+    Ok(repository
+        .get_quiz_exam(&exam.session_id)?
+        .unwrap_or_else(|| exam.clone()))
+}
+
+/// Report of a finished exam; section names are in `language`
+pub fn exam_report(
+    repository: &RuleRepository,
+    scope: &QuizScope,
+    exam: &QuizExam,
+    language: &str,
+) -> Result<ExamReport> {
+    let attempts: HashMap<String, (Option<String>, bool)> = repository
+        .get_session_attempts(&exam.session_id)?
+        .into_iter()
+        .map(|attempt| {
+            (
+                attempt.question_id,
+                (attempt.selected_answer_id, attempt.is_correct == Some(true)),
+            )
+        })
+        .collect();
+    let sections = scope.question_sections(repository)?;
+    let section_names: HashMap<String, String> = scope
+        .sections(repository, language)?
+        .into_iter()
+        .map(|section| {
+            let name = format!("{}. {}", section.number, section.title);
+            (section.rule_id, name)
+        })
+        .collect();
+
+    let mut questions = Vec::new();
+    let mut by_difficulty: BTreeMap<String, ExamBreakdown> = BTreeMap::new();
+    let mut by_section: BTreeMap<String, ExamBreakdown> = BTreeMap::new();
+    for exam_question in repository.get_quiz_exam_questions(&exam.session_id)? {
+        let Some(question) = repository.get_quiz_question_by_id(&exam_question.question_id)? else {
+            continue;
+        };
+        let answers = repository.get_quiz_answers(&question.id)?;
+        let (selected_answer_id, is_correct) =
+            attempts.get(&question.id).cloned().unwrap_or((None, false));
+
+        let section = sections
+            .get(&question.id)
+            .and_then(|section| section_names.get(section))
+            .cloned()
+            .unwrap_or_else(|| "Other".to_string());
+        for (breakdown, label) in [
+            (&mut by_difficulty, question.difficulty_level.to_lowercase()),
+            (&mut by_section, section),
+        ] {
+            let entry = breakdown
+                .entry(label.clone())
+                .or_insert_with(|| ExamBreakdown {
+                    label,
+                    correct: 0,
+                    total: 0,
+                });
+            entry.total += 1;
+            entry.correct += usize::from(is_correct);
+        }
+
+        questions.push(ExamReportQuestion {
+            position: exam_question.position as usize + 1,
+            selected_answer: answers
+                .iter()
+                .find(|answer| Some(&answer.id) == selected_answer_id.as_ref())
+                .map(|answer| answer.answer_text.clone()),
+            correct_answers: answers
+                .iter()
+                .filter(|answer| answer.is_correct)
+                .map(|answer| answer.answer_text.clone())
+                .collect(),
+            is_correct,
+            question_text: question.question_text,
+            difficulty_level: question.difficulty_level,
+            explanation: question.explanation,
+        });
+    }
+
+    let question_count = exam.question_count.max(0) as usize;
+    let answered = questions
+        .iter()
+        .filter(|question| question.selected_answer.is_some())
+        .count();
+    let correct = questions
+        .iter()
+        .filter(|question| question.is_correct)
+        .count();
+    Ok(ExamReport {
+        question_count,
+        answered,
+        correct,
+        score_percent: (correct * 100)
+            .checked_div(question_count)
+            .unwrap_or_default() as u32,
+        pass_percent: exam.pass_percent.max(0) as u32,
+        passed: exam.passed.unwrap_or(false),
+        timed_out: answered < question_count,
+        started_at: exam.started_at,
+        finished_at: exam.finished_at.unwrap_or(exam.ends_at),
+        by_difficulty: by_difficulty.into_values().collect(),
+        by_section: by_section.into_values().collect(),
+        questions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NewRule, QuestionStatus, QuizAnswerData, QuizQuestionData};
+    use crate::test_support::repository_with_current_version;
+
+    #[test]
+    fn test_allocation_is_proportional_and_adds_up() {
+        assert_eq!(allocate(&[10, 6, 4], 10), vec![5, 3, 2]);
+        // Shares of 3, 1.5, 0.375 and 1.125 round down to 5; the largest remainder gets the 6th
+        assert_eq!(allocate(&[8, 4, 1, 3], 6), vec![3, 2, 0, 1]);
+        assert_eq!(allocate(&[2, 1], 5), vec![2, 1]);
+        assert_eq!(allocate(&[], 5), Vec::<usize>::new());
+        for count in 0..=20 {
+            assert_eq!(allocate(&[7, 5, 3, 5], count).iter().sum::<usize>(), count);
+        }
+    }
+
+    #[test]
+    fn test_draw_covers_every_stratum() {
+        let strata: Vec<Vec<String>> = [("b", 12), ("i", 6), ("a", 2)]
+            .into_iter()
+            .map(|(prefix, size)| (0..size).map(|i| format!("{prefix}{i}")).collect())
+            .collect();
+        let drawn = draw(&strata, 10, &mut rand::rng());
+        assert_eq!(drawn.len(), 10);
+        assert_eq!(drawn.iter().collect::<HashSet<_>>().len(), 10);
+        for (prefix, expected) in [("b", 6), ("i", 3), ("a", 1)] {
+            assert_eq!(
+                drawn.iter().filter(|id| id.starts_with(prefix)).count(),
+                expected
+            );
+        }
+    }
+
+    /// Quiz with sections 1 and 2 and six questions, two of them advanced
+    fn seeded_repo() -> (RuleRepository, QuizScope) {
+        let (repo, rule_set, version) = repository_with_current_version();
+        let sections: Vec<String> = ["1", "2"]
+            .into_iter()
+            .map(|number| {
+                repo.create_rule(NewRule::new(
+                    format!("rule-{number}"),
+                    rule_set.id.clone(),
+                    version.id.clone(),
+                    None,
+                    number.to_string(),
+                ))
+                .unwrap()
+                .id
+            })
+            .collect();
+        for (i, difficulty) in ["beginner", "beginner", "intermediate", "intermediate"]
+            .into_iter()
+            .chain(["advanced", "advanced"])
+            .enumerate()
+        {
+            repo.create_quiz_question_complete(&QuizQuestionData::new(
+                rule_set.id.clone(),
+                version.id.clone(),
+                format!("Question {i}?"),
+                "Because.".to_string(),
+                difficulty.to_string(),
+                vec![
+                    QuizAnswerData {
+                        answer_text: "Right".to_string(),
+                        is_correct: true,
+                    },
+                    QuizAnswerData {
+                        answer_text: "Wrong".to_string(),
+                        is_correct: false,
+                    },
+                ],
+                vec![sections[i % 2].clone()],
+            ))
+            .unwrap();
+        }
+        let scope = QuizScope::current(&repo, "indoor").unwrap().unwrap();
+        (repo, scope)
+    }
+
+    fn config(question_count: u32) -> ExamConfig {
+        ExamConfig {
+            question_count,
+            time_limit_minutes: 30,
+            pass_percent: 75,
+        }
+    }
+
+    fn answer(repo: &RuleRepository, exam: &QuizExam, correctly: bool, now: NaiveDateTime) -> bool {
+        let next = next_question(repo, exam, now).unwrap().unwrap();
+        let answer = next
+            .question
+            .answers
+            .iter()
+            .find(|answer| answer.is_correct == correctly)
+            .unwrap();
+        answer_question(
+            repo,
+            exam,
+            &next.question.question.id,
+            &answer.id,
+            None,
+            now,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_exam_is_scored_against_the_pass_mark() {
+        let (repo, scope) = seeded_repo();
+        let practice = scope
+            .start_session(&repo, "en", QuizMode::Practice, None)
+            .unwrap();
+        let now = chrono::Utc::now().naive_utc();
+
+        let session = start_exam(&repo, &scope, &practice, &config(4), now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.mode, QuizMode::Exam);
+        let exam = repo.get_quiz_exam(&session.id).unwrap().unwrap();
+        assert_eq!(exam.practice_session_id.as_ref(), Some(&practice.id));
+        let drawn = repo.get_quiz_exam_questions(&session.id).unwrap();
+        assert_eq!(drawn.len(), 4);
+
+        for correctly in [true, false, true, true] {
+            assert!(answer(&repo, &exam, correctly, now));
+        }
+        assert!(next_question(&repo, &exam, now).unwrap().is_none());
+        // Answering again does not count
+        assert!(
+            !answer_question(
+                &repo,
+                &exam,
+                &drawn[0].question_id,
+                "not-an-answer",
+                None,
+                now
+            )
+            .unwrap()
+        );
+
+        let finished = finish_exam(&repo, &exam, now).unwrap();
+        assert_eq!(finished.correct_count, Some(3));
+        assert_eq!(finished.passed, Some(true));
+        assert!(
+            repo.get_quiz_session(&session.id)
+                .unwrap()
+                .unwrap()
+                .ended_at
+                .is_some()
+        );
+
+        let report = exam_report(&repo, &scope, &finished, "en").unwrap();
+        assert_eq!((report.correct, report.answered), (3, 4));
+        assert_eq!(report.score_percent, 75);
+        assert!(report.passed && !report.timed_out);
+        assert_eq!(
+            report.by_difficulty.iter().map(|b| b.total).sum::<usize>(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_answers_after_the_time_limit_are_rejected() {
+        let (repo, scope) = seeded_repo();
+        let practice = scope
+            .start_session(&repo, "en", QuizMode::Practice, None)
+            .unwrap();
+        let now = chrono::Utc::now().naive_utc();
+        let session = start_exam(&repo, &scope, &practice, &config(20), now)
+            .unwrap()
+            .unwrap();
+        let exam = repo.get_quiz_exam(&session.id).unwrap().unwrap();
+        // Only six questions exist
+        assert_eq!(exam.question_count, 6);
+
+        assert!(answer(&repo, &exam, true, now));
+        let late = exam.ends_at;
+        let next = next_question(&repo, &exam, now).unwrap().unwrap();
+        assert!(
+            !answer_question(
+                &repo,
+                &exam,
+                &next.question.question.id,
+                &next.question.answers[0].id,
+                None,
+                late
+            )
+            .unwrap()
+        );
+        assert!(next_question(&repo, &exam, late).unwrap().is_none());
+
+        let finished = finish_exam(&repo, &exam, late).unwrap();
+        assert_eq!(finished.passed, Some(false));
+        let report = exam_report(&repo, &scope, &finished, "en").unwrap();
+        assert!(report.timed_out);
+        assert_eq!((report.answered, report.question_count), (1, 6));
+    }
+
+    #[test]
+    fn test_draft_questions_are_never_drawn() {
+        let (repo, scope) = seeded_repo();
+        let questions = repo
+            .get_quiz_questions(&scope.rule_set.id, &scope.version.id)
+            .unwrap();
+        let drafts = [&questions[0].id, &questions[4].id];
+        for draft in drafts {
+            repo.update_question_status(draft, QuestionStatus::Draft)
+                .unwrap();
+        }
+
+        let practice = scope
+            .start_session(&repo, "en", QuizMode::Practice, None)
+            .unwrap();
+        let now = chrono::Utc::now().naive_utc();
+        let session = start_exam(&repo, &scope, &practice, &config(20), now)
+            .unwrap()
+            .unwrap();
+        let drawn = repo.get_quiz_exam_questions(&session.id).unwrap();
+        assert_eq!(drawn.len(), 4);
+        assert!(
+            drawn
+                .iter()
+                .all(|question| !drafts.contains(&&question.question_id))
+        );
+    }
+}
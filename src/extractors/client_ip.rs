@@ -0,0 +1,97 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::{ConnectInfo, FromRef, FromRequestParts};
+use axum::http::{header::USER_AGENT, request::Parts};
+use regelator::config::Config;
+
+/// Address of the client making the request, if known.
+/// Uses `X-Forwarded-For` only when `server.trust_forwarded_for` is enabled.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+/// The address our reverse proxy appended to an `X-Forwarded-For` value.
+///
+/// Only the last entry is taken: everything before it was sent by the client, which
+/// could otherwise pick a new address for every request and escape the throttles.
+fn forwarded_client_ip(value: &str) -> Option<IpAddr> {
+    value.rsplit(',').next()?.trim().parse().ok()
+}
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+    Config: FromRef<S>,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let config = Config::from_ref(state);
+
+        if config.server.trust_forwarded_for
+            && let Some(forwarded_ip) = parts
+                .headers
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok())
+                .and_then(forwarded_client_ip)
+        {
+            return Ok(ClientIp(Some(forwarded_ip)));
+        }
+
+        Ok(ClientIp(
+            parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| address.ip()),
+        ))
+    }
+}
+
+/// Client address and browser, recorded with admin sessions
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    pub ip: Option<IpAddr>,
+    pub user_agent: Option<String>,
+}
+
+/// Longest user agent kept, so a client cannot bloat session rows
+const MAX_USER_AGENT_LENGTH: usize = 200;
+
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+    Config: FromRef<S>,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, state).await?;
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(MAX_USER_AGENT_LENGTH).collect());
+
+        Ok(ClientInfo { ip, user_agent })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarded_ip_is_the_one_the_proxy_appended() {
+        let proxy_seen: IpAddr = "203.0.113.7".parse().unwrap();
+        assert_eq!(forwarded_client_ip("203.0.113.7"), Some(proxy_seen));
+
+        // A forged leftmost entry doesn't change the address throttles are keyed on
+        for forged in [
+            "198.51.100.1, 203.0.113.7",
+            "10.0.0.1,192.0.2.9, 203.0.113.7",
+        ] {
+            assert_eq!(forwarded_client_ip(forged), Some(proxy_seen));
+        }
+        assert_eq!(forwarded_client_ip("198.51.100.1, not-an-ip"), None);
+    }
+}
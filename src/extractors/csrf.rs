@@ -0,0 +1,25 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{StatusCode, request::Parts},
+};
+use serde::Serialize;
+
+/// CSRF token for the current visitor, set by the `csrf_protection` middleware.
+/// Templates render it into a hidden `csrf_token` field on every POST form.
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct CsrfToken(pub String);
+
+impl<S> FromRequestParts<S> for CsrfToken
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<CsrfToken>().cloned().ok_or((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "CSRF middleware is not configured",
+        ))
+    }
+}
@@ -1,3 +1,7 @@
+pub mod client_ip;
+pub mod csrf;
 pub mod theme;
 
+pub use client_ip::ClientInfo;
+pub use csrf::CsrfToken;
 pub use theme::Theme;
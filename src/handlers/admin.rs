@@ -1,21 +1,36 @@
-use crate::extractors::Theme;
-use crate::models::QuestionStatus;
+use crate::extractors::{ClientInfo, CsrfToken, Theme};
+use crate::models::{
+    Admin, AdminRole, AuditAction, AuditLogEntry, AuditLogFilter, LoginThrottle, NewAdminSession,
+    NewAuditLogEntry, QuestionStatus, ThrottleScope,
+};
 use crate::{AppError, repository::RuleRepository};
-use argon2::password_hash::{SaltString, rand_core::OsRng};
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use axum::{
     extract::{Path, State},
-    response::{Html, Redirect},
+    http::StatusCode,
+    response::{Html, IntoResponse, Redirect, Response},
 };
 use axum_extra::extract::{CookieJar, Form, Query};
 use chrono::Utc;
 use minijinja::Environment;
-use regelator::auth::{AdminToken, clear_admin_cookie, create_admin_cookie};
-use regelator::config::Config;
+use regelator::auth::{
+    AdminAuthError, AdminToken, Analyst, Editor, MIN_PASSWORD_LENGTH, Permissions, RequireRole,
+    Superadmin, clear_admin_cookie, clear_oidc_cookie, clear_two_factor_cookie,
+    create_admin_cookie, create_oidc_cookie, create_two_factor_cookie, hash_password,
+    verify_oidc_cookie, verify_password, verify_two_factor_cookie,
+};
+use regelator::config::{Config, LoginThrottleConfig, OidcMatchClaim};
+use regelator::oidc::{OidcClient, PendingLogin};
+use regelator::throttle::ThrottleDecision;
+use regelator::two_factor;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use std::sync::Arc;
-use tracing::{Span, instrument};
+use tracing::{Span, instrument, warn};
+
+use super::two_factor::{
+    TwoFactorCodeForm, issue_recovery_codes, render_recovery_codes, render_two_factor_setup,
+    verify_second_factor,
+};
 
 // Admin authentication structures
 #[derive(Deserialize)]
@@ -27,11 +42,16 @@ pub struct AdminLoginForm {
 #[derive(Serialize)]
 struct AdminLoginContext {
     error: Option<String>,
+    /// Name of the OpenID Connect provider, if single sign-on is configured
+    oidc_provider: Option<String>,
+    csrf_token: CsrfToken,
 }
 
 #[derive(Serialize)]
 struct AdminDashboardContext {
     username: String,
+    permissions: Permissions,
+    csrf_token: CsrfToken,
 }
 
 #[derive(Deserialize)]
@@ -45,122 +65,592 @@ pub struct ChangePasswordForm {
 struct ChangePasswordContext {
     error: Option<String>,
     success: Option<String>,
+    csrf_token: CsrfToken,
+}
+
+/// Record an action taken by the logged-in admin in the audit log
+pub(super) fn record_audit(
+    repository: &RuleRepository,
+    admin: &AdminToken,
+    action: AuditAction,
+    entity_id: &str,
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+) -> Result<(), AppError> {
+    repository.record_audit_event(NewAuditLogEntry::new(
+        Some(admin.admin_id().to_string()),
+        admin.username().to_string(),
+        action,
+        Some(entity_id.to_string()),
+        before,
+        after,
+    ))?;
+    Ok(())
+}
+
+/// Snapshot a question with its answers for the audit log
+fn question_snapshot(
+    repository: &RuleRepository,
+    question_id: &str,
+) -> Result<Option<serde_json::Value>, AppError> {
+    Ok(repository
+        .get_question_with_answers(question_id)?
+        .map(|(question, answers)| serde_json::json!({ "question": question, "answers": answers })))
+}
+
+/// Snapshot an admin account (without its password hash) for the audit log
+fn admin_snapshot(admin: &crate::models::Admin) -> Option<serde_json::Value> {
+    serde_json::to_value(admin).ok()
+}
+
+/// Render the login form with an optional error
+fn render_login(
+    templates: &Environment<'static>,
+    config: &Config,
+    csrf_token: CsrfToken,
+    error: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let context = AdminLoginContext {
+        error,
+        oidc_provider: config
+            .security
+            .oidc
+            .as_ref()
+            .map(|oidc| oidc.provider_name.clone()),
+        csrf_token,
+    };
+    let tmpl = templates.get_template("admin_login.html")?;
+    let rendered = tmpl.render(context)?;
+    Ok(Html(rendered))
+}
+
+/// Count a failed login against each throttled subject and report new lockouts
+fn record_login_failure(
+    repository: &RuleRepository,
+    throttle_config: &LoginThrottleConfig,
+    subjects: &[(ThrottleScope, String)],
+    now: chrono::NaiveDateTime,
+) -> Result<(), AppError> {
+    for (scope, subject) in subjects {
+        let (throttle, newly_locked) =
+            repository.record_login_failure(throttle_config, *scope, subject, now)?;
+
+        if newly_locked {
+            warn!(
+                scope = scope.as_str(),
+                subject = %subject,
+                failed_attempts = throttle.failed_attempts,
+                locked_until = ?throttle.locked_until,
+                "Admin login locked out after repeated failures"
+            );
+            repository.record_audit_event(NewAuditLogEntry::new(
+                None,
+                "system".to_string(),
+                AuditAction::LoginLockedOut,
+                Some(format!("{}:{}", scope.as_str(), subject)),
+                None,
+                serde_json::to_value(&throttle).ok(),
+            ))?;
+        }
+    }
+    Ok(())
 }
 
 /// Show admin login form
-#[instrument(skip(templates))]
+#[instrument(skip(templates, config, csrf_token))]
 pub async fn admin_login_form(
     State(templates): State<Arc<Environment<'static>>>,
+    State(config): State<Config>,
+    csrf_token: CsrfToken,
 ) -> Result<Html<String>, AppError> {
-    let context = AdminLoginContext { error: None };
-    let tmpl = templates.get_template("admin_login.html")?;
+    render_login(&templates, &config, csrf_token, None)
+}
+
+/// Failed attempts count against both the username and the client address
+fn login_subjects(
+    username: &str,
+    client_ip: Option<std::net::IpAddr>,
+) -> Vec<(ThrottleScope, String)> {
+    let mut subjects = vec![(ThrottleScope::Username, username.to_string())];
+    if let Some(ip) = client_ip {
+        subjects.push((ThrottleScope::Ip, ip.to_string()));
+    }
+    subjects
+}
+
+/// Error message for the first throttled subject, if any
+fn login_throttle_error(
+    repository: &RuleRepository,
+    throttle_config: &LoginThrottleConfig,
+    subjects: &[(ThrottleScope, String)],
+    now: chrono::NaiveDateTime,
+) -> Result<Option<String>, AppError> {
+    for (scope, subject) in subjects {
+        let throttle = repository.get_login_throttle(*scope, subject)?;
+        match throttle_config.check(throttle.as_ref(), now) {
+            ThrottleDecision::Allowed => continue,
+            ThrottleDecision::Delayed { retry_after } => {
+                warn!(scope = scope.as_str(), subject = %subject, "Admin login attempt rejected by backoff");
+                return Ok(Some(format!(
+                    "Too many failed attempts. Try again in {} seconds.",
+                    retry_after.num_seconds().max(1)
+                )));
+            }
+            ThrottleDecision::LockedOut { until } => {
+                warn!(scope = scope.as_str(), subject = %subject, locked_until = %until, "Admin login attempt rejected by lockout");
+                return Ok(Some(format!(
+                    "Too many failed attempts. Login is locked until {} UTC.",
+                    until.format("%H:%M")
+                )));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Issue the admin session once every login step has passed
+fn start_admin_session(
+    repository: &RuleRepository,
+    config: &Config,
+    jar: CookieJar,
+    admin: &Admin,
+    client: &ClientInfo,
+) -> Result<CookieJar, AppError> {
+    // Forget earlier failures, update last login and record the server-side session
+    repository.clear_login_throttle(ThrottleScope::Username, &admin.username)?;
+    repository.update_admin_last_login(&admin.id)?;
+    let session = repository.create_admin_session(NewAdminSession::new(
+        admin.id.clone(),
+        (Utc::now() + config.session_duration()).naive_utc(),
+        client.ip.map(|ip| ip.to_string()),
+        client.user_agent.clone(),
+    ))?;
+
+    let cookie = create_admin_cookie(
+        session.id,
+        admin.id.clone(),
+        admin.username.clone(),
+        admin.role,
+        &config.security.jwt_secret,
+        config.session_duration(),
+    )
+    .map_err(|e| {
+        AppError(color_eyre::eyre::eyre!(
+            "Failed to create admin cookie: {}",
+            e
+        ))
+    })?;
+
+    Ok(jar.add(cookie).add(clear_two_factor_cookie()))
+}
+
+/// Short-lived cookie carrying the admin from the password step to the code step
+fn pending_two_factor_cookie(
+    config: &Config,
+    admin: &Admin,
+) -> Result<axum_extra::extract::cookie::Cookie<'static>, AppError> {
+    create_two_factor_cookie(admin.id.clone(), &config.security.jwt_secret).map_err(|e| {
+        AppError(color_eyre::eyre::eyre!(
+            "Failed to create two-factor cookie: {}",
+            e
+        ))
+    })
+}
+
+fn render_dashboard(
+    templates: &Environment<'static>,
+    admin: &Admin,
+    csrf_token: CsrfToken,
+) -> Result<Html<String>, AppError> {
+    let context = AdminDashboardContext {
+        username: admin.username.clone(),
+        permissions: Permissions::for_role(admin.role),
+        csrf_token,
+    };
+    let tmpl = templates.get_template("admin_dashboard.html")?;
+    let rendered = tmpl.render(context)?;
+    Ok(Html(rendered))
+}
+
+fn render_two_factor_step(
+    templates: &Environment<'static>,
+    csrf_token: CsrfToken,
+    error: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let context = AdminLoginContext {
+        error,
+        oidc_provider: None,
+        csrf_token,
+    };
+    let tmpl = templates.get_template("admin_login_two_factor.html")?;
     let rendered = tmpl.render(context)?;
     Ok(Html(rendered))
 }
 
 /// Process admin login
-#[instrument(skip(templates, repository, config, jar, form_data), fields(username))]
+#[instrument(
+    skip(templates, repository, config, jar, client, csrf_token, form_data),
+    fields(username, client_ip = ?client.ip)
+)]
 pub async fn admin_login_submit(
     State(templates): State<Arc<Environment<'static>>>,
     State(repository): State<RuleRepository>,
     State(config): State<Config>,
     jar: CookieJar,
+    client: ClientInfo,
+    csrf_token: CsrfToken,
     Form(form_data): Form<AdminLoginForm>,
 ) -> Result<(CookieJar, Html<String>), AppError> {
     // Record username in span (but not password for security)
     Span::current().record("username", &form_data.username);
 
-    // Find admin by username
+    let throttle_config = &config.security.login_throttle;
+    let now = Utc::now().naive_utc();
+    let subjects = login_subjects(&form_data.username, client.ip);
+
+    // Refuse throttled attempts before spending time on Argon2
+    if let Some(error) = login_throttle_error(&repository, throttle_config, &subjects, now)? {
+        return Ok((
+            jar,
+            render_login(&templates, &config, csrf_token, Some(error))?,
+        ));
+    }
+
+    // Verify password using Argon2
     let admin = match repository.find_admin_by_username(&form_data.username)? {
-        Some(admin) => admin,
-        None => {
-            // Show login form with error
-            let context = AdminLoginContext {
-                error: Some("Invalid username or password".to_string()),
-            };
-            let tmpl = templates.get_template("admin_login.html")?;
-            let rendered = tmpl.render(context)?;
-            return Ok((jar, Html(rendered)));
+        Some(admin) if verify_password(&form_data.password, &admin.password_hash)? => admin,
+        _ => {
+            record_login_failure(&repository, throttle_config, &subjects, now)?;
+            let error = Some("Invalid username or password".to_string());
+            return Ok((jar, render_login(&templates, &config, csrf_token, error)?));
         }
     };
 
-    // Verify password using Argon2
-    let argon2 = Argon2::default();
-    let parsed_hash = PasswordHash::new(&admin.password_hash).map_err(|e| {
+    // Password is correct
+    continue_login(
+        &templates,
+        &repository,
+        &config,
+        jar,
+        &client,
+        csrf_token,
+        &admin,
+    )
+}
+
+/// Continue a login whose first factor (password or identity provider) has passed:
+/// ask for the second factor, start a required enrolment, or issue the session
+fn continue_login(
+    templates: &Environment<'static>,
+    repository: &RuleRepository,
+    config: &Config,
+    jar: CookieJar,
+    client: &ClientInfo,
+    csrf_token: CsrfToken,
+    admin: &Admin,
+) -> Result<(CookieJar, Html<String>), AppError> {
+    // Ask for the second factor if the admin has one
+    if admin.two_factor_enabled() {
+        let jar = jar.add(pending_two_factor_cookie(config, admin)?);
+        return Ok((jar, render_two_factor_step(templates, csrf_token, None)?));
+    }
+
+    // Enrolment is mandatory before the first session when two-factor is required
+    if repository.is_two_factor_required()? {
+        let secret = two_factor::generate_secret();
+        repository.set_admin_totp_secret(&admin.id, Some(&secret))?;
+        let jar = jar.add(pending_two_factor_cookie(config, admin)?);
+        let page = render_two_factor_setup(
+            templates,
+            csrf_token,
+            &admin.username,
+            &secret,
+            "/admin/login/two-factor",
+            true,
+            None,
+        )?;
+        return Ok((jar, page));
+    }
+
+    let jar = start_admin_session(repository, config, jar, admin, client)?;
+    Ok((jar, render_dashboard(templates, admin, csrf_token)?))
+}
+
+/// Process the second login step: a TOTP code, a recovery code, or a required enrolment
+#[instrument(
+    skip(templates, repository, config, jar, client, csrf_token, form_data),
+    fields(username, client_ip = ?client.ip)
+)]
+pub async fn admin_login_two_factor_submit(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(config): State<Config>,
+    jar: CookieJar,
+    client: ClientInfo,
+    csrf_token: CsrfToken,
+    Form(form_data): Form<TwoFactorCodeForm>,
+) -> Result<(CookieJar, Html<String>), AppError> {
+    let expired = || Some("Your login has expired. Please sign in again.".to_string());
+
+    let admin = match verify_two_factor_cookie(&jar, &config.security.jwt_secret) {
+        Ok(claims) => repository
+            .get_admin_by_id(&claims.admin_id)?
+            .filter(|admin| admin.is_active),
+        Err(_) => None,
+    };
+    let Some(admin) = admin else {
+        let jar = jar.add(clear_two_factor_cookie());
+        return Ok((
+            jar,
+            render_login(&templates, &config, csrf_token, expired())?,
+        ));
+    };
+    Span::current().record("username", &admin.username);
+
+    let throttle_config = &config.security.login_throttle;
+    let now = Utc::now().naive_utc();
+    let subjects = login_subjects(&admin.username, client.ip);
+
+    if let Some(error) = login_throttle_error(&repository, throttle_config, &subjects, now)? {
+        let jar = jar.add(clear_two_factor_cookie());
+        return Ok((
+            jar,
+            render_login(&templates, &config, csrf_token, Some(error))?,
+        ));
+    }
+
+    if admin.two_factor_enabled() {
+        if verify_second_factor(&repository, &admin, &form_data.code)? {
+            let jar = start_admin_session(&repository, &config, jar, &admin, &client)?;
+            return Ok((jar, render_dashboard(&templates, &admin, csrf_token)?));
+        }
+
+        record_login_failure(&repository, throttle_config, &subjects, now)?;
+        let error = Some("Invalid authentication code".to_string());
+        return Ok((jar, render_two_factor_step(&templates, csrf_token, error)?));
+    }
+
+    // Not enrolled yet: this is the mandatory enrolment started by the password step
+    let Some(secret) = admin.totp_secret.clone() else {
+        let jar = jar.add(clear_two_factor_cookie());
+        return Ok((
+            jar,
+            render_login(&templates, &config, csrf_token, expired())?,
+        ));
+    };
+
+    let Some(step) =
+        two_factor::verify_current_code(&secret, &admin.username, &form_data.code, None)?
+    else {
+        record_login_failure(&repository, throttle_config, &subjects, now)?;
+        let page = render_two_factor_setup(
+            &templates,
+            csrf_token,
+            &admin.username,
+            &secret,
+            "/admin/login/two-factor",
+            true,
+            Some(
+                "That code did not match. Check the time on your device and try again.".to_string(),
+            ),
+        )?;
+        return Ok((jar, page));
+    };
+
+    repository.enable_admin_totp(&admin.id, step)?;
+    let codes = issue_recovery_codes(&repository, &admin.id)?;
+    repository.record_audit_event(NewAuditLogEntry::new(
+        Some(admin.id.clone()),
+        admin.username.clone(),
+        AuditAction::TwoFactorEnabled,
+        Some(admin.id.clone()),
+        None,
+        None,
+    ))?;
+
+    let jar = start_admin_session(&repository, &config, jar, &admin, &client)?;
+    Ok((jar, render_recovery_codes(&templates, codes)?))
+}
+
+#[derive(Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Start an OpenID Connect login by redirecting to the identity provider
+#[instrument(skip(templates, config, jar, csrf_token))]
+pub async fn admin_oidc_login(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(config): State<Config>,
+    jar: CookieJar,
+    csrf_token: CsrfToken,
+) -> Result<Response, AppError> {
+    let Some(oidc_config) = config.security.oidc.clone() else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    let pending = PendingLogin::generate();
+    let url = match OidcClient::new(oidc_config)?
+        .authorization_url(&pending)
+        .await
+    {
+        Ok(url) => url,
+        Err(e) => {
+            warn!(error = ?e, "Failed to start OpenID Connect login");
+            let error = Some(
+                "The identity provider is not reachable. Try again or log in with your password."
+                    .to_string(),
+            );
+            return Ok(render_login(&templates, &config, csrf_token, error)?.into_response());
+        }
+    };
+
+    let cookie = create_oidc_cookie(pending, &config.security.jwt_secret).map_err(|e| {
         AppError(color_eyre::eyre::eyre!(
-            "Failed to parse password hash: {}",
+            "Failed to create OIDC cookie: {}",
             e
         ))
     })?;
+    Ok((jar.add(cookie), Redirect::to(&url)).into_response())
+}
 
-    if argon2
-        .verify_password(form_data.password.as_bytes(), &parsed_hash)
-        .is_ok()
-    {
-        // Password is correct - update last login and create signed cookie
-        repository.update_admin_last_login(&admin.id)?;
-
-        let cookie = create_admin_cookie(
-            admin.id,
-            admin.username.clone(),
-            &config.security.jwt_secret,
-            config.session_duration(),
-        )
-        .map_err(|e| {
-            AppError(color_eyre::eyre::eyre!(
-                "Failed to create admin cookie: {}",
-                e
-            ))
-        })?;
-
-        // Show admin dashboard
-        let context = AdminDashboardContext {
-            username: admin.username,
-        };
-        let tmpl = templates.get_template("admin_dashboard.html")?;
-        let rendered = tmpl.render(context)?;
-        Ok((jar.add(cookie), Html(rendered)))
-    } else {
-        // Show login form with error
-        let context = AdminLoginContext {
-            error: Some("Invalid username or password".to_string()),
-        };
-        let tmpl = templates.get_template("admin_login.html")?;
-        let rendered = tmpl.render(context)?;
-        Ok((jar, Html(rendered)))
+/// Finish an OpenID Connect login and map the identity to an admin account.
+/// The identity provider stands in for the password only: admins with two-factor
+/// authentication, or all admins when it is required, still go through the code step.
+#[instrument(
+    skip(templates, repository, config, jar, client, csrf_token, query),
+    fields(username, client_ip = ?client.ip)
+)]
+pub async fn admin_oidc_callback(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(config): State<Config>,
+    jar: CookieJar,
+    client: ClientInfo,
+    csrf_token: CsrfToken,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Result<(CookieJar, Html<String>), AppError> {
+    let pending = verify_oidc_cookie(&jar, &config.security.jwt_secret);
+    // The pending login can only be used once
+    let jar = jar.add(clear_oidc_cookie());
+    let fail = |jar: CookieJar, csrf_token: CsrfToken, message: &str| {
+        Ok((
+            jar,
+            render_login(&templates, &config, csrf_token, Some(message.to_string()))?,
+        ))
+    };
+
+    let Some(oidc_config) = config.security.oidc.clone() else {
+        return fail(jar, csrf_token, "Single sign-on is not configured");
+    };
+    if let Some(error) = &query.error {
+        warn!(error = %error, "Identity provider refused the login");
+        return fail(
+            jar,
+            csrf_token,
+            "The identity provider did not complete the login.",
+        );
     }
+    let (Ok(pending), Some(code), Some(state)) = (pending, &query.code, &query.state) else {
+        return fail(jar, csrf_token, "Your login has expired. Please try again.");
+    };
+    if *state != pending.state {
+        warn!("OpenID Connect callback state does not match");
+        return fail(jar, csrf_token, "Your login has expired. Please try again.");
+    }
+
+    let identity = match OidcClient::new(oidc_config.clone())?
+        .exchange_code(code, &pending)
+        .await
+    {
+        Ok(identity) => identity,
+        Err(e) => {
+            warn!(error = ?e, "OpenID Connect code exchange failed");
+            return fail(
+                jar,
+                csrf_token,
+                "The login could not be verified. Please try again.",
+            );
+        }
+    };
+
+    let admin = match oidc_config.match_claim {
+        OidcMatchClaim::Subject => repository.find_admin_by_oidc_subject(&identity.subject)?,
+        // Unverified addresses could be chosen freely at some providers
+        OidcMatchClaim::Email => match (&identity.email, identity.email_verified) {
+            (Some(email), true) => repository.find_admin_by_email(email)?,
+            _ => None,
+        },
+    };
+    let Some(admin) = admin else {
+        warn!(
+            subject = %identity.subject,
+            email = ?identity.email,
+            "No active admin account for OpenID Connect identity"
+        );
+        return fail(
+            jar,
+            csrf_token,
+            "No admin account is linked to this identity.",
+        );
+    };
+    Span::current().record("username", &admin.username);
+
+    continue_login(
+        &templates,
+        &repository,
+        &config,
+        jar,
+        &client,
+        csrf_token,
+        &admin,
+    )
 }
 
 /// Show admin dashboard (protected route)
-#[instrument(skip(templates, admin), fields(admin_username = %admin.username()))]
+#[instrument(skip(templates, admin, csrf_token), fields(admin_username = %admin.username()))]
 pub async fn admin_dashboard(
     State(templates): State<Arc<Environment<'static>>>,
     admin: AdminToken,
+    csrf_token: CsrfToken,
 ) -> Result<Html<String>, AppError> {
     let context = AdminDashboardContext {
         username: admin.username().to_string(),
+        permissions: admin.permissions(),
+        csrf_token,
     };
     let tmpl = templates.get_template("admin_dashboard.html")?;
     let rendered = tmpl.render(context)?;
     Ok(Html(rendered))
 }
 
-/// Admin logout (clears cookie and redirects to login)
-#[instrument(skip(jar))]
-pub async fn admin_logout(jar: CookieJar) -> Result<(CookieJar, Redirect), AppError> {
+/// Admin logout via POST (revokes the session, clears cookie and redirects to login)
+#[instrument(skip(repository, admin, jar))]
+pub async fn admin_logout(
+    State(repository): State<RuleRepository>,
+    admin: Result<AdminToken, AdminAuthError>,
+    jar: CookieJar,
+) -> Result<(CookieJar, Redirect), AppError> {
+    if let Ok(admin) = admin {
+        repository.revoke_admin_session(admin.admin_id(), admin.session_id())?;
+    }
     let clear_cookie = clear_admin_cookie();
     Ok((jar.add(clear_cookie), Redirect::to("/admin/login")))
 }
 
 /// Show password change form (protected route)
-#[instrument(skip(templates, _admin), fields(admin_username = %_admin.username()))]
+#[instrument(skip(templates, _admin, csrf_token), fields(admin_username = %_admin.username()))]
 pub async fn admin_change_password_form(
     State(templates): State<Arc<Environment<'static>>>,
     _admin: AdminToken,
+    csrf_token: CsrfToken,
 ) -> Result<Html<String>, AppError> {
     let context = ChangePasswordContext {
         error: None,
         success: None,
+        csrf_token,
     };
     let tmpl = templates.get_template("admin_change_password.html")?;
     let rendered = tmpl.render(context)?;
@@ -168,11 +658,12 @@ pub async fn admin_change_password_form(
 }
 
 /// Process password change (protected route)
-#[instrument(skip(templates, repository, admin, form_data), fields(admin_username = %admin.username()))]
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username()))]
 pub async fn admin_change_password_submit(
     State(templates): State<Arc<Environment<'static>>>,
     State(repository): State<RuleRepository>,
     admin: AdminToken,
+    csrf_token: CsrfToken,
     Form(form_data): Form<ChangePasswordForm>,
 ) -> Result<Html<String>, AppError> {
     // Validate form data
@@ -180,16 +671,20 @@ pub async fn admin_change_password_submit(
         let context = ChangePasswordContext {
             error: Some("New passwords do not match".to_string()),
             success: None,
+            csrf_token,
         };
         let tmpl = templates.get_template("admin_change_password.html")?;
         let rendered = tmpl.render(context)?;
         return Ok(Html(rendered));
     }
 
-    if form_data.new_password.len() < 8 {
+    if form_data.new_password.len() < MIN_PASSWORD_LENGTH {
         let context = ChangePasswordContext {
-            error: Some("New password must be at least 8 characters".to_string()),
+            error: Some(format!(
+                "New password must be at least {MIN_PASSWORD_LENGTH} characters"
+            )),
             success: None,
+            csrf_token,
         };
         let tmpl = templates.get_template("admin_change_password.html")?;
         let rendered = tmpl.render(context)?;
@@ -202,21 +697,11 @@ pub async fn admin_change_password_submit(
         .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Admin not found")))?;
 
     // Verify current password
-    let argon2 = Argon2::default();
-    let current_parsed_hash = PasswordHash::new(&admin_record.password_hash).map_err(|e| {
-        AppError(color_eyre::eyre::eyre!(
-            "Failed to parse current password hash: {}",
-            e
-        ))
-    })?;
-
-    if argon2
-        .verify_password(form_data.current_password.as_bytes(), &current_parsed_hash)
-        .is_err()
-    {
+    if !verify_password(&form_data.current_password, &admin_record.password_hash)? {
         let context = ChangePasswordContext {
             error: Some("Current password is incorrect".to_string()),
             success: None,
+            csrf_token,
         };
         let tmpl = templates.get_template("admin_change_password.html")?;
         let rendered = tmpl.render(context)?;
@@ -224,16 +709,7 @@ pub async fn admin_change_password_submit(
     }
 
     // Hash new password
-    let salt = SaltString::generate(&mut OsRng);
-    let new_password_hash = argon2
-        .hash_password(form_data.new_password.as_bytes(), &salt)
-        .map_err(|e| {
-            AppError(color_eyre::eyre::eyre!(
-                "Failed to hash new password: {}",
-                e
-            ))
-        })?
-        .to_string();
+    let new_password_hash = hash_password(&form_data.new_password)?;
 
     // Update password in database (with current hash verification for extra security)
     repository.update_admin_password(
@@ -241,17 +717,713 @@ pub async fn admin_change_password_submit(
         &admin_record.password_hash,
         &new_password_hash,
     )?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::PasswordChanged,
+        &admin_record.id,
+        None,
+        None,
+    )?;
+
+    // Anyone holding an old session must log in with the new password
+    let revoked = repository.revoke_admin_sessions(&admin_record.id, Some(admin.session_id()))?;
 
     // Show success message
+    let success = if revoked > 0 {
+        format!("Password changed successfully. {revoked} other session(s) were logged out.")
+    } else {
+        "Password changed successfully".to_string()
+    };
     let context = ChangePasswordContext {
         error: None,
-        success: Some("Password changed successfully".to_string()),
+        success: Some(success),
+        csrf_token,
     };
     let tmpl = templates.get_template("admin_change_password.html")?;
     let rendered = tmpl.render(context)?;
     Ok(Html(rendered))
 }
 
+// Admin Account Management Handlers
+
+#[derive(Deserialize)]
+pub struct CreateAdminForm {
+    pub username: String,
+    pub role: AdminRole,
+    pub password: String,
+    pub confirm_password: String,
+}
+
+#[derive(Deserialize)]
+pub struct AdminRoleForm {
+    pub role: AdminRole,
+}
+
+#[derive(Deserialize)]
+pub struct AdminStatusForm {
+    pub active: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ResetPasswordForm {
+    pub new_password: String,
+    pub confirm_password: String,
+}
+
+#[derive(Serialize)]
+struct AdminAccountRow {
+    admin: crate::models::Admin,
+    last_login: Option<String>,
+    is_current: bool,
+}
+
+#[derive(Deserialize)]
+pub struct UnlockLoginForm {
+    pub scope: ThrottleScope,
+    pub subject: String,
+}
+
+#[derive(Deserialize)]
+pub struct AdminIdentityForm {
+    pub email: Option<String>,
+    pub oidc_subject: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TwoFactorPolicyForm {
+    pub required: bool,
+}
+
+#[derive(Serialize)]
+struct LoginLockoutRow {
+    throttle: LoginThrottle,
+    locked_until: String,
+}
+
+#[derive(Serialize)]
+struct AdminAccountsContext {
+    accounts: Vec<AdminAccountRow>,
+    lockouts: Vec<LoginLockoutRow>,
+    roles: Vec<&'static str>,
+    two_factor_required: bool,
+    error: Option<String>,
+    success: Option<String>,
+    csrf_token: CsrfToken,
+}
+
+/// Check a new password against the confirmation and length requirements
+fn validate_new_password(password: &str, confirmation: &str) -> Option<String> {
+    if password != confirmation {
+        return Some("Passwords do not match".to_string());
+    }
+    if password.len() < MIN_PASSWORD_LENGTH {
+        return Some(format!(
+            "Password must be at least {MIN_PASSWORD_LENGTH} characters"
+        ));
+    }
+    None
+}
+
+fn render_admin_accounts(
+    templates: &Environment<'static>,
+    repository: &RuleRepository,
+    current_admin_id: &str,
+    csrf_token: CsrfToken,
+    error: Option<String>,
+    success: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let accounts = repository
+        .get_admins()?
+        .into_iter()
+        .map(|admin| AdminAccountRow {
+            last_login: admin
+                .last_login
+                .map(|login| login.format("%Y-%m-%d %H:%M").to_string()),
+            is_current: admin.id == current_admin_id,
+            admin,
+        })
+        .collect();
+
+    let lockouts = repository
+        .get_active_login_lockouts(Utc::now().naive_utc())?
+        .into_iter()
+        .map(|throttle| LoginLockoutRow {
+            locked_until: throttle
+                .locked_until
+                .map(|until| until.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default(),
+            throttle,
+        })
+        .collect();
+
+    let context = AdminAccountsContext {
+        accounts,
+        lockouts,
+        roles: AdminRole::ALL.iter().map(AdminRole::as_str).collect(),
+        two_factor_required: repository.is_two_factor_required()?,
+        error,
+        success,
+        csrf_token,
+    };
+    let tmpl = templates.get_template("admin_accounts.html")?;
+    let rendered = tmpl.render(context)?;
+    Ok(Html(rendered))
+}
+
+/// List admin accounts with their last login
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username()))]
+pub async fn admin_accounts(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+) -> Result<Html<String>, AppError> {
+    render_admin_accounts(
+        &templates,
+        &repository,
+        admin.admin_id(),
+        csrf_token,
+        None,
+        None,
+    )
+}
+
+/// Create a new admin account
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username(), new_username = %form_data.username))]
+pub async fn create_admin_account(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Form(form_data): Form<CreateAdminForm>,
+) -> Result<Html<String>, AppError> {
+    let username = form_data.username.trim();
+
+    let error = if username.is_empty() {
+        Some("Username is required".to_string())
+    } else if repository.get_admin_by_username(username)?.is_some() {
+        Some(format!("An admin named '{username}' already exists"))
+    } else {
+        validate_new_password(&form_data.password, &form_data.confirm_password)
+    };
+
+    if error.is_some() {
+        return render_admin_accounts(
+            &templates,
+            &repository,
+            admin.admin_id(),
+            csrf_token,
+            error,
+            None,
+        );
+    }
+
+    let password_hash = hash_password(&form_data.password)?;
+    let created = repository.create_admin(crate::models::NewAdmin::new(
+        username.to_string(),
+        password_hash,
+        form_data.role,
+    ))?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::AdminCreated,
+        &created.id,
+        None,
+        admin_snapshot(&created),
+    )?;
+
+    render_admin_accounts(
+        &templates,
+        &repository,
+        admin.admin_id(),
+        csrf_token,
+        None,
+        Some(format!("Created admin '{username}'")),
+    )
+}
+
+/// Activate or deactivate an admin account
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username(), target_admin_id = %target_admin_id, active = form_data.active))]
+pub async fn set_admin_account_status(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Path(target_admin_id): Path<String>,
+    Form(form_data): Form<AdminStatusForm>,
+) -> Result<Html<String>, AppError> {
+    // Deactivating yourself would lock you out of this page
+    if target_admin_id == admin.admin_id() && !form_data.active {
+        return render_admin_accounts(
+            &templates,
+            &repository,
+            admin.admin_id(),
+            csrf_token,
+            Some("You cannot deactivate your own account".to_string()),
+            None,
+        );
+    }
+
+    let target = repository
+        .get_admin_by_id(&target_admin_id)?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Admin not found")))?;
+
+    repository.set_admin_active(&target.id, form_data.active)?;
+    if !form_data.active {
+        repository.revoke_admin_sessions(&target.id, None)?;
+    }
+    let updated = repository.get_admin_by_id(&target.id)?;
+    record_audit(
+        &repository,
+        &admin,
+        if form_data.active {
+            AuditAction::AdminActivated
+        } else {
+            AuditAction::AdminDeactivated
+        },
+        &target.id,
+        admin_snapshot(&target),
+        updated.as_ref().and_then(admin_snapshot),
+    )?;
+
+    let message = if form_data.active {
+        format!("Activated admin '{}'", target.username)
+    } else {
+        format!("Deactivated admin '{}'", target.username)
+    };
+    render_admin_accounts(
+        &templates,
+        &repository,
+        admin.admin_id(),
+        csrf_token,
+        None,
+        Some(message),
+    )
+}
+
+/// Change the role of another admin
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username(), target_admin_id = %target_admin_id, role = form_data.role.as_str()))]
+pub async fn set_admin_account_role(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Path(target_admin_id): Path<String>,
+    Form(form_data): Form<AdminRoleForm>,
+) -> Result<Html<String>, AppError> {
+    // Demoting yourself would lock you out of this page
+    if target_admin_id == admin.admin_id() {
+        return render_admin_accounts(
+            &templates,
+            &repository,
+            admin.admin_id(),
+            csrf_token,
+            Some("You cannot change your own role".to_string()),
+            None,
+        );
+    }
+
+    let target = repository
+        .get_admin_by_id(&target_admin_id)?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Admin not found")))?;
+
+    repository.set_admin_role(&target.id, form_data.role)?;
+    let updated = repository.get_admin_by_id(&target.id)?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::AdminRoleChanged,
+        &target.id,
+        admin_snapshot(&target),
+        updated.as_ref().and_then(admin_snapshot),
+    )?;
+
+    render_admin_accounts(
+        &templates,
+        &repository,
+        admin.admin_id(),
+        csrf_token,
+        None,
+        Some(format!(
+            "'{}' is now {}",
+            target.username,
+            form_data.role.as_str()
+        )),
+    )
+}
+
+/// Set a new password for another admin
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username(), target_admin_id = %target_admin_id))]
+pub async fn reset_admin_account_password(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Path(target_admin_id): Path<String>,
+    Form(form_data): Form<ResetPasswordForm>,
+) -> Result<Html<String>, AppError> {
+    let target = repository
+        .get_admin_by_id(&target_admin_id)?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Admin not found")))?;
+
+    if let Some(error) = validate_new_password(&form_data.new_password, &form_data.confirm_password)
+    {
+        return render_admin_accounts(
+            &templates,
+            &repository,
+            admin.admin_id(),
+            csrf_token,
+            Some(error),
+            None,
+        );
+    }
+
+    let password_hash = hash_password(&form_data.new_password)?;
+    repository.reset_admin_password(&target.id, &password_hash)?;
+    repository.revoke_admin_sessions(&target.id, None)?;
+    repository.clear_login_throttle(ThrottleScope::Username, &target.username)?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::AdminPasswordReset,
+        &target.id,
+        None,
+        None,
+    )?;
+
+    render_admin_accounts(
+        &templates,
+        &repository,
+        admin.admin_id(),
+        csrf_token,
+        None,
+        Some(format!("Password reset for '{}'", target.username)),
+    )
+}
+
+/// Log another admin out of every session, e.g. after a stolen device
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username(), target_admin_id = %target_admin_id))]
+pub async fn revoke_admin_account_sessions(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Path(target_admin_id): Path<String>,
+) -> Result<Html<String>, AppError> {
+    let target = repository
+        .get_admin_by_id(&target_admin_id)?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Admin not found")))?;
+
+    let revoked = repository.revoke_admin_sessions(&target.id, None)?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::SessionsRevoked,
+        &target.id,
+        None,
+        Some(serde_json::json!({ "sessions": revoked })),
+    )?;
+
+    render_admin_accounts(
+        &templates,
+        &repository,
+        admin.admin_id(),
+        csrf_token,
+        None,
+        Some(format!(
+            "Logged '{}' out of {revoked} session(s)",
+            target.username
+        )),
+    )
+}
+
+/// Lift a login lockout before it expires
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username(), scope = form_data.scope.as_str(), subject = %form_data.subject))]
+pub async fn unlock_login(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Form(form_data): Form<UnlockLoginForm>,
+) -> Result<Html<String>, AppError> {
+    let before = repository.get_login_throttle(form_data.scope, &form_data.subject)?;
+    repository.clear_login_throttle(form_data.scope, &form_data.subject)?;
+
+    if let Some(before) = before {
+        record_audit(
+            &repository,
+            &admin,
+            AuditAction::LoginUnlocked,
+            &format!("{}:{}", form_data.scope.as_str(), form_data.subject),
+            serde_json::to_value(&before).ok(),
+            None,
+        )?;
+    }
+
+    render_admin_accounts(
+        &templates,
+        &repository,
+        admin.admin_id(),
+        csrf_token,
+        None,
+        Some(format!(
+            "Unlocked {} '{}'",
+            form_data.scope.as_str(),
+            form_data.subject
+        )),
+    )
+}
+
+/// Link an admin to an identity provider account for single sign-on
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username(), target_admin_id = %target_admin_id))]
+pub async fn set_admin_account_identity(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Path(target_admin_id): Path<String>,
+    Form(form_data): Form<AdminIdentityForm>,
+) -> Result<Html<String>, AppError> {
+    let target = repository
+        .get_admin_by_id(&target_admin_id)?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Admin not found")))?;
+
+    let email = form_data
+        .email
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let subject = form_data
+        .oidc_subject
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+
+    // Each identity may log in as one admin only
+    let others: Vec<_> = repository
+        .get_admins()?
+        .into_iter()
+        .filter(|other| other.id != target.id)
+        .collect();
+    let error = if let Some(email) = email
+        && others
+            .iter()
+            .any(|other| other.email.as_deref() == Some(email.to_lowercase().as_str()))
+    {
+        Some(format!("Another admin already uses {email}"))
+    } else if let Some(subject) = subject
+        && others
+            .iter()
+            .any(|other| other.oidc_subject.as_deref() == Some(subject))
+    {
+        Some("Another admin already uses this subject".to_string())
+    } else {
+        None
+    };
+    if error.is_some() {
+        return render_admin_accounts(
+            &templates,
+            &repository,
+            admin.admin_id(),
+            csrf_token,
+            error,
+            None,
+        );
+    }
+
+    repository.set_admin_identity(&target.id, email, subject)?;
+    let updated = repository.get_admin_by_id(&target.id)?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::AdminIdentityChanged,
+        &target.id,
+        admin_snapshot(&target),
+        updated.as_ref().and_then(admin_snapshot),
+    )?;
+
+    render_admin_accounts(
+        &templates,
+        &repository,
+        admin.admin_id(),
+        csrf_token,
+        None,
+        Some(format!("Single sign-on updated for '{}'", target.username)),
+    )
+}
+
+/// Require or stop requiring two-factor authentication for every admin
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username(), required = form_data.required))]
+pub async fn set_two_factor_policy(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Form(form_data): Form<TwoFactorPolicyForm>,
+) -> Result<Html<String>, AppError> {
+    let before = repository.is_two_factor_required()?;
+    repository.set_setting(
+        two_factor::REQUIRE_TWO_FACTOR_SETTING,
+        if form_data.required { "true" } else { "false" },
+    )?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::TwoFactorPolicyChanged,
+        two_factor::REQUIRE_TWO_FACTOR_SETTING,
+        Some(serde_json::json!({ "required": before })),
+        Some(serde_json::json!({ "required": form_data.required })),
+    )?;
+
+    let message = if form_data.required {
+        "Two-factor authentication is now required; admins without it enrol at their next login"
+    } else {
+        "Two-factor authentication is now optional"
+    };
+    render_admin_accounts(
+        &templates,
+        &repository,
+        admin.admin_id(),
+        csrf_token,
+        None,
+        Some(message.to_string()),
+    )
+}
+
+/// Remove another admin's second factor, e.g. after a lost device
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username(), target_admin_id = %target_admin_id))]
+pub async fn reset_admin_two_factor(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Path(target_admin_id): Path<String>,
+) -> Result<Html<String>, AppError> {
+    let target = repository
+        .get_admin_by_id(&target_admin_id)?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Admin not found")))?;
+
+    repository.disable_admin_two_factor(&target.id)?;
+    // A lost device may also hold a logged-in session
+    repository.revoke_admin_sessions(&target.id, None)?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::TwoFactorDisabled,
+        &target.id,
+        None,
+        None,
+    )?;
+
+    render_admin_accounts(
+        &templates,
+        &repository,
+        admin.admin_id(),
+        csrf_token,
+        None,
+        Some(format!(
+            "Two-factor authentication reset for '{}'",
+            target.username
+        )),
+    )
+}
+
+// Audit Log Handlers
+
+const AUDIT_LOG_PAGE_SIZE: i64 = 50;
+
+#[derive(Deserialize)]
+pub struct AuditLogQuery {
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub start_date: Option<chrono::NaiveDate>,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub page: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct AuditLogRow {
+    entry: AuditLogEntry,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AuditLogContext {
+    entries: Vec<AuditLogRow>,
+    filter: AuditLogFilter,
+    actors: Vec<String>,
+    actions: Vec<&'static str>,
+    page: i64,
+    has_next: bool,
+}
+
+/// Pretty-print a stored JSON snapshot for display
+fn pretty_json(raw: Option<&str>) -> Option<String> {
+    raw.map(|raw| {
+        serde_json::from_str::<serde_json::Value>(raw)
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .unwrap_or_else(|_| raw.to_string())
+    })
+}
+
+/// Browse the audit log with filters
+#[instrument(skip(templates, repository, admin, query), fields(admin_username = %admin.username(), actor = ?query.actor, action = ?query.action))]
+pub async fn admin_audit_log(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: RequireRole<Superadmin>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Html<String>, AppError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let filter = AuditLogFilter {
+        actor: query.actor,
+        action: query.action,
+        entity_type: query.entity_type,
+        entity_id: query.entity_id.map(|id| id.trim().to_string()),
+        start_date: query.start_date,
+        end_date: query.end_date,
+    };
+
+    // Fetch one extra entry to know whether there is a next page
+    let mut entries = repository.get_audit_log(
+        &filter,
+        AUDIT_LOG_PAGE_SIZE + 1,
+        (page - 1) * AUDIT_LOG_PAGE_SIZE,
+    )?;
+    let has_next = entries.len() as i64 > AUDIT_LOG_PAGE_SIZE;
+    entries.truncate(AUDIT_LOG_PAGE_SIZE as usize);
+
+    let entries = entries
+        .into_iter()
+        .map(|entry| AuditLogRow {
+            before: pretty_json(entry.before_json.as_deref()),
+            after: pretty_json(entry.after_json.as_deref()),
+            entry,
+        })
+        .collect();
+
+    let context = AuditLogContext {
+        entries,
+        filter,
+        actors: repository.get_audit_log_actors()?,
+        actions: AuditAction::ALL
+            .iter()
+            .map(|action| action.as_str())
+            .collect(),
+        page,
+        has_next,
+    };
+
+    let tmpl = templates.get_template("admin_audit_log.html")?;
+    let rendered = tmpl.render(context)?;
+    Ok(Html(rendered))
+}
+
 // Question Management Handlers
 
 #[derive(Deserialize)]
@@ -280,6 +1452,7 @@ struct QuestionsListContext {
     status_filter: Option<QuestionStatus>,
     difficulty_filter: Option<String>,
     rule_set_name: String,
+    permissions: Permissions,
 }
 
 #[derive(Serialize)]
@@ -293,20 +1466,22 @@ struct QuestionFormContext {
     question: Option<crate::models::quiz::QuizQuestion>,
     answers: Option<Vec<crate::models::quiz::QuizAnswer>>,
     errors: Option<Vec<String>>,
+    csrf_token: CsrfToken,
 }
 
 #[derive(Serialize)]
 struct QuestionPreviewContext {
     question: crate::models::quiz::QuizQuestion,
     answers: Vec<crate::models::quiz::QuizAnswer>,
+    permissions: Permissions,
 }
 
 /// Show questions list with filtering
-#[instrument(skip(templates, repository, _admin, filters), fields(admin_username = %_admin.username(), search = ?filters.search, status = ?filters.status, difficulty = ?filters.difficulty))]
+#[instrument(skip(templates, repository, admin, filters), fields(admin_username = %admin.username(), search = ?filters.search, status = ?filters.status, difficulty = ?filters.difficulty))]
 pub async fn questions_list(
     State(templates): State<Arc<Environment<'static>>>,
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    admin: AdminToken,
     Query(filters): Query<QuestionFilters>,
 ) -> Result<Html<String>, AppError> {
     // Parse status filter
@@ -336,6 +1511,7 @@ pub async fn questions_list(
         status_filter: filters.status,
         difficulty_filter: filters.difficulty,
         rule_set_name: "Ultimate Frisbee Rules".to_string(),
+        permissions: admin.permissions(),
     };
 
     let tmpl = templates.get_template("admin_questions_list.html")?;
@@ -344,15 +1520,17 @@ pub async fn questions_list(
 }
 
 /// Show new question form
-#[instrument(skip(templates, _admin), fields(admin_username = %_admin.username()))]
+#[instrument(skip(templates, _admin, csrf_token), fields(admin_username = %_admin.username()))]
 pub async fn new_question_form(
     State(templates): State<Arc<Environment<'static>>>,
-    _admin: AdminToken,
+    _admin: RequireRole<Editor>,
+    csrf_token: CsrfToken,
 ) -> Result<Html<String>, AppError> {
     let context = QuestionFormContext {
         question: None,
         answers: None,
         errors: None,
+        csrf_token,
     };
 
     let tmpl = templates.get_template("admin_question_form.html")?;
@@ -361,10 +1539,10 @@ pub async fn new_question_form(
 }
 
 /// Create new question
-#[instrument(skip(repository, _admin, form_data), fields(admin_username = %_admin.username(), question_text, difficulty = %form_data.difficulty_level))]
+#[instrument(skip(repository, admin, form_data), fields(admin_username = %admin.username(), question_text, difficulty = %form_data.difficulty_level))]
 pub async fn create_question(
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    admin: RequireRole<Editor>,
     Form(form_data): Form<QuestionForm>,
 ) -> Result<Redirect, AppError> {
     // Basic validation
@@ -428,17 +1606,26 @@ pub async fn create_question(
         .collect();
 
     // Save to database
-    let _created_question = repository.create_question_with_answers(new_question, new_answers)?;
+    let created_question = repository.create_question_with_answers(new_question, new_answers)?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::QuestionCreated,
+        &created_question.id,
+        None,
+        question_snapshot(&repository, &created_question.id)?,
+    )?;
 
     Ok(Redirect::to("/admin/questions"))
 }
 
 /// Show edit question form
-#[instrument(skip(templates, repository, _admin), fields(admin_username = %_admin.username(), question_id = %question_id))]
+#[instrument(skip(templates, repository, _admin, csrf_token), fields(admin_username = %_admin.username(), question_id = %question_id))]
 pub async fn edit_question_form(
     State(templates): State<Arc<Environment<'static>>>,
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    _admin: RequireRole<Editor>,
+    csrf_token: CsrfToken,
     Path(question_id): Path<String>,
 ) -> Result<Html<String>, AppError> {
     // Get question with answers
@@ -450,6 +1637,7 @@ pub async fn edit_question_form(
         question: Some(question),
         answers: Some(answers),
         errors: None,
+        csrf_token,
     };
 
     let tmpl = templates.get_template("admin_question_form.html")?;
@@ -458,11 +1646,11 @@ pub async fn edit_question_form(
 }
 
 /// Show question preview
-#[instrument(skip(templates, repository, _admin), fields(admin_username = %_admin.username(), question_id = %question_id))]
+#[instrument(skip(templates, repository, admin), fields(admin_username = %admin.username(), question_id = %question_id))]
 pub async fn preview_question(
     State(templates): State<Arc<Environment<'static>>>,
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    admin: AdminToken,
     Path(question_id): Path<String>,
 ) -> Result<Html<String>, AppError> {
     // Get question with answers
@@ -470,7 +1658,11 @@ pub async fn preview_question(
         .get_question_with_answers(&question_id)?
         .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Question not found")))?;
 
-    let context = QuestionPreviewContext { question, answers };
+    let context = QuestionPreviewContext {
+        question,
+        answers,
+        permissions: admin.permissions(),
+    };
 
     let tmpl = templates.get_template("admin_question_preview.html")?;
     let rendered = tmpl.render(context)?;
@@ -478,16 +1670,26 @@ pub async fn preview_question(
 }
 
 /// Update question
-#[instrument(skip(repository, _admin, form_data), fields(admin_username = %_admin.username(), question_id = %question_id, action = ?form_data.action, difficulty = %form_data.difficulty_level))]
+#[instrument(skip(repository, admin, form_data), fields(admin_username = %admin.username(), question_id = %question_id, action = ?form_data.action, difficulty = %form_data.difficulty_level))]
 pub async fn update_question(
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    admin: RequireRole<Editor>,
     Path(question_id): Path<String>,
     Form(form_data): Form<QuestionForm>,
 ) -> Result<Redirect, AppError> {
+    let before = question_snapshot(&repository, &question_id)?;
+
     // Handle delete action
     if form_data.action.as_deref() == Some("delete") {
         repository.delete_question(&question_id)?;
+        record_audit(
+            &repository,
+            &admin,
+            AuditAction::QuestionDeleted,
+            &question_id,
+            before,
+            None,
+        )?;
         return Ok(Redirect::to("/admin/questions"));
     }
 
@@ -528,6 +1730,15 @@ pub async fn update_question(
         repository.update_question_answers(&question_id, new_answers)?;
     }
 
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::QuestionUpdated,
+        &question_id,
+        before,
+        question_snapshot(&repository, &question_id)?,
+    )?;
+
     Ok(Redirect::to("/admin/questions"))
 }
 
@@ -537,25 +1748,34 @@ pub async fn update_question(
 pub struct AdminStatsContext {
     pub aggregate_stats: crate::models::quiz::AggregateStatistics,
     pub question_stats: Vec<crate::models::quiz::QuestionStatistics>,
+    /// Attempts segmented by the sessions' quiz and difficulty modes
+    pub mode_stats: Vec<crate::models::quiz::ModeStatistics>,
     pub current_filter: String,
     pub current_start_date: String,
     pub current_end_date: String,
     pub current_filter_value: String,
+    pub permissions: Permissions,
+    pub csrf_token: CsrfToken,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct StatsQueryParams {
+    /// `7days`, `30days` or `custom`; anything else exports all attempts
     pub filter: Option<String>,
+    /// First day of a `custom` range
     pub start_date: Option<chrono::NaiveDate>,
+    /// Last day of a `custom` range
     pub end_date: Option<chrono::NaiveDate>,
 }
 
 /// Show admin statistics dashboard
-#[instrument(skip(templates, repository, _admin), fields(admin_username = %_admin.username()))]
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username()))]
 pub async fn admin_stats_dashboard(
     State(templates): State<Arc<Environment<'static>>>,
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    admin: RequireRole<Analyst>,
+    csrf_token: CsrfToken,
     Query(params): Query<StatsQueryParams>,
 ) -> Result<Html<String>, AppError> {
     // Determine date range based on filter
@@ -596,14 +1816,18 @@ pub async fn admin_stats_dashboard(
         start_date, end_date, None, // No limit
         None, // No offset
     )?;
+    let mode_stats = repository.get_mode_statistics(start_date, end_date)?;
 
     let context = AdminStatsContext {
         aggregate_stats,
         question_stats,
+        mode_stats,
         current_filter: filter_name,
         current_start_date: start_date.map(|d| d.to_string()).unwrap_or_default(),
         current_end_date: end_date.map(|d| d.to_string()).unwrap_or_default(),
         current_filter_value: filter_value,
+        permissions: admin.permissions(),
+        csrf_token,
     };
 
     let template = templates.get_template("admin_stats.html")?;
@@ -617,13 +1841,16 @@ pub async fn admin_stats_dashboard(
 struct QuestionDetailStatsContext {
     pub question_detail_stats: crate::models::quiz::QuestionDetailStats,
     pub current_filter: String,
+    pub permissions: Permissions,
+    pub csrf_token: CsrfToken,
 }
 
-#[instrument(skip(templates, repository, _admin), fields(admin_username = %_admin.username(), question_id = %question_id))]
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username(), question_id = %question_id))]
 pub async fn admin_question_detail_stats(
     State(templates): State<Arc<Environment<'static>>>,
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    admin: RequireRole<Analyst>,
+    csrf_token: CsrfToken,
     Path(question_id): Path<String>,
     Query(params): Query<StatsQueryParams>,
 ) -> Result<Html<String>, AppError> {
@@ -655,6 +1882,8 @@ pub async fn admin_question_detail_stats(
     let context = QuestionDetailStatsContext {
         question_detail_stats,
         current_filter: filter_name,
+        permissions: admin.permissions(),
+        csrf_token,
     };
 
     let template = templates.get_template("admin_question_detail_stats.html")?;
@@ -669,7 +1898,7 @@ pub async fn admin_question_detail_stats(
 #[instrument(skip(repository, _admin), fields(admin_username = %_admin.username()))]
 pub async fn success_trends_chart(
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    _admin: RequireRole<Analyst>,
     theme: Theme,
     Query(params): Query<StatsQueryParams>,
 ) -> Result<axum::response::Response, AppError> {
@@ -709,7 +1938,7 @@ pub async fn success_trends_chart(
 #[instrument(skip(repository, _admin), fields(admin_username = %_admin.username()))]
 pub async fn difficulty_distribution_chart(
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    _admin: RequireRole<Analyst>,
     theme: Theme,
     Query(params): Query<StatsQueryParams>,
 ) -> Result<axum::response::Response, AppError> {
@@ -749,7 +1978,7 @@ pub async fn difficulty_distribution_chart(
 #[instrument(skip(repository, _admin), fields(admin_username = %_admin.username()))]
 pub async fn question_performance_chart(
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    _admin: RequireRole<Analyst>,
     theme: Theme,
     Query(params): Query<StatsQueryParams>,
 ) -> Result<axum::response::Response, AppError> {
@@ -792,7 +2021,7 @@ pub async fn question_performance_chart(
 #[instrument(skip(repository, _admin), fields(admin_username = %_admin.username(), question_id = %question_id))]
 pub async fn answer_distribution_chart(
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    _admin: RequireRole<Analyst>,
     theme: Theme,
     Path(question_id): Path<String>,
     Query(params): Query<StatsQueryParams>,
@@ -835,10 +2064,23 @@ pub async fn answer_distribution_chart(
 }
 
 /// Export admin statistics as Parquet with nested answer structure
+#[utoipa::path(
+    get,
+    path = "/admin/stats/export.parquet",
+    tag = "stats",
+    params(StatsQueryParams),
+    security(("api_token" = []), ("admin_session" = [])),
+    responses(
+        (status = 200, description = "Parquet file with one row per question and nested answers", content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 303, description = "Not logged in; redirects to the login page"),
+        (status = 401, description = "Invalid API token"),
+        (status = 403, description = "Role below analyst"),
+    )
+)]
 #[instrument(skip(repository, _admin), fields(admin_username = %_admin.username()))]
 pub async fn export_stats_parquet(
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    _admin: RequireRole<Analyst>,
     Query(params): Query<StatsQueryParams>,
 ) -> Result<axum::response::Response, AppError> {
     // Parse date range (same logic as stats dashboard)
@@ -891,10 +2133,23 @@ pub async fn export_stats_parquet(
 }
 
 /// Export admin statistics as CSV with answer selection analytics
+#[utoipa::path(
+    get,
+    path = "/admin/stats/export.csv",
+    tag = "stats",
+    params(StatsQueryParams),
+    security(("api_token" = []), ("admin_session" = [])),
+    responses(
+        (status = 200, description = "CSV file with one row per answer", content_type = "text/csv", body = Vec<u8>),
+        (status = 303, description = "Not logged in; redirects to the login page"),
+        (status = 401, description = "Invalid API token"),
+        (status = 403, description = "Role below analyst"),
+    )
+)]
 #[instrument(skip(repository, _admin), fields(admin_username = %_admin.username()))]
 pub async fn export_stats_csv(
     State(repository): State<RuleRepository>,
-    _admin: AdminToken,
+    _admin: RequireRole<Analyst>,
     Query(params): Query<StatsQueryParams>,
 ) -> Result<axum::response::Response, AppError> {
     // Parse date range (same logic as stats dashboard)
@@ -918,78 +2173,8 @@ pub async fn export_stats_csv(
         .get_questions_with_selection_data_for_export(start_date, end_date)
         .map_err(|e| AppError(color_eyre::eyre::eyre!("Failed to get export data: {}", e)))?;
 
-    // Find maximum number of answers across all questions
-    let max_answers = questions.iter().map(|q| q.answers.len()).max().unwrap_or(0);
-
-    // Build dynamic headers
-    let mut headers = vec![
-        "question_id".to_string(),
-        "question_text".to_string(),
-        "explanation".to_string(),
-        "difficulty_level".to_string(),
-        "rule_references".to_string(),
-        "total_attempts".to_string(),
-        "correct_attempts".to_string(),
-        "success_rate_percent".to_string(),
-    ];
-
-    for i in 1..=max_answers {
-        headers.push(format!("answer_{}_text", i));
-        headers.push(format!("answer_{}_correct", i));
-        headers.push(format!("answer_{}_selections", i));
-        headers.push(format!("answer_{}_percentage", i));
-    }
-    headers.extend_from_slice(&["created_at".to_string(), "updated_at".to_string()]);
-
-    // Write CSV with manual row construction
-    let mut csv_output = Vec::new();
-    {
-        let mut wtr = csv::Writer::from_writer(&mut csv_output);
-        wtr.write_record(&headers).map_err(|e| {
-            AppError(color_eyre::eyre::eyre!(
-                "Failed to write CSV headers: {}",
-                e
-            ))
-        })?;
-
-        for question in questions {
-            let mut row = vec![
-                question.question_id,
-                question.question_text,
-                question.explanation,
-                question.difficulty_level,
-                question.rule_references,
-                question.total_attempts.to_string(),
-                question.correct_attempts.to_string(),
-                format!("{:.1}", question.success_rate_percent),
-            ];
-
-            // Add answers with padding for missing ones
-            for i in 0..max_answers {
-                if let Some(answer) = question.answers.get(i) {
-                    row.push(answer.text.clone());
-                    row.push(answer.is_correct.to_string());
-                    row.push(answer.selection_count.to_string());
-                    row.push(format!("{:.1}", answer.selection_percentage));
-                } else {
-                    row.extend_from_slice(&[
-                        String::new(),
-                        String::new(),
-                        String::new(),
-                        String::new(),
-                    ]);
-                }
-            }
-
-            row.push(question.created_at.format("%Y-%m-%d %H:%M:%S").to_string());
-            row.push(question.updated_at.format("%Y-%m-%d %H:%M:%S").to_string());
-
-            wtr.write_record(&row)
-                .map_err(|e| AppError(color_eyre::eyre::eyre!("Failed to write CSV row: {}", e)))?;
-        }
-        wtr.flush()
-            .map_err(|e| AppError(color_eyre::eyre::eyre!("Failed to flush CSV: {}", e)))?;
-    }
+    let csv_output = crate::analytics::write_csv(questions)
+        .map_err(|e| AppError(color_eyre::eyre::eyre!("Failed to write CSV: {}", e)))?;
 
     // Generate timestamped filename
     let timestamp = Utc::now().format("%Y-%m-%d_%H%M");
@@ -0,0 +1,898 @@
+//! Read-only JSON API under `/api/v1` for embedding the rules elsewhere.
+//!
+//! Content endpoints pick their language from `?lang=`, falling back to the
+//! `Accept-Language` header and then English. Every response carries a weak ETag
+//! derived from its body so clients can revalidate with `If-None-Match`, and list
+//! endpoints page their results with `?page=` and `?per_page=`.
+
+use crate::{
+    handlers::web::{RuleNode, build_rule_tree, find_rule_in_tree},
+    models::{RuleSet, Version},
+    repository::RuleRepository,
+};
+use axum::{
+    Json,
+    extract::{FromRef, FromRequestParts, Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use tower_http::set_header::SetResponseHeaderLayer;
+use tracing::instrument;
+use utoipa::{IntoParams, ToSchema};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+const DEFAULT_LANGUAGE: &str = "en";
+const DEFAULT_PER_PAGE: u32 = 50;
+const MAX_PER_PAGE: u32 = 200;
+
+/// Routes for `/api/v1`, open to any origin since the data is public.
+///
+/// Register handlers with `routes!` only, so each one is added to the OpenAPI document.
+pub fn routes<S>() -> OpenApiRouter<S>
+where
+    RuleRepository: FromRef<S>,
+    S: Clone + Send + Sync + 'static,
+{
+    OpenApiRouter::new()
+        .routes(routes!(list_rule_sets))
+        .routes(routes!(get_rule_set))
+        .routes(routes!(list_versions))
+        .routes(routes!(rule_tree))
+        .routes(routes!(get_rule))
+        .routes(routes!(list_glossary))
+        .routes(routes!(get_glossary_term))
+        .layer(SetResponseHeaderLayer::overriding(
+            header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            HeaderValue::from_static("*"),
+        ))
+}
+
+/// Errors reported to API clients as `{"error": "..."}`
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    NotFound(String),
+    Internal(color_eyre::eyre::Error),
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorBody {
+    error: String,
+}
+
+impl ErrorBody {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self {
+            error: error.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error) = match self {
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ApiError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            ApiError::Internal(err) => {
+                tracing::error!("API error: {:?}", err);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal Server Error".to_string(),
+                )
+            }
+        };
+        (status, Json(ErrorBody::new(error))).into_response()
+    }
+}
+
+impl<E> From<E> for ApiError
+where
+    E: Into<color_eyre::eyre::Error>,
+{
+    fn from(err: E) -> Self {
+        Self::Internal(err.into())
+    }
+}
+
+/// Query string extractor that reports malformed parameters as JSON errors
+pub struct ApiQuery<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|Query(query)| ApiQuery(query))
+            .map_err(|rejection| ApiError::BadRequest(rejection.body_text()))
+    }
+}
+
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageQuery {
+    /// Page number, starting at 1
+    page: Option<u32>,
+    /// Items per page (default 50, at most 200)
+    per_page: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ContentQuery {
+    /// Language code; takes precedence over `Accept-Language`
+    lang: Option<String>,
+    /// Version name; defaults to the current version
+    version: Option<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq, ToSchema)]
+pub struct PageInfo {
+    page: u32,
+    per_page: u32,
+    total: usize,
+    total_pages: usize,
+}
+
+/// Rule set, version and language a content response was rendered for
+#[derive(Serialize, ToSchema)]
+pub struct Scope {
+    rule_set: String,
+    version: String,
+    language: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Page<T> {
+    data: Vec<T>,
+    pagination: PageInfo,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ScopedPage<T> {
+    #[serde(flatten)]
+    scope: Scope,
+    data: Vec<T>,
+    pagination: PageInfo,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RuleSetResource {
+    slug: String,
+    name: String,
+    description: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RuleSetDetail {
+    #[serde(flatten)]
+    rule_set: RuleSetResource,
+    current_version: Option<VersionResource>,
+    languages: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct VersionResource {
+    name: String,
+    effective_from: chrono::NaiveDate,
+    effective_to: Option<chrono::NaiveDate>,
+    description: Option<String>,
+    is_current: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RuleLink {
+    number: String,
+    slug: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RuleResource {
+    #[serde(flatten)]
+    scope: Scope,
+    number: String,
+    slug: String,
+    content_markdown: String,
+    parent: Option<RuleLink>,
+    children: Vec<RuleNode>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GlossaryEntry {
+    slug: String,
+    term: String,
+    definition_markdown: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GlossaryTermResource {
+    #[serde(flatten)]
+    scope: Scope,
+    #[serde(flatten)]
+    entry: GlossaryEntry,
+}
+
+impl From<RuleSet> for RuleSetResource {
+    fn from(rule_set: RuleSet) -> Self {
+        Self {
+            slug: rule_set.slug,
+            name: rule_set.name,
+            description: rule_set.description,
+        }
+    }
+}
+
+impl From<Version> for VersionResource {
+    fn from(version: Version) -> Self {
+        Self {
+            name: version.version_name,
+            effective_from: version.effective_from,
+            effective_to: version.effective_to,
+            description: version.description,
+            is_current: version.is_current,
+        }
+    }
+}
+
+/// Cut one page out of `items`, clamping the page size to `MAX_PER_PAGE`
+pub fn paginate<T>(items: Vec<T>, page: Option<u32>, per_page: Option<u32>) -> (Vec<T>, PageInfo) {
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    let total = items.len();
+    let total_pages = total.div_ceil(per_page as usize);
+
+    let data = items
+        .into_iter()
+        .skip((page as usize - 1) * per_page as usize)
+        .take(per_page as usize)
+        .collect();
+
+    (
+        data,
+        PageInfo {
+            page,
+            per_page,
+            total,
+            total_pages,
+        },
+    )
+}
+
+/// Choose the response language from the query, then `Accept-Language`, then English.
+///
+/// An explicitly requested language must exist; header preferences that cannot be
+/// served are skipped, as usual for content negotiation.
+pub fn negotiate_language(
+    requested: Option<&str>,
+    accept_language: Option<&str>,
+    available: &[String],
+) -> Result<String, ApiError> {
+    let find = |tag: &str| {
+        available
+            .iter()
+            .find(|language| language.eq_ignore_ascii_case(tag))
+            .cloned()
+    };
+
+    if let Some(requested) = requested {
+        return find(requested).ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "Language '{requested}' is not available (available: {})",
+                available.join(", ")
+            ))
+        });
+    }
+
+    let mut preferences: Vec<(&str, f32)> = accept_language
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';').map(str::trim);
+            let tag = parts.next().filter(|tag| !tag.is_empty() && *tag != "*")?;
+            let quality = parts
+                .find_map(|param| param.strip_prefix("q="))
+                .and_then(|q| q.parse().ok())
+                .unwrap_or(1.0);
+            (quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    preferences.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let preferred = preferences
+        .iter()
+        .find_map(|(tag, _)| find(tag).or_else(|| tag.split('-').next().and_then(&find)));
+
+    Ok(preferred
+        .or_else(|| find(DEFAULT_LANGUAGE))
+        .or_else(|| available.first().cloned())
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string()))
+}
+
+/// Weak validator over the serialized body
+fn etag_for(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    let hex: String = digest[..16]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("W/\"{hex}\"")
+}
+
+/// Weak comparison of `If-None-Match` against our ETag
+fn is_not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+}
+
+/// Serialize `value` and answer with 304 when the client already has it
+fn json_response<T: Serialize>(
+    headers: &HeaderMap,
+    language: Option<&str>,
+    value: &T,
+) -> Result<Response, ApiError> {
+    let body = serde_json::to_vec(value)?;
+    let etag = etag_for(&body);
+
+    let mut response = if is_not_modified(headers, &etag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(header::CONTENT_TYPE, "application/json")], body).into_response()
+    };
+
+    let response_headers = response.headers_mut();
+    response_headers.insert(header::ETAG, HeaderValue::from_str(&etag)?);
+    response_headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("public, max-age=300"),
+    );
+    response_headers.insert(header::VARY, HeaderValue::from_static("Accept-Language"));
+    if let Some(language) = language {
+        response_headers.insert(header::CONTENT_LANGUAGE, HeaderValue::from_str(language)?);
+    }
+
+    Ok(response)
+}
+
+fn find_rule_set(repo: &RuleRepository, rule_set_slug: &str) -> Result<RuleSet, ApiError> {
+    repo.get_rule_sets()?
+        .into_iter()
+        .find(|rule_set| rule_set.slug == rule_set_slug)
+        .ok_or_else(|| ApiError::NotFound(format!("Rule set '{rule_set_slug}' not found")))
+}
+
+/// Resolve the requested version of a rule set, or its current one
+fn resolve_version(
+    repo: &RuleRepository,
+    rule_set_slug: &str,
+    version_name: Option<&str>,
+) -> Result<(RuleSet, Version), ApiError> {
+    let rule_set = find_rule_set(repo, rule_set_slug)?;
+    let version = match version_name {
+        Some(name) => repo
+            .get_version_by_name(rule_set_slug, name)?
+            .ok_or_else(|| ApiError::NotFound(format!("Version '{name}' not found")))?,
+        None => repo
+            .get_current_version(rule_set_slug)?
+            .ok_or_else(|| ApiError::NotFound("Rule set has no current version".to_string()))?,
+    };
+    Ok((rule_set, version))
+}
+
+/// Resolve version and language for a content endpoint
+fn resolve_scope(
+    repo: &RuleRepository,
+    headers: &HeaderMap,
+    rule_set_slug: &str,
+    query: &ContentQuery,
+) -> Result<(RuleSet, Version, Scope), ApiError> {
+    let (rule_set, version) = resolve_version(repo, rule_set_slug, query.version.as_deref())?;
+    let available = repo.get_languages_for_version(&version.id)?;
+    let accept_language = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok());
+    let language = negotiate_language(query.lang.as_deref(), accept_language, &available)?;
+
+    let scope = Scope {
+        rule_set: rule_set.slug.clone(),
+        version: version.version_name.clone(),
+        language,
+    };
+    Ok((rule_set, version, scope))
+}
+
+/// List rule sets
+#[utoipa::path(
+    get,
+    path = "/api/v1/rule-sets",
+    tag = "rules",
+    params(PageQuery),
+    responses(
+        (status = 200, description = "Rule sets", body = Page<RuleSetResource>, headers(("ETag" = String, description = "Weak validator for If-None-Match"))),
+        (status = 304, description = "Unchanged since the given ETag"),
+        (status = 400, description = "Malformed query parameters", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo, headers))]
+pub async fn list_rule_sets(
+    State(repo): State<RuleRepository>,
+    headers: HeaderMap,
+    ApiQuery(page): ApiQuery<PageQuery>,
+) -> Result<Response, ApiError> {
+    let rule_sets = repo
+        .get_rule_sets()?
+        .into_iter()
+        .map(RuleSetResource::from)
+        .collect();
+    let (data, pagination) = paginate(rule_sets, page.page, page.per_page);
+
+    json_response(&headers, None, &Page { data, pagination })
+}
+
+/// Rule set with its current version and languages
+#[utoipa::path(
+    get,
+    path = "/api/v1/rule-sets/{rule_set}",
+    tag = "rules",
+    params(("rule_set" = String, Path, description = "Rule set slug")),
+    responses(
+        (status = 200, description = "Rule set with its current version and languages", body = RuleSetDetail, headers(("ETag" = String, description = "Weak validator for If-None-Match"))),
+        (status = 304, description = "Unchanged since the given ETag"),
+        (status = 404, description = "Rule set, version, language or item not found", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo, headers), fields(rule_set_slug = %rule_set_slug))]
+pub async fn get_rule_set(
+    State(repo): State<RuleRepository>,
+    headers: HeaderMap,
+    Path(rule_set_slug): Path<String>,
+) -> Result<Response, ApiError> {
+    let rule_set = find_rule_set(&repo, &rule_set_slug)?;
+    let current_version = repo.get_current_version(&rule_set_slug)?;
+    let languages = match &current_version {
+        Some(version) => repo.get_languages_for_version(&version.id)?,
+        None => Vec::new(),
+    };
+
+    json_response(
+        &headers,
+        None,
+        &RuleSetDetail {
+            rule_set: rule_set.into(),
+            current_version: current_version.map(VersionResource::from),
+            languages,
+        },
+    )
+}
+
+/// List versions, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/rule-sets/{rule_set}/versions",
+    tag = "rules",
+    params(("rule_set" = String, Path, description = "Rule set slug"), PageQuery),
+    responses(
+        (status = 200, description = "Versions, newest first", body = Page<VersionResource>, headers(("ETag" = String, description = "Weak validator for If-None-Match"))),
+        (status = 304, description = "Unchanged since the given ETag"),
+        (status = 400, description = "Malformed query parameters", body = ErrorBody),
+        (status = 404, description = "Rule set, version, language or item not found", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo, headers), fields(rule_set_slug = %rule_set_slug))]
+pub async fn list_versions(
+    State(repo): State<RuleRepository>,
+    headers: HeaderMap,
+    Path(rule_set_slug): Path<String>,
+    ApiQuery(page): ApiQuery<PageQuery>,
+) -> Result<Response, ApiError> {
+    let rule_set = find_rule_set(&repo, &rule_set_slug)?;
+    let versions = repo
+        .get_versions_for_rule_set(&rule_set.id)?
+        .into_iter()
+        .map(VersionResource::from)
+        .collect();
+    let (data, pagination) = paginate(versions, page.page, page.per_page);
+
+    json_response(&headers, None, &Page { data, pagination })
+}
+
+/// Rule tree, paginated by top-level section
+#[utoipa::path(
+    get,
+    path = "/api/v1/rule-sets/{rule_set}/rules",
+    tag = "rules",
+    params(("rule_set" = String, Path, description = "Rule set slug"), ContentQuery, PageQuery),
+    responses(
+        (status = 200, description = "Rule tree, paginated by top-level section", body = ScopedPage<RuleNode>, headers(("ETag" = String, description = "Weak validator for If-None-Match"))),
+        (status = 304, description = "Unchanged since the given ETag"),
+        (status = 400, description = "Malformed query parameters", body = ErrorBody),
+        (status = 404, description = "Rule set, version, language or item not found", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo, headers), fields(rule_set_slug = %rule_set_slug))]
+pub async fn rule_tree(
+    State(repo): State<RuleRepository>,
+    headers: HeaderMap,
+    Path(rule_set_slug): Path<String>,
+    ApiQuery(query): ApiQuery<ContentQuery>,
+    ApiQuery(page): ApiQuery<PageQuery>,
+) -> Result<Response, ApiError> {
+    let (_, version, scope) = resolve_scope(&repo, &headers, &rule_set_slug, &query)?;
+
+    let rules_with_content =
+        repo.get_rules_with_content_for_version(&version.id, &scope.language)?;
+    let tree = build_rule_tree(rules_with_content);
+    let (data, pagination) = paginate(tree, page.page, page.per_page);
+
+    let language = scope.language.clone();
+    json_response(
+        &headers,
+        Some(&language),
+        &ScopedPage {
+            scope,
+            data,
+            pagination,
+        },
+    )
+}
+
+/// Single rule by slug or number, with its parent and children
+#[utoipa::path(
+    get,
+    path = "/api/v1/rule-sets/{rule_set}/rules/{rule}",
+    tag = "rules",
+    params(("rule_set" = String, Path, description = "Rule set slug"), ("rule" = String, Path, description = "Rule slug or number, e.g. `15.13`"), ContentQuery),
+    responses(
+        (status = 200, description = "Rule with its parent and children", body = RuleResource, headers(("ETag" = String, description = "Weak validator for If-None-Match"))),
+        (status = 304, description = "Unchanged since the given ETag"),
+        (status = 400, description = "Malformed query parameters", body = ErrorBody),
+        (status = 404, description = "Rule set, version, language or item not found", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo, headers), fields(rule_set_slug = %rule_set_slug, rule = %rule_ref))]
+pub async fn get_rule(
+    State(repo): State<RuleRepository>,
+    headers: HeaderMap,
+    Path((rule_set_slug, rule_ref)): Path<(String, String)>,
+    ApiQuery(query): ApiQuery<ContentQuery>,
+) -> Result<Response, ApiError> {
+    let (_, version, mut scope) = resolve_scope(&repo, &headers, &rule_set_slug, &query)?;
+
+    let rule = match repo.get_rule_by_slug(&rule_ref, &version.id)? {
+        Some(rule) => rule,
+        None => repo
+            .get_rule_by_number(&rule_ref, &version.id)?
+            .ok_or_else(|| ApiError::NotFound(format!("Rule '{rule_ref}' not found")))?,
+    };
+
+    // Falls back to English when the rule is not translated yet
+    let content = repo
+        .get_rule_content(&rule.id, &scope.language)?
+        .ok_or_else(|| ApiError::NotFound(format!("Rule '{rule_ref}' has no content")))?;
+    scope.language = content.language.clone();
+
+    let parent = match &rule.parent_rule_id {
+        Some(parent_id) => repo.get_rule_by_id(parent_id)?.map(|parent| RuleLink {
+            number: parent.number,
+            slug: parent.slug,
+        }),
+        None => None,
+    };
+
+    let tree =
+        build_rule_tree(repo.get_rules_with_content_for_version(&version.id, &scope.language)?);
+    let children = find_rule_in_tree(&tree, &rule.slug)
+        .map(|node| node.children.clone())
+        .unwrap_or_default();
+
+    let language = scope.language.clone();
+    json_response(
+        &headers,
+        Some(&language),
+        &RuleResource {
+            scope,
+            number: rule.number,
+            slug: rule.slug,
+            content_markdown: content.content_markdown,
+            parent,
+            children,
+        },
+    )
+}
+
+/// Glossary terms sorted alphabetically
+#[utoipa::path(
+    get,
+    path = "/api/v1/rule-sets/{rule_set}/glossary",
+    tag = "rules",
+    params(("rule_set" = String, Path, description = "Rule set slug"), ContentQuery, PageQuery),
+    responses(
+        (status = 200, description = "Glossary terms sorted alphabetically", body = ScopedPage<GlossaryEntry>, headers(("ETag" = String, description = "Weak validator for If-None-Match"))),
+        (status = 304, description = "Unchanged since the given ETag"),
+        (status = 400, description = "Malformed query parameters", body = ErrorBody),
+        (status = 404, description = "Rule set, version, language or item not found", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo, headers), fields(rule_set_slug = %rule_set_slug))]
+pub async fn list_glossary(
+    State(repo): State<RuleRepository>,
+    headers: HeaderMap,
+    Path(rule_set_slug): Path<String>,
+    ApiQuery(query): ApiQuery<ContentQuery>,
+    ApiQuery(page): ApiQuery<PageQuery>,
+) -> Result<Response, ApiError> {
+    let (rule_set, version, scope) = resolve_scope(&repo, &headers, &rule_set_slug, &query)?;
+
+    let mut entries: Vec<GlossaryEntry> = repo
+        .get_glossary_terms(&rule_set.id, &version.id, &scope.language)?
+        .into_iter()
+        .map(|(term, content)| GlossaryEntry {
+            slug: term.slug,
+            term: content.term,
+            definition_markdown: content.definition_markdown,
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.term.to_lowercase());
+    let (data, pagination) = paginate(entries, page.page, page.per_page);
+
+    let language = scope.language.clone();
+    json_response(
+        &headers,
+        Some(&language),
+        &ScopedPage {
+            scope,
+            data,
+            pagination,
+        },
+    )
+}
+
+/// Single glossary term
+#[utoipa::path(
+    get,
+    path = "/api/v1/rule-sets/{rule_set}/glossary/{term}",
+    tag = "rules",
+    params(("rule_set" = String, Path, description = "Rule set slug"), ("term" = String, Path, description = "Glossary term slug"), ContentQuery),
+    responses(
+        (status = 200, description = "Glossary term, in English if not translated", body = GlossaryTermResource, headers(("ETag" = String, description = "Weak validator for If-None-Match"))),
+        (status = 304, description = "Unchanged since the given ETag"),
+        (status = 400, description = "Malformed query parameters", body = ErrorBody),
+        (status = 404, description = "Rule set, version, language or item not found", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo, headers), fields(rule_set_slug = %rule_set_slug, term = %term_slug))]
+pub async fn get_glossary_term(
+    State(repo): State<RuleRepository>,
+    headers: HeaderMap,
+    Path((rule_set_slug, term_slug)): Path<(String, String)>,
+    ApiQuery(query): ApiQuery<ContentQuery>,
+) -> Result<Response, ApiError> {
+    let (rule_set, version, mut scope) = resolve_scope(&repo, &headers, &rule_set_slug, &query)?;
+
+    let not_found = || ApiError::NotFound(format!("Glossary term '{term_slug}' not found"));
+    let term = repo
+        .find_glossary_term(&rule_set.id, &version.id, &term_slug)?
+        .ok_or_else(not_found)?;
+    let content = match repo.find_glossary_content_for_language(&term.id, &scope.language)? {
+        Some(content) => content,
+        None => repo
+            .find_glossary_content_for_language(&term.id, DEFAULT_LANGUAGE)?
+            .ok_or_else(not_found)?,
+    };
+    scope.language = content.language.clone();
+
+    let language = scope.language.clone();
+    json_response(
+        &headers,
+        Some(&language),
+        &GlossaryTermResource {
+            scope,
+            entry: GlossaryEntry {
+                slug: term.slug,
+                term: content.term,
+                definition_markdown: content.definition_markdown,
+            },
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NewGlossaryContent, NewGlossaryTerm, NewRule, NewRuleContent};
+    use axum::body::Body;
+    use axum::http::Request;
+    use regelator::test_support::repository_with_current_version;
+    use tower::ServiceExt;
+
+    fn languages(codes: &[&str]) -> Vec<String> {
+        codes.iter().map(|code| code.to_string()).collect()
+    }
+
+    fn seeded_app() -> axum::Router {
+        let (repo, rule_set, version) = repository_with_current_version();
+        let add_rule = |number: &str, slug: &str, parent: Option<String>| {
+            let rule = repo
+                .create_rule(NewRule::new(
+                    slug.to_string(),
+                    rule_set.id.clone(),
+                    version.id.clone(),
+                    parent,
+                    number.to_string(),
+                ))
+                .unwrap();
+            for language in ["en", "de"] {
+                repo.create_rule_content(NewRuleContent::new(
+                    rule.id.clone(),
+                    language.to_string(),
+                    format!("{language}: rule {number}"),
+                    None,
+                ))
+                .unwrap();
+            }
+            rule
+        };
+        let spirit = add_rule("1", "spirit", None);
+        add_rule("1.1", "respect", Some(spirit.id.clone()));
+        add_rule("2", "field", None);
+
+        let term = repo
+            .create_glossary_term(NewGlossaryTerm::new(
+                rule_set.id.clone(),
+                version.id.clone(),
+                "pull".to_string(),
+            ))
+            .unwrap();
+        repo.create_glossary_content(NewGlossaryContent::new(
+            term.id,
+            "en".to_string(),
+            "Pull".to_string(),
+            "The throw that starts a point".to_string(),
+        ))
+        .unwrap();
+
+        axum::Router::from(routes()).with_state(repo)
+    }
+
+    async fn get(app: &axum::Router, uri: &str, headers: &[(&str, &str)]) -> Response {
+        let mut request = Request::get(uri);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        app.clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_negotiate_language() {
+        let available = languages(&["de", "en"]);
+
+        let pick = |requested, header| negotiate_language(requested, header, &available).unwrap();
+        assert_eq!(pick(Some("DE"), None), "de");
+        assert_eq!(pick(None, Some("fr-CH, de-CH;q=0.8, en;q=0.5")), "de");
+        assert_eq!(pick(None, Some("de;q=0, en")), "en");
+        assert_eq!(pick(None, Some("fr")), "en");
+        assert_eq!(pick(None, None), "en");
+        assert!(matches!(
+            negotiate_language(Some("fr"), None, &available),
+            Err(ApiError::NotFound(_))
+        ));
+        assert_eq!(
+            negotiate_language(None, None, &languages(&["de"])).unwrap(),
+            "de"
+        );
+    }
+
+    #[test]
+    fn test_paginate() {
+        let (data, info) = paginate((1..=5).collect(), Some(2), Some(2));
+        assert_eq!(data, vec![3, 4]);
+        assert_eq!(
+            info,
+            PageInfo {
+                page: 2,
+                per_page: 2,
+                total: 5,
+                total_pages: 3
+            }
+        );
+
+        let (data, info) = paginate((1..=5).collect::<Vec<_>>(), Some(0), Some(1000));
+        assert_eq!(data.len(), 5);
+        assert_eq!((info.page, info.per_page), (1, MAX_PER_PAGE));
+    }
+
+    #[tokio::test]
+    async fn test_rule_tree_with_language_and_etag() {
+        let app = seeded_app();
+
+        let response = get(
+            &app,
+            "/api/v1/rule-sets/indoor/rules?per_page=1",
+            &[("accept-language", "de")],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_LANGUAGE], "de");
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let body = body_json(response).await;
+        assert_eq!(body["language"], "de");
+        assert_eq!(body["version"], "2025");
+        assert_eq!(body["pagination"]["total"], 2);
+        assert_eq!(body["data"][0]["number"], "1");
+        assert_eq!(body["data"][0]["children"][0]["content"], "de: rule 1.1");
+
+        let response = get(
+            &app,
+            "/api/v1/rule-sets/indoor/rules?per_page=1",
+            &[("accept-language", "de"), ("if-none-match", &etag)],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let response = get(&app, "/api/v1/rule-sets/indoor/rules?lang=fr", &[]).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(body_json(response).await["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_rule_by_slug_or_number() {
+        let app = seeded_app();
+
+        let by_number = body_json(get(&app, "/api/v1/rule-sets/indoor/rules/1.1", &[]).await).await;
+        assert_eq!(by_number["slug"], "respect");
+        assert_eq!(by_number["parent"]["slug"], "spirit");
+
+        let by_slug =
+            body_json(get(&app, "/api/v1/rule-sets/indoor/rules/spirit", &[]).await).await;
+        assert_eq!(by_slug["number"], "1");
+        assert_eq!(by_slug["children"][0]["slug"], "respect");
+
+        let response = get(&app, "/api/v1/rule-sets/indoor/rules/99", &[]).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_glossary_and_rule_sets() {
+        let app = seeded_app();
+
+        let glossary =
+            body_json(get(&app, "/api/v1/rule-sets/indoor/glossary?lang=en", &[]).await).await;
+        assert_eq!(glossary["data"][0]["term"], "Pull");
+
+        // Untranslated terms fall back to English
+        let term =
+            body_json(get(&app, "/api/v1/rule-sets/indoor/glossary/pull?lang=de", &[]).await).await;
+        assert_eq!(term["language"], "en");
+
+        let rule_set = body_json(get(&app, "/api/v1/rule-sets/indoor", &[]).await).await;
+        assert_eq!(rule_set["current_version"]["name"], "2025");
+        assert_eq!(rule_set["languages"], serde_json::json!(["de", "en"]));
+
+        let response = get(&app, "/api/v1/rule-sets?page=abc", &[]).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::extractors::CsrfToken;
+use crate::models::{AdminApiToken, AdminRole, AuditAction, NewAdminApiToken};
+use crate::{AppError, repository::RuleRepository};
+use axum::{
+    extract::{Path, State},
+    response::Html,
+};
+use axum_extra::extract::Form;
+use minijinja::Environment;
+use regelator::api_token;
+use regelator::auth::AdminToken;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::instrument;
+
+use super::admin::record_audit;
+
+#[derive(Deserialize)]
+pub struct CreateApiTokenForm {
+    pub name: String,
+    pub scope: AdminRole,
+}
+
+#[derive(Serialize)]
+struct ApiTokenRow {
+    token: AdminApiToken,
+    created_at: String,
+    last_used_at: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ApiTokensContext {
+    tokens: Vec<ApiTokenRow>,
+    scopes: Vec<&'static str>,
+    new_token: Option<String>,
+    error: Option<String>,
+    success: Option<String>,
+    csrf_token: CsrfToken,
+}
+
+fn render_api_tokens(
+    templates: &Environment<'static>,
+    repository: &RuleRepository,
+    admin: &AdminToken,
+    csrf_token: CsrfToken,
+    new_token: Option<String>,
+    error: Option<String>,
+    success: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let tokens = repository
+        .get_admin_api_tokens(admin.admin_id())?
+        .into_iter()
+        .map(|token| ApiTokenRow {
+            created_at: token.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            last_used_at: token
+                .last_used_at
+                .map(|used| used.format("%Y-%m-%d %H:%M").to_string()),
+            token,
+        })
+        .collect();
+
+    let context = ApiTokensContext {
+        tokens,
+        scopes: AdminRole::ALL
+            .into_iter()
+            .filter(|scope| admin.can(*scope))
+            .map(|scope| scope.as_str())
+            .collect(),
+        new_token,
+        error,
+        success,
+        csrf_token,
+    };
+    let tmpl = templates.get_template("admin_api_tokens.html")?;
+    let rendered = tmpl.render(context)?;
+    Ok(Html(rendered))
+}
+
+/// Tokens may only be managed from a browser session, so a leaked token cannot mint more
+fn api_token_request_error(admin: &AdminToken) -> Option<String> {
+    admin
+        .api_token_id()
+        .map(|_| "API tokens cannot be managed with an API token".to_string())
+}
+
+/// List the logged-in admin's API tokens
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username()))]
+pub async fn api_tokens(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    csrf_token: CsrfToken,
+) -> Result<Html<String>, AppError> {
+    render_api_tokens(
+        &templates,
+        &repository,
+        &admin,
+        csrf_token,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Create a token and show it once
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username(), scope = form_data.scope.as_str()))]
+pub async fn create_api_token(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    csrf_token: CsrfToken,
+    Form(form_data): Form<CreateApiTokenForm>,
+) -> Result<Html<String>, AppError> {
+    let name = form_data.name.trim();
+
+    let error = if let Some(error) = api_token_request_error(&admin) {
+        Some(error)
+    } else if name.is_empty() {
+        Some("Token name is required".to_string())
+    } else if !admin.can(form_data.scope) {
+        Some(format!(
+            "Your role does not allow {} tokens",
+            form_data.scope.as_str()
+        ))
+    } else {
+        None
+    };
+    if error.is_some() {
+        return render_api_tokens(
+            &templates,
+            &repository,
+            &admin,
+            csrf_token,
+            None,
+            error,
+            None,
+        );
+    }
+
+    let token = api_token::generate_api_token();
+    let created = repository.create_api_token(NewAdminApiToken::new(
+        admin.admin_id().to_string(),
+        name.to_string(),
+        api_token::hash_api_token(&token),
+        api_token::token_hint(&token),
+        form_data.scope,
+    ))?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::ApiTokenCreated,
+        &created.id,
+        None,
+        serde_json::to_value(&created).ok(),
+    )?;
+
+    render_api_tokens(
+        &templates,
+        &repository,
+        &admin,
+        csrf_token,
+        Some(token),
+        None,
+        Some(format!("Created token '{name}'")),
+    )
+}
+
+/// Revoke one of the admin's tokens
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username(), token_id = %token_id))]
+pub async fn revoke_api_token(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    csrf_token: CsrfToken,
+    Path(token_id): Path<String>,
+) -> Result<Html<String>, AppError> {
+    if let Some(error) = api_token_request_error(&admin) {
+        return render_api_tokens(
+            &templates,
+            &repository,
+            &admin,
+            csrf_token,
+            None,
+            Some(error),
+            None,
+        );
+    }
+
+    let success = match repository.revoke_api_token(admin.admin_id(), &token_id)? {
+        Some(revoked) => {
+            record_audit(
+                &repository,
+                &admin,
+                AuditAction::ApiTokenRevoked,
+                &revoked.id,
+                serde_json::to_value(&revoked).ok(),
+                None,
+            )?;
+            Some(format!("Revoked token '{}'", revoked.name))
+        }
+        None => None,
+    };
+    render_api_tokens(
+        &templates,
+        &repository,
+        &admin,
+        csrf_token,
+        None,
+        None,
+        success,
+    )
+}
@@ -0,0 +1,308 @@
+use crate::extractors::CsrfToken;
+use crate::models::{AuditAction, QuizCertificate};
+use crate::{
+    AppError, quiz_engine::QuizScope, quiz_session::QuizSession, repository::RuleRepository,
+};
+use axum::{
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use axum_extra::extract::{Form, Query};
+use chrono::Utc;
+use minijinja::Environment;
+use regelator::auth::{RequireRole, Superadmin};
+use regelator::certificate::{self, CertificateSigner};
+use regelator::config::Config;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+use super::admin::record_audit;
+use super::quiz::render_exam_report;
+
+#[derive(Deserialize)]
+pub struct ClaimCertificateForm {
+    pub exam_session_id: String,
+    pub holder_name: String,
+}
+
+#[derive(Deserialize)]
+pub struct CertificateSearchParams {
+    pub search: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RevokeCertificateForm {
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+struct CertificateView {
+    certificate: QuizCertificate,
+    signed_id: String,
+    passed_on: String,
+    revoked_on: Option<String>,
+}
+
+impl CertificateView {
+    fn new(certificate: QuizCertificate, signer: &CertificateSigner) -> Self {
+        Self {
+            signed_id: signer.signed_id(&certificate.id),
+            passed_on: certificate.passed_at.format("%Y-%m-%d").to_string(),
+            revoked_on: certificate
+                .revoked_at
+                .map(|revoked| revoked.format("%Y-%m-%d").to_string()),
+            certificate,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CertificatePageContext {
+    /// `None` if the id is unknown or its signature doesn't match
+    certificate: Option<CertificateView>,
+    verify_url: String,
+}
+
+#[derive(Serialize)]
+struct AdminCertificatesContext {
+    certificates: Vec<CertificateView>,
+    search: String,
+    error: Option<String>,
+    success: Option<String>,
+    csrf_token: CsrfToken,
+}
+
+/// The certificate a public id stands for, if its signature matches
+fn lookup_certificate(
+    repository: &RuleRepository,
+    signer: &CertificateSigner,
+    signed_id: &str,
+) -> Result<Option<QuizCertificate>, AppError> {
+    let Some(certificate_id) = signer.verify(signed_id) else {
+        return Ok(None);
+    };
+    Ok(repository.get_quiz_certificate(&certificate_id)?)
+}
+
+/// Public page confirming that a certificate is genuine and not revoked
+#[instrument(skip(templates, repository, signer, config))]
+pub async fn show_certificate(
+    Path(certificate_id): Path<String>,
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(signer): State<CertificateSigner>,
+    State(config): State<Config>,
+) -> Result<Response, AppError> {
+    let certificate = lookup_certificate(&repository, &signer, &certificate_id)?;
+    let status = if certificate.is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    let context = CertificatePageContext {
+        certificate: certificate.map(|certificate| CertificateView::new(certificate, &signer)),
+        verify_url: certificate::verify_url(&config.learners.site_url, &certificate_id),
+    };
+
+    let tmpl = templates.get_template("certificate.html")?;
+    Ok((status, Html(tmpl.render(context)?)).into_response())
+}
+
+/// The certificate as SVG, unless it was revoked
+#[instrument(skip(repository, signer, config))]
+pub async fn certificate_svg(
+    Path(certificate_id): Path<String>,
+    State(repository): State<RuleRepository>,
+    State(signer): State<CertificateSigner>,
+    State(config): State<Config>,
+) -> Result<Response, AppError> {
+    let Some(certificate) = lookup_certificate(&repository, &signer, &certificate_id)?
+        .filter(|certificate| !certificate.is_revoked())
+    else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+    let svg = certificate::certificate_svg(
+        &certificate,
+        &certificate_id,
+        &certificate::verify_url(&config.learners.site_url, &certificate_id),
+    )?;
+
+    Ok(([(header::CONTENT_TYPE, "image/svg+xml")], svg).into_response())
+}
+
+/// The certificate as a PDF download, unless it was revoked
+#[instrument(skip(repository, signer, config))]
+pub async fn certificate_pdf(
+    Path(certificate_id): Path<String>,
+    State(repository): State<RuleRepository>,
+    State(signer): State<CertificateSigner>,
+    State(config): State<Config>,
+) -> Result<Response, AppError> {
+    let Some(certificate) = lookup_certificate(&repository, &signer, &certificate_id)?
+        .filter(|certificate| !certificate.is_revoked())
+    else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+    let pdf = certificate::certificate_pdf(
+        &certificate,
+        &certificate_id,
+        &certificate::verify_url(&config.learners.site_url, &certificate_id),
+    )?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"certificate.pdf\"",
+            ),
+        ],
+        pdf,
+    )
+        .into_response())
+}
+
+/// Put a name on the certificate of a passed exam, or open the one issued before
+#[instrument(skip(templates, repository, signer, quiz_session, csrf_token, form), fields(session_id = %quiz_session.session_id(), exam_session_id = %form.exam_session_id))]
+pub async fn claim_certificate(
+    Path((language, rule_set_slug)): Path<(String, String)>,
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(signer): State<CertificateSigner>,
+    quiz_session: QuizSession,
+    csrf_token: CsrfToken,
+    Form(form): Form<ClaimCertificateForm>,
+) -> Result<Response, AppError> {
+    let Some(quiz_exam) =
+        certificate::claimable_exam(&repository, &form.exam_session_id, quiz_session.record())?
+    else {
+        return Ok(Redirect::to(&format!("/{language}/quiz/{rule_set_slug}")).into_response());
+    };
+
+    if repository
+        .get_exam_certificate(&quiz_exam.session_id)?
+        .is_none()
+        && let Some(error) = certificate::holder_name_error(&form.holder_name)
+    {
+        let exam_session = repository
+            .get_quiz_session(&quiz_exam.session_id)?
+            .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Exam session not found")))?;
+        let scope = QuizScope::for_session(&repository, &rule_set_slug, &exam_session)?
+            .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Rule set not found")))?;
+        return Ok(render_exam_report(
+            &repository,
+            &templates,
+            &scope,
+            &quiz_exam,
+            language,
+            csrf_token,
+            Some(error),
+        )?
+        .into_response());
+    }
+
+    let issued = certificate::issue_certificate(
+        &repository,
+        &quiz_exam,
+        &form.holder_name,
+        Utc::now().naive_utc(),
+    )?;
+    Ok(Redirect::to(&format!("/certificates/{}", signer.signed_id(&issued.id))).into_response())
+}
+
+fn render_admin_certificates(
+    templates: &Environment<'static>,
+    repository: &RuleRepository,
+    signer: &CertificateSigner,
+    search: Option<String>,
+    csrf_token: CsrfToken,
+    error: Option<String>,
+    success: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let search = search.unwrap_or_default().trim().to_string();
+    let certificates = repository
+        .get_quiz_certificates(Some(search.as_str()).filter(|search| !search.is_empty()))?
+        .into_iter()
+        .map(|certificate| CertificateView::new(certificate, signer))
+        .collect();
+
+    let context = AdminCertificatesContext {
+        certificates,
+        search,
+        error,
+        success,
+        csrf_token,
+    };
+    let tmpl = templates.get_template("admin_certificates.html")?;
+    Ok(Html(tmpl.render(context)?))
+}
+
+/// List issued certificates, optionally searching by holder name or id
+#[instrument(skip(templates, repository, signer, admin, csrf_token, params), fields(admin_username = %admin.username()))]
+pub async fn admin_certificates(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(signer): State<CertificateSigner>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Query(params): Query<CertificateSearchParams>,
+) -> Result<Html<String>, AppError> {
+    render_admin_certificates(
+        &templates,
+        &repository,
+        &signer,
+        params.search,
+        csrf_token,
+        None,
+        None,
+    )
+}
+
+/// Revoke a certificate so its verification page reports it as no longer valid
+#[instrument(skip(templates, repository, signer, admin, csrf_token, form_data), fields(admin_username = %admin.username(), certificate_id = %certificate_id))]
+pub async fn revoke_certificate(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(signer): State<CertificateSigner>,
+    admin: RequireRole<Superadmin>,
+    csrf_token: CsrfToken,
+    Path(certificate_id): Path<String>,
+    Form(form_data): Form<RevokeCertificateForm>,
+) -> Result<Html<String>, AppError> {
+    let reason = Some(form_data.reason.trim()).filter(|reason| !reason.is_empty());
+    let revoked =
+        repository.revoke_quiz_certificate(&certificate_id, Utc::now().naive_utc(), reason)?;
+    let Some(before) = revoked else {
+        return render_admin_certificates(
+            &templates,
+            &repository,
+            &signer,
+            None,
+            csrf_token,
+            Some("Certificate not found or already revoked".to_string()),
+            None,
+        );
+    };
+    let after = repository.get_quiz_certificate(&certificate_id)?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::CertificateRevoked,
+        &certificate_id,
+        serde_json::to_value(&before).ok(),
+        after.and_then(|after| serde_json::to_value(after).ok()),
+    )?;
+    info!("Revoked certificate {}", certificate_id);
+
+    render_admin_certificates(
+        &templates,
+        &repository,
+        &signer,
+        None,
+        csrf_token,
+        None,
+        Some(format!("Revoked the certificate of {}", before.holder_name)),
+    )
+}
@@ -0,0 +1,269 @@
+use crate::extractors::CsrfToken;
+use crate::models::Learner;
+use crate::{AppError, quiz_session::QuizCookieSessionId, repository::RuleRepository};
+use axum::{
+    Form,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::{Html, IntoResponse, Redirect},
+};
+use chrono::Utc;
+use minijinja::Environment;
+use regelator::config::Config;
+use regelator::email::EmailSender;
+use regelator::learner::{self, LearnerCookies, LoginLinkOutcome};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tracing::{info, instrument};
+
+#[derive(Serialize)]
+struct SessionRow {
+    rule_set_name: String,
+    started_at: String,
+    answers: usize,
+    accuracy_percentage: u32,
+    is_open: bool,
+}
+
+#[derive(Serialize)]
+struct AccountContext {
+    language: String,
+    learner: Option<Learner>,
+    member_since: String,
+    sessions: Vec<SessionRow>,
+    success: Option<String>,
+    error: Option<String>,
+    csrf_token: CsrfToken,
+}
+
+#[derive(Serialize)]
+struct VerifyContext {
+    language: String,
+    token: String,
+    csrf_token: CsrfToken,
+}
+
+#[derive(Deserialize)]
+pub struct LoginLinkForm {
+    pub email: String,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyParams {
+    pub token: String,
+}
+
+fn render_account(
+    templates: &Environment<'static>,
+    repository: &RuleRepository,
+    language: String,
+    learner: Option<Learner>,
+    csrf_token: CsrfToken,
+    success: Option<String>,
+    error: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let mut sessions = Vec::new();
+    if let Some(learner) = &learner {
+        let rule_set_names: HashMap<String, String> = repository
+            .get_rule_sets()?
+            .into_iter()
+            .map(|rule_set| (rule_set.id, rule_set.name))
+            .collect();
+        for session in repository.get_learner_sessions(&learner.id)? {
+            let stats = repository.get_session_statistics(&session.id)?;
+            sessions.push(SessionRow {
+                rule_set_name: rule_set_names
+                    .get(&session.rule_set_id)
+                    .cloned()
+                    .unwrap_or_default(),
+                started_at: session.started_at.format("%Y-%m-%d %H:%M").to_string(),
+                answers: stats.total_questions,
+                accuracy_percentage: stats.accuracy_percentage,
+                is_open: session.ended_at.is_none(),
+            });
+        }
+    }
+
+    let context = AccountContext {
+        language,
+        member_since: learner
+            .as_ref()
+            .map(|learner| learner.created_at.format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+        learner,
+        sessions,
+        success,
+        error,
+        csrf_token,
+    };
+    let tmpl = templates.get_template("learner_account.html")?;
+    Ok(Html(tmpl.render(context)?))
+}
+
+/// Account page: quiz history when signed in, otherwise the sign-in form
+#[instrument(skip(templates, repository, cookies, headers, csrf_token))]
+pub async fn account_page(
+    Path(language): Path<String>,
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(cookies): State<LearnerCookies>,
+    headers: HeaderMap,
+    csrf_token: CsrfToken,
+) -> Result<Html<String>, AppError> {
+    let learner = learner::signed_in_learner(&repository, &cookies, &headers)?;
+    render_account(
+        &templates,
+        &repository,
+        language,
+        learner,
+        csrf_token,
+        None,
+        None,
+    )
+}
+
+/// Email a sign-in link; the same message is shown whether or not the address has an account
+#[instrument(skip(templates, repository, config, sender, csrf_token, form))]
+pub async fn send_login_link(
+    Path(language): Path<String>,
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(config): State<Config>,
+    State(sender): State<Arc<dyn EmailSender>>,
+    csrf_token: CsrfToken,
+    Form(form): Form<LoginLinkForm>,
+) -> Result<Html<String>, AppError> {
+    let outcome = learner::send_login_link(
+        &repository,
+        sender.as_ref(),
+        &config.learners,
+        &form.email,
+        &language,
+        Utc::now().naive_utc(),
+    )
+    .await?;
+
+    let (success, error) = match outcome {
+        LoginLinkOutcome::Sent | LoginLinkOutcome::RateLimited => (
+            Some(format!(
+                "Check your inbox: we sent a sign-in link to {}. It expires in {} minutes.",
+                form.email.trim(),
+                config.learners.login_link_minutes
+            )),
+            None,
+        ),
+        LoginLinkOutcome::InvalidEmail => (
+            None,
+            Some("Please enter a valid email address.".to_string()),
+        ),
+    };
+    render_account(
+        &templates,
+        &repository,
+        language,
+        None,
+        csrf_token,
+        success,
+        error,
+    )
+}
+
+/// Confirmation page for an emailed link, so link scanners don't use it up
+#[instrument(skip(templates, params, csrf_token))]
+pub async fn verify_login_form(
+    Path(language): Path<String>,
+    State(templates): State<Arc<Environment<'static>>>,
+    Query(params): Query<VerifyParams>,
+    csrf_token: CsrfToken,
+) -> Result<Html<String>, AppError> {
+    let tmpl = templates.get_template("learner_verify.html")?;
+    Ok(Html(tmpl.render(VerifyContext {
+        language,
+        token: params.token,
+        csrf_token,
+    })?))
+}
+
+/// Sign in with an emailed link and adopt this browser's anonymous quiz session
+#[instrument(skip(templates, repository, cookies, quiz_session_id, csrf_token, form))]
+pub async fn verify_login(
+    Path(language): Path<String>,
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(cookies): State<LearnerCookies>,
+    QuizCookieSessionId(quiz_session_id): QuizCookieSessionId,
+    csrf_token: CsrfToken,
+    Form(form): Form<VerifyParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(learner) = learner::complete_login(&repository, &form.token, Utc::now().naive_utc())?
+    else {
+        let page = render_account(
+            &templates,
+            &repository,
+            language,
+            None,
+            csrf_token,
+            None,
+            Some(
+                "This sign-in link is invalid or has expired. Please request a new one."
+                    .to_string(),
+            ),
+        )?;
+        return Ok(page.into_response());
+    };
+
+    if let Some(session_id) = quiz_session_id
+        && let Some(session) = repository.get_quiz_session(&session_id)?
+        && session.ended_at.is_none()
+        && session.learner_id.is_none()
+    {
+        learner::adopt_session(&repository, &learner.id, &session)?;
+    }
+    info!("Learner {} signed in", learner.id);
+
+    Ok((
+        cookies.sign_in(learner.id),
+        Redirect::to(&format!("/{language}/account")),
+    )
+        .into_response())
+}
+
+/// Sign this browser out; quiz progress stays with the account
+#[instrument(skip(cookies))]
+pub async fn sign_out(
+    Path(language): Path<String>,
+    State(cookies): State<LearnerCookies>,
+) -> impl IntoResponse {
+    (
+        cookies.sign_out(),
+        Redirect::to(&format!("/{language}/account")),
+    )
+}
+
+/// Delete the signed-in learner with all their quiz history
+#[instrument(skip(templates, repository, cookies, headers, csrf_token))]
+pub async fn delete_account(
+    Path(language): Path<String>,
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(cookies): State<LearnerCookies>,
+    headers: HeaderMap,
+    csrf_token: CsrfToken,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(learner) = learner::signed_in_learner(&repository, &cookies, &headers)? else {
+        return Ok(Redirect::to(&format!("/{language}/account")).into_response());
+    };
+    repository.delete_learner(&learner.id)?;
+    info!("Deleted learner {}", learner.id);
+
+    let page = render_account(
+        &templates,
+        &repository,
+        language,
+        None,
+        csrf_token,
+        Some("Your account and its quiz history were deleted.".to_string()),
+        None,
+    )?;
+    Ok((cookies.sign_out(), page).into_response())
+}
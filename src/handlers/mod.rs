@@ -1,5 +1,13 @@
 pub mod admin;
+pub mod api;
+pub mod api_tokens;
+pub mod certificates;
+pub mod learner;
 pub mod quiz;
+pub mod quiz_api;
+pub mod sessions;
+pub mod transfer;
+pub mod two_factor;
 pub mod web;
 
 // Re-export all handlers for easy access
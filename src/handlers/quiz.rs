@@ -1,16 +1,23 @@
 use crate::{
     AppError,
-    models::NewQuizAttempt,
-    quiz_session::{QuizSession, clear_quiz_session_cookie},
+    extractors::CsrfToken,
+    models::{Difficulty, DifficultyMode, QuizExam, QuizMode},
+    quiz_engine::QuizScope,
+    quiz_session::{QuizCookies, QuizSession, rotate_session},
     repository::RuleRepository,
 };
 use axum::{
-    extract::{Form, Path, State},
-    response::{Html, IntoResponse},
+    extract::{Path, State},
+    response::{Html, IntoResponse, Redirect},
 };
-use axum_extra::extract::CookieJar;
+use axum_extra::extract::Form;
+use chrono::Utc;
 use minijinja::Environment;
-use rand::seq::IndexedRandom;
+use regelator::{
+    config::Config,
+    exam::{self, ExamQuestion, ExamReport},
+    response_time::QuestionTimer,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{Span, instrument};
@@ -22,6 +29,58 @@ pub struct QuizLandingData {
     pub has_progress: bool,
     pub questions_attempted: usize,
     pub total_questions: usize,
+    /// Spaced-repetition reviews due by the end of today
+    pub due_today: usize,
+    pub session_started_at: String,
+    /// Top-level rule sections to focus the quiz on
+    pub sections: Vec<QuizSectionView>,
+    /// The session is focused on some sections rather than the whole rule set
+    pub is_focused: bool,
+    /// Selected difficulty option: `all`, a level, `progressive` or `mix`
+    pub difficulty: String,
+    pub mix_beginner: u32,
+    pub mix_intermediate: u32,
+    pub mix_advanced: u32,
+    pub exam: ExamLandingView,
+    pub csrf_token: CsrfToken,
+}
+
+#[derive(Serialize)]
+pub struct ExamLandingView {
+    pub question_count: u32,
+    pub time_limit_minutes: u32,
+    pub pass_percent: u32,
+    /// The session is an exam that has not been scored yet
+    pub in_exam: bool,
+    /// Where the exam stands; `None` once time is up or every question is answered
+    pub progress: Option<ExamProgressView>,
+}
+
+#[derive(Serialize)]
+pub struct ExamProgressView {
+    pub position: usize,
+    pub question_count: usize,
+    /// Rounded up, so the last seconds still show as a minute
+    pub minutes_left: i64,
+}
+
+impl ExamProgressView {
+    fn new(next: &ExamQuestion) -> Self {
+        Self {
+            position: next.position,
+            question_count: next.question_count,
+            minutes_left: (next.seconds_left + 59) / 60,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct QuizSectionView {
+    pub rule_id: String,
+    pub number: String,
+    pub title: String,
+    pub question_count: usize,
+    pub selected: bool,
 }
 
 #[derive(Serialize)]
@@ -30,9 +89,15 @@ pub struct QuizQuestionData {
     pub question_text: String,
     pub difficulty_level: String,
     pub answers: Vec<QuizAnswerData>,
+    pub is_review: bool,
+    /// Set for exam questions, which get no feedback until the end
+    pub exam: Option<ExamProgressView>,
     pub session_id: String,
+    /// Signed time the question was served, sent back with the answer
+    pub served_stamp: String,
     pub rule_set_slug: String,
     pub language: String,
+    pub csrf_token: CsrfToken,
 }
 
 #[derive(Serialize)]
@@ -56,13 +121,32 @@ pub struct QuizResultData {
     pub questions_attempted: usize,
     pub language: String,
     pub rule_set_slug: String,
+    pub csrf_token: CsrfToken,
 }
 
 #[derive(Serialize)]
 pub struct QuizSessionCompleteData {
-    pub session_id: String,
+    pub language: String,
+    pub rule_set_slug: String,
     pub stats: SessionStatsView,
     pub missed_questions: Vec<MissedQuestionView>,
+    /// When the next spaced-repetition review falls due, formatted for display
+    pub next_review_due: Option<String>,
+    pub csrf_token: CsrfToken,
+}
+
+#[derive(Serialize)]
+pub struct QuizExamReportData {
+    pub report: ExamReport,
+    /// Time taken, e.g. `12 min 30 s`
+    pub duration: String,
+    pub language: String,
+    pub rule_set_slug: String,
+    pub exam_session_id: String,
+    /// A passed exam's certificate has been claimed already
+    pub certificate_issued: bool,
+    pub certificate_error: Option<String>,
+    pub csrf_token: CsrfToken,
 }
 
 #[derive(Serialize)]
@@ -88,21 +172,50 @@ pub struct QuizAnswerWithResult {
     pub was_selected: bool,
 }
 
+#[derive(Deserialize)]
+pub struct StartQuizForm {
+    /// Rule ids of the sections to focus on; none for the whole rule set
+    #[serde(default)]
+    pub topic: Vec<String>,
+    /// `all`, a level, `progressive` or `mix`; all levels if missing or unknown
+    pub difficulty: Option<String>,
+    pub mix_beginner: Option<u8>,
+    pub mix_intermediate: Option<u8>,
+    pub mix_advanced: Option<u8>,
+}
+
+impl StartQuizForm {
+    fn difficulty_mode(&self) -> DifficultyMode {
+        match self.difficulty.as_deref() {
+            Some("mix") => DifficultyMode::Mix {
+                beginner: self.mix_beginner.unwrap_or(0),
+                intermediate: self.mix_intermediate.unwrap_or(0),
+                advanced: self.mix_advanced.unwrap_or(0),
+            },
+            Some(mode) => mode.parse().unwrap_or(DifficultyMode::All),
+            None => DifficultyMode::All,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct QuizSubmission {
     pub question_id: String,
     pub answer_id: String,
+    pub served_stamp: Option<String>,
     // session_id now comes from QuizSession extractor
     // rule_set_slug and language come from path parameters
 }
 
 /// Quiz landing page
-#[instrument(skip(template_env, repository, quiz_session), fields(language = %language, rule_set_slug = %rule_set_slug, session_id = %quiz_session.session_id()))]
+#[instrument(skip(template_env, repository, config, quiz_session, csrf_token), fields(language = %language, rule_set_slug = %rule_set_slug, session_id = %quiz_session.session_id()))]
 pub async fn quiz_landing(
     Path((language, rule_set_slug)): Path<(String, String)>,
     State(template_env): State<Arc<Environment<'static>>>,
     State(repository): State<RuleRepository>,
+    State(config): State<Config>,
     quiz_session: QuizSession,
+    csrf_token: CsrfToken,
 ) -> Result<Html<String>, AppError> {
     let session_id = quiz_session.session_id();
 
@@ -112,33 +225,81 @@ pub async fn quiz_landing(
         .as_ref()
         .is_some_and(|stats| stats.total_questions > 0);
 
+    let scope = QuizScope::for_session(&repository, &rule_set_slug, quiz_session.record())?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Rule set not found")))?;
+
     // Get total available questions for progress calculation
-    let total_questions = if has_progress {
-        // Get the rule set and version to calculate total questions
-        let rule_sets = repository.get_rule_sets()?;
-        let rule_set = rule_sets
-            .iter()
-            .find(|rs| rs.slug == rule_set_slug)
-            .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Rule set not found")))?;
-
-        let version = repository
-            .get_current_version(&rule_set_slug)?
-            .ok_or_else(|| AppError(color_eyre::eyre::eyre!("No current version found")))?;
-
-        let all_questions = repository.get_quiz_questions(&rule_set.id, &version.id)?;
-        all_questions.len()
+    let (questions_attempted, total_questions) = if has_progress {
+        (
+            scope.questions_seen(&repository, session_id)?,
+            scope.total_questions(&repository)?,
+        )
     } else {
-        0
+        (0, 0)
+    };
+    let due_today = scope.due_today(&repository, session_id)?;
+
+    let topics = quiz_session.record().topic_rule_ids();
+    let sections = scope
+        .sections(&repository, &language)?
+        .into_iter()
+        .map(|section| QuizSectionView {
+            selected: topics.contains(&section.rule_id),
+            rule_id: section.rule_id,
+            number: section.number,
+            title: section.title,
+            question_count: section.question_count,
+        })
+        .collect();
+
+    // Mixes start from the usual 50/30/20 split
+    let mix = match scope.difficulty {
+        DifficultyMode::Mix { .. } => scope.difficulty,
+        _ => DifficultyMode::Mix {
+            beginner: 50,
+            intermediate: 30,
+            advanced: 20,
+        },
+    };
+    let difficulty = match scope.difficulty {
+        DifficultyMode::Mix { .. } => "mix".to_string(),
+        mode => mode.to_string(),
+    };
+
+    let quiz_exam = repository.get_quiz_exam(session_id)?;
+    let progress = match &quiz_exam {
+        Some(quiz_exam) => exam::next_question(&repository, quiz_exam, Utc::now().naive_utc())?
+            .map(|next| ExamProgressView::new(&next)),
+        None => None,
+    };
+    let exam = ExamLandingView {
+        question_count: config.exam.question_count,
+        time_limit_minutes: config.exam.time_limit_minutes,
+        pass_percent: config.exam.pass_percent,
+        in_exam: quiz_exam.is_some(),
+        progress,
     };
 
     let template_data = QuizLandingData {
         language,
         rule_set_slug,
         has_progress,
-        questions_attempted: session_stats
-            .as_ref()
-            .map_or(0, |stats| stats.total_questions),
+        questions_attempted,
         total_questions,
+        due_today,
+        session_started_at: quiz_session
+            .record()
+            .started_at
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        sections,
+        is_focused: scope.focus.is_some(),
+        difficulty,
+        mix_beginner: mix.weight(Difficulty::Beginner),
+        mix_intermediate: mix.weight(Difficulty::Intermediate),
+        mix_advanced: mix.weight(Difficulty::Advanced),
+        exam,
+        csrf_token,
     };
 
     let template = template_env.get_template("quiz_landing.html")?;
@@ -147,43 +308,85 @@ pub async fn quiz_landing(
     Ok(Html(response))
 }
 
-/// Start a new quiz session
-#[instrument(skip(repository, template_env, quiz_session), fields(language = %language, rule_set_slug = %rule_set_slug, session_id = %quiz_session.session_id()))]
+/// Start the quiz with the selected sections and difficulty mode
+#[instrument(skip(repository, template_env, timer, quiz_session, csrf_token, form), fields(language = %language, rule_set_slug = %rule_set_slug, session_id = %quiz_session.session_id()))]
 pub async fn start_quiz_session(
     Path((language, rule_set_slug)): Path<(String, String)>,
     State(repository): State<RuleRepository>,
     State(template_env): State<Arc<Environment<'static>>>,
+    State(timer): State<QuestionTimer>,
     quiz_session: QuizSession,
+    csrf_token: CsrfToken,
+    Form(form): Form<StartQuizForm>,
 ) -> Result<Html<String>, AppError> {
     let session_id = quiz_session.session_id().to_string();
+    let scope = QuizScope::current(&repository, &rule_set_slug)?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Rule set not found")))?
+        .focus_session(&repository, &session_id, &form.topic)?
+        .set_session_difficulty(&repository, &session_id, form.difficulty_mode())?;
 
     // Get first question for this session (middleware handles cookies)
     get_quiz_question_for_session(
         repository,
         template_env,
+        &timer,
+        &scope,
         session_id,
         language,
-        rule_set_slug,
+        csrf_token,
     )
     .await
 }
 
+/// Start a timed exam in a new session; the practice session resumes once it is scored
+#[instrument(skip(repository, config, cookies, quiz_session), fields(language = %language, rule_set_slug = %rule_set_slug, session_id = %quiz_session.session_id()))]
+pub async fn start_quiz_exam(
+    Path((language, rule_set_slug)): Path<(String, String)>,
+    State(repository): State<RuleRepository>,
+    State(config): State<Config>,
+    State(cookies): State<QuizCookies>,
+    quiz_session: QuizSession,
+) -> Result<impl IntoResponse, AppError> {
+    let landing = Redirect::to(&format!("/{language}/quiz/{rule_set_slug}"));
+    // An exam under way has to be finished first
+    if quiz_session.record().mode == QuizMode::Exam {
+        return Ok((None, landing));
+    }
+
+    let scope = QuizScope::current(&repository, &rule_set_slug)?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Rule set not found")))?;
+    let started = exam::start_exam(
+        &repository,
+        &scope,
+        quiz_session.record(),
+        &config.exam,
+        Utc::now().naive_utc(),
+    )?;
+    Ok((started.map(|session| cookies.set(session.id)), landing))
+}
+
 /// Get a random quiz question (for next question flow)
-#[instrument(skip(repository, template_env, quiz_session), fields(language = %language, rule_set_slug = %rule_set_slug, session_id = %quiz_session.session_id()))]
+#[instrument(skip(repository, template_env, timer, quiz_session, csrf_token), fields(language = %language, rule_set_slug = %rule_set_slug, session_id = %quiz_session.session_id()))]
 pub async fn random_quiz_question(
     Path((language, rule_set_slug)): Path<(String, String)>,
     State(repository): State<RuleRepository>,
     State(template_env): State<Arc<Environment<'static>>>,
+    State(timer): State<QuestionTimer>,
     quiz_session: QuizSession,
+    csrf_token: CsrfToken,
 ) -> Result<Html<String>, AppError> {
     let session_id = quiz_session.session_id().to_string();
+    let scope = QuizScope::for_session(&repository, &rule_set_slug, quiz_session.record())?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Rule set not found")))?;
 
     get_quiz_question_for_session(
         repository,
         template_env,
+        &timer,
+        &scope,
         session_id,
         language,
-        rule_set_slug,
+        csrf_token,
     )
     .await
 }
@@ -192,47 +395,40 @@ pub async fn random_quiz_question(
 async fn get_quiz_question_for_session(
     repository: RuleRepository,
     template_env: Arc<Environment<'static>>,
+    timer: &QuestionTimer,
+    scope: &QuizScope,
     session_id: String,
     language: String,
-    rule_set_slug: String,
+    csrf_token: CsrfToken,
 ) -> Result<Html<String>, AppError> {
-    let rule_sets = repository.get_rule_sets()?;
-    let rule_set = rule_sets
-        .iter()
-        .find(|rs| rs.slug == rule_set_slug)
-        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Rule set not found")))?;
-
-    let version = repository
-        .get_current_version(&rule_set_slug)?
-        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("No current version found")))?;
-
-    // Get questions not yet attempted in this session
-    let questions =
-        repository.get_unattempted_questions_for_session(&session_id, &rule_set.id, &version.id)?;
-
-    if questions.is_empty() {
-        // All questions have been attempted - show session complete
+    let next = match repository.get_quiz_exam(&session_id)? {
+        // Exams serve their drawn questions in order until time is up
+        Some(quiz_exam) => exam::next_question(&repository, &quiz_exam, Utc::now().naive_utc())?
+            .map(|next| {
+                let progress = ExamProgressView::new(&next);
+                (next.question, Some(progress))
+            }),
+        // Due reviews first, then a question not yet attempted in this session
+        None => scope
+            .next_question(&repository, &session_id)?
+            .map(|next| (next, None)),
+    };
+    let Some((next, exam)) = next else {
+        // Everything attempted and nothing due, or the exam is over - show session complete
         return show_session_complete(
             repository,
             template_env,
+            scope,
             session_id,
             language,
-            rule_set_slug,
+            csrf_token,
         )
         .await;
-    }
-
-    // Select random question (simple approach for now)
-    let mut rng = rand::rng();
-    let selected_question = questions
-        .choose(&mut rng)
-        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Failed to select random question")))?;
-
-    // Get answers for this question
-    let db_answers = repository.get_quiz_answers(&selected_question.id)?;
+    };
 
     // Convert to handler-specific structs
-    let answers = db_answers
+    let answers = next
+        .answers
         .iter()
         .map(|a| QuizAnswerData {
             id: a.id.clone(),
@@ -240,14 +436,19 @@ async fn get_quiz_question_for_session(
         })
         .collect();
 
+    let served_stamp = timer.stamp(&session_id, &next.question.id, Utc::now())?;
     let template_data = QuizQuestionData {
-        question_id: selected_question.id.clone(),
-        question_text: selected_question.question_text.clone(),
-        difficulty_level: selected_question.difficulty_level.clone(),
+        question_id: next.question.id,
+        question_text: next.question.question_text,
+        difficulty_level: next.question.difficulty_level,
         answers,
+        is_review: next.is_review,
+        exam,
         session_id,
-        rule_set_slug,
+        served_stamp,
+        rule_set_slug: scope.rule_set.slug.clone(),
         language,
+        csrf_token,
     };
 
     let template = template_env.get_template("quiz_question.html")?;
@@ -256,14 +457,59 @@ async fn get_quiz_question_for_session(
     Ok(Html(response))
 }
 
-/// Show session complete page with statistics
+/// Report of a finished exam, with the form to claim its certificate if it was passed
+pub(crate) fn render_exam_report(
+    repository: &RuleRepository,
+    template_env: &Environment<'static>,
+    scope: &QuizScope,
+    quiz_exam: &QuizExam,
+    language: String,
+    csrf_token: CsrfToken,
+    certificate_error: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let report = exam::exam_report(repository, scope, quiz_exam, &language)?;
+    let seconds = (report.finished_at - report.started_at)
+        .num_seconds()
+        .max(0);
+    let template_data = QuizExamReportData {
+        report,
+        duration: format!("{} min {} s", seconds / 60, seconds % 60),
+        language,
+        rule_set_slug: scope.rule_set.slug.clone(),
+        exam_session_id: quiz_exam.session_id.clone(),
+        certificate_issued: repository
+            .get_exam_certificate(&quiz_exam.session_id)?
+            .is_some(),
+        certificate_error,
+        csrf_token,
+    };
+
+    let template = template_env.get_template("quiz_exam_report.html")?;
+    Ok(Html(template.render(&template_data)?))
+}
+
+/// Show session complete page with statistics, or the report of a finished exam
 async fn show_session_complete(
     repository: RuleRepository,
     template_env: Arc<Environment<'static>>,
+    scope: &QuizScope,
     session_id: String,
-    _language: String,
-    _rule_set_slug: String,
+    language: String,
+    csrf_token: CsrfToken,
 ) -> Result<Html<String>, AppError> {
+    if let Some(quiz_exam) = repository.get_quiz_exam(&session_id)? {
+        let quiz_exam = exam::finish_exam(&repository, &quiz_exam, Utc::now().naive_utc())?;
+        return render_exam_report(
+            &repository,
+            &template_env,
+            scope,
+            &quiz_exam,
+            language,
+            csrf_token,
+            None,
+        );
+    }
+
     // Get session statistics
     let db_stats = repository.get_session_statistics(&session_id)?;
     let stats = SessionStatsView {
@@ -284,10 +530,17 @@ async fn show_session_complete(
         })
         .collect();
 
+    let next_review_due = scope
+        .next_review_due(&repository, &session_id)?
+        .map(|due_at| due_at.format("%Y-%m-%d %H:%M").to_string());
+
     let template_data = QuizSessionCompleteData {
-        session_id,
+        language,
+        rule_set_slug: scope.rule_set.slug.clone(),
         stats,
         missed_questions,
+        next_review_due,
+        csrf_token,
     };
 
     let template = template_env.get_template("quiz_session_complete.html")?;
@@ -297,12 +550,14 @@ async fn show_session_complete(
 }
 
 /// Submit quiz answer and show results  
-#[instrument(skip(repository, template_env, quiz_session, submission), fields(language = %language, rule_set_slug = %rule_set_slug, session_id = %quiz_session.session_id(), question_id, answer_id))]
+#[instrument(skip(repository, template_env, timer, quiz_session, csrf_token, submission), fields(language = %language, rule_set_slug = %rule_set_slug, session_id = %quiz_session.session_id(), question_id, answer_id))]
 pub async fn submit_quiz_answer(
     Path((language, rule_set_slug)): Path<(String, String)>,
     State(repository): State<RuleRepository>,
     State(template_env): State<Arc<Environment<'static>>>,
+    State(timer): State<QuestionTimer>,
     quiz_session: QuizSession,
+    csrf_token: CsrfToken,
     Form(submission): Form<QuizSubmission>,
 ) -> Result<Html<String>, AppError> {
     let session_id = quiz_session.session_id().to_string();
@@ -311,31 +566,51 @@ pub async fn submit_quiz_answer(
     Span::current().record("question_id", &submission.question_id);
     Span::current().record("answer_id", &submission.answer_id);
 
-    // Get the question by ID
-    let question = repository
-        .get_quiz_question_by_id(&submission.question_id)?
-        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Question not found")))?;
-
-    let answers = repository.get_quiz_answers(&submission.question_id)?;
+    let scope = QuizScope::for_session(&repository, &rule_set_slug, quiz_session.record())?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Rule set not found")))?;
 
-    // Find selected answer and check if correct
-    let selected_answer = answers
-        .iter()
-        .find(|a| a.id == submission.answer_id)
-        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Answer not found")))?;
-
-    let is_correct = selected_answer.is_correct;
-
-    // Record the attempt
-    let attempt = NewQuizAttempt::new(
-        session_id.clone(),
-        submission.question_id.clone(),
-        Some(submission.answer_id.clone()),
-        Some(is_correct),
-        None, // No timing for now
+    // Record the attempt, timed from when the question was served
+    let response_time_ms = timer.response_time_ms(
+        submission.served_stamp.as_deref(),
+        &session_id,
+        &submission.question_id,
+        Utc::now(),
     );
 
-    repository.create_quiz_attempt(&attempt)?;
+    // Exams give no feedback: an answer in time is recorded and the next question follows
+    if let Some(quiz_exam) = repository.get_quiz_exam(&session_id)? {
+        exam::answer_question(
+            &repository,
+            &quiz_exam,
+            &submission.question_id,
+            &submission.answer_id,
+            response_time_ms,
+            Utc::now().naive_utc(),
+        )?;
+        return get_quiz_question_for_session(
+            repository,
+            template_env,
+            &timer,
+            &scope,
+            session_id,
+            language,
+            csrf_token,
+        )
+        .await;
+    }
+
+    let outcome = scope
+        .submit_answer(
+            &repository,
+            &session_id,
+            &submission.question_id,
+            &submission.answer_id,
+            response_time_ms,
+        )?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Question or answer not found")))?;
+    let is_correct = outcome.is_correct;
+    let question = outcome.question;
+    let answers = outcome.answers;
 
     // Get session statistics after recording the attempt
     let db_stats = repository.get_session_statistics(&session_id)?;
@@ -346,19 +621,8 @@ pub async fn submit_quiz_answer(
         current_streak: db_stats.current_streak,
     };
 
-    // Get total available questions for this rule set using path parameter
-    let rule_sets = repository.get_rule_sets()?;
-    let rule_set = rule_sets
-        .iter()
-        .find(|rs| rs.slug == rule_set_slug)
-        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Rule set not found")))?;
-    let version = repository
-        .get_current_version(&rule_set_slug)?
-        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("No current version found")))?;
-
-    let all_questions = repository.get_quiz_questions(&rule_set.id, &version.id)?;
-    let total_questions_available = all_questions.len();
-    let questions_attempted = db_stats.total_questions;
+    let total_questions_available = scope.total_questions(&repository)?;
+    let questions_attempted = scope.questions_seen(&repository, &session_id)?;
 
     // Prepare answer data with selection markers
     let answers_with_result: Vec<QuizAnswerWithResult> = answers
@@ -385,6 +649,7 @@ pub async fn submit_quiz_answer(
         questions_attempted,
         language,
         rule_set_slug,
+        csrf_token,
     };
 
     let template = template_env.get_template("quiz_result.html")?;
@@ -393,14 +658,16 @@ pub async fn submit_quiz_answer(
     Ok(Html(response))
 }
 
-/// Clear session data for privacy
-#[instrument(skip(quiz_session, jar), fields(session_id = %quiz_session.session_id()))]
+/// End the session and continue in a fresh one under a new cookie
+#[instrument(skip(repository, quiz_session, cookies), fields(session_id = %quiz_session.session_id()))]
 pub async fn clear_session_data(
     Path((language, rule_set_slug)): Path<(String, String)>,
+    State(repository): State<RuleRepository>,
+    State(cookies): State<QuizCookies>,
     quiz_session: QuizSession,
-    jar: CookieJar,
 ) -> Result<impl IntoResponse, AppError> {
-    let jar = jar.add(clear_quiz_session_cookie());
+    let record = rotate_session(&repository, &quiz_session)?;
+    let jar = cookies.set(record.id);
 
     // Redirect back to quiz home for this rule set
     Ok((
@@ -0,0 +1,545 @@
+//! JSON quiz API under `/api/v1/quiz` for chat bots and native apps.
+//!
+//! Clients start a session explicitly and send the token it returns in the
+//! `X-Quiz-Session` header; attempts land in `quiz_attempts` just like those from
+//! the HTML quiz.
+
+use crate::{
+    handlers::api::{ApiError, ErrorBody},
+    models::{QuizMode, QuizSessionRecord},
+    quiz_engine::QuizScope,
+    repository::RuleRepository,
+};
+use axum::{
+    Json,
+    extract::{FromRef, FromRequestParts, Path, State, rejection::JsonRejection},
+    http::{HeaderValue, StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use regelator::quiz_token;
+use serde::{Deserialize, Serialize};
+use tracing::{Span, instrument};
+use utoipa::ToSchema;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+pub const QUIZ_SESSION_HEADER: &str = "x-quiz-session";
+
+/// Routes for `/api/v1/quiz`; register handlers with `routes!` so they are documented
+pub fn routes<S>() -> OpenApiRouter<S>
+where
+    RuleRepository: FromRef<S>,
+    S: Clone + Send + Sync + 'static,
+{
+    OpenApiRouter::new()
+        .routes(routes!(start_session))
+        .routes(routes!(next_question))
+        .routes(routes!(submit_answer))
+        .routes(routes!(session_stats))
+}
+
+/// Open quiz session whose token is in the `X-Quiz-Session` header.
+///
+/// Only tokens issued by [`start_session`] for the rule set in the path are accepted;
+/// session ids are never taken as tokens, and exams can't be answered through the API.
+#[derive(Debug)]
+pub struct QuizSessionToken(pub QuizSessionRecord);
+
+impl<S> FromRequestParts<S> for QuizSessionToken
+where
+    RuleRepository: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let unauthorized = |message: &str| {
+            (StatusCode::UNAUTHORIZED, Json(ErrorBody::new(message))).into_response()
+        };
+
+        let Some(token) = parts
+            .headers
+            .get(QUIZ_SESSION_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(str::to_string)
+        else {
+            return Err(unauthorized(
+                "Missing X-Quiz-Session header; start a session first",
+            ));
+        };
+
+        let Path(rule_set_slug) = Path::<String>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let repo = RuleRepository::from_ref(state);
+        let scope = quiz_scope(&repo, &rule_set_slug).map_err(IntoResponse::into_response)?;
+
+        match quiz_token::resolve_quiz_token(&repo, &token, &scope.rule_set.id) {
+            Ok(Some(record)) => Ok(QuizSessionToken(record)),
+            Ok(None) => Err(unauthorized(
+                "Unknown or ended quiz session token for this rule set; start a new session",
+            )),
+            Err(e) => Err(ApiError::Internal(e).into_response()),
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AnswerSubmission {
+    question_id: String,
+    answer_id: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct QuizSessionResource {
+    /// Send this in the `X-Quiz-Session` header of later requests
+    session_token: String,
+    rule_set: String,
+    version: String,
+    total_questions: usize,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct QuestionResource {
+    question_id: String,
+    question_text: String,
+    difficulty_level: String,
+    answers: Vec<AnswerOption>,
+    /// Answered before and asked again because its spaced-repetition review is due
+    is_review: bool,
+}
+
+/// Answer option without its correctness
+#[derive(Serialize, ToSchema)]
+pub struct AnswerOption {
+    id: String,
+    answer_text: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct NextQuestionResource {
+    /// `null` once every question has been attempted and no review is due
+    question: Option<QuestionResource>,
+    /// Distinct questions answered so far
+    questions_attempted: usize,
+    total_questions: usize,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AnswerFeedback {
+    is_correct: bool,
+    correct_answer_ids: Vec<String>,
+    explanation: String,
+    stats: SessionStatsResource,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SessionStatsResource {
+    /// Distinct questions answered so far
+    questions_attempted: usize,
+    correct_answers: usize,
+    accuracy_percentage: u32,
+    current_streak: usize,
+    total_questions: usize,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MissedQuestion {
+    question_id: String,
+    question_text: String,
+    explanation: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SessionReport {
+    #[serde(flatten)]
+    stats: SessionStatsResource,
+    missed_questions: Vec<MissedQuestion>,
+}
+
+/// Quiz responses are personal and must not be cached
+fn no_store(status: StatusCode, body: impl Serialize) -> Response {
+    (
+        status,
+        [(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))],
+        Json(body),
+    )
+        .into_response()
+}
+
+fn quiz_scope(repo: &RuleRepository, rule_set_slug: &str) -> Result<QuizScope, ApiError> {
+    QuizScope::current(repo, rule_set_slug)?
+        .ok_or_else(|| ApiError::NotFound(format!("Rule set '{rule_set_slug}' not found")))
+}
+
+fn session_stats_resource(
+    repo: &RuleRepository,
+    scope: &QuizScope,
+    session_id: &str,
+) -> Result<SessionStatsResource, ApiError> {
+    let stats = repo.get_session_statistics(session_id)?;
+    Ok(SessionStatsResource {
+        questions_attempted: stats.questions_seen,
+        correct_answers: stats.correct_answers,
+        accuracy_percentage: stats.accuracy_percentage,
+        current_streak: stats.current_streak,
+        total_questions: scope.total_questions(repo)?,
+    })
+}
+
+/// Start a quiz session for the current version of a rule set
+#[utoipa::path(
+    post,
+    path = "/api/v1/quiz/{rule_set}/sessions",
+    tag = "quiz",
+    params(("rule_set" = String, Path, description = "Rule set slug")),
+    responses(
+        (status = 201, description = "Session started", body = QuizSessionResource),
+        (status = 404, description = "Rule set not found", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo), fields(rule_set_slug = %rule_set_slug))]
+pub async fn start_session(
+    State(repo): State<RuleRepository>,
+    Path(rule_set_slug): Path<String>,
+) -> Result<Response, ApiError> {
+    let scope = quiz_scope(&repo, &rule_set_slug)?;
+    let session = scope.start_session(&repo, "en", QuizMode::Practice, None)?;
+    let session_token = quiz_token::issue_quiz_token(&repo, &session, Utc::now().naive_utc())?;
+
+    Ok(no_store(
+        StatusCode::CREATED,
+        QuizSessionResource {
+            session_token,
+            total_questions: scope.total_questions(&repo)?,
+            rule_set: scope.rule_set.slug,
+            version: scope.version.version_name,
+        },
+    ))
+}
+
+/// Pick a random question the session has not answered yet
+#[utoipa::path(
+    get,
+    path = "/api/v1/quiz/{rule_set}/question",
+    tag = "quiz",
+    params(("rule_set" = String, Path, description = "Rule set slug")),
+    security(("quiz_session" = [])),
+    responses(
+        (status = 200, description = "Next question, or none when the quiz is complete", body = NextQuestionResource),
+        (status = 401, description = "Missing, unknown or ended session token, or one for another rule set", body = ErrorBody),
+        (status = 404, description = "Rule set not found", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo, session), fields(rule_set_slug = %rule_set_slug, session_id = %session.0.id))]
+pub async fn next_question(
+    State(repo): State<RuleRepository>,
+    session: QuizSessionToken,
+    Path(rule_set_slug): Path<String>,
+) -> Result<Response, ApiError> {
+    let scope = quiz_scope(&repo, &rule_set_slug)?;
+
+    let question = scope
+        .next_question(&repo, &session.0.id)?
+        .map(|next| QuestionResource {
+            question_id: next.question.id,
+            question_text: next.question.question_text,
+            difficulty_level: next.question.difficulty_level,
+            answers: next
+                .answers
+                .into_iter()
+                .map(|answer| AnswerOption {
+                    id: answer.id,
+                    answer_text: answer.answer_text,
+                })
+                .collect(),
+            is_review: next.is_review,
+        });
+
+    Ok(no_store(
+        StatusCode::OK,
+        NextQuestionResource {
+            question,
+            questions_attempted: repo.get_session_statistics(&session.0.id)?.questions_seen,
+            total_questions: scope.total_questions(&repo)?,
+        },
+    ))
+}
+
+/// Answer a question and get feedback
+#[utoipa::path(
+    post,
+    path = "/api/v1/quiz/{rule_set}/answers",
+    tag = "quiz",
+    params(("rule_set" = String, Path, description = "Rule set slug")),
+    request_body = AnswerSubmission,
+    security(("quiz_session" = [])),
+    responses(
+        (status = 200, description = "Whether the answer was correct, with the explanation", body = AnswerFeedback),
+        (status = 400, description = "Malformed body, or question and answer do not match", body = ErrorBody),
+        (status = 401, description = "Missing, unknown or ended session token, or one for another rule set", body = ErrorBody),
+        (status = 404, description = "Rule set not found", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo, session, payload), fields(rule_set_slug = %rule_set_slug, session_id = %session.0.id, question_id, answer_id))]
+pub async fn submit_answer(
+    State(repo): State<RuleRepository>,
+    session: QuizSessionToken,
+    Path(rule_set_slug): Path<String>,
+    payload: Result<Json<AnswerSubmission>, JsonRejection>,
+) -> Result<Response, ApiError> {
+    let Json(submission) =
+        payload.map_err(|rejection| ApiError::BadRequest(rejection.body_text()))?;
+    Span::current().record("question_id", &submission.question_id);
+    Span::current().record("answer_id", &submission.answer_id);
+
+    let scope = quiz_scope(&repo, &rule_set_slug)?;
+    let outcome = scope
+        .submit_answer(
+            &repo,
+            &session.0.id,
+            &submission.question_id,
+            &submission.answer_id,
+            None,
+        )?
+        .ok_or_else(|| {
+            ApiError::BadRequest("Answer does not belong to a question in this quiz".to_string())
+        })?;
+
+    Ok(no_store(
+        StatusCode::OK,
+        AnswerFeedback {
+            is_correct: outcome.is_correct,
+            correct_answer_ids: outcome
+                .answers
+                .into_iter()
+                .filter(|answer| answer.is_correct)
+                .map(|answer| answer.id)
+                .collect(),
+            explanation: outcome.question.explanation,
+            stats: session_stats_resource(&repo, &scope, &session.0.id)?,
+        },
+    ))
+}
+
+/// Statistics for the session, including the questions it got wrong
+#[utoipa::path(
+    get,
+    path = "/api/v1/quiz/{rule_set}/stats",
+    tag = "quiz",
+    params(("rule_set" = String, Path, description = "Rule set slug")),
+    security(("quiz_session" = [])),
+    responses(
+        (status = 200, description = "Session statistics", body = SessionReport),
+        (status = 401, description = "Missing, unknown or ended session token, or one for another rule set", body = ErrorBody),
+        (status = 404, description = "Rule set not found", body = ErrorBody),
+    )
+)]
+#[instrument(skip(repo, session), fields(rule_set_slug = %rule_set_slug, session_id = %session.0.id))]
+pub async fn session_stats(
+    State(repo): State<RuleRepository>,
+    session: QuizSessionToken,
+    Path(rule_set_slug): Path<String>,
+) -> Result<Response, ApiError> {
+    let scope = quiz_scope(&repo, &rule_set_slug)?;
+
+    let missed_questions = repo
+        .get_session_missed_questions(&session.0.id)?
+        .into_iter()
+        .filter(|(question, _)| question.rule_set_id == scope.rule_set.id)
+        .map(|(question, _)| MissedQuestion {
+            question_id: question.id,
+            question_text: question.question_text,
+            explanation: question.explanation,
+        })
+        .collect();
+
+    Ok(no_store(
+        StatusCode::OK,
+        SessionReport {
+            stats: session_stats_resource(&repo, &scope, &session.0.id)?,
+            missed_questions,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{QuizAnswerData, QuizQuestionData};
+    use axum::body::Body;
+    use axum::http::Request;
+    use regelator::test_support::repository_with_current_version;
+    use tower::ServiceExt;
+
+    fn seeded_app() -> (axum::Router, RuleRepository) {
+        let (repo, rule_set, version) = repository_with_current_version();
+        repo.create_quiz_question_complete(&QuizQuestionData {
+            rule_set_id: rule_set.id,
+            version_id: version.id,
+            question_text: "Who calls fouls?".to_string(),
+            explanation: "Players referee themselves.".to_string(),
+            difficulty_level: "beginner".to_string(),
+            answers: vec![
+                QuizAnswerData {
+                    answer_text: "The players".to_string(),
+                    is_correct: true,
+                },
+                QuizAnswerData {
+                    answer_text: "A referee".to_string(),
+                    is_correct: false,
+                },
+            ],
+            rule_ids: vec![],
+        })
+        .unwrap();
+
+        (axum::Router::from(routes()).with_state(repo.clone()), repo)
+    }
+
+    async fn send(
+        app: &axum::Router,
+        request: axum::http::request::Builder,
+        body: Body,
+    ) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .clone()
+            .oneshot(request.body(body).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_quiz_flow_with_session_token() {
+        let (app, _) = seeded_app();
+
+        let (status, session) = send(
+            &app,
+            Request::post("/api/v1/quiz/indoor/sessions"),
+            Body::empty(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(session["total_questions"], 1);
+        let token = session["session_token"].as_str().unwrap().to_string();
+
+        let (status, _) = send(
+            &app,
+            Request::get("/api/v1/quiz/indoor/question"),
+            Body::empty(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Tokens must have been issued by the server
+        let (status, _) = send(
+            &app,
+            Request::get("/api/v1/quiz/indoor/question")
+                .header(QUIZ_SESSION_HEADER, quiz_token::generate_quiz_token()),
+            Body::empty(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, next) = send(
+            &app,
+            Request::get("/api/v1/quiz/indoor/question").header(QUIZ_SESSION_HEADER, &token),
+            Body::empty(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let question = &next["question"];
+        assert!(question["answers"][0].get("is_correct").is_none());
+        let wrong = question["answers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|answer| answer["answer_text"] == "A referee")
+            .unwrap();
+
+        let submission = serde_json::json!({
+            "question_id": question["question_id"],
+            "answer_id": wrong["id"],
+        });
+        let (status, feedback) = send(
+            &app,
+            Request::post("/api/v1/quiz/indoor/answers")
+                .header(QUIZ_SESSION_HEADER, &token)
+                .header(header::CONTENT_TYPE, "application/json"),
+            Body::from(submission.to_string()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(feedback["is_correct"], false);
+        assert_eq!(feedback["correct_answer_ids"].as_array().unwrap().len(), 1);
+        assert_eq!(feedback["stats"]["questions_attempted"], 1);
+
+        let (_, next) = send(
+            &app,
+            Request::get("/api/v1/quiz/indoor/question").header(QUIZ_SESSION_HEADER, &token),
+            Body::empty(),
+        )
+        .await;
+        assert!(next["question"].is_null());
+
+        let (_, report) = send(
+            &app,
+            Request::get("/api/v1/quiz/indoor/stats").header(QUIZ_SESSION_HEADER, &token),
+            Body::empty(),
+        )
+        .await;
+        assert_eq!(report["accuracy_percentage"], 0);
+        assert_eq!(
+            report["missed_questions"][0]["question_text"],
+            "Who calls fouls?"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_answer_must_match_question() {
+        let (app, _) = seeded_app();
+        let (_, session) = send(
+            &app,
+            Request::post("/api/v1/quiz/indoor/sessions"),
+            Body::empty(),
+        )
+        .await;
+        let token = session["session_token"].as_str().unwrap().to_string();
+
+        let submission = serde_json::json!({ "question_id": "nope", "answer_id": "nope" });
+        let (status, body) = send(
+            &app,
+            Request::post("/api/v1/quiz/indoor/answers")
+                .header(QUIZ_SESSION_HEADER, &token)
+                .header(header::CONTENT_TYPE, "application/json"),
+            Body::from(submission.to_string()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_session_ids_are_not_tokens() {
+        let (app, repo) = seeded_app();
+        let scope = QuizScope::current(&repo, "indoor").unwrap().unwrap();
+        // Sessions of the web quiz, named by its cookie, and exams have no token
+        for mode in [QuizMode::Practice, QuizMode::Exam] {
+            let session = scope.start_session(&repo, "en", mode, None).unwrap();
+            let (status, _) = send(
+                &app,
+                Request::get("/api/v1/quiz/indoor/stats").header(QUIZ_SESSION_HEADER, &session.id),
+                Body::empty(),
+            )
+            .await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+    }
+}
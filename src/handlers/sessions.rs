@@ -0,0 +1,114 @@
+use crate::extractors::CsrfToken;
+use crate::models::{AdminSession, AuditAction};
+use crate::{AppError, repository::RuleRepository};
+use axum::{
+    extract::{Path, State},
+    response::{Html, Redirect},
+};
+use axum_extra::extract::CookieJar;
+use chrono::Utc;
+use minijinja::Environment;
+use regelator::auth::{AdminToken, clear_admin_cookie};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::instrument;
+
+use super::admin::record_audit;
+
+#[derive(Serialize)]
+struct SessionRow {
+    session: AdminSession,
+    created_at: String,
+    last_seen_at: String,
+    is_current: bool,
+}
+
+#[derive(Serialize)]
+struct SessionsContext {
+    sessions: Vec<SessionRow>,
+    success: Option<String>,
+    csrf_token: CsrfToken,
+}
+
+fn render_sessions(
+    templates: &Environment<'static>,
+    repository: &RuleRepository,
+    admin: &AdminToken,
+    csrf_token: CsrfToken,
+    success: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let sessions = repository
+        .get_active_admin_sessions(admin.admin_id(), Utc::now().naive_utc())?
+        .into_iter()
+        .map(|session| SessionRow {
+            created_at: session.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            last_seen_at: session.last_seen_at.format("%Y-%m-%d %H:%M").to_string(),
+            is_current: session.id == admin.session_id(),
+            session,
+        })
+        .collect();
+
+    let context = SessionsContext {
+        sessions,
+        success,
+        csrf_token,
+    };
+    let tmpl = templates.get_template("admin_sessions.html")?;
+    let rendered = tmpl.render(context)?;
+    Ok(Html(rendered))
+}
+
+/// List the logged-in admin's active sessions
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username()))]
+pub async fn admin_sessions(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    csrf_token: CsrfToken,
+) -> Result<Html<String>, AppError> {
+    render_sessions(&templates, &repository, &admin, csrf_token, None)
+}
+
+/// Log out one of the admin's other sessions
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username(), session_id = %session_id))]
+pub async fn revoke_admin_session(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    csrf_token: CsrfToken,
+    Path(session_id): Path<String>,
+) -> Result<Html<String>, AppError> {
+    let success = if repository.revoke_admin_session(admin.admin_id(), &session_id)? {
+        record_audit(
+            &repository,
+            &admin,
+            AuditAction::SessionsRevoked,
+            admin.admin_id(),
+            None,
+            Some(serde_json::json!({ "session_id": session_id })),
+        )?;
+        Some("Session logged out".to_string())
+    } else {
+        None
+    };
+    render_sessions(&templates, &repository, &admin, csrf_token, success)
+}
+
+/// Log out every session of the admin, including this one
+#[instrument(skip(repository, admin, jar), fields(admin_username = %admin.username()))]
+pub async fn revoke_all_admin_sessions(
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    jar: CookieJar,
+) -> Result<(CookieJar, Redirect), AppError> {
+    let revoked = repository.revoke_admin_sessions(admin.admin_id(), None)?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::SessionsRevoked,
+        admin.admin_id(),
+        None,
+        Some(serde_json::json!({ "sessions": revoked })),
+    )?;
+    Ok((jar.add(clear_admin_cookie()), Redirect::to("/admin/login")))
+}
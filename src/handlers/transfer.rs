@@ -0,0 +1,204 @@
+use crate::extractors::{CsrfToken, client_ip::ClientIp};
+use crate::{
+    AppError,
+    quiz_session::{QuizCookies, QuizSession},
+    repository::RuleRepository,
+};
+use axum::{
+    Form,
+    extract::{FromRef, FromRequestParts, Path, Query, State},
+    http::request::Parts,
+    response::{Html, IntoResponse, Redirect},
+};
+use chrono::Utc;
+use minijinja::Environment;
+use regelator::config::{Config, LoginThrottleConfig};
+use regelator::models::QuizSessionRecord;
+use regelator::transfer::Redemption;
+use regelator::{transfer, two_factor};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+#[derive(Serialize)]
+struct TransferContext {
+    language: String,
+    rule_set_slug: String,
+    /// Sessions of signed-in learners follow the account instead
+    signed_in: bool,
+    code: Option<String>,
+    qr_svg: Option<String>,
+    expires_at: Option<String>,
+    expires_minutes: i64,
+    entered_code: String,
+    error: Option<String>,
+    csrf_token: CsrfToken,
+}
+
+#[derive(Deserialize)]
+pub struct TransferParams {
+    pub code: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RedeemForm {
+    pub code: String,
+}
+
+impl TransferContext {
+    fn new(
+        language: String,
+        rule_set_slug: String,
+        quiz_session: &QuizSession,
+        csrf_token: CsrfToken,
+    ) -> Self {
+        Self {
+            language,
+            rule_set_slug,
+            signed_in: quiz_session.record().learner_id.is_some(),
+            code: None,
+            qr_svg: None,
+            expires_at: None,
+            expires_minutes: transfer::TRANSFER_CODE_MINUTES,
+            entered_code: String::new(),
+            error: None,
+            csrf_token,
+        }
+    }
+}
+
+/// Redeems codes for the requesting client, whose failed attempts are throttled
+/// with the admin login policy
+pub struct TransferRedeemer {
+    repository: RuleRepository,
+    throttle_config: LoginThrottleConfig,
+    client_ip: Option<IpAddr>,
+}
+
+impl<S> FromRequestParts<S> for TransferRedeemer
+where
+    S: Send + Sync,
+    Config: FromRef<S>,
+    RuleRepository: FromRef<S>,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ClientIp(client_ip) = ClientIp::from_request_parts(parts, state).await?;
+        Ok(Self {
+            repository: RuleRepository::from_ref(state),
+            throttle_config: Config::from_ref(state).security.login_throttle,
+            client_ip,
+        })
+    }
+}
+
+impl TransferRedeemer {
+    fn redeem(&self, code: &str, current: &QuizSessionRecord) -> color_eyre::Result<Redemption> {
+        transfer::redeem_transfer_code_from(
+            &self.repository,
+            &self.throttle_config,
+            self.client_ip,
+            code,
+            current,
+            Utc::now().naive_utc(),
+        )
+    }
+}
+
+fn render_transfer(
+    templates: &Environment<'static>,
+    context: TransferContext,
+) -> Result<Html<String>, AppError> {
+    let tmpl = templates.get_template("quiz_transfer.html")?;
+    Ok(Html(tmpl.render(context)?))
+}
+
+/// Transfer page: get a code for another device, or enter one from it.
+///
+/// The QR code links here with `?code=`, which only fills in the form so link
+/// previews can't use the code up.
+#[instrument(skip(templates, quiz_session, params, csrf_token), fields(session_id = %quiz_session.session_id()))]
+pub async fn transfer_page(
+    Path((language, rule_set_slug)): Path<(String, String)>,
+    State(templates): State<Arc<Environment<'static>>>,
+    quiz_session: QuizSession,
+    Query(params): Query<TransferParams>,
+    csrf_token: CsrfToken,
+) -> Result<Html<String>, AppError> {
+    let mut context = TransferContext::new(language, rule_set_slug, &quiz_session, csrf_token);
+    context.entered_code = params.code.unwrap_or_default();
+    render_transfer(&templates, context)
+}
+
+/// Issue a transfer code for this device's session
+#[instrument(skip(templates, repository, config, quiz_session, csrf_token), fields(session_id = %quiz_session.session_id()))]
+pub async fn create_transfer_code(
+    Path((language, rule_set_slug)): Path<(String, String)>,
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    State(config): State<Config>,
+    quiz_session: QuizSession,
+    csrf_token: CsrfToken,
+) -> Result<Html<String>, AppError> {
+    let mut context = TransferContext::new(language, rule_set_slug, &quiz_session, csrf_token);
+    if !context.signed_in {
+        let issued = transfer::issue_transfer_code(
+            &repository,
+            quiz_session.record(),
+            Utc::now().naive_utc(),
+        )?;
+        let link = format!(
+            "{}/{}/quiz/{}/transfer?code={}",
+            config.learners.site_url.trim_end_matches('/'),
+            context.language,
+            context.rule_set_slug,
+            issued.code
+        );
+        context.qr_svg = Some(two_factor::svg_qr_code(&link)?);
+        context.expires_at = Some(issued.expires_at.format("%H:%M").to_string());
+        context.code = Some(issued.code);
+    }
+    render_transfer(&templates, context)
+}
+
+/// Continue the session of another device on this one
+#[instrument(skip(templates, cookies, redeemer, quiz_session, csrf_token, form), fields(session_id = %quiz_session.session_id(), client_ip = ?redeemer.client_ip))]
+pub async fn redeem_transfer_code(
+    Path((language, rule_set_slug)): Path<(String, String)>,
+    State(templates): State<Arc<Environment<'static>>>,
+    State(cookies): State<QuizCookies>,
+    redeemer: TransferRedeemer,
+    quiz_session: QuizSession,
+    csrf_token: CsrfToken,
+    Form(form): Form<RedeemForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let error = match redeemer.redeem(&form.code, quiz_session.record())? {
+        Redemption::Continued(session) => {
+            info!("Transferred quiz session {} to another device", session.id);
+            return Ok((
+                cookies.set(session.id),
+                Redirect::to(&format!("/{language}/quiz/{rule_set_slug}")),
+            )
+                .into_response());
+        }
+        Redemption::Rejected => {
+            "This code is invalid or has expired. Get a new one on your other device.".to_string()
+        }
+        Redemption::Delayed { retry_after } => format!(
+            "Too many invalid codes. Try again in {} seconds.",
+            retry_after.num_seconds().max(1)
+        ),
+        Redemption::LockedOut { until } => format!(
+            "Too many invalid codes. Try again after {} UTC.",
+            until.format("%H:%M")
+        ),
+    };
+
+    let mut context = TransferContext::new(language, rule_set_slug, &quiz_session, csrf_token);
+    context.entered_code = form.code;
+    context.error = Some(error);
+    Ok(render_transfer(&templates, context)?.into_response())
+}
@@ -0,0 +1,341 @@
+use crate::extractors::CsrfToken;
+use crate::models::{Admin, AuditAction};
+use crate::{AppError, repository::RuleRepository};
+use axum::{extract::State, response::Html};
+use axum_extra::extract::Form;
+use minijinja::Environment;
+use regelator::auth::AdminToken;
+use regelator::two_factor;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::instrument;
+
+use super::admin::record_audit;
+
+#[derive(Deserialize)]
+pub struct TwoFactorCodeForm {
+    pub code: String,
+}
+
+#[derive(Serialize)]
+struct TwoFactorStatusContext {
+    enabled: bool,
+    required: bool,
+    recovery_codes_remaining: i64,
+    error: Option<String>,
+    success: Option<String>,
+    csrf_token: CsrfToken,
+}
+
+#[derive(Serialize)]
+struct TwoFactorSetupContext {
+    qr_svg: String,
+    secret: String,
+    action: &'static str,
+    enforced: bool,
+    error: Option<String>,
+    csrf_token: CsrfToken,
+}
+
+#[derive(Serialize)]
+struct RecoveryCodesContext {
+    codes: Vec<String>,
+}
+
+/// Check a TOTP code or an unused recovery code for an enrolled admin.
+/// Accepted TOTP steps and recovery codes cannot be used again.
+pub(super) fn verify_second_factor(
+    repository: &RuleRepository,
+    admin: &Admin,
+    code: &str,
+) -> Result<bool, AppError> {
+    if two_factor::is_totp_code(code) {
+        let Some(secret) = &admin.totp_secret else {
+            return Ok(false);
+        };
+        return Ok(
+            match two_factor::verify_current_code(
+                secret,
+                &admin.username,
+                code,
+                admin.totp_last_step,
+            )? {
+                Some(step) => repository.record_admin_totp_step(&admin.id, step)?,
+                None => false,
+            },
+        );
+    }
+
+    Ok(repository.use_recovery_code(&admin.id, &two_factor::hash_recovery_code(code))?)
+}
+
+/// Replace an admin's recovery codes and return the new plain codes for display
+pub(super) fn issue_recovery_codes(
+    repository: &RuleRepository,
+    admin_id: &str,
+) -> Result<Vec<String>, AppError> {
+    let codes = two_factor::generate_recovery_codes();
+    let hashes: Vec<String> = codes
+        .iter()
+        .map(|code| two_factor::hash_recovery_code(code))
+        .collect();
+    repository.replace_recovery_codes(admin_id, &hashes)?;
+    Ok(codes)
+}
+
+/// Render the enrolment page with the QR code for a pending secret
+pub(super) fn render_two_factor_setup(
+    templates: &Environment<'static>,
+    csrf_token: CsrfToken,
+    username: &str,
+    secret: &str,
+    action: &'static str,
+    enforced: bool,
+    error: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let context = TwoFactorSetupContext {
+        qr_svg: two_factor::qr_code_svg(secret, username)?,
+        secret: secret.to_string(),
+        action,
+        enforced,
+        error,
+        csrf_token,
+    };
+    let tmpl = templates.get_template("admin_two_factor_setup.html")?;
+    let rendered = tmpl.render(context)?;
+    Ok(Html(rendered))
+}
+
+/// Show freshly issued recovery codes; they are never shown again
+pub(super) fn render_recovery_codes(
+    templates: &Environment<'static>,
+    codes: Vec<String>,
+) -> Result<Html<String>, AppError> {
+    let tmpl = templates.get_template("admin_two_factor_recovery_codes.html")?;
+    let rendered = tmpl.render(RecoveryCodesContext { codes })?;
+    Ok(Html(rendered))
+}
+
+fn render_status(
+    templates: &Environment<'static>,
+    repository: &RuleRepository,
+    admin: &Admin,
+    csrf_token: CsrfToken,
+    error: Option<String>,
+    success: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let context = TwoFactorStatusContext {
+        enabled: admin.two_factor_enabled(),
+        required: repository.is_two_factor_required()?,
+        recovery_codes_remaining: repository.count_unused_recovery_codes(&admin.id)?,
+        error,
+        success,
+        csrf_token,
+    };
+    let tmpl = templates.get_template("admin_two_factor.html")?;
+    let rendered = tmpl.render(context)?;
+    Ok(Html(rendered))
+}
+
+fn current_admin(repository: &RuleRepository, admin: &AdminToken) -> Result<Admin, AppError> {
+    repository
+        .get_admin_by_id(admin.admin_id())?
+        .ok_or_else(|| AppError(color_eyre::eyre::eyre!("Admin not found")))
+}
+
+/// Show two-factor status for the logged-in admin
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username()))]
+pub async fn two_factor_settings(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    csrf_token: CsrfToken,
+) -> Result<Html<String>, AppError> {
+    let admin_record = current_admin(&repository, &admin)?;
+    render_status(
+        &templates,
+        &repository,
+        &admin_record,
+        csrf_token,
+        None,
+        None,
+    )
+}
+
+/// Start enrolment with a new secret
+#[instrument(skip(templates, repository, admin, csrf_token), fields(admin_username = %admin.username()))]
+pub async fn two_factor_setup(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    csrf_token: CsrfToken,
+) -> Result<Html<String>, AppError> {
+    let admin_record = current_admin(&repository, &admin)?;
+    if admin_record.two_factor_enabled() {
+        let error = Some("Two-factor authentication is already enabled".to_string());
+        return render_status(
+            &templates,
+            &repository,
+            &admin_record,
+            csrf_token,
+            error,
+            None,
+        );
+    }
+
+    let secret = two_factor::generate_secret();
+    repository.set_admin_totp_secret(&admin_record.id, Some(&secret))?;
+
+    render_two_factor_setup(
+        &templates,
+        csrf_token,
+        &admin_record.username,
+        &secret,
+        "/admin/two-factor/confirm",
+        false,
+        None,
+    )
+}
+
+/// Finish enrolment by checking a code from the authenticator app
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username()))]
+pub async fn two_factor_confirm(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    csrf_token: CsrfToken,
+    Form(form_data): Form<TwoFactorCodeForm>,
+) -> Result<Html<String>, AppError> {
+    let admin_record = current_admin(&repository, &admin)?;
+    let Some(secret) = admin_record
+        .totp_secret
+        .clone()
+        .filter(|_| !admin_record.two_factor_enabled())
+    else {
+        let error = Some("Start the setup again to get a new QR code".to_string());
+        return render_status(
+            &templates,
+            &repository,
+            &admin_record,
+            csrf_token,
+            error,
+            None,
+        );
+    };
+
+    match two_factor::verify_current_code(&secret, &admin_record.username, &form_data.code, None)? {
+        Some(step) => {
+            repository.enable_admin_totp(&admin_record.id, step)?;
+            let codes = issue_recovery_codes(&repository, &admin_record.id)?;
+            record_audit(
+                &repository,
+                &admin,
+                AuditAction::TwoFactorEnabled,
+                &admin_record.id,
+                None,
+                None,
+            )?;
+            render_recovery_codes(&templates, codes)
+        }
+        None => render_two_factor_setup(
+            &templates,
+            csrf_token,
+            &admin_record.username,
+            &secret,
+            "/admin/two-factor/confirm",
+            false,
+            Some(
+                "That code did not match. Check the time on your device and try again.".to_string(),
+            ),
+        ),
+    }
+}
+
+/// Replace all recovery codes after checking a current code
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username()))]
+pub async fn regenerate_recovery_codes(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    csrf_token: CsrfToken,
+    Form(form_data): Form<TwoFactorCodeForm>,
+) -> Result<Html<String>, AppError> {
+    let admin_record = current_admin(&repository, &admin)?;
+    if !admin_record.two_factor_enabled()
+        || !verify_second_factor(&repository, &admin_record, &form_data.code)?
+    {
+        let error = Some("Invalid authentication code".to_string());
+        return render_status(
+            &templates,
+            &repository,
+            &admin_record,
+            csrf_token,
+            error,
+            None,
+        );
+    }
+
+    let codes = issue_recovery_codes(&repository, &admin_record.id)?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::RecoveryCodesRegenerated,
+        &admin_record.id,
+        None,
+        None,
+    )?;
+    render_recovery_codes(&templates, codes)
+}
+
+/// Turn off two-factor authentication unless it is required
+#[instrument(skip(templates, repository, admin, csrf_token, form_data), fields(admin_username = %admin.username()))]
+pub async fn disable_two_factor(
+    State(templates): State<Arc<Environment<'static>>>,
+    State(repository): State<RuleRepository>,
+    admin: AdminToken,
+    csrf_token: CsrfToken,
+    Form(form_data): Form<TwoFactorCodeForm>,
+) -> Result<Html<String>, AppError> {
+    let admin_record = current_admin(&repository, &admin)?;
+
+    let error = if repository.is_two_factor_required()? {
+        Some("Two-factor authentication is required for all admins".to_string())
+    } else if !admin_record.two_factor_enabled()
+        || !verify_second_factor(&repository, &admin_record, &form_data.code)?
+    {
+        Some("Invalid authentication code".to_string())
+    } else {
+        None
+    };
+    if error.is_some() {
+        return render_status(
+            &templates,
+            &repository,
+            &admin_record,
+            csrf_token,
+            error,
+            None,
+        );
+    }
+
+    repository.disable_admin_two_factor(&admin_record.id)?;
+    record_audit(
+        &repository,
+        &admin,
+        AuditAction::TwoFactorDisabled,
+        &admin_record.id,
+        None,
+        None,
+    )?;
+
+    let admin_record = current_admin(&repository, &admin)?;
+    let success = Some("Two-factor authentication disabled".to_string());
+    render_status(
+        &templates,
+        &repository,
+        &admin_record,
+        csrf_token,
+        None,
+        success,
+    )
+}
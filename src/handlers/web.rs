@@ -40,11 +40,13 @@ struct RulesListContext {
     rule_tree: Vec<RuleNode>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, utoipa::ToSchema)]
 pub struct RuleNode {
     pub number: String,
     pub slug: String,
+    /// Rule text as Markdown
     pub content: String,
+    #[schema(no_recursion)]
     pub children: Vec<RuleNode>,
 }
 
@@ -274,7 +276,7 @@ pub async fn definitions_page(
         .collect();
 
     // Sort alphabetically by term (case-insensitive)
-    definitions.sort_by(|a, b| a.term.to_lowercase().cmp(&b.term.to_lowercase()));
+    definitions.sort_by_key(|a| a.term.to_lowercase());
 
     let template_data = DefinitionsPageData {
         rule_set_name: rule_set.name.clone(),
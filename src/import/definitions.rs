@@ -1,23 +1,21 @@
-use color_eyre::{Result, eyre::WrapErr};
-use diesel::r2d2::{ConnectionManager, Pool};
-use diesel::sqlite::SqliteConnection;
+use color_eyre::Result;
 use regex::Regex;
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use tracing::{info, warn};
 
-use regelator::config::{Config, ImportConfig};
-use regelator::models::*;
-use regelator::repository::RuleRepository;
+use super::{ImportTarget, find_rule_set_and_version};
+use crate::models::*;
+use crate::repository::RuleRepository;
 
 #[derive(Debug)]
-struct DefinitionData {
-    term: String,
-    slug: String,
-    definition: String,
+pub struct DefinitionData {
+    pub term: String,
+    pub slug: String,
+    pub definition: String,
 }
 
 /// Generate a slug from a term (e.g., "Affect the play" -> "affect-the-play")
-fn generate_slug(term: &str) -> String {
+pub fn generate_slug(term: &str) -> String {
     term.to_lowercase()
         .chars()
         .map(|c| if c.is_alphanumeric() { c } else { '-' })
@@ -28,26 +26,27 @@ fn generate_slug(term: &str) -> String {
         .join("-")
 }
 
-/// Read definitions from stdin, handling multi-line definitions
+/// Read definitions, handling multi-line definitions
 /// New definitions start with pattern: ^[^.:\[\]]+: (anything except dots, colons, and brackets, then colon)
-fn read_definitions_from_stdin() -> Result<Vec<DefinitionData>> {
-    let stdin = io::stdin();
-    let term_start_pattern = Regex::new(r"^([^.:\[\]]+?):\s*(.*)$").unwrap();
+/// An explicit slug may follow the term in brackets (`Wurf [act-of-throwing]: ...`),
+/// which lets translated terms attach to the existing English glossary entry.
+pub fn parse_definitions(reader: impl BufRead) -> Result<Vec<DefinitionData>> {
+    let term_start_pattern =
+        Regex::new(r"^([^.:\[\]]+?)(?:\s*\[([a-z0-9-]+)\])?:\s*(.*)$").unwrap();
     let mut definitions = Vec::new();
-    let mut current_term: Option<String> = None;
+    let mut current: Option<(String, String)> = None; // (term, slug)
     let mut current_definition = String::new();
 
-    for line in stdin.lock().lines() {
+    for line in reader.lines() {
         let line = line?;
         let line = line.trim();
 
         // Check if this line starts a new definition
         if let Some(caps) = term_start_pattern.captures(line) {
             // Save the previous definition if we have one
-            if let Some(term) = current_term.take()
+            if let Some((term, slug)) = current.take()
                 && !current_definition.trim().is_empty()
             {
-                let slug = generate_slug(&term);
                 definitions.push(DefinitionData {
                     term,
                     slug,
@@ -56,9 +55,14 @@ fn read_definitions_from_stdin() -> Result<Vec<DefinitionData>> {
             }
 
             // Start new definition
-            current_term = Some(caps.get(1).unwrap().as_str().trim().to_string());
-            current_definition = caps.get(2).unwrap().as_str().to_string();
-        } else if current_term.is_some() {
+            let term = caps.get(1).unwrap().as_str().trim().to_string();
+            let slug = caps
+                .get(2)
+                .map(|slug| slug.as_str().to_string())
+                .unwrap_or_else(|| generate_slug(&term));
+            current = Some((term, slug));
+            current_definition = caps.get(3).unwrap().as_str().to_string();
+        } else if current.is_some() {
             // Continue current definition (including empty lines for paragraph breaks)
             if !current_definition.is_empty() {
                 current_definition.push('\n');
@@ -70,10 +74,9 @@ fn read_definitions_from_stdin() -> Result<Vec<DefinitionData>> {
     }
 
     // Don't forget the last definition
-    if let Some(term) = current_term
+    if let Some((term, slug)) = current
         && !current_definition.trim().is_empty()
     {
-        let slug = generate_slug(&term);
         definitions.push(DefinitionData {
             term,
             slug,
@@ -84,89 +87,68 @@ fn read_definitions_from_stdin() -> Result<Vec<DefinitionData>> {
     Ok(definitions)
 }
 
-/// Import definitions into the database
-fn import_definitions(definitions: Vec<DefinitionData>) -> Result<()> {
-    // Load configuration
-    let config = Config::load().wrap_err("Failed to load configuration")?;
-    let import_config = ImportConfig::load().wrap_err("Failed to load configuration")?;
-
-    // Database setup
-    let manager = ConnectionManager::<SqliteConnection>::new(&config.database.url);
-    let pool = Pool::builder()
-        .build(manager)
-        .wrap_err("Failed to create connection pool")?;
-
-    let repo = RuleRepository::new(pool);
-
-    let rule_set_slug = import_config.rule_set_slug;
-    let version_name = import_config.version_name;
-
-    // Find the rule set by slug
-    let rule_sets = repo.get_rule_sets()?;
-    let rule_set = rule_sets
-        .iter()
-        .find(|rs| rs.slug == *rule_set_slug)
-        .ok_or_else(|| {
-            color_eyre::eyre::eyre!(
-                "Rule set '{}' not found. Please import rules first.",
-                rule_set_slug
-            )
-        })?;
-
-    let version = repo
-        .get_version_by_name(&rule_set_slug, &version_name)?
-        .ok_or_else(|| {
-            color_eyre::eyre::eyre!("No current version found for rule set '{}'", rule_set_slug)
-        })?;
-
-    let definitions_count = definitions.len();
+/// Import definitions into the glossary of an existing rule set version
+pub fn import_definitions(
+    repo: &RuleRepository,
+    target: &ImportTarget,
+    definitions: Vec<DefinitionData>,
+) -> Result<usize> {
+    let (rule_set, version) = find_rule_set_and_version(repo, target)?;
+
     info!(
-        "Importing {} definitions into rule set '{}' version '{}'",
-        definitions_count, rule_set.name, version.version_name
+        "Importing {} definitions into rule set '{}' version '{}' ({})",
+        definitions.len(),
+        rule_set.name,
+        version.version_name,
+        target.language
     );
 
+    let mut imported = 0;
     for definition in definitions {
         info!("Importing: {} -> {}", definition.term, definition.slug);
 
-        // Create glossary term
-        let new_term = NewGlossaryTerm::new(
-            rule_set.id.clone(),
-            version.id.clone(),
-            definition.slug.clone(),
-        );
-
-        let term = repo.create_glossary_term(new_term)?;
+        let term = match repo.find_glossary_term(&rule_set.id, &version.id, &definition.slug)? {
+            Some(term) => term,
+            None => repo.create_glossary_term(NewGlossaryTerm::new(
+                rule_set.id.clone(),
+                version.id.clone(),
+                definition.slug.clone(),
+            ))?,
+        };
+
+        if repo
+            .find_glossary_content_for_language(&term.id, &target.language)?
+            .is_some()
+        {
+            warn!(
+                "Term '{}' already has '{}' content, skipping",
+                definition.slug, target.language
+            );
+            continue;
+        }
 
-        // Create glossary content
-        let new_content = NewGlossaryContent::new(
+        repo.create_glossary_content(NewGlossaryContent::new(
             term.id,
-            "en".to_string(),
+            target.language.clone(),
             definition.term,
             definition.definition,
-        );
+        ))?;
 
-        repo.create_glossary_content(new_content)?;
+        imported += 1;
     }
 
-    info!("Successfully imported {} definitions", definitions_count);
-    Ok(())
-}
-
-fn main() -> Result<()> {
-    color_eyre::install()?;
-    tracing_subscriber::fmt::init();
-
-    let definitions = read_definitions_from_stdin()?;
-    if definitions.is_empty() {
-        warn!("No definitions provided");
-        return Ok(());
-    }
-    import_definitions(definitions)
+    info!("Successfully imported {} definitions", imported);
+    Ok(imported)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Cursor;
+
+    fn parse(input: &str) -> Vec<DefinitionData> {
+        parse_definitions(Cursor::new(input)).unwrap()
+    }
 
     #[test]
     fn test_generate_slug() {
@@ -176,62 +158,11 @@ mod tests {
         assert_eq!(generate_slug("Non-contact sport"), "non-contact-sport");
     }
 
-    // Helper function to simulate stdin input for testing
-    fn test_parse_definitions(input: &str) -> Result<Vec<DefinitionData>> {
-        use std::io::Cursor;
-
-        let cursor = Cursor::new(input);
-        let term_start_pattern = Regex::new(r"^([^.:\[\]]+?):\s*(.*)$").unwrap();
-        let mut definitions = Vec::new();
-        let mut current_term: Option<String> = None;
-        let mut current_definition = String::new();
-
-        for line in cursor.lines() {
-            let line = line?;
-            let line = line.trim();
-
-            if let Some(caps) = term_start_pattern.captures(line) {
-                if let Some(term) = current_term.take() {
-                    if !current_definition.trim().is_empty() {
-                        let slug = generate_slug(&term);
-                        definitions.push(DefinitionData {
-                            term,
-                            slug,
-                            definition: current_definition.trim().to_string(),
-                        });
-                    }
-                }
-
-                current_term = Some(caps.get(1).unwrap().as_str().trim().to_string());
-                current_definition = caps.get(2).unwrap().as_str().to_string();
-            } else if current_term.is_some() {
-                // Continue current definition (including empty lines for paragraph breaks)
-                if !current_definition.is_empty() {
-                    current_definition.push('\n');
-                }
-                current_definition.push_str(line);
-            }
-        }
-
-        if let Some(term) = current_term {
-            if !current_definition.trim().is_empty() {
-                let slug = generate_slug(&term);
-                definitions.push(DefinitionData {
-                    term,
-                    slug,
-                    definition: current_definition.trim().to_string(),
-                });
-            }
-        }
-
-        Ok(definitions)
-    }
-
     #[test]
     fn test_single_line_definition() {
         let input = "Affect the play: A breach or call affects the play if it is reasonable to assume that the outcome of the specific play may have been meaningfully different had the breach or call not occurred.";
 
-        let definitions = test_parse_definitions(input).unwrap();
+        let definitions = parse(input);
 
         assert_eq!(definitions.len(), 1);
         assert_eq!(definitions[0].term, "Affect the play");
@@ -246,7 +177,7 @@ mod tests {
     fn test_multi_line_definition() {
         let input = "Ultimate Frisbee: A sport played by two teams\nof seven players each on a rectangular field\nwith end zones at each end.";
 
-        let definitions = test_parse_definitions(input).unwrap();
+        let definitions = parse(input);
 
         assert_eq!(definitions.len(), 1);
         assert_eq!(definitions[0].term, "Ultimate Frisbee");
@@ -261,7 +192,7 @@ mod tests {
     fn test_multiple_definitions() {
         let input = "Act of throwing: The act of releasing the disc.\n\nFoul: A violation of the rules.\nCalling fouls is important for fair play.";
 
-        let definitions = test_parse_definitions(input).unwrap();
+        let definitions = parse(input);
 
         assert_eq!(definitions.len(), 2);
 
@@ -281,7 +212,7 @@ mod tests {
     fn test_parentheses_and_abbreviations() {
         let input = "Out-of-bounds (OB): Everything that is not part of the playing field, including the perimeter lines.";
 
-        let definitions = test_parse_definitions(input).unwrap();
+        let definitions = parse(input);
 
         assert_eq!(definitions.len(), 1);
         assert_eq!(definitions[0].term, "Out-of-bounds (OB)");
@@ -297,7 +228,7 @@ mod tests {
         let input =
             "1.2.3. this should be ignored\nAct of throwing: The act of releasing the disc.";
 
-        let definitions = test_parse_definitions(input).unwrap();
+        let definitions = parse(input);
 
         assert_eq!(definitions.len(), 1);
         assert_eq!(definitions[0].term, "Act of throwing");
@@ -307,7 +238,7 @@ mod tests {
     fn test_definition_with_links() {
         let input = "Affect the play: A [breach](definition:breach) or [call](definition:call) affects the play if it is reasonable to assume that the outcome of the specific play may have been meaningfully different.";
 
-        let definitions = test_parse_definitions(input).unwrap();
+        let definitions = parse(input);
 
         assert_eq!(definitions.len(), 1);
         assert_eq!(definitions[0].term, "Affect the play");
@@ -317,4 +248,16 @@ mod tests {
             "A [breach](definition:breach) or [call](definition:call) affects the play if it is reasonable to assume that the outcome of the specific play may have been meaningfully different."
         );
     }
+
+    #[test]
+    fn test_explicit_slug_for_translation() {
+        let input = "Wurfbewegung [act-of-throwing]: Das Loslassen der Scheibe.";
+
+        let definitions = parse(input);
+
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].term, "Wurfbewegung");
+        assert_eq!(definitions[0].slug, "act-of-throwing");
+        assert_eq!(definitions[0].definition, "Das Loslassen der Scheibe.");
+    }
 }
@@ -0,0 +1,158 @@
+//! Parsers and loaders for the plain-text formats in `import_docs/`
+pub mod definitions;
+pub mod questions;
+pub mod rules;
+
+use color_eyre::Result;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::models::{RuleSet, Version};
+use crate::repository::RuleRepository;
+
+pub use definitions::{DefinitionData, import_definitions, parse_definitions};
+pub use questions::{
+    QuizQuestionImport, import_quiz_questions, parse_quiz_questions, read_quiz_questions,
+};
+pub use rules::{RuleData, RuleImportOptions, import_rules, parse_rules};
+
+/// Rule set, version and language an import writes into
+#[derive(Debug, Clone)]
+pub struct ImportTarget {
+    pub rule_set_slug: String,
+    pub version_name: String,
+    pub language: String,
+}
+
+/// Look up an existing rule set and version for an import
+pub fn find_rule_set_and_version(
+    repo: &RuleRepository,
+    target: &ImportTarget,
+) -> Result<(RuleSet, Version)> {
+    let rule_set = repo
+        .get_rule_sets()?
+        .into_iter()
+        .find(|rs| rs.slug == target.rule_set_slug)
+        .ok_or_else(|| {
+            color_eyre::eyre::eyre!(
+                "Rule set '{}' not found. Please import rules first.",
+                target.rule_set_slug
+            )
+        })?;
+
+    let version = repo
+        .get_version_by_name(&target.rule_set_slug, &target.version_name)?
+        .ok_or_else(|| {
+            color_eyre::eyre::eyre!(
+                "Version '{}' not found for rule set '{}'",
+                target.version_name,
+                target.rule_set_slug
+            )
+        })?;
+
+    Ok((rule_set, version))
+}
+
+/// Process rule number references in content and replace with [text](rule:slug) links
+pub fn process_number_references(
+    content: &str,
+    number_to_slug: &HashMap<String, String>, // rule number -> slug
+) -> (String, Vec<String>) {
+    // Match rule references: numbers with dots OR numbers prefixed by "Section"
+    let reference_pattern =
+        Regex::new(r"\b(?:Section\s+(\d+(?:\.\d+)*)|(\d+\.\d+(?:\.\d+)*))\b").unwrap();
+    let mut processed_content = content.to_string();
+    let mut broken_references = Vec::new();
+
+    // Find all rule reference patterns and collect replacements
+    let mut replacements = Vec::new();
+
+    for captures in reference_pattern.captures_iter(content) {
+        let full_match = captures.get(0).unwrap();
+
+        let rule_number = if let Some(section_num) = captures.get(1) {
+            section_num.as_str() // "Section 16" -> "16"
+        } else {
+            captures.get(2).unwrap().as_str() // "16.3" -> "16.3"
+        };
+
+        if let Some(slug) = number_to_slug.get(rule_number) {
+            // Replace with markdown link - both "Section X" and "X.Y" use rule: scheme
+            let markdown_link = format!("[{}](rule:{slug})", full_match.as_str());
+            replacements.push((full_match.start(), full_match.end(), markdown_link));
+        } else {
+            // Keep original but track as potential broken reference
+            broken_references.push(rule_number.to_string());
+        }
+    }
+
+    // Apply replacements from end to start to preserve indices
+    for (start, end, replacement) in replacements.into_iter().rev() {
+        processed_content.replace_range(start..end, &replacement);
+    }
+
+    (processed_content, broken_references)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_number_references() {
+        let mut number_to_slug = HashMap::new();
+        number_to_slug.insert("16.3".to_string(), "handling-contested-calls".to_string());
+        number_to_slug.insert("1".to_string(), "spirit-of-the-game".to_string());
+        number_to_slug.insert(
+            "11.8".to_string(),
+            "observers-and-rules-advisors".to_string(),
+        );
+
+        let content = "If the opposition does not gain possession, apply 16.3 according to Section 1 and Section 11.8.";
+        let (processed, broken_refs) = process_number_references(content, &number_to_slug);
+
+        let expected = "If the opposition does not gain possession, apply [16.3](rule:handling-contested-calls) according to [Section 1](rule:spirit-of-the-game) and [Section 11.8](rule:observers-and-rules-advisors).";
+        assert_eq!(processed, expected);
+        assert!(broken_refs.is_empty());
+    }
+
+    #[test]
+    fn test_process_number_references_ignores_parenthetical() {
+        let mut number_to_slug = HashMap::new();
+        number_to_slug.insert("16.3".to_string(), "handling-contested-calls".to_string());
+
+        let content = "Add two (2) seconds to the stall count. Apply 16.3 if needed. This results in ten (10) seconds.";
+        let (processed, broken_refs) = process_number_references(content, &number_to_slug);
+
+        let expected = "Add two (2) seconds to the stall count. Apply [16.3](rule:handling-contested-calls) if needed. This results in ten (10) seconds.";
+        assert_eq!(processed, expected);
+        assert!(broken_refs.is_empty());
+    }
+
+    #[test]
+    fn test_process_number_references_with_broken_refs() {
+        let mut number_to_slug = HashMap::new();
+        number_to_slug.insert("16.3".to_string(), "handling-contested-calls".to_string());
+
+        let content = "Apply 16.3 and also 99.9 here.";
+        let (processed, broken_refs) = process_number_references(content, &number_to_slug);
+
+        let expected = "Apply [16.3](rule:handling-contested-calls) and also 99.9 here.";
+        assert_eq!(processed, expected);
+        assert_eq!(broken_refs, vec!["99.9"]);
+    }
+
+    #[test]
+    fn test_process_number_references_in_explanation() {
+        let mut number_to_slug = HashMap::new();
+        number_to_slug.insert("16.3".to_string(), "handling-contested-calls".to_string());
+        number_to_slug.insert("1".to_string(), "spirit-of-the-game".to_string());
+
+        let content = "According to 16.3 and Section 1, the disc returns to the thrower.";
+        let (processed, broken_refs) = process_number_references(content, &number_to_slug);
+
+        let expected = "According to [16.3](rule:handling-contested-calls) and [Section 1](rule:spirit-of-the-game), the disc returns to the thrower.";
+        assert_eq!(processed, expected);
+        assert!(broken_refs.is_empty());
+    }
+}
@@ -1,134 +1,36 @@
-use color_eyre::{Result, eyre::WrapErr};
-use diesel::prelude::*;
-use diesel::r2d2::{ConnectionManager, Pool};
+use color_eyre::Result;
 use regex::Regex;
 use std::collections::HashMap;
-use std::io::{self, BufRead};
-use tracing::info;
+use std::io::BufRead;
+use tracing::{info, warn};
 
-use regelator::config::{Config, ImportConfig};
-use regelator::models::*;
-use regelator::repository::RuleRepository;
+use super::{ImportTarget, find_rule_set_and_version, process_number_references};
+use crate::models::*;
+use crate::repository::RuleRepository;
 
 #[derive(Debug)]
-struct QuizQuestionImport {
-    question_text: String,
-    difficulty: String,
-    answers: Vec<QuizAnswerImport>,
-    explanation: String,
-    rule_references: Vec<String>, // Rule numbers mentioned in REF lines
+pub struct QuizQuestionImport {
+    pub question_text: String,
+    pub difficulty: String,
+    pub answers: Vec<QuizAnswerImport>,
+    pub explanation: String,
+    pub rule_references: Vec<String>, // Rule numbers mentioned in REF lines
 }
 
 #[derive(Debug)]
-struct QuizAnswerImport {
-    text: String,
-    is_correct: bool,
+pub struct QuizAnswerImport {
+    pub text: String,
+    pub is_correct: bool,
 }
 
-fn main() -> Result<()> {
-    color_eyre::install()?;
-    tracing_subscriber::fmt::init();
-
-    // Configuration constants
-    // Load configuration
-    let config = Config::load().wrap_err("Failed to load configuration")?;
-    let import_config = ImportConfig::load().wrap_err("Failed to load configuration")?;
-
-    let rule_set_slug = import_config.rule_set_slug;
-    let version_name = import_config.version_name;
-    let language = "en";
-
-    let manager = ConnectionManager::<SqliteConnection>::new(&config.database.url);
-    let pool = Pool::builder()
-        .build(manager)
-        .wrap_err("Failed to create connection pool")?;
-
-    let repository = RuleRepository::new(pool);
-
-    // Read questions from stdin
-    let stdin = io::stdin();
-    let lines: Vec<String> = stdin.lock().lines().collect::<Result<_, _>>()?;
-
-    let questions = parse_quiz_questions(&lines)?;
-    info!("Parsed {} quiz questions", questions.len());
-
-    let rule_sets = repository.get_rule_sets()?;
-    let rule_set = rule_sets
-        .iter()
-        .find(|rs| rs.slug == *rule_set_slug)
-        .ok_or_else(|| color_eyre::eyre::eyre!("Rule set '{}' not found", rule_set_slug))?;
-
-    let version = repository
-        .get_version_by_name(&rule_set_slug, &version_name)?
-        .ok_or_else(|| {
-            color_eyre::eyre::eyre!("No current version found for rule set '{}'", rule_set_slug)
-        })?;
-
-    // Get all rules to build number-to-slug and number-to-id mappings
-    let all_rules_with_content =
-        repository.get_rules_with_content_for_version(&version.id, language)?;
-
-    let number_to_slug: HashMap<String, String> = all_rules_with_content
-        .iter()
-        .map(|(rule, _)| (rule.number.clone(), rule.slug.clone()))
-        .collect();
-
-    let number_to_id: HashMap<String, String> = all_rules_with_content
-        .iter()
-        .map(|(rule, _)| (rule.number.clone(), rule.id.clone()))
-        .collect();
-
-    // Process and import each question
-    for question_import in questions {
-        info!(
-            "Importing question: {}",
-            &question_import.question_text[..50.min(question_import.question_text.len())]
-        );
-
-        // Process rule references in explanation
-        let (processed_explanation, _broken_refs) =
-            process_number_references(&question_import.explanation, &number_to_slug);
-
-        // Convert answers to business layer format
-        let answers: Vec<QuizAnswerData> = question_import
-            .answers
-            .into_iter()
-            .map(|a| QuizAnswerData {
-                answer_text: a.text,
-                is_correct: a.is_correct,
-            })
-            .collect();
-
-        // Get rule IDs for the question based on rule references
-        let rule_ids: Vec<String> = question_import
-            .rule_references
-            .iter()
-            .filter_map(|rule_num| number_to_id.get(rule_num))
-            .cloned()
-            .collect();
-
-        // Create business layer question data
-        let question_data = QuizQuestionData::new(
-            rule_set.id.clone(),
-            version.id.clone(),
-            question_import.question_text,
-            processed_explanation,
-            question_import.difficulty,
-            answers,
-            rule_ids,
-        );
-
-        // Import the complete question
-        let created_question = repository.create_quiz_question_complete(&question_data)?;
-        info!("Created question with ID: {}", created_question.id);
-    }
-
-    info!("Import completed successfully!");
-    Ok(())
+/// Read all lines and parse them as quiz questions
+pub fn read_quiz_questions(reader: impl BufRead) -> Result<Vec<QuizQuestionImport>> {
+    let lines: Vec<String> = reader.lines().collect::<Result<_, _>>()?;
+    parse_quiz_questions(&lines)
 }
 
 /// Parse quiz questions from input lines
-fn parse_quiz_questions(lines: &[String]) -> Result<Vec<QuizQuestionImport>> {
+pub fn parse_quiz_questions(lines: &[String]) -> Result<Vec<QuizQuestionImport>> {
     let mut questions = Vec::new();
     let mut current_question: Option<QuizQuestionImport> = None;
     let mut i = 0;
@@ -143,7 +45,6 @@ fn parse_quiz_questions(lines: &[String]) -> Result<Vec<QuizQuestionImport>> {
             }
 
             // Parse question with difficulty
-            // Remove "Q: "
             let (difficulty, question_text) = parse_question_with_difficulty(question_line)?;
 
             current_question = Some(QuizQuestionImport {
@@ -156,7 +57,6 @@ fn parse_quiz_questions(lines: &[String]) -> Result<Vec<QuizQuestionImport>> {
         } else if let Some(refs) = line.strip_prefix("REF: ") {
             // Parse rule references
             if let Some(ref mut question) = current_question {
-                // Remove "REF: "
                 for rule_ref in refs.split(',') {
                     question.rule_references.push(rule_ref.trim().to_string());
                 }
@@ -164,7 +64,6 @@ fn parse_quiz_questions(lines: &[String]) -> Result<Vec<QuizQuestionImport>> {
         } else if let Some(answer_line) = line.strip_prefix("A: ") {
             // Parse answer
             if let Some(ref mut question) = current_question {
-                // Remove "A: "
                 let (is_correct, answer_text) = parse_answer(answer_line);
                 question.answers.push(QuizAnswerImport {
                     text: answer_text.to_string(),
@@ -232,48 +131,79 @@ fn parse_answer(line: &str) -> (bool, &str) {
     }
 }
 
-/// Process rule number references in content and replace with [text](rule:slug) links
-/// Reuses the same logic as the rule import script
-fn process_number_references(
-    content: &str,
-    number_to_slug: &HashMap<String, String>,
-) -> (String, Vec<String>) {
-    // Match rule references: numbers with dots OR numbers prefixed by "Section"
-    let reference_pattern =
-        Regex::new(r"\b(?:Section\s+(\d+(?:\.\d+)*)|(\d+\.\d+(?:\.\d+)*))\b").unwrap();
-    let mut processed_content = content.to_string();
-    let mut broken_references = Vec::new();
-
-    // Find all rule reference patterns and collect replacements
-    let mut replacements = Vec::new();
-
-    for mat in reference_pattern.find_iter(content) {
-        let full_match = mat.as_str();
-        let match_start = mat.start();
-        let match_end = mat.end();
-
-        // Extract the rule number (handle both "Section X" and "X.Y" patterns)
-        let rule_number = full_match.strip_prefix("Section ").unwrap_or(full_match);
-
-        if let Some(slug) = number_to_slug.get(rule_number) {
-            let replacement = if full_match.starts_with("Section ") {
-                format!("[Section {rule_number}](rule:{slug})")
-            } else {
-                format!("[{rule_number}](rule:{slug})")
-            };
-            replacements.push((match_start, match_end, replacement));
-        } else {
-            broken_references.push(rule_number.to_string());
+/// Import quiz questions into an existing rule set version
+pub fn import_quiz_questions(
+    repo: &RuleRepository,
+    target: &ImportTarget,
+    questions: Vec<QuizQuestionImport>,
+) -> Result<usize> {
+    let (rule_set, version) = find_rule_set_and_version(repo, target)?;
+
+    // Get all rules to build number-to-slug and number-to-id mappings
+    let all_rules = repo.get_rules_for_version(&version.id)?;
+
+    let number_to_slug: HashMap<String, String> = all_rules
+        .iter()
+        .map(|rule| (rule.number.clone(), rule.slug.clone()))
+        .collect();
+
+    let number_to_id: HashMap<String, String> = all_rules
+        .iter()
+        .map(|rule| (rule.number.clone(), rule.id.clone()))
+        .collect();
+
+    let question_count = questions.len();
+    for question_import in questions {
+        info!(
+            "Importing question: {}",
+            question_import
+                .question_text
+                .chars()
+                .take(50)
+                .collect::<String>()
+        );
+
+        // Process rule references in explanation
+        let (processed_explanation, broken_refs) =
+            process_number_references(&question_import.explanation, &number_to_slug);
+        if !broken_refs.is_empty() {
+            warn!("Explanation contains unknown rule references: {broken_refs:?}");
         }
-    }
 
-    // Apply replacements in reverse order to maintain correct positions
-    replacements.reverse();
-    for (start, end, replacement) in replacements {
-        processed_content.replace_range(start..end, &replacement);
+        // Convert answers to business layer format
+        let answers: Vec<QuizAnswerData> = question_import
+            .answers
+            .into_iter()
+            .map(|a| QuizAnswerData {
+                answer_text: a.text,
+                is_correct: a.is_correct,
+            })
+            .collect();
+
+        // Get rule IDs for the question based on rule references
+        let rule_ids: Vec<String> = question_import
+            .rule_references
+            .iter()
+            .filter_map(|rule_num| number_to_id.get(rule_num))
+            .cloned()
+            .collect();
+
+        let question_data = QuizQuestionData::new(
+            rule_set.id.clone(),
+            version.id.clone(),
+            question_import.question_text,
+            processed_explanation,
+            question_import.difficulty,
+            answers,
+            rule_ids,
+        );
+
+        let created_question = repo.create_quiz_question_complete(&question_data)?;
+        info!("Created question with ID: {}", created_question.id);
     }
 
-    (processed_content, broken_references)
+    info!("Imported {} quiz questions", question_count);
+    Ok(question_count)
 }
 
 #[cfg(test)]
@@ -306,16 +236,18 @@ mod tests {
     }
 
     #[test]
-    fn test_process_number_references() {
-        let mut number_to_slug = HashMap::new();
-        number_to_slug.insert("16.3".to_string(), "handling-contested-calls".to_string());
-        number_to_slug.insert("1".to_string(), "spirit-of-the-game".to_string());
-
-        let content = "According to 16.3 and Section 1, the disc returns to the thrower.";
-        let (processed, broken_refs) = process_number_references(content, &number_to_slug);
-
-        let expected = "According to [16.3](rule:handling-contested-calls) and [Section 1](rule:spirit-of-the-game), the disc returns to the thrower.";
-        assert_eq!(processed, expected);
-        assert!(broken_refs.is_empty());
+    fn test_read_quiz_questions() {
+        let input = "Q: [BEGINNER] What is the primary objective?\nREF: 1.1, 2.1\nA: Score points [CORRECT]\nA: Hold the disc\nEXPLAIN: See Section 1.\nMore detail.\n\nQ: [ADVANCED] Second?\nA: Yes [CORRECT]\nA: No\nEXPLAIN: Because.";
+
+        let questions = read_quiz_questions(std::io::Cursor::new(input)).unwrap();
+
+        assert_eq!(questions.len(), 2);
+        assert_eq!(questions[0].difficulty, "beginner");
+        assert_eq!(questions[0].rule_references, vec!["1.1", "2.1"]);
+        assert_eq!(questions[0].answers.len(), 2);
+        assert!(questions[0].answers[0].is_correct);
+        assert_eq!(questions[0].explanation, "See Section 1.\nMore detail.");
+        assert_eq!(questions[1].difficulty, "advanced");
+        assert_eq!(questions[1].explanation, "Because.");
     }
 }
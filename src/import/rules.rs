@@ -0,0 +1,278 @@
+use color_eyre::{Result, eyre::WrapErr};
+use regex::Regex;
+use std::collections::HashMap;
+use std::io::BufRead;
+use tracing::{info, warn};
+
+use super::{ImportTarget, process_number_references};
+use crate::models::*;
+use crate::repository::RuleRepository;
+
+#[derive(Debug)]
+pub struct RuleData {
+    pub number: String,
+    pub slug: String,
+    pub content: String,
+}
+
+/// Settings used when the rule set or version does not exist yet
+#[derive(Debug, Clone, Default)]
+pub struct RuleImportOptions {
+    /// Display name for a newly created rule set (defaults to the slug)
+    pub rule_set_name: Option<String>,
+    /// Effective date for a newly created version
+    pub effective_from: Option<chrono::NaiveDate>,
+}
+
+fn parse_rule_number(number: &str) -> Vec<u32> {
+    number.split('.').map(|s| s.parse().unwrap_or(0)).collect()
+}
+
+fn find_parent_rule(rules: &HashMap<String, String>, current_number: &str) -> Option<String> {
+    let current_parts = parse_rule_number(current_number);
+    if current_parts.len() <= 1 {
+        return None; // Top-level rule has no parent
+    }
+
+    // Parent has one less level
+    let parent_parts = &current_parts[..current_parts.len() - 1];
+    let parent_number = parent_parts
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(".");
+
+    rules.get(&parent_number).cloned()
+}
+
+/// Read rules in the `1.2.3. slug Content` line format
+pub fn parse_rules(reader: impl BufRead) -> Result<Vec<RuleData>> {
+    let rule_pattern = Regex::new(r"^((?:\d+\.)+)\s+(\S+)\s+(.+)$").unwrap();
+    let mut rules = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+
+        // Extract rule number, slug, and content
+        if let Some(caps) = rule_pattern.captures(line) {
+            let number_with_dot = caps.get(1).unwrap().as_str();
+            let number = number_with_dot.trim_end_matches('.').to_string();
+            let slug = caps.get(2).unwrap().as_str().to_string();
+            let content = caps.get(3).unwrap().as_str().to_string();
+
+            rules.push(RuleData {
+                number,
+                slug,
+                content,
+            });
+        }
+    }
+
+    Ok(rules)
+}
+
+/// Import rules into a rule set version, creating the rule set and version if needed.
+///
+/// Rules that already exist in the version (matched by number) only receive content
+/// for the target language, so the same command imports translations.
+pub fn import_rules(
+    repo: &RuleRepository,
+    target: &ImportTarget,
+    options: &RuleImportOptions,
+    rule_data: Vec<RuleData>,
+) -> Result<usize> {
+    let rule_set = match repo
+        .get_rule_sets()?
+        .into_iter()
+        .find(|rs| rs.slug == target.rule_set_slug)
+    {
+        Some(rule_set) => rule_set,
+        None => {
+            info!("Creating rule set '{}'...", target.rule_set_slug);
+            repo.create_rule_set(NewRuleSet::new(
+                options
+                    .rule_set_name
+                    .clone()
+                    .unwrap_or_else(|| target.rule_set_slug.clone()),
+                target.rule_set_slug.clone(),
+                None,
+            ))?
+        }
+    };
+
+    let version = match repo.get_version_by_name(&rule_set.slug, &target.version_name)? {
+        Some(version) => version,
+        None => {
+            let effective_from = options.effective_from.ok_or_else(|| {
+                color_eyre::eyre::eyre!(
+                    "Version '{}' does not exist yet; an effective date is required to create it",
+                    target.version_name
+                )
+            })?;
+            // Only the first version of a rule set becomes current automatically
+            let is_current = repo.get_current_version(&rule_set.slug)?.is_none();
+
+            info!("Creating version '{}'...", target.version_name);
+            repo.create_version(NewVersion::new(
+                rule_set.id.clone(),
+                target.version_name.clone(),
+                effective_from,
+                None,
+                None,
+                is_current,
+            ))?
+        }
+    };
+
+    // Build number -> slug mapping for reference processing
+    let number_to_slug: HashMap<String, String> = rule_data
+        .iter()
+        .map(|rule| (rule.number.clone(), rule.slug.clone()))
+        .collect();
+
+    // Sort rules by number to ensure parents are created before children
+    let mut sorted_rules: Vec<RuleData> = rule_data
+        .into_iter()
+        .map(|mut rule| {
+            let (processed_content, broken_refs) =
+                process_number_references(&rule.content, &number_to_slug);
+
+            if !broken_refs.is_empty() {
+                warn!(
+                    "Rule {} contains potential broken references: {:?}",
+                    rule.number, broken_refs
+                );
+            }
+
+            rule.content = processed_content;
+            rule
+        })
+        .collect();
+
+    sorted_rules.sort_by(|a, b| {
+        let a_parts = parse_rule_number(&a.number);
+        let b_parts = parse_rule_number(&b.number);
+        a_parts.cmp(&b_parts)
+    });
+
+    // Existing rules are reused so that translations attach to the same rule IDs
+    let existing_rules: HashMap<String, Rule> = repo
+        .get_rules_for_version(&version.id)?
+        .into_iter()
+        .map(|rule| (rule.number.clone(), rule))
+        .collect();
+
+    // Track rule IDs by their number for parent relationships
+    let mut rule_ids: HashMap<String, String> = existing_rules
+        .iter()
+        .map(|(number, rule)| (number.clone(), rule.id.clone()))
+        .collect();
+
+    info!(
+        "Importing {} rules into '{}' version '{}' ({})...",
+        sorted_rules.len(),
+        rule_set.name,
+        version.version_name,
+        target.language
+    );
+
+    let mut imported = 0;
+    for rule_data in sorted_rules {
+        let rule_id = match existing_rules.get(&rule_data.number) {
+            Some(existing) => {
+                if existing.slug != rule_data.slug {
+                    warn!(
+                        "Rule {} has slug '{}' in the database but '{}' in the input",
+                        rule_data.number, existing.slug, rule_data.slug
+                    );
+                }
+                existing.id.clone()
+            }
+            None => {
+                let rule = NewRule::new(
+                    rule_data.slug.clone(),
+                    rule_set.id.clone(),
+                    version.id.clone(),
+                    find_parent_rule(&rule_ids, &rule_data.number),
+                    rule_data.number.clone(),
+                );
+
+                info!("Creating rule {} ({})", rule_data.number, rule_data.slug);
+                let created = repo.create_rule(rule)?;
+                rule_ids.insert(rule_data.number.clone(), created.id.clone());
+                created.id
+            }
+        };
+
+        if repo
+            .find_rule_content_for_language(&rule_id, &target.language)?
+            .is_some()
+        {
+            warn!(
+                "Rule {} already has '{}' content, skipping",
+                rule_data.number, target.language
+            );
+            continue;
+        }
+
+        // Translations point back at the English original
+        let source_content_id = if target.language == "en" {
+            None
+        } else {
+            repo.find_rule_content_for_language(&rule_id, "en")?
+                .map(|content| content.id)
+        };
+
+        repo.create_rule_content(NewRuleContent::new(
+            rule_id,
+            target.language.clone(),
+            rule_data.content,
+            source_content_id,
+        ))
+        .wrap_err_with(|| format!("Failed to import rule {}", rule_data.number))?;
+
+        imported += 1;
+    }
+
+    info!("Imported content for {} rules", imported);
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_parse_rules() {
+        let input = "1. spirit-of-game Spirit of the Game\n\n1.1. spirit-self-officiated Ultimate is a non-contact sport.\nnot a rule line";
+
+        let rules = parse_rules(Cursor::new(input)).unwrap();
+
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].number, "1");
+        assert_eq!(rules[0].slug, "spirit-of-game");
+        assert_eq!(rules[0].content, "Spirit of the Game");
+        assert_eq!(rules[1].number, "1.1");
+        assert_eq!(rules[1].slug, "spirit-self-officiated");
+    }
+
+    #[test]
+    fn test_find_parent_rule() {
+        let mut rule_ids = HashMap::new();
+        rule_ids.insert("1".to_string(), "rule-1".to_string());
+        rule_ids.insert("1.2".to_string(), "rule-1-2".to_string());
+
+        assert_eq!(find_parent_rule(&rule_ids, "1"), None);
+        assert_eq!(
+            find_parent_rule(&rule_ids, "1.2"),
+            Some("rule-1".to_string())
+        );
+        assert_eq!(
+            find_parent_rule(&rule_ids, "1.2.1"),
+            Some("rule-1-2".to_string())
+        );
+        assert_eq!(find_parent_rule(&rule_ids, "2.1"), None);
+    }
+}
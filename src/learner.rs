@@ -0,0 +1,373 @@
+//! Opt-in learner accounts that keep quiz progress across devices.
+//!
+//! Learners sign in with a single-use link emailed to them; the first sign-in
+//! creates the account. Signing in adopts the browser's anonymous quiz session, and
+//! signed-in visits resume the learner's own session in each rule set, so history
+//! and review schedules follow them. Deleting the account deletes all of it.
+
+use axum::http::HeaderMap;
+use axum_extra::extract::cookie::{Cookie, Key, PrivateCookieJar, SameSite};
+use chrono::{Duration, NaiveDateTime};
+use color_eyre::Result;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+
+use crate::{
+    config::{LearnerConfig, SecurityConfig},
+    email::{EmailMessage, EmailSender},
+    models::{Learner, NewLearner, NewLearnerLoginLink, QuizSessionRecord},
+    quiz_session::cookie_key,
+    repository::RuleRepository,
+};
+
+const LEARNER_COOKIE_NAME: &str = "learner_session";
+
+/// Sign-in links sent to one address per hour, so the form can't be used to spam it
+const MAX_LINKS_PER_HOUR: i64 = 5;
+
+/// Reads and writes the encrypted cookie naming the signed-in learner
+#[derive(Clone)]
+pub struct LearnerCookies {
+    key: Key,
+    secure: bool,
+    max_age: time::Duration,
+}
+
+impl LearnerCookies {
+    pub fn new(security: &SecurityConfig, learners: &LearnerConfig) -> Self {
+        Self {
+            key: cookie_key(security, "learner session"),
+            secure: security.quiz_cookie.secure,
+            max_age: time::Duration::days(learners.session_days.into()),
+        }
+    }
+
+    /// Learner id from the request's cookie, if it decrypts
+    pub fn learner_id(&self, headers: &HeaderMap) -> Option<String> {
+        PrivateCookieJar::from_headers(headers, self.key.clone())
+            .get(LEARNER_COOKIE_NAME)
+            .map(|cookie| cookie.value().to_string())
+    }
+
+    /// Cookie jar signing the learner in
+    pub fn sign_in(&self, learner_id: String) -> PrivateCookieJar {
+        PrivateCookieJar::new(self.key.clone()).add(self.cookie(learner_id, self.max_age))
+    }
+
+    /// Cookie jar signing the browser out
+    pub fn sign_out(&self) -> PrivateCookieJar {
+        PrivateCookieJar::new(self.key.clone())
+            .add(self.cookie(String::new(), time::Duration::ZERO))
+    }
+
+    fn cookie(&self, value: String, max_age: time::Duration) -> Cookie<'static> {
+        Cookie::build((LEARNER_COOKIE_NAME, value))
+            .http_only(true)
+            .secure(self.secure)
+            .same_site(SameSite::Lax)
+            .max_age(max_age)
+            .path("/")
+            .build()
+    }
+}
+
+/// The learner named by the request's cookie, if the account still exists
+pub fn signed_in_learner(
+    repository: &RuleRepository,
+    cookies: &LearnerCookies,
+    headers: &HeaderMap,
+) -> Result<Option<Learner>> {
+    match cookies.learner_id(headers) {
+        Some(learner_id) => repository.get_learner(&learner_id),
+        None => Ok(None),
+    }
+}
+
+/// Result of asking for a sign-in link
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginLinkOutcome {
+    Sent,
+    InvalidEmail,
+    /// Too many links were sent to the address recently
+    RateLimited,
+}
+
+/// Trimmed, lowercased address if it is a valid email address
+pub fn normalize_email(email: &str) -> Option<String> {
+    let email = email.trim().to_lowercase();
+    email.parse::<lettre::Address>().is_ok().then_some(email)
+}
+
+fn generate_login_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hash_login_token(token: &str) -> String {
+    Sha256::digest(token.trim().as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Email a single-use sign-in link; the account is created when it is first used
+pub async fn send_login_link(
+    repository: &RuleRepository,
+    sender: &dyn EmailSender,
+    config: &LearnerConfig,
+    email: &str,
+    language: &str,
+    now: NaiveDateTime,
+) -> Result<LoginLinkOutcome> {
+    let Some(email) = normalize_email(email) else {
+        return Ok(LoginLinkOutcome::InvalidEmail);
+    };
+    if repository.count_learner_login_links_since(&email, now - Duration::hours(1))?
+        >= MAX_LINKS_PER_HOUR
+    {
+        return Ok(LoginLinkOutcome::RateLimited);
+    }
+
+    let token = generate_login_token();
+    repository.create_learner_login_link(NewLearnerLoginLink {
+        token_hash: hash_login_token(&token),
+        email: email.clone(),
+        created_at: now,
+        expires_at: now + Duration::minutes(config.login_link_minutes.into()),
+    })?;
+
+    let link = format!(
+        "{}/{language}/account/verify?token={token}",
+        config.site_url.trim_end_matches('/')
+    );
+    sender
+        .send(&EmailMessage {
+            to: email,
+            subject: "Your Regelator sign-in link".to_string(),
+            body: format!(
+                "Open this link to sign in and keep your quiz progress:\n\n{link}\n\n\
+                 It works once and expires in {} minutes. If you did not ask to sign in, \
+                 you can ignore this email.",
+                config.login_link_minutes
+            ),
+        })
+        .await?;
+
+    Ok(LoginLinkOutcome::Sent)
+}
+
+/// Use a sign-in link, creating the account on first sign-in.
+///
+/// Returns `None` for unknown, used or expired links.
+pub fn complete_login(
+    repository: &RuleRepository,
+    token: &str,
+    now: NaiveDateTime,
+) -> Result<Option<Learner>> {
+    let Some(link) = repository.use_learner_login_link(&hash_login_token(token), now)? else {
+        return Ok(None);
+    };
+
+    let learner = match repository.get_learner_by_email(&link.email)? {
+        Some(learner) => learner,
+        None => {
+            let learner = repository.create_learner(NewLearner::new(link.email))?;
+            info!("Created learner account {}", learner.id);
+            learner
+        }
+    };
+    repository.touch_learner_login(&learner.id, now)?;
+    Ok(Some(learner))
+}
+
+/// Give an anonymous session to a learner.
+///
+/// If the learner already has an open session in the same quiz and mode, the anonymous
+/// one is merged into it; otherwise it simply becomes theirs. Returns the learner's
+/// session.
+pub fn adopt_session(
+    repository: &RuleRepository,
+    learner_id: &str,
+    session: &QuizSessionRecord,
+) -> Result<QuizSessionRecord> {
+    if let Some(existing) = repository.get_open_learner_session(learner_id, &session.rule_set_id)?
+        && existing.id != session.id
+        && existing.version_id == session.version_id
+        && existing.mode == session.mode
+    {
+        repository.merge_quiz_sessions(&session.id, &existing.id)?;
+        debug!("Merged quiz session {} into {}", session.id, existing.id);
+        return Ok(existing);
+    }
+    repository.set_quiz_session_learner(&session.id, learner_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{QuizMode, QuizReview};
+    use crate::test_support::{self, repository_with_current_version};
+    use std::sync::Mutex;
+
+    /// Keeps sent messages for inspection
+    #[derive(Default)]
+    struct RecordingSender(Mutex<Vec<EmailMessage>>);
+
+    impl EmailSender for RecordingSender {
+        fn send<'a>(&'a self, message: &'a EmailMessage) -> crate::email::SendFuture<'a> {
+            self.0.lock().unwrap().push(message.clone());
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    fn now() -> NaiveDateTime {
+        chrono::Utc::now().naive_utc()
+    }
+
+    /// The token from the link in the last sent message
+    fn sent_token(sender: &RecordingSender) -> String {
+        let messages = sender.0.lock().unwrap();
+        let body = &messages.last().unwrap().body;
+        let start = body.find("token=").unwrap() + "token=".len();
+        body[start..start + 64].to_string()
+    }
+
+    #[tokio::test]
+    async fn test_login_link_signs_in_once() {
+        let repo = test_support::repository();
+        let sender = RecordingSender::default();
+        let config = LearnerConfig::default();
+
+        let outcome = send_login_link(&repo, &sender, &config, " Player@Example.org ", "de", now())
+            .await
+            .unwrap();
+        assert_eq!(outcome, LoginLinkOutcome::Sent);
+        {
+            let messages = sender.0.lock().unwrap();
+            assert_eq!(messages[0].to, "player@example.org");
+            assert!(
+                messages[0]
+                    .body
+                    .contains("http://localhost:8000/de/account/verify?token=")
+            );
+        }
+
+        let token = sent_token(&sender);
+        let learner = complete_login(&repo, &token, now()).unwrap().unwrap();
+        assert_eq!(learner.email, "player@example.org");
+        assert!(complete_login(&repo, &token, now()).unwrap().is_none());
+
+        // Signing in again finds the same account
+        send_login_link(&repo, &sender, &config, "player@example.org", "en", now())
+            .await
+            .unwrap();
+        let again = complete_login(&repo, &sent_token(&sender), now())
+            .unwrap()
+            .unwrap();
+        assert_eq!(again.id, learner.id);
+    }
+
+    #[tokio::test]
+    async fn test_login_links_expire_and_are_rate_limited() {
+        let repo = test_support::repository();
+        let sender = RecordingSender::default();
+        let config = LearnerConfig::default();
+
+        assert_eq!(
+            send_login_link(&repo, &sender, &config, "not-an-address", "en", now())
+                .await
+                .unwrap(),
+            LoginLinkOutcome::InvalidEmail
+        );
+
+        send_login_link(&repo, &sender, &config, "player@example.org", "en", now())
+            .await
+            .unwrap();
+        let late =
+            now() + Duration::minutes(config.login_link_minutes.into()) + Duration::seconds(1);
+        assert!(
+            complete_login(&repo, &sent_token(&sender), late)
+                .unwrap()
+                .is_none()
+        );
+
+        for _ in 1..MAX_LINKS_PER_HOUR {
+            send_login_link(&repo, &sender, &config, "player@example.org", "en", now())
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            send_login_link(&repo, &sender, &config, "player@example.org", "en", now())
+                .await
+                .unwrap(),
+            LoginLinkOutcome::RateLimited
+        );
+        assert_eq!(sender.0.lock().unwrap().len(), MAX_LINKS_PER_HOUR as usize);
+    }
+
+    #[test]
+    fn test_adopting_merges_into_the_learners_session_and_delete_removes_everything() {
+        let (repo, rule_set, version) = repository_with_current_version();
+        let question = repo
+            .create_quiz_question_complete(&crate::models::QuizQuestionData {
+                rule_set_id: rule_set.id.clone(),
+                version_id: version.id.clone(),
+                question_text: "Who calls fouls?".to_string(),
+                explanation: String::new(),
+                difficulty_level: "beginner".to_string(),
+                answers: vec![],
+                rule_ids: vec![],
+            })
+            .unwrap();
+        let learner = repo
+            .create_learner(NewLearner::new("player@example.org".to_string()))
+            .unwrap();
+        let new_session =
+            || test_support::quiz_session(&repo, &rule_set, &version, QuizMode::Practice);
+        let review = |session: &QuizSessionRecord, repetitions, reviewed_at| QuizReview {
+            session_id: session.id.clone(),
+            question_id: question.id.clone(),
+            repetitions,
+            interval_days: 1,
+            ease_factor: 2.5,
+            due_at: reviewed_at + Duration::days(1),
+            last_reviewed_at: reviewed_at,
+        };
+
+        // The first anonymous session simply becomes the learner's
+        let phone = new_session();
+        repo.save_quiz_review(&review(&phone, 1, now() - Duration::days(2)))
+            .unwrap();
+        let adopted = adopt_session(&repo, &learner.id, &phone).unwrap();
+        assert_eq!(adopted.id, phone.id);
+        assert_eq!(adopted.learner_id.as_deref(), Some(learner.id.as_str()));
+
+        // A second one is merged into it, keeping the newer review
+        let laptop = new_session();
+        repo.save_quiz_review(&review(&laptop, 2, now() - Duration::days(1)))
+            .unwrap();
+        let merged = adopt_session(&repo, &learner.id, &laptop).unwrap();
+        assert_eq!(merged.id, phone.id);
+        assert!(repo.get_quiz_session(&laptop.id).unwrap().is_none());
+        let kept = repo
+            .get_quiz_review(&phone.id, &question.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(kept.repetitions, 2);
+        assert!(
+            repo.get_quiz_review(&laptop.id, &question.id)
+                .unwrap()
+                .is_none()
+        );
+
+        repo.delete_learner(&learner.id).unwrap();
+        assert!(repo.get_learner(&learner.id).unwrap().is_none());
+        assert!(repo.get_quiz_session(&phone.id).unwrap().is_none());
+        assert!(
+            repo.get_quiz_review(&phone.id, &question.id)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -1,7 +1,28 @@
 pub mod analytics;
+pub mod api_token;
 pub mod auth;
+pub mod certificate;
+pub mod chat;
 pub mod config;
+pub mod db;
+pub mod difficulty;
+pub mod email;
+pub mod exam;
+pub mod import;
+pub mod learner;
+pub mod logging;
 pub mod models;
+pub mod oidc;
+pub mod quiz_engine;
 pub mod quiz_session;
+pub mod quiz_token;
 pub mod repository;
+pub mod response_time;
 pub mod schema;
+pub mod spaced_repetition;
+pub mod telegram;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod throttle;
+pub mod transfer;
+pub mod two_factor;
@@ -0,0 +1,62 @@
+//! Tracing subscriber setup shared by the web server and the Telegram bot.
+
+use color_eyre::eyre::Context;
+use tracing::level_filters::LevelFilter;
+
+use crate::config::LoggingConfig;
+
+/// Initialize tracing subscriber based on configuration
+pub fn init_tracing(config: &LoggingConfig) -> color_eyre::Result<()> {
+    use tracing_subscriber::{EnvFilter, Registry, layer::SubscriberExt, util::SubscriberInitExt};
+
+    let env_filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::from_level(config.level).into())
+        .from_env()
+        .context("Set up env filter")?;
+
+    // Create ErrorLayer for better error tracing
+    let error_layer = tracing_error::ErrorLayer::default();
+
+    match config.format.as_str() {
+        "tree" => {
+            let tree = tracing_tree::HierarchicalLayer::new(2)
+                .with_targets(true)
+                .with_ansi(config.enable_colors)
+                .with_bracketed_fields(true);
+
+            Registry::default()
+                .with(env_filter)
+                .with(error_layer)
+                .with(tree)
+                .init();
+        }
+        "json" => {
+            let json_layer = tracing_subscriber::fmt::layer()
+                .json()
+                .with_current_span(false)
+                .with_span_list(true)
+                .with_timer(tracing_subscriber::fmt::time::SystemTime);
+
+            Registry::default()
+                .with(env_filter)
+                .with(error_layer)
+                .with(json_layer)
+                .init();
+        }
+        _ => {
+            // Default to compact format
+            let fmt_layer = tracing_subscriber::fmt::layer()
+                .compact()
+                .with_ansi(config.enable_colors)
+                .with_timer(tracing_subscriber::fmt::time::SystemTime);
+
+            Registry::default()
+                .with(env_filter)
+                .with(error_layer)
+                .with(fmt_layer)
+                .init();
+        }
+    }
+
+    Ok(())
+}
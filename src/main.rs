@@ -7,34 +7,33 @@ use axum::{
     response::{IntoResponse, Response},
     routing::{get, post},
 };
-use color_eyre::eyre::Context;
-use diesel::{
-    RunQueryDsl,
-    r2d2::{ConnectionManager, Pool},
-    sql_query,
-    sqlite::SqliteConnection,
-};
+use diesel::{RunQueryDsl, sql_query};
 use minijinja::{Environment, Value};
 use pulldown_cmark::{Event, Parser, Tag, html};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{info, instrument, level_filters::LevelFilter, warn};
+use tracing::{info, instrument, warn};
 
-mod analytics;
 mod charts;
+mod cli;
 mod extractors;
 mod handlers;
 mod middleware;
-mod models;
-mod quiz_session;
-mod repository;
-mod schema;
-
-use regelator::config::{Config, LoggingConfig};
+mod openapi;
+
+// Domain modules live in the library so the CLI and server share one copy
+use regelator::{analytics, models, quiz_engine, quiz_session, repository};
+
+use clap::Parser as _;
+use cli::{Cli, Command};
+use regelator::certificate::CertificateSigner;
+use regelator::config::Config;
+use regelator::db::DbPool;
+use regelator::email::EmailSender;
+use regelator::learner::LearnerCookies;
+use regelator::response_time::QuestionTimer;
 use repository::RuleRepository;
 
-type DbPool = Pool<ConnectionManager<SqliteConnection>>;
-
 /// Round float to 1 decimal place for display  
 fn round1_filter(value: Value) -> Result<String, minijinja::Error> {
     // Use try_into to convert to f64
@@ -131,28 +130,35 @@ struct AppState {
     templates: Arc<Environment<'static>>,
     db: DbPool,
     rule_repository: RuleRepository,
+    quiz_cookies: quiz_session::QuizCookies,
+    learner_cookies: LearnerCookies,
+    question_timer: QuestionTimer,
+    certificate_signer: CertificateSigner,
+    email_sender: Arc<dyn EmailSender>,
     config: Config,
 }
 
 impl AppState {
-    fn new() -> Result<Self, color_eyre::eyre::Error> {
-        let config = Config::load()
-            .map_err(|e| color_eyre::eyre::eyre!("Failed to load configuration: {}", e))?;
-
+    fn new(config: Config) -> Result<Self, color_eyre::eyre::Error> {
         let mut env = Environment::new();
         env.set_loader(minijinja::path_loader("src/templates"));
 
         // Register custom filters
         env.add_filter("markdown", markdown_filter);
         env.add_filter("round1", round1_filter);
+        env.add_global("min_password_length", regelator::auth::MIN_PASSWORD_LENGTH);
 
-        let manager = ConnectionManager::<SqliteConnection>::new(&config.database.url);
-        let pool = Pool::builder().build(manager)?;
+        let pool = regelator::db::create_pool(&config.database.url)?;
 
         Ok(AppState {
             templates: Arc::new(env),
             db: pool.clone(),
             rule_repository: RuleRepository::new(pool),
+            quiz_cookies: quiz_session::QuizCookies::new(&config.security),
+            learner_cookies: LearnerCookies::new(&config.security, &config.learners),
+            question_timer: QuestionTimer::new(&config.security),
+            certificate_signer: CertificateSigner::new(&config.security),
+            email_sender: regelator::email::email_sender(config.email.as_ref())?,
             config,
         })
     }
@@ -183,62 +189,6 @@ async fn health(State(state): State<AppState>) -> Result<&'static str, AppError>
     Ok("OK")
 }
 
-/// Initialize tracing subscriber based on configuration
-fn init_tracing(config: &LoggingConfig) -> color_eyre::Result<()> {
-    use tracing_subscriber::{EnvFilter, Registry, layer::SubscriberExt, util::SubscriberInitExt};
-
-    let env_filter = EnvFilter::builder()
-        .with_default_directive(LevelFilter::from_level(config.level).into())
-        .from_env()
-        .context("Set up env filter")?;
-
-    // Create ErrorLayer for better error tracing
-    let error_layer = tracing_error::ErrorLayer::default();
-
-    match config.format.as_str() {
-        "tree" => {
-            let tree = tracing_tree::HierarchicalLayer::new(2)
-                .with_targets(true)
-                .with_ansi(config.enable_colors)
-                .with_bracketed_fields(true);
-
-            Registry::default()
-                .with(env_filter)
-                .with(error_layer)
-                .with(tree)
-                .init();
-        }
-        "json" => {
-            let json_layer = tracing_subscriber::fmt::layer()
-                .json()
-                .with_current_span(false)
-                .with_span_list(true)
-                .with_timer(tracing_subscriber::fmt::time::SystemTime);
-
-            Registry::default()
-                .with(env_filter)
-                .with(error_layer)
-                .with(json_layer)
-                .init();
-        }
-        _ => {
-            // Default to compact format
-            let fmt_layer = tracing_subscriber::fmt::layer()
-                .compact()
-                .with_ansi(config.enable_colors)
-                .with_timer(tracing_subscriber::fmt::time::SystemTime);
-
-            Registry::default()
-                .with(env_filter)
-                .with(error_layer)
-                .with(fmt_layer)
-                .init();
-        }
-    }
-
-    Ok(())
-}
-
 /// Create a cross-platform shutdown signal handler
 async fn shutdown_signal() {
     let ctrl_c = async {
@@ -269,14 +219,30 @@ async fn shutdown_signal() {
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> color_eyre::Result<()> {
     // Initialize error reporting
-    color_eyre::install().expect("Failed to install color-eyre");
+    color_eyre::install()?;
 
-    let state = AppState::new().expect("Failed to initialize application state");
+    let cli = Cli::parse();
+
+    let config = Config::load()
+        .map_err(|e| color_eyre::eyre::eyre!("Failed to load configuration: {}", e))?;
 
     // Initialize tracing
-    init_tracing(&state.config.logging).expect("Failed to initialize tracing");
+    regelator::logging::init_tracing(&config.logging)?;
+
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => {
+            serve(config).await;
+            Ok(())
+        }
+        command => cli::run(command, &config),
+    }
+}
+
+/// Run the web server until a shutdown signal is received
+async fn serve(config: Config) {
+    let state = AppState::new(config).expect("Failed to initialize application state");
 
     let bind_address = state.config.bind_address();
     let shutdown_timeout = state.config.shutdown_timeout();
@@ -301,6 +267,37 @@ async fn main() {
             "/quiz",
             get(|| async { axum::response::Redirect::to("/en/quiz/wfdf-ultimate") }),
         )
+        // Optional learner accounts
+        .route("/{language}/account", get(handlers::learner::account_page))
+        .route(
+            "/{language}/account/login",
+            post(handlers::learner::send_login_link),
+        )
+        .route(
+            "/{language}/account/verify",
+            get(handlers::learner::verify_login_form).post(handlers::learner::verify_login),
+        )
+        .route(
+            "/{language}/account/logout",
+            post(handlers::learner::sign_out),
+        )
+        .route(
+            "/{language}/account/delete",
+            post(handlers::learner::delete_account),
+        )
+        // Public verification of exam certificates
+        .route(
+            "/certificates/{certificate_id}",
+            get(handlers::certificates::show_certificate),
+        )
+        .route(
+            "/certificates/{certificate_id}/certificate.svg",
+            get(handlers::certificates::certificate_svg),
+        )
+        .route(
+            "/certificates/{certificate_id}/certificate.pdf",
+            get(handlers::certificates::certificate_pdf),
+        )
         // Quiz routes with session middleware
         .merge(
             Router::new()
@@ -312,6 +309,14 @@ async fn main() {
                     "/{language}/quiz/{rule_set_slug}/start",
                     post(handlers::start_quiz_session),
                 )
+                .route(
+                    "/{language}/quiz/{rule_set_slug}/exam",
+                    post(handlers::start_quiz_exam),
+                )
+                .route(
+                    "/{language}/quiz/{rule_set_slug}/certificate",
+                    post(handlers::certificates::claim_certificate),
+                )
                 .route(
                     "/{language}/quiz/{rule_set_slug}/question",
                     post(handlers::random_quiz_question),
@@ -322,10 +327,20 @@ async fn main() {
                 )
                 .route(
                     "/{language}/quiz/{rule_set_slug}/clear",
-                    get(handlers::clear_session_data),
+                    post(handlers::clear_session_data),
+                )
+                .route(
+                    "/{language}/quiz/{rule_set_slug}/transfer",
+                    get(handlers::transfer::transfer_page)
+                        .post(handlers::transfer::create_transfer_code),
+                )
+                .route(
+                    "/{language}/quiz/{rule_set_slug}/transfer/redeem",
+                    post(handlers::transfer::redeem_transfer_code),
                 )
-                .layer(axum_middleware::from_fn(
-                    quiz_session::quiz_session_middleware,
+                .layer(axum_middleware::from_fn_with_state(
+                    state.clone(),
+                    quiz_session::quiz_session_middleware::<AppState>,
                 )),
         )
         // Admin routes
@@ -335,6 +350,15 @@ async fn main() {
         )
         .route("/admin/login", get(handlers::admin_login_form))
         .route("/admin/login", post(handlers::admin_login_submit))
+        .route("/admin/login/oidc", get(handlers::admin::admin_oidc_login))
+        .route(
+            "/admin/login/oidc/callback",
+            get(handlers::admin::admin_oidc_callback),
+        )
+        .route(
+            "/admin/login/two-factor",
+            post(handlers::admin::admin_login_two_factor_submit),
+        )
         .route("/admin/dashboard", get(handlers::admin_dashboard))
         .route(
             "/admin/change-password",
@@ -344,20 +368,97 @@ async fn main() {
             "/admin/change-password",
             post(handlers::admin_change_password_submit),
         )
-        .route("/admin/logout", get(handlers::admin_logout))
-        // Admin statistics routes
-        .route("/admin/stats", get(handlers::admin::admin_stats_dashboard))
+        .route("/admin/logout", post(handlers::admin_logout))
+        // Admin account management routes
         .route(
-            "/admin/stats/question/{question_id}",
-            get(handlers::admin::admin_question_detail_stats),
+            "/admin/two-factor",
+            get(handlers::two_factor::two_factor_settings),
+        )
+        .route(
+            "/admin/two-factor/setup",
+            post(handlers::two_factor::two_factor_setup),
+        )
+        .route(
+            "/admin/two-factor/confirm",
+            post(handlers::two_factor::two_factor_confirm),
+        )
+        .route(
+            "/admin/two-factor/recovery-codes",
+            post(handlers::two_factor::regenerate_recovery_codes),
+        )
+        .route(
+            "/admin/two-factor/disable",
+            post(handlers::two_factor::disable_two_factor),
         )
+        .route("/admin/sessions", get(handlers::sessions::admin_sessions))
         .route(
-            "/admin/stats/export.csv",
-            get(handlers::admin::export_stats_csv),
+            "/admin/sessions/revoke-all",
+            post(handlers::sessions::revoke_all_admin_sessions),
         )
         .route(
-            "/admin/stats/export.parquet",
-            get(handlers::admin::export_stats_parquet),
+            "/admin/sessions/{session_id}/revoke",
+            post(handlers::sessions::revoke_admin_session),
+        )
+        .route("/admin/api-tokens", get(handlers::api_tokens::api_tokens))
+        .route(
+            "/admin/api-tokens",
+            post(handlers::api_tokens::create_api_token),
+        )
+        .route(
+            "/admin/api-tokens/{token_id}/revoke",
+            post(handlers::api_tokens::revoke_api_token),
+        )
+        .route("/admin/audit", get(handlers::admin::admin_audit_log))
+        .route(
+            "/admin/certificates",
+            get(handlers::certificates::admin_certificates),
+        )
+        .route(
+            "/admin/certificates/{certificate_id}/revoke",
+            post(handlers::certificates::revoke_certificate),
+        )
+        .route("/admin/accounts", get(handlers::admin::admin_accounts))
+        .route(
+            "/admin/accounts",
+            post(handlers::admin::create_admin_account),
+        )
+        .route(
+            "/admin/accounts/{admin_id}/status",
+            post(handlers::admin::set_admin_account_status),
+        )
+        .route(
+            "/admin/accounts/{admin_id}/role",
+            post(handlers::admin::set_admin_account_role),
+        )
+        .route(
+            "/admin/accounts/{admin_id}/password",
+            post(handlers::admin::reset_admin_account_password),
+        )
+        .route(
+            "/admin/accounts/two-factor-policy",
+            post(handlers::admin::set_two_factor_policy),
+        )
+        .route(
+            "/admin/accounts/{admin_id}/identity",
+            post(handlers::admin::set_admin_account_identity),
+        )
+        .route(
+            "/admin/accounts/{admin_id}/sessions/revoke",
+            post(handlers::admin::revoke_admin_account_sessions),
+        )
+        .route(
+            "/admin/accounts/{admin_id}/two-factor/reset",
+            post(handlers::admin::reset_admin_two_factor),
+        )
+        .route(
+            "/admin/lockouts/unlock",
+            post(handlers::admin::unlock_login),
+        )
+        // Admin statistics routes
+        .route("/admin/stats", get(handlers::admin::admin_stats_dashboard))
+        .route(
+            "/admin/stats/question/{question_id}",
+            get(handlers::admin::admin_question_detail_stats),
         )
         // Admin chart routes
         .route(
@@ -398,6 +499,12 @@ async fn main() {
             "/admin/questions/{question_id}/preview",
             get(handlers::admin::preview_question),
         )
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            middleware::csrf_protection,
+        ))
+        // JSON API and exports described by /api/openapi.json; no cookie-authenticated writes, so no CSRF layer
+        .merge(openapi::router())
         .nest_service(
             "/static",
             SetResponseHeaderLayer::if_not_present(
@@ -418,8 +525,12 @@ async fn main() {
     info!("Server listening on {}", actual_address);
     info!("Configured shutdown timeout: {:?}", shutdown_timeout);
 
-    // Serve with graceful shutdown
-    let server = axum::serve(listener, app).with_graceful_shutdown(shutdown_signal());
+    // Serve with graceful shutdown; connect info exposes client addresses for login throttling
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal());
 
     info!("Server starting with graceful shutdown handling");
 
@@ -0,0 +1,284 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use rand::Rng;
+use tracing::{debug, warn};
+
+use crate::extractors::CsrfToken;
+use regelator::config::Config;
+
+pub const CSRF_COOKIE_NAME: &str = "csrf_token";
+pub const CSRF_FORM_FIELD: &str = "csrf_token";
+pub const CSRF_HEADER_NAME: &str = "x-csrf-token";
+
+/// Largest form body buffered while looking for the token
+const MAX_FORM_BYTES: usize = 2 * 1024 * 1024;
+
+/// Generate a random token as 64 hex characters
+fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn is_valid_token(token: &str) -> bool {
+    token.len() == 64 && token.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// Compare tokens without short-circuiting on the first differing byte
+fn tokens_match(expected: &str, submitted: &str) -> bool {
+    expected.len() == submitted.len()
+        && expected
+            .bytes()
+            .zip(submitted.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Secure unless `security.quiz_cookie.secure` is turned off for plain-HTTP development
+fn create_csrf_cookie(token: String, secure: bool) -> Cookie<'static> {
+    Cookie::build((CSRF_COOKIE_NAME, token))
+        .http_only(true)
+        .secure(secure)
+        .same_site(SameSite::Lax)
+        .path("/")
+        .build()
+}
+
+fn is_state_changing(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+fn is_form(request: &Request) -> bool {
+    request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"))
+}
+
+/// Double-submit CSRF protection for all routes.
+///
+/// Every visitor gets a random token in an HttpOnly cookie, which handlers embed in
+/// their forms via the [`CsrfToken`] extractor. State-changing requests must send the
+/// same token back in the `csrf_token` form field or the `X-CSRF-Token` header.
+/// Requests authenticated with an API bearer token are exempt: browsers never attach
+/// that header on their own, and `AdminToken` ignores cookies when it is present.
+pub async fn csrf_protection(
+    State(config): State<Config>,
+    jar: CookieJar,
+    request: Request,
+    next: Next,
+) -> Response {
+    let existing = jar
+        .get(CSRF_COOKIE_NAME)
+        .map(|cookie| cookie.value().to_string())
+        .filter(|token| is_valid_token(token));
+
+    let mut request = request;
+    let has_bearer_token = regelator::api_token::bearer_token(request.headers()).is_some();
+    if is_state_changing(request.method()) && !has_bearer_token {
+        let header_token = request
+            .headers()
+            .get(CSRF_HEADER_NAME)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let submitted = match header_token {
+            Some(token) => Some(token),
+            None if is_form(&request) => {
+                // Buffer the form body to read the token, then hand it on unchanged
+                let (parts, body) = request.into_parts();
+                let bytes = match axum::body::to_bytes(body, MAX_FORM_BYTES).await {
+                    Ok(bytes) => bytes,
+                    Err(_) => {
+                        return (StatusCode::PAYLOAD_TOO_LARGE, "Form body too large")
+                            .into_response();
+                    }
+                };
+                let token = form_urlencoded::parse(&bytes)
+                    .find(|(key, _)| key == CSRF_FORM_FIELD)
+                    .map(|(_, value)| value.into_owned());
+                request = Request::from_parts(parts, Body::from(bytes));
+                token
+            }
+            None => None,
+        };
+
+        let valid = matches!(
+            (&existing, &submitted),
+            (Some(expected), Some(submitted)) if tokens_match(expected, submitted)
+        );
+        if !valid {
+            warn!(
+                method = %request.method(),
+                path = %request.uri().path(),
+                has_cookie = existing.is_some(),
+                has_token = submitted.is_some(),
+                "Rejected request with invalid CSRF token"
+            );
+            return (
+                StatusCode::FORBIDDEN,
+                "Invalid or missing CSRF token. Please reload the page and try again.",
+            )
+                .into_response();
+        }
+    }
+
+    let needs_cookie = existing.is_none();
+    let token = existing.unwrap_or_else(generate_token);
+    request.extensions_mut().insert(CsrfToken(token.clone()));
+
+    let mut response = next.run(request).await;
+
+    if needs_cookie {
+        debug!("Setting new CSRF cookie");
+        if let Ok(cookie_header) = create_csrf_cookie(token, config.security.quiz_cookie.secure)
+            .to_string()
+            .parse::<HeaderValue>()
+        {
+            response
+                .headers_mut()
+                .append(header::SET_COOKIE, cookie_header);
+        }
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Router, middleware,
+        routing::{get, post},
+    };
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn app() -> Router {
+        app_with(regelator::test_support::config())
+    }
+
+    fn app_with(config: Config) -> Router {
+        Router::new()
+            .route("/", get(|token: CsrfToken| async move { token.0 }))
+            .route("/", post(|body: String| async move { body }))
+            .layer(middleware::from_fn_with_state(config, csrf_protection))
+    }
+
+    async fn issued_cookie(app: Router) -> Cookie<'static> {
+        let response = app
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let header = response.headers().get(header::SET_COOKIE).unwrap();
+        Cookie::parse(header.to_str().unwrap().to_string()).unwrap()
+    }
+
+    fn form_post(cookie: Option<&str>, body: &str) -> Request {
+        let mut builder =
+            Request::post("/").header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+        if let Some(token) = cookie {
+            builder = builder.header(header::COOKIE, format!("{CSRF_COOKIE_NAME}={token}"));
+        }
+        builder.body(Body::from(body.to_string())).unwrap()
+    }
+
+    async fn body_string(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_issues_token_cookie() {
+        let response = app()
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let cookie = response
+            .headers()
+            .get(header::SET_COOKIE)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        let token = body_string(response).await;
+        assert!(is_valid_token(&token));
+        assert!(cookie.starts_with(&format!("{CSRF_COOKIE_NAME}={token}")));
+    }
+
+    #[tokio::test]
+    async fn test_cookie_secure_flag_follows_config() {
+        assert_eq!(issued_cookie(app()).await.secure(), Some(true));
+
+        let mut config = regelator::test_support::config();
+        config.security.quiz_cookie.secure = false;
+        assert_ne!(issued_cookie(app_with(config)).await.secure(), Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_post_without_token_is_rejected() {
+        let response = app()
+            .oneshot(form_post(Some(TOKEN), "answer_id=a1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app()
+            .oneshot(form_post(None, &format!("csrf_token={TOKEN}")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_post_with_mismatched_token_is_rejected() {
+        let other = TOKEN.replace('0', "f");
+        let response = app()
+            .oneshot(form_post(Some(TOKEN), &format!("csrf_token={other}")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_post_with_matching_token_keeps_body() {
+        let body = format!("answer_id=a1&csrf_token={TOKEN}");
+        let response = app().oneshot(form_post(Some(TOKEN), &body)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::SET_COOKIE).is_none());
+        assert_eq!(body_string(response).await, body);
+    }
+
+    #[tokio::test]
+    async fn test_post_with_header_token() {
+        let request = Request::post("/")
+            .header(header::COOKIE, format!("{CSRF_COOKIE_NAME}={TOKEN}"))
+            .header(CSRF_HEADER_NAME, TOKEN)
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_post_with_bearer_token_skips_check() {
+        let request = Request::post("/")
+            .header(header::AUTHORIZATION, "Bearer rgl_abc")
+            .body(Body::from("question_text=q"))
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod csrf;
 pub mod theme;
 
+pub use csrf::csrf_protection;
 pub use theme::add_client_hints;
@@ -1,19 +1,112 @@
+use std::str::FromStr;
+
+use diesel::FromSqlRow;
+use diesel::deserialize::{FromSql, Result as DeserializeResult};
+use diesel::expression::AsExpression;
 use diesel::prelude::*;
+use diesel::serialize::{Output, Result as SerializeResult, ToSql};
+use diesel::sqlite::Sqlite;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 // Import the schema generated by Diesel
 use crate::schema::*;
 
+// Admin role enum
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, AsExpression, FromSqlRow)]
+#[serde(rename_all = "lowercase")]
+#[diesel(sql_type = diesel::sql_types::Text)]
+pub enum AdminRole {
+    Superadmin,
+    Editor,
+    Translator,
+    Analyst,
+}
+
+impl AdminRole {
+    pub const ALL: [AdminRole; 4] = [
+        AdminRole::Superadmin,
+        AdminRole::Editor,
+        AdminRole::Translator,
+        AdminRole::Analyst,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdminRole::Superadmin => "superadmin",
+            AdminRole::Editor => "editor",
+            AdminRole::Translator => "translator",
+            AdminRole::Analyst => "analyst",
+        }
+    }
+
+    /// Whether this role may do what `required` is allowed to do.
+    /// Superadmins can do everything and editors can also translate.
+    pub fn grants(self, required: AdminRole) -> bool {
+        match (self, required) {
+            (AdminRole::Superadmin, _) => true,
+            (AdminRole::Editor, AdminRole::Translator) => true,
+            (role, required) => role == required,
+        }
+    }
+}
+
+impl FromStr for AdminRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "superadmin" => Ok(AdminRole::Superadmin),
+            "editor" => Ok(AdminRole::Editor),
+            "translator" => Ok(AdminRole::Translator),
+            "analyst" => Ok(AdminRole::Analyst),
+            s => Err(format!("invalid admin role: {s}")),
+        }
+    }
+}
+
+impl FromSql<diesel::sql_types::Text, Sqlite> for AdminRole {
+    fn from_sql(
+        bytes: <Sqlite as diesel::backend::Backend>::RawValue<'_>,
+    ) -> DeserializeResult<Self> {
+        let s = <String as FromSql<diesel::sql_types::Text, Sqlite>>::from_sql(bytes)?;
+        Ok(Self::from_str(&s)?)
+    }
+}
+
+impl ToSql<diesel::sql_types::Text, Sqlite> for AdminRole {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> SerializeResult {
+        <str as ToSql<diesel::sql_types::Text, Sqlite>>::to_sql(self.as_str(), out)
+    }
+}
+
 // Admin models - Queryable structs (for reading from database)
-#[derive(Queryable, Selectable, Debug, Clone)]
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
 #[diesel(table_name = admins)]
 pub struct Admin {
     pub id: String,
     pub username: String,
+    #[serde(skip)]
     pub password_hash: String,
     pub is_active: bool,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
     pub last_login: Option<chrono::NaiveDateTime>,
+    pub role: AdminRole,
+    #[serde(skip)]
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<chrono::NaiveDateTime>,
+    #[serde(skip)]
+    pub totp_last_step: Option<i64>,
+    pub email: Option<String>,
+    pub oidc_subject: Option<String>,
+}
+
+impl Admin {
+    /// Whether the admin has confirmed a TOTP enrolment
+    pub fn two_factor_enabled(&self) -> bool {
+        self.totp_enabled_at.is_some() && self.totp_secret.is_some()
+    }
 }
 
 // Admin models - Insertable structs (for creating new records)
@@ -24,4 +117,156 @@ pub struct NewAdmin {
     pub username: String,
     pub password_hash: String,
     pub is_active: bool,
+    pub role: AdminRole,
+}
+
+impl NewAdmin {
+    pub fn new(username: String, password_hash: String, role: AdminRole) -> Self {
+        Self {
+            id: Uuid::now_v7().to_string(),
+            username,
+            password_hash,
+            is_active: true,
+            role,
+        }
+    }
+}
+
+// One-time recovery code for an admin's second factor
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = admin_recovery_codes)]
+pub struct AdminRecoveryCode {
+    pub id: String,
+    pub admin_id: String,
+    pub code_hash: String,
+    pub used_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = admin_recovery_codes)]
+pub struct NewAdminRecoveryCode {
+    pub id: String,
+    pub admin_id: String,
+    pub code_hash: String,
+}
+
+impl NewAdminRecoveryCode {
+    pub fn new(admin_id: String, code_hash: String) -> Self {
+        Self {
+            id: Uuid::now_v7().to_string(),
+            admin_id,
+            code_hash,
+        }
+    }
+}
+
+// Server-side admin session referenced by the session cookie
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = admin_sessions)]
+pub struct AdminSession {
+    pub id: String,
+    pub admin_id: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_seen_at: chrono::NaiveDateTime,
+    pub expires_at: chrono::NaiveDateTime,
+    pub revoked_at: Option<chrono::NaiveDateTime>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = admin_sessions)]
+pub struct NewAdminSession {
+    pub id: String,
+    pub admin_id: String,
+    pub expires_at: chrono::NaiveDateTime,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl NewAdminSession {
+    pub fn new(
+        admin_id: String,
+        expires_at: chrono::NaiveDateTime,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7().to_string(),
+            admin_id,
+            expires_at,
+            ip_address,
+            user_agent,
+        }
+    }
+}
+
+// Personal API token; only the hash of the token is stored
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = admin_api_tokens)]
+pub struct AdminApiToken {
+    pub id: String,
+    pub admin_id: String,
+    pub name: String,
+    #[serde(skip)]
+    pub token_hash: String,
+    pub token_hint: String,
+    pub scope: AdminRole,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+    pub revoked_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = admin_api_tokens)]
+pub struct NewAdminApiToken {
+    pub id: String,
+    pub admin_id: String,
+    pub name: String,
+    pub token_hash: String,
+    pub token_hint: String,
+    pub scope: AdminRole,
+}
+
+impl NewAdminApiToken {
+    pub fn new(
+        admin_id: String,
+        name: String,
+        token_hash: String,
+        token_hint: String,
+        scope: AdminRole,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7().to_string(),
+            admin_id,
+            name,
+            token_hash,
+            token_hint,
+            scope,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_grants() {
+        assert!(AdminRole::Superadmin.grants(AdminRole::Analyst));
+        assert!(AdminRole::Editor.grants(AdminRole::Editor));
+        assert!(AdminRole::Editor.grants(AdminRole::Translator));
+        assert!(!AdminRole::Editor.grants(AdminRole::Analyst));
+        assert!(!AdminRole::Translator.grants(AdminRole::Editor));
+        assert!(!AdminRole::Analyst.grants(AdminRole::Superadmin));
+    }
+
+    #[test]
+    fn test_role_round_trip() {
+        for role in AdminRole::ALL {
+            assert_eq!(AdminRole::from_str(role.as_str()), Ok(role));
+        }
+        assert!(AdminRole::from_str("owner").is_err());
+    }
 }
@@ -0,0 +1,234 @@
+use std::str::FromStr;
+
+use diesel::FromSqlRow;
+use diesel::deserialize::{FromSql, Result as DeserializeResult};
+use diesel::expression::AsExpression;
+use diesel::prelude::*;
+use diesel::serialize::{Output, Result as SerializeResult, ToSql};
+use diesel::sqlite::Sqlite;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::*;
+
+// Audited admin action enum
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, AsExpression, FromSqlRow)]
+#[serde(rename_all = "snake_case")]
+#[diesel(sql_type = diesel::sql_types::Text)]
+pub enum AuditAction {
+    QuestionCreated,
+    QuestionUpdated,
+    QuestionDeleted,
+    PasswordChanged,
+    AdminCreated,
+    AdminActivated,
+    AdminDeactivated,
+    AdminRoleChanged,
+    AdminPasswordReset,
+    LoginLockedOut,
+    LoginUnlocked,
+    TwoFactorEnabled,
+    TwoFactorDisabled,
+    RecoveryCodesRegenerated,
+    TwoFactorPolicyChanged,
+    SessionsRevoked,
+    ApiTokenCreated,
+    ApiTokenRevoked,
+    AdminIdentityChanged,
+    CertificateRevoked,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 20] = [
+        AuditAction::QuestionCreated,
+        AuditAction::QuestionUpdated,
+        AuditAction::QuestionDeleted,
+        AuditAction::PasswordChanged,
+        AuditAction::AdminCreated,
+        AuditAction::AdminActivated,
+        AuditAction::AdminDeactivated,
+        AuditAction::AdminRoleChanged,
+        AuditAction::AdminPasswordReset,
+        AuditAction::LoginLockedOut,
+        AuditAction::LoginUnlocked,
+        AuditAction::TwoFactorEnabled,
+        AuditAction::TwoFactorDisabled,
+        AuditAction::RecoveryCodesRegenerated,
+        AuditAction::TwoFactorPolicyChanged,
+        AuditAction::SessionsRevoked,
+        AuditAction::ApiTokenCreated,
+        AuditAction::ApiTokenRevoked,
+        AuditAction::AdminIdentityChanged,
+        AuditAction::CertificateRevoked,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::QuestionCreated => "question_created",
+            AuditAction::QuestionUpdated => "question_updated",
+            AuditAction::QuestionDeleted => "question_deleted",
+            AuditAction::PasswordChanged => "password_changed",
+            AuditAction::AdminCreated => "admin_created",
+            AuditAction::AdminActivated => "admin_activated",
+            AuditAction::AdminDeactivated => "admin_deactivated",
+            AuditAction::AdminRoleChanged => "admin_role_changed",
+            AuditAction::AdminPasswordReset => "admin_password_reset",
+            AuditAction::LoginLockedOut => "login_locked_out",
+            AuditAction::LoginUnlocked => "login_unlocked",
+            AuditAction::TwoFactorEnabled => "two_factor_enabled",
+            AuditAction::TwoFactorDisabled => "two_factor_disabled",
+            AuditAction::RecoveryCodesRegenerated => "recovery_codes_regenerated",
+            AuditAction::TwoFactorPolicyChanged => "two_factor_policy_changed",
+            AuditAction::SessionsRevoked => "sessions_revoked",
+            AuditAction::ApiTokenCreated => "api_token_created",
+            AuditAction::ApiTokenRevoked => "api_token_revoked",
+            AuditAction::AdminIdentityChanged => "admin_identity_changed",
+            AuditAction::CertificateRevoked => "certificate_revoked",
+        }
+    }
+
+    /// Kind of record the action changes
+    pub fn entity_type(&self) -> &'static str {
+        match self {
+            AuditAction::QuestionCreated
+            | AuditAction::QuestionUpdated
+            | AuditAction::QuestionDeleted => "question",
+            AuditAction::PasswordChanged
+            | AuditAction::AdminCreated
+            | AuditAction::AdminActivated
+            | AuditAction::AdminDeactivated
+            | AuditAction::AdminRoleChanged
+            | AuditAction::AdminPasswordReset
+            | AuditAction::TwoFactorEnabled
+            | AuditAction::TwoFactorDisabled
+            | AuditAction::RecoveryCodesRegenerated
+            | AuditAction::SessionsRevoked
+            | AuditAction::AdminIdentityChanged => "admin",
+            AuditAction::LoginLockedOut | AuditAction::LoginUnlocked => "login",
+            AuditAction::TwoFactorPolicyChanged => "settings",
+            AuditAction::ApiTokenCreated | AuditAction::ApiTokenRevoked => "api_token",
+            AuditAction::CertificateRevoked => "certificate",
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditAction::ALL
+            .into_iter()
+            .find(|action| action.as_str() == s)
+            .ok_or_else(|| format!("invalid audit action: {s}"))
+    }
+}
+
+impl FromSql<diesel::sql_types::Text, Sqlite> for AuditAction {
+    fn from_sql(
+        bytes: <Sqlite as diesel::backend::Backend>::RawValue<'_>,
+    ) -> DeserializeResult<Self> {
+        let s = <String as FromSql<diesel::sql_types::Text, Sqlite>>::from_sql(bytes)?;
+        Ok(Self::from_str(&s)?)
+    }
+}
+
+impl ToSql<diesel::sql_types::Text, Sqlite> for AuditAction {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> SerializeResult {
+        <str as ToSql<diesel::sql_types::Text, Sqlite>>::to_sql(self.as_str(), out)
+    }
+}
+
+// Audit log models - Queryable structs (for reading from database)
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = admin_audit_log)]
+pub struct AuditLogEntry {
+    pub id: String,
+    pub admin_id: Option<String>,
+    pub actor: String,
+    pub action: AuditAction,
+    pub entity_type: String,
+    pub entity_id: Option<String>,
+    pub before_json: Option<String>,
+    pub after_json: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+// Audit log models - Insertable structs (for creating new records)
+#[derive(Insertable, Debug)]
+#[diesel(table_name = admin_audit_log)]
+pub struct NewAuditLogEntry {
+    pub id: String,
+    pub admin_id: Option<String>,
+    pub actor: String,
+    pub action: AuditAction,
+    pub entity_type: String,
+    pub entity_id: Option<String>,
+    pub before_json: Option<String>,
+    pub after_json: Option<String>,
+}
+
+impl NewAuditLogEntry {
+    /// `admin_id` is `None` for actions taken through the CLI
+    pub fn new(
+        admin_id: Option<String>,
+        actor: String,
+        action: AuditAction,
+        entity_id: Option<String>,
+        before: Option<serde_json::Value>,
+        after: Option<serde_json::Value>,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7().to_string(),
+            admin_id,
+            actor,
+            action,
+            entity_type: action.entity_type().to_string(),
+            entity_id,
+            before_json: before.map(|value| value.to_string()),
+            after_json: after.map(|value| value.to_string()),
+        }
+    }
+}
+
+/// Criteria for browsing the audit log; unset fields match everything
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct AuditLogFilter {
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub start_date: Option<chrono::NaiveDate>,
+    pub end_date: Option<chrono::NaiveDate>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_round_trip() {
+        for action in AuditAction::ALL {
+            assert_eq!(AuditAction::from_str(action.as_str()), Ok(action));
+        }
+        assert!(AuditAction::from_str("question_exploded").is_err());
+    }
+
+    #[test]
+    fn test_new_entry_serializes_snapshots() {
+        let entry = NewAuditLogEntry::new(
+            None,
+            "cli".to_string(),
+            AuditAction::QuestionDeleted,
+            Some("q1".to_string()),
+            Some(serde_json::json!({ "question_text": "Is a pick called?" })),
+            None,
+        );
+
+        assert_eq!(entry.entity_type, "question");
+        assert_eq!(
+            entry.before_json.as_deref(),
+            Some(r#"{"question_text":"Is a pick called?"}"#)
+        );
+        assert!(entry.after_json.is_none());
+    }
+}
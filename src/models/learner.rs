@@ -0,0 +1,51 @@
+use diesel::prelude::*;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::schema::*;
+
+// Opt-in learner account; quiz sessions with its id keep progress across devices
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = learners)]
+pub struct Learner {
+    pub id: String,
+    pub email: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_login_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = learners)]
+pub struct NewLearner {
+    pub id: String,
+    pub email: String,
+}
+
+impl NewLearner {
+    pub fn new(email: String) -> Self {
+        Self {
+            id: Uuid::now_v7().to_string(),
+            email,
+        }
+    }
+}
+
+// Emailed sign-in link; only the hash of its token is stored
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = learner_login_links)]
+pub struct LearnerLoginLink {
+    pub token_hash: String,
+    pub email: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub expires_at: chrono::NaiveDateTime,
+    pub used_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = learner_login_links)]
+pub struct NewLearnerLoginLink {
+    pub token_hash: String,
+    pub email: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub expires_at: chrono::NaiveDateTime,
+}
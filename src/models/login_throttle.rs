@@ -0,0 +1,78 @@
+use std::str::FromStr;
+
+use diesel::FromSqlRow;
+use diesel::deserialize::{FromSql, Result as DeserializeResult};
+use diesel::expression::AsExpression;
+use diesel::prelude::*;
+use diesel::serialize::{Output, Result as SerializeResult, ToSql};
+use diesel::sqlite::Sqlite;
+use serde::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+// What a login throttle counts failures against
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, AsExpression, FromSqlRow)]
+#[serde(rename_all = "snake_case")]
+#[diesel(sql_type = diesel::sql_types::Text)]
+pub enum ThrottleScope {
+    Username,
+    Ip,
+    /// Transfer code redemptions from a client IP
+    TransferIp,
+}
+
+impl ThrottleScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThrottleScope::Username => "username",
+            ThrottleScope::Ip => "ip",
+            ThrottleScope::TransferIp => "transfer_ip",
+        }
+    }
+}
+
+impl FromStr for ThrottleScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "username" => Ok(ThrottleScope::Username),
+            "ip" => Ok(ThrottleScope::Ip),
+            "transfer_ip" => Ok(ThrottleScope::TransferIp),
+            _ => Err(format!("invalid throttle scope: {s}")),
+        }
+    }
+}
+
+impl FromSql<diesel::sql_types::Text, Sqlite> for ThrottleScope {
+    fn from_sql(
+        bytes: <Sqlite as diesel::backend::Backend>::RawValue<'_>,
+    ) -> DeserializeResult<Self> {
+        let s = <String as FromSql<diesel::sql_types::Text, Sqlite>>::from_sql(bytes)?;
+        Ok(Self::from_str(&s)?)
+    }
+}
+
+impl ToSql<diesel::sql_types::Text, Sqlite> for ThrottleScope {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> SerializeResult {
+        <str as ToSql<diesel::sql_types::Text, Sqlite>>::to_sql(self.as_str(), out)
+    }
+}
+
+// Failed login tracking for one username or client IP
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, PartialEq, Serialize)]
+#[diesel(table_name = login_throttles)]
+pub struct LoginThrottle {
+    pub scope: ThrottleScope,
+    pub subject: String,
+    pub failed_attempts: i32,
+    pub last_failed_at: chrono::NaiveDateTime,
+    pub locked_until: Option<chrono::NaiveDateTime>,
+}
+
+impl LoginThrottle {
+    /// Whether a lockout is in force at `now`
+    pub fn is_locked(&self, now: chrono::NaiveDateTime) -> bool {
+        self.locked_until.is_some_and(|until| until > now)
+    }
+}
@@ -4,10 +4,16 @@
 )]
 
 pub mod admin;
+pub mod audit;
 pub mod core;
+pub mod learner;
+pub mod login_throttle;
 pub mod quiz;
 
 // Re-export all models for easy access
 pub use admin::*;
+pub use audit::*;
 pub use core::*;
+pub use learner::*;
+pub use login_throttle::*;
 pub use quiz::*;
@@ -60,6 +60,203 @@ impl ToSql<diesel::sql_types::Text, Sqlite> for QuestionStatus {
     }
 }
 
+/// How a quiz session picks and presents questions
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, AsExpression, FromSqlRow)]
+#[serde(rename_all = "lowercase")]
+#[diesel(sql_type = diesel::sql_types::Text)]
+pub enum QuizMode {
+    /// Random unattempted questions with feedback after each answer
+    Practice,
+    /// A fixed, timed question draw without feedback until the end
+    Exam,
+}
+
+impl QuizMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuizMode::Practice => "practice",
+            QuizMode::Exam => "exam",
+        }
+    }
+}
+
+impl FromStr for QuizMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "practice" => Ok(QuizMode::Practice),
+            "exam" => Ok(QuizMode::Exam),
+            s => Err(format!("invalid quiz mode: {s}")),
+        }
+    }
+}
+
+impl FromSql<diesel::sql_types::Text, Sqlite> for QuizMode {
+    fn from_sql(
+        bytes: <Sqlite as diesel::backend::Backend>::RawValue<'_>,
+    ) -> DeserializeResult<Self> {
+        let s = <String as FromSql<diesel::sql_types::Text, Sqlite>>::from_sql(bytes)?;
+        Ok(Self::from_str(&s)?)
+    }
+}
+
+impl ToSql<diesel::sql_types::Text, Sqlite> for QuizMode {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> SerializeResult {
+        <str as ToSql<diesel::sql_types::Text, Sqlite>>::to_sql(self.as_str(), out)
+    }
+}
+
+/// Difficulty level of a quiz question, as stored in `difficulty_level`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Advanced,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [
+        Difficulty::Beginner,
+        Difficulty::Intermediate,
+        Difficulty::Advanced,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Intermediate => "intermediate",
+            Difficulty::Advanced => "advanced",
+        }
+    }
+
+    /// The next harder level, if any
+    pub fn next(&self) -> Option<Difficulty> {
+        match self {
+            Difficulty::Beginner => Some(Difficulty::Intermediate),
+            Difficulty::Intermediate => Some(Difficulty::Advanced),
+            Difficulty::Advanced => None,
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "beginner" => Ok(Difficulty::Beginner),
+            "intermediate" => Ok(Difficulty::Intermediate),
+            "advanced" => Ok(Difficulty::Advanced),
+            s => Err(format!("invalid difficulty: {s}")),
+        }
+    }
+}
+
+/// How a session picks question difficulties
+///
+/// Stored as `all`, a level like `beginner`, `progressive`, or `mix:50-30-20` with
+/// the beginner, intermediate and advanced weights.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[diesel(sql_type = diesel::sql_types::Text)]
+pub enum DifficultyMode {
+    /// Any question, picked uniformly
+    All,
+    /// Only questions of one level
+    Single(Difficulty),
+    /// Starts with beginner questions and moves up a level after a streak of correct answers
+    Progressive,
+    /// New questions drawn by level in proportion to the weights
+    Mix {
+        beginner: u8,
+        intermediate: u8,
+        advanced: u8,
+    },
+}
+
+impl DifficultyMode {
+    /// Weight of a level in a mix; every level counts equally in other modes
+    pub fn weight(&self, difficulty: Difficulty) -> u32 {
+        match (self, difficulty) {
+            (DifficultyMode::Mix { beginner, .. }, Difficulty::Beginner) => (*beginner).into(),
+            (DifficultyMode::Mix { intermediate, .. }, Difficulty::Intermediate) => {
+                (*intermediate).into()
+            }
+            (DifficultyMode::Mix { advanced, .. }, Difficulty::Advanced) => (*advanced).into(),
+            _ => 1,
+        }
+    }
+}
+
+impl std::fmt::Display for DifficultyMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DifficultyMode::All => write!(f, "all"),
+            DifficultyMode::Single(difficulty) => write!(f, "{}", difficulty.as_str()),
+            DifficultyMode::Progressive => write!(f, "progressive"),
+            DifficultyMode::Mix {
+                beginner,
+                intermediate,
+                advanced,
+            } => write!(f, "mix:{beginner}-{intermediate}-{advanced}"),
+        }
+    }
+}
+
+impl FromStr for DifficultyMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "all" => Ok(DifficultyMode::All),
+            "progressive" => Ok(DifficultyMode::Progressive),
+            s => {
+                if let Some(weights) = s.strip_prefix("mix:") {
+                    let weights: Vec<u8> = weights
+                        .split('-')
+                        .map(|weight| weight.parse::<u8>())
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| format!("invalid difficulty mix: {s}"))?;
+                    let [beginner, intermediate, advanced] = weights[..] else {
+                        return Err(format!("invalid difficulty mix: {s}"));
+                    };
+                    return Ok(DifficultyMode::Mix {
+                        beginner,
+                        intermediate,
+                        advanced,
+                    });
+                }
+                s.parse()
+                    .map(DifficultyMode::Single)
+                    .map_err(|_| format!("invalid difficulty mode: {s}"))
+            }
+        }
+    }
+}
+
+impl Serialize for DifficultyMode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl FromSql<diesel::sql_types::Text, Sqlite> for DifficultyMode {
+    fn from_sql(
+        bytes: <Sqlite as diesel::backend::Backend>::RawValue<'_>,
+    ) -> DeserializeResult<Self> {
+        let s = <String as FromSql<diesel::sql_types::Text, Sqlite>>::from_sql(bytes)?;
+        Ok(Self::from_str(&s)?)
+    }
+}
+
+impl ToSql<diesel::sql_types::Text, Sqlite> for DifficultyMode {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> SerializeResult {
+        out.set_value(self.to_string());
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
 // Quiz models - Queryable structs (for reading from database)
 #[derive(Queryable, Selectable, Debug, Clone, Serialize)]
 #[diesel(table_name = quiz_questions)]
@@ -152,6 +349,157 @@ pub struct NewQuizAttempt {
     pub response_time_ms: Option<i32>,
 }
 
+/// A learner's quiz session; its id is what `quiz_attempts.session_id` refers to
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = quiz_sessions)]
+pub struct QuizSessionRecord {
+    pub id: String,
+    pub rule_set_id: String,
+    pub version_id: String,
+    pub language: String,
+    pub mode: QuizMode,
+    pub started_at: chrono::NaiveDateTime,
+    pub ended_at: Option<chrono::NaiveDateTime>,
+    /// Learner account the session belongs to; `None` for anonymous sessions
+    pub learner_id: Option<String>,
+    /// Comma-separated ids of the rule subtrees the quiz is focused on; `None` for all rules
+    pub topic_rule_ids: Option<String>,
+    pub difficulty_mode: DifficultyMode,
+}
+
+impl QuizSessionRecord {
+    /// Roots of the rule subtrees the quiz draws from; empty for the whole rule set
+    pub fn topic_rule_ids(&self) -> Vec<String> {
+        self.topic_rule_ids
+            .iter()
+            .flat_map(|ids| ids.split(','))
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Spaced-repetition state of one question for one learner session
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, PartialEq, Serialize)]
+#[diesel(table_name = quiz_reviews)]
+pub struct QuizReview {
+    pub session_id: String,
+    pub question_id: String,
+    /// Correct answers in a row since the last lapse
+    pub repetitions: i32,
+    pub interval_days: i32,
+    pub ease_factor: f64,
+    pub due_at: chrono::NaiveDateTime,
+    pub last_reviewed_at: chrono::NaiveDateTime,
+}
+
+/// Bearer token of a quiz session started through the JSON API; only its hash is stored
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = quiz_api_tokens)]
+pub struct QuizApiToken {
+    pub token_hash: String,
+    pub session_id: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Code that opens an anonymous quiz session on another device; only its hash is stored
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = quiz_transfer_codes)]
+pub struct QuizTransferCode {
+    pub code_hash: String,
+    pub session_id: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub expires_at: chrono::NaiveDateTime,
+    pub used_at: Option<chrono::NaiveDateTime>,
+}
+
+/// A timed exam; its attempts are recorded in its own quiz session
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, Serialize)]
+#[diesel(table_name = quiz_exams)]
+pub struct QuizExam {
+    pub session_id: String,
+    /// Practice session to return to once the exam is over
+    pub practice_session_id: Option<String>,
+    pub question_count: i32,
+    /// Share of correct answers needed to pass
+    pub pass_percent: i32,
+    pub started_at: chrono::NaiveDateTime,
+    /// Answers after this are not accepted
+    pub ends_at: chrono::NaiveDateTime,
+    pub finished_at: Option<chrono::NaiveDateTime>,
+    /// Set with `passed` when the exam is finished
+    pub correct_count: Option<i32>,
+    pub passed: Option<bool>,
+}
+
+/// One question of an exam's fixed draw
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = quiz_exam_questions)]
+pub struct QuizExamQuestion {
+    pub session_id: String,
+    /// Order the question is served in, from 0
+    pub position: i32,
+    pub question_id: String,
+}
+
+/// Certificate for a passed exam, verifiable on its public page
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, Serialize)]
+#[diesel(table_name = quiz_certificates)]
+pub struct QuizCertificate {
+    pub id: String,
+    pub exam_session_id: String,
+    pub holder_name: String,
+    pub rule_set_name: String,
+    pub version_name: String,
+    pub score_percent: i32,
+    pub passed_at: chrono::NaiveDateTime,
+    pub issued_at: chrono::NaiveDateTime,
+    pub revoked_at: Option<chrono::NaiveDateTime>,
+    pub revocation_reason: Option<String>,
+}
+
+impl QuizCertificate {
+    pub fn new(
+        exam_session_id: String,
+        holder_name: String,
+        rule_set_name: String,
+        version_name: String,
+        score_percent: i32,
+        passed_at: chrono::NaiveDateTime,
+        issued_at: chrono::NaiveDateTime,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7().to_string(),
+            exam_session_id,
+            holder_name,
+            rule_set_name,
+            version_name,
+            score_percent,
+            passed_at,
+            issued_at,
+            revoked_at: None,
+            revocation_reason: None,
+        }
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = quiz_sessions)]
+pub struct NewQuizSessionRecord {
+    pub id: String,
+    pub rule_set_id: String,
+    pub version_id: String,
+    pub language: String,
+    pub mode: QuizMode,
+    pub learner_id: Option<String>,
+    pub topic_rule_ids: Option<String>,
+    pub difficulty_mode: DifficultyMode,
+}
+
 // Helper functions for generating UUIDs - Quiz models
 impl NewQuizQuestion {
     pub fn new(
@@ -201,6 +549,55 @@ impl NewQuizQuestionRule {
     }
 }
 
+impl NewQuizSessionRecord {
+    pub fn new(rule_set_id: String, version_id: String, language: String, mode: QuizMode) -> Self {
+        Self::with_id(
+            Uuid::now_v7().to_string(),
+            rule_set_id,
+            version_id,
+            language,
+            mode,
+        )
+    }
+
+    /// Session with an id chosen by the caller, e.g. one derived from a chat
+    pub fn with_id(
+        id: String,
+        rule_set_id: String,
+        version_id: String,
+        language: String,
+        mode: QuizMode,
+    ) -> Self {
+        Self {
+            id,
+            rule_set_id,
+            version_id,
+            language,
+            mode,
+            learner_id: None,
+            topic_rule_ids: None,
+            difficulty_mode: DifficultyMode::All,
+        }
+    }
+
+    /// Make the session belong to a learner account
+    pub fn for_learner(mut self, learner_id: Option<String>) -> Self {
+        self.learner_id = learner_id;
+        self
+    }
+
+    /// Focus the session on rule subtrees, stored as returned by the record
+    pub fn with_topics(mut self, topic_rule_ids: Option<String>) -> Self {
+        self.topic_rule_ids = topic_rule_ids;
+        self
+    }
+
+    pub fn with_difficulty_mode(mut self, difficulty_mode: DifficultyMode) -> Self {
+        self.difficulty_mode = difficulty_mode;
+        self
+    }
+}
+
 impl NewQuizAttempt {
     pub fn new(
         session_id: String,
@@ -312,7 +709,10 @@ impl QuizQuestionData {
 /// Session statistics summary
 #[derive(Debug, Clone)]
 pub struct SessionStatistics {
+    /// Answers given, counting reviews of the same question separately
     pub total_questions: usize,
+    /// Distinct questions answered at least once
+    pub questions_seen: usize,
     pub correct_answers: usize,
     pub accuracy_percentage: u32,
     pub current_streak: usize,
@@ -344,6 +744,17 @@ pub struct AggregateStatistics {
     pub date_range_end: Option<chrono::NaiveDate>,
 }
 
+/// Attempts in sessions of one quiz mode and difficulty mode
+#[derive(Debug, Clone, Serialize)]
+pub struct ModeStatistics {
+    pub mode: String,
+    pub difficulty_mode: String,
+    pub sessions: usize,
+    pub attempts: usize,
+    pub correct_attempts: usize,
+    pub success_rate: f64,
+}
+
 /// Answer distribution statistics for question detail view
 #[derive(Debug, Clone, Serialize)]
 pub struct AnswerDistribution {
@@ -365,9 +776,38 @@ pub struct QuestionDetailStats {
     pub correct_attempts: usize,
     pub success_rate: f64,
     pub most_common_wrong_answer: Option<String>,
+    /// Over attempts with a measured response time; `None` if there are none
+    pub response_times: Option<ResponseTimePercentiles>,
     pub recent_attempts: Vec<RecentAttempt>,
 }
 
+/// Response time percentiles of a question's attempts, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ResponseTimePercentiles {
+    /// Attempts with a measured response time
+    pub samples: usize,
+    pub p50_ms: i32,
+    pub p90_ms: i32,
+    pub p95_ms: i32,
+}
+
+impl ResponseTimePercentiles {
+    /// Nearest-rank percentiles of the given times; `None` if there are none
+    pub fn from_times(mut times: Vec<i32>) -> Option<Self> {
+        if times.is_empty() {
+            return None;
+        }
+        times.sort_unstable();
+        let percentile = |p: usize| times[(p * times.len()).div_ceil(100).max(1) - 1];
+        Some(Self {
+            samples: times.len(),
+            p50_ms: percentile(50),
+            p90_ms: percentile(90),
+            p95_ms: percentile(95),
+        })
+    }
+}
+
 /// Recent attempt data for question detail view
 #[derive(Debug, Clone, Serialize)]
 pub struct RecentAttempt {
@@ -420,6 +860,7 @@ pub struct QuestionExportData {
     pub total_attempts: usize,
     pub correct_attempts: usize,
     pub success_rate_percent: f64,
+    pub response_times: Option<ResponseTimePercentiles>,
     pub answers: Vec<AnswerExportData>, // Variable length with selection data
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
@@ -434,3 +875,33 @@ pub struct AnswerExportData {
     pub selection_count: usize, // How many times this answer was selected
     pub selection_percentage: f64, // Percentage of total selections
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_time_percentiles_use_nearest_rank() {
+        assert_eq!(ResponseTimePercentiles::from_times(vec![]), None);
+        assert_eq!(
+            ResponseTimePercentiles::from_times(vec![4000]),
+            Some(ResponseTimePercentiles {
+                samples: 1,
+                p50_ms: 4000,
+                p90_ms: 4000,
+                p95_ms: 4000,
+            })
+        );
+
+        let times = (1..=20).rev().map(|second| second * 1000).collect();
+        assert_eq!(
+            ResponseTimePercentiles::from_times(times),
+            Some(ResponseTimePercentiles {
+                samples: 20,
+                p50_ms: 10_000,
+                p90_ms: 18_000,
+                p95_ms: 19_000,
+            })
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! OpenAPI 3.1 document for the JSON API and the statistics exports.
+//!
+//! The document is collected from the `#[utoipa::path]` annotations of the handlers
+//! that [`documented`] registers, so the routes and the spec cannot drift apart.
+
+use axum::{Json, Router, extract::FromRef, routing::get};
+use regelator::{auth::ADMIN_COOKIE_NAME, config::Config};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::{handlers, repository::RuleRepository};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Regelator API",
+        description = "Read-only access to rule sets, rules and glossary terms, quizzes, and quiz statistics exports for admins"
+    ),
+    tags(
+        (name = "rules", description = "Public rule content"),
+        (name = "quiz", description = "Quiz sessions identified by the `X-Quiz-Session` header"),
+        (name = "stats", description = "Quiz statistics exports for the analyst role and above"),
+    ),
+    modifiers(&SecuritySchemes)
+)]
+struct ApiDoc;
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("Personal API token from /admin/api-tokens"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "admin_session",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(ADMIN_COOKIE_NAME))),
+        );
+        components.add_security_scheme(
+            "quiz_session",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(
+                handlers::quiz_api::QUIZ_SESSION_HEADER,
+            ))),
+        );
+    }
+}
+
+/// Documented routes together with the document describing them
+fn documented<S>() -> (Router<S>, utoipa::openapi::OpenApi)
+where
+    Config: FromRef<S>,
+    RuleRepository: FromRef<S>,
+    S: Clone + Send + Sync + 'static,
+{
+    OpenApiRouter::with_openapi(ApiDoc::openapi())
+        .merge(handlers::api::routes())
+        .merge(handlers::quiz_api::routes())
+        .routes(routes!(handlers::admin::export_stats_csv))
+        .routes(routes!(handlers::admin::export_stats_parquet))
+        .split_for_parts()
+}
+
+/// Documented routes plus the document itself at `/api/openapi.json`
+pub fn router<S>() -> Router<S>
+where
+    Config: FromRef<S>,
+    RuleRepository: FromRef<S>,
+    S: Clone + Send + Sync + 'static,
+{
+    let (router, openapi) = documented();
+    router.route(
+        "/api/openapi.json",
+        get(move || {
+            let openapi = openapi.clone();
+            async move { Json(openapi) }
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use regelator::config::Config;
+    use regelator::test_support;
+    use tower::ServiceExt;
+
+    #[derive(Clone, FromRef)]
+    struct TestState {
+        config: Config,
+        repository: RuleRepository,
+    }
+
+    fn test_state() -> TestState {
+        TestState {
+            config: test_support::config(),
+            repository: test_support::repository(),
+        }
+    }
+
+    /// Replace `{param}` segments with a value no fixture uses
+    fn concrete_path(path: &str) -> String {
+        path.split('/')
+            .map(|segment| {
+                if segment.starts_with('{') {
+                    "missing"
+                } else {
+                    segment
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    #[test]
+    fn test_spec_lists_api_and_exports() {
+        let (_, openapi) = documented::<TestState>();
+        let json = serde_json::to_value(&openapi).unwrap();
+        assert!(json["openapi"].as_str().unwrap().starts_with("3.1"));
+
+        let paths: Vec<&str> = openapi.paths.paths.keys().map(String::as_str).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/admin/stats/export.csv",
+                "/admin/stats/export.parquet",
+                "/api/v1/quiz/{rule_set}/answers",
+                "/api/v1/quiz/{rule_set}/question",
+                "/api/v1/quiz/{rule_set}/sessions",
+                "/api/v1/quiz/{rule_set}/stats",
+                "/api/v1/rule-sets",
+                "/api/v1/rule-sets/{rule_set}",
+                "/api/v1/rule-sets/{rule_set}/glossary",
+                "/api/v1/rule-sets/{rule_set}/glossary/{term}",
+                "/api/v1/rule-sets/{rule_set}/rules",
+                "/api/v1/rule-sets/{rule_set}/rules/{rule}",
+                "/api/v1/rule-sets/{rule_set}/versions",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_every_documented_path_is_routed() {
+        let (router, openapi) = documented::<TestState>();
+        let app = router.with_state(test_state());
+
+        for (path, item) in &openapi.paths.paths {
+            let method = match (&item.get, &item.post) {
+                (Some(_), None) => "GET",
+                (None, Some(_)) => "POST",
+                _ => panic!("{path} should document exactly one of GET and POST"),
+            };
+
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(method)
+                        .uri(concrete_path(path))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let status = response.status();
+            assert_ne!(status, StatusCode::METHOD_NOT_ALLOWED, "{method} {path}");
+
+            // The router's own 404 has an empty body; handlers answer with a JSON error
+            if status == StatusCode::NOT_FOUND {
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                assert!(!body.is_empty(), "{path} is documented but not routed");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spec_is_served() {
+        let app = router::<TestState>().with_state(test_state());
+        let response = app
+            .oneshot(
+                Request::get("/api/openapi.json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let served: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let (_, openapi) = documented::<TestState>();
+        assert_eq!(served, serde_json::to_value(&openapi).unwrap());
+    }
+}
@@ -0,0 +1,690 @@
+//! Quiz flow shared by the HTML quiz pages and the JSON quiz API.
+//!
+//! Transports only differ in how they identify the learner's session; picking
+//! questions, recording attempts and scheduling reviews happens here.
+
+use std::collections::{HashMap, HashSet};
+
+use color_eyre::Result;
+use pulldown_cmark::{Event, Parser};
+use rand::seq::IndexedRandom;
+
+use crate::{
+    difficulty,
+    models::{
+        Difficulty, DifficultyMode, NewQuizAttempt, NewQuizSessionRecord, QuizAnswer, QuizMode,
+        QuizQuestion, QuizSessionRecord, RuleSet, Version,
+    },
+    repository::RuleRepository,
+    spaced_repetition,
+};
+
+/// Rule set and version a quiz draws its questions from
+#[derive(Debug, Clone)]
+pub struct QuizScope {
+    pub rule_set: RuleSet,
+    pub version: Version,
+    /// Questions covering the rule subtrees the quiz is focused on; `None` for all questions
+    pub focus: Option<HashSet<String>>,
+    /// How new questions are picked by difficulty; a single level narrows the quiz
+    pub difficulty: DifficultyMode,
+}
+
+/// A top-level rule section and the number of questions covering it
+#[derive(Debug, Clone)]
+pub struct QuizSection {
+    pub rule_id: String,
+    pub number: String,
+    /// Section heading as plain text
+    pub title: String,
+    pub question_count: usize,
+}
+
+/// A question with its answer options in display order
+#[derive(Debug, Clone)]
+pub struct QuestionWithAnswers {
+    pub question: QuizQuestion,
+    pub answers: Vec<QuizAnswer>,
+    /// Answered before and served again because its review is due
+    pub is_review: bool,
+}
+
+/// Result of answering a question
+#[derive(Debug, Clone)]
+pub struct AnswerOutcome {
+    pub question: QuizQuestion,
+    pub answers: Vec<QuizAnswer>,
+    pub selected_answer_id: String,
+    pub is_correct: bool,
+}
+
+impl QuizScope {
+    /// Quiz over the current version of a rule set, if both exist
+    pub fn current(repo: &RuleRepository, rule_set_slug: &str) -> Result<Option<Self>> {
+        let Some(rule_set) = repo
+            .get_rule_sets()?
+            .into_iter()
+            .find(|rule_set| rule_set.slug == rule_set_slug)
+        else {
+            return Ok(None);
+        };
+        let Some(version) = repo.get_current_version(rule_set_slug)? else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            rule_set,
+            version,
+            focus: None,
+            difficulty: DifficultyMode::All,
+        }))
+    }
+
+    /// Quiz over the current version with the session's focus and difficulty mode
+    pub fn for_session(
+        repo: &RuleRepository,
+        rule_set_slug: &str,
+        session: &QuizSessionRecord,
+    ) -> Result<Option<Self>> {
+        match Self::current(repo, rule_set_slug)? {
+            Some(mut scope) => {
+                scope.difficulty = session.difficulty_mode;
+                Ok(Some(scope.focused(repo, &session.topic_rule_ids())?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Narrow the quiz to questions covering a rule in the subtrees rooted at
+    /// `topic_rule_ids`; no topics means the whole rule set
+    pub fn focused(mut self, repo: &RuleRepository, topic_rule_ids: &[String]) -> Result<Self> {
+        if topic_rule_ids.is_empty() {
+            self.focus = None;
+            return Ok(self);
+        }
+        let parents = self.rule_parents(repo)?;
+        let topics: HashSet<&str> = topic_rule_ids.iter().map(String::as_str).collect();
+        let focus = repo
+            .get_question_rule_links(&self.rule_set.id, &self.version.id)?
+            .into_iter()
+            .filter(|(_, rule_id)| ancestry(&parents, rule_id).any(|id| topics.contains(id)))
+            .map(|(question_id, _)| question_id)
+            .collect();
+        self.focus = Some(focus);
+        Ok(self)
+    }
+
+    /// Store the session's focus, ignoring ids that are not rules of this quiz, and
+    /// return the quiz narrowed to it
+    pub fn focus_session(
+        self,
+        repo: &RuleRepository,
+        session_id: &str,
+        topic_rule_ids: &[String],
+    ) -> Result<Self> {
+        let parents = self.rule_parents(repo)?;
+        let mut topics: Vec<String> = topic_rule_ids
+            .iter()
+            .filter(|rule_id| parents.contains_key(rule_id.as_str()))
+            .cloned()
+            .collect();
+        topics.sort();
+        topics.dedup();
+        repo.set_quiz_session_topics(session_id, (!topics.is_empty()).then(|| topics.join(",")))?;
+        self.focused(repo, &topics)
+    }
+
+    /// Store the session's difficulty mode and return the quiz using it; a mix
+    /// weighting every level 0 is stored as all levels
+    pub fn set_session_difficulty(
+        mut self,
+        repo: &RuleRepository,
+        session_id: &str,
+        mode: DifficultyMode,
+    ) -> Result<Self> {
+        self.difficulty = match mode {
+            DifficultyMode::Mix { .. } if Difficulty::ALL.iter().all(|d| mode.weight(*d) == 0) => {
+                DifficultyMode::All
+            }
+            mode => mode,
+        };
+        repo.set_quiz_session_difficulty_mode(session_id, self.difficulty)?;
+        Ok(self)
+    }
+
+    /// Top-level rule sections in number order, with how many questions cover each
+    pub fn sections(&self, repo: &RuleRepository, language: &str) -> Result<Vec<QuizSection>> {
+        let rules = repo.get_rules_for_version(&self.version.id)?;
+        let parents: HashMap<String, Option<String>> = rules
+            .iter()
+            .map(|rule| (rule.id.clone(), rule.parent_rule_id.clone()))
+            .collect();
+        let titles: HashMap<String, String> = repo
+            .get_rules_with_content_for_version(&self.version.id, language)?
+            .into_iter()
+            .map(|(rule, content)| (rule.id, plain_text(&content.content_markdown)))
+            .collect();
+
+        let mut questions: HashMap<String, HashSet<String>> = HashMap::new();
+        for (question_id, rule_id) in
+            repo.get_question_rule_links(&self.rule_set.id, &self.version.id)?
+        {
+            if let Some(section) = ancestry(&parents, &rule_id).last() {
+                questions
+                    .entry(section.to_string())
+                    .or_default()
+                    .insert(question_id);
+            }
+        }
+
+        let mut sections: Vec<QuizSection> = rules
+            .into_iter()
+            .filter(|rule| rule.parent_rule_id.is_none())
+            .map(|rule| QuizSection {
+                question_count: questions.get(&rule.id).map_or(0, HashSet::len),
+                title: titles.get(&rule.id).cloned().unwrap_or_default(),
+                number: rule.number,
+                rule_id: rule.id,
+            })
+            .collect();
+        sections.sort_by_key(|section| number_key(&section.number));
+        Ok(sections)
+    }
+
+    /// Top-level rule section of each question linked to a rule, by question id;
+    /// a question linked to several sections counts for the first one linked
+    pub fn question_sections(&self, repo: &RuleRepository) -> Result<HashMap<String, String>> {
+        let parents = self.rule_parents(repo)?;
+        let mut sections = HashMap::new();
+        for (question_id, rule_id) in
+            repo.get_question_rule_links(&self.rule_set.id, &self.version.id)?
+        {
+            if let Some(section) = ancestry(&parents, &rule_id).last() {
+                sections
+                    .entry(question_id)
+                    .or_insert_with(|| section.to_string());
+            }
+        }
+        Ok(sections)
+    }
+
+    /// Rule ids of this quiz's version with their parent rule ids
+    fn rule_parents(&self, repo: &RuleRepository) -> Result<HashMap<String, Option<String>>> {
+        Ok(repo
+            .get_rules_for_version(&self.version.id)?
+            .into_iter()
+            .map(|rule| (rule.id, rule.parent_rule_id))
+            .collect())
+    }
+
+    /// Whether the question is in the focus and, for a single-level quiz, at that level
+    fn includes(&self, question: &QuizQuestion) -> bool {
+        let in_focus = self
+            .focus
+            .as_ref()
+            .is_none_or(|focus| focus.contains(&question.id));
+        let at_level = match self.difficulty {
+            DifficultyMode::Single(level) => difficulty_of(question) == Some(level),
+            _ => true,
+        };
+        in_focus && at_level
+    }
+
+    /// Start a new session in this quiz, anonymous or for a learner account
+    pub fn start_session(
+        &self,
+        repo: &RuleRepository,
+        language: &str,
+        mode: QuizMode,
+        learner_id: Option<&str>,
+    ) -> Result<QuizSessionRecord> {
+        repo.create_quiz_session(
+            NewQuizSessionRecord::new(
+                self.rule_set.id.clone(),
+                self.version.id.clone(),
+                language.to_string(),
+                mode,
+            )
+            .for_learner(learner_id.map(str::to_string)),
+        )
+    }
+
+    /// Session record for an id the caller derives itself, created on first use
+    pub fn ensure_session(
+        &self,
+        repo: &RuleRepository,
+        session_id: &str,
+        language: &str,
+        mode: QuizMode,
+    ) -> Result<QuizSessionRecord> {
+        if let Some(existing) = repo.get_quiz_session(session_id)? {
+            return Ok(existing);
+        }
+        repo.create_quiz_session(NewQuizSessionRecord::with_id(
+            session_id.to_string(),
+            self.rule_set.id.clone(),
+            self.version.id.clone(),
+            language.to_string(),
+            mode,
+        ))
+    }
+
+    /// Number of questions in this quiz
+    pub fn total_questions(&self, repo: &RuleRepository) -> Result<usize> {
+        Ok(repo
+            .get_quiz_questions(&self.rule_set.id, &self.version.id)?
+            .iter()
+            .filter(|question| self.includes(question))
+            .count())
+    }
+
+    /// Number of this quiz's questions the session has answered at least once
+    pub fn questions_seen(&self, repo: &RuleRepository, session_id: &str) -> Result<usize> {
+        let unattempted = repo
+            .get_unattempted_questions_for_session(session_id, &self.rule_set.id, &self.version.id)?
+            .iter()
+            .filter(|question| self.includes(question))
+            .count();
+        Ok(self.total_questions(repo)?.saturating_sub(unattempted))
+    }
+
+    /// Next question for the session: the most overdue review, else a random question
+    /// it has not seen yet; `None` once everything is answered and nothing is due
+    pub fn next_question(
+        &self,
+        repo: &RuleRepository,
+        session_id: &str,
+    ) -> Result<Option<QuestionWithAnswers>> {
+        let now = chrono::Utc::now().naive_utc();
+        let due =
+            repo.get_due_review_questions(session_id, &self.rule_set.id, &self.version.id, now)?;
+        let (question, is_review) =
+            if let Some(question) = due.into_iter().find(|question| self.includes(question)) {
+                (question, true)
+            } else {
+                let questions: Vec<QuizQuestion> = repo
+                    .get_unattempted_questions_for_session(
+                        session_id,
+                        &self.rule_set.id,
+                        &self.version.id,
+                    )?
+                    .into_iter()
+                    .filter(|question| self.includes(question))
+                    .collect();
+                let Some(question) = self.pick_new_question(repo, session_id, questions)? else {
+                    return Ok(None);
+                };
+                (question, false)
+            };
+
+        let answers = repo.get_quiz_answers(&question.id)?;
+        Ok(Some(QuestionWithAnswers {
+            question,
+            answers,
+            is_review,
+        }))
+    }
+
+    /// Random question out of the unattempted ones, drawn from the level the
+    /// difficulty mode picks when it is progressive or a mix
+    fn pick_new_question(
+        &self,
+        repo: &RuleRepository,
+        session_id: &str,
+        questions: Vec<QuizQuestion>,
+    ) -> Result<Option<QuizQuestion>> {
+        let mut rng = rand::rng();
+        let progressive = match self.difficulty {
+            DifficultyMode::Progressive => difficulty::progressive_level(
+                repo.get_session_answer_difficulties(session_id)?
+                    .into_iter()
+                    .filter_map(|(level, is_correct)| {
+                        Some((level.parse().ok()?, is_correct == Some(true)))
+                    }),
+            ),
+            _ => Difficulty::Beginner,
+        };
+        let available: Vec<Difficulty> = Difficulty::ALL
+            .into_iter()
+            .filter(|level| {
+                questions
+                    .iter()
+                    .any(|question| difficulty_of(question) == Some(*level))
+            })
+            .collect();
+        let candidates: Vec<&QuizQuestion> =
+            match difficulty::next_level(self.difficulty, progressive, &available, &mut rng) {
+                Some(level) => questions
+                    .iter()
+                    .filter(|question| difficulty_of(question) == Some(level))
+                    .collect(),
+                None => questions.iter().collect(),
+            };
+        Ok(candidates
+            .choose(&mut rng)
+            .map(|question| (*question).clone()))
+    }
+
+    /// Number of the session's reviews due by the end of today (UTC), including overdue ones
+    pub fn due_today(&self, repo: &RuleRepository, session_id: &str) -> Result<usize> {
+        let end_of_day = spaced_repetition::end_of_day(chrono::Utc::now().naive_utc());
+        Ok(repo
+            .get_due_review_questions(session_id, &self.rule_set.id, &self.version.id, end_of_day)?
+            .iter()
+            .filter(|question| self.includes(question))
+            .count())
+    }
+
+    /// When the session's next review falls due, if any are scheduled
+    pub fn next_review_due(
+        &self,
+        repo: &RuleRepository,
+        session_id: &str,
+    ) -> Result<Option<chrono::NaiveDateTime>> {
+        if self.focus.is_none() && !matches!(self.difficulty, DifficultyMode::Single(_)) {
+            return repo.get_next_review_due(session_id, &self.rule_set.id, &self.version.id);
+        }
+        let included: HashSet<String> = repo
+            .get_quiz_questions(&self.rule_set.id, &self.version.id)?
+            .into_iter()
+            .filter(|question| self.includes(question))
+            .map(|question| question.id)
+            .collect();
+        Ok(repo
+            .get_session_reviews(session_id)?
+            .into_iter()
+            .filter(|review| included.contains(&review.question_id))
+            .map(|review| review.due_at)
+            .min())
+    }
+
+    /// Record an answer for the session, with how long it took if that is known.
+    ///
+    /// Returns `None` if the question is not part of this quiz or the answer does not
+    /// belong to the question, so callers can report a client error.
+    pub fn submit_answer(
+        &self,
+        repo: &RuleRepository,
+        session_id: &str,
+        question_id: &str,
+        answer_id: &str,
+        response_time_ms: Option<i32>,
+    ) -> Result<Option<AnswerOutcome>> {
+        let Some(question) = repo
+            .get_quiz_question_by_id(question_id)?
+            .filter(|question| {
+                question.rule_set_id == self.rule_set.id && question.version_id == self.version.id
+            })
+        else {
+            return Ok(None);
+        };
+
+        let answers = repo.get_quiz_answers(&question.id)?;
+        let Some(is_correct) = answers
+            .iter()
+            .find(|answer| answer.id == answer_id)
+            .map(|answer| answer.is_correct)
+        else {
+            return Ok(None);
+        };
+
+        repo.create_quiz_attempt(&NewQuizAttempt::new(
+            session_id.to_string(),
+            question.id.clone(),
+            Some(answer_id.to_string()),
+            Some(is_correct),
+            response_time_ms,
+        ))?;
+
+        let previous = repo.get_quiz_review(session_id, &question.id)?;
+        repo.save_quiz_review(&spaced_repetition::review(
+            previous.as_ref(),
+            session_id,
+            &question.id,
+            spaced_repetition::quality(is_correct),
+            chrono::Utc::now().naive_utc(),
+        ))?;
+
+        Ok(Some(AnswerOutcome {
+            question,
+            answers,
+            selected_answer_id: answer_id.to_string(),
+            is_correct,
+        }))
+    }
+}
+
+fn difficulty_of(question: &QuizQuestion) -> Option<Difficulty> {
+    question.difficulty_level.parse().ok()
+}
+
+/// A rule followed by its ancestors, ending with its top-level section
+fn ancestry<'a>(
+    parents: &'a HashMap<String, Option<String>>,
+    rule_id: &'a str,
+) -> impl Iterator<Item = &'a str> {
+    std::iter::successors(Some(rule_id), move |id| {
+        parents.get(*id).and_then(|parent| parent.as_deref())
+    })
+}
+
+/// Sort key ordering rule numbers like 2 < 10 < 10.1
+fn number_key(number: &str) -> Vec<u32> {
+    number
+        .split('.')
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse().unwrap_or(u32::MAX))
+        .collect()
+}
+
+/// Text of a rule heading without Markdown, e.g. definition links
+fn plain_text(markdown: &str) -> String {
+    Parser::new(markdown)
+        .filter_map(|event| match event {
+            Event::Text(text) | Event::Code(text) => Some(text.into_string()),
+            _ => None,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NewRule, NewRuleContent, QuizAnswerData, QuizQuestionData};
+    use crate::test_support::repository_with_current_version;
+
+    /// Rule set with sections 2, 10 and 17 and questions on 10.1, 17 and 17.2.1;
+    /// two are beginner questions, one intermediate and one advanced
+    fn seeded_repo() -> RuleRepository {
+        let (repo, rule_set, version) = repository_with_current_version();
+
+        let mut ids: HashMap<&str, String> = HashMap::new();
+        for (number, parent, title) in [
+            ("17", None, "Fouls"),
+            ("17.2", Some("17"), "Receiving Fouls:"),
+            ("17.2.1", Some("17.2"), "Contact before the catch."),
+            ("2", None, "[Playing Field](definition:playing-field)"),
+            ("10", None, "The Check"),
+            ("10.1", Some("10"), "Play stops."),
+        ] {
+            let rule = repo
+                .create_rule(NewRule::new(
+                    format!("rule-{number}"),
+                    rule_set.id.clone(),
+                    version.id.clone(),
+                    parent.map(|parent| ids[parent].clone()),
+                    number.to_string(),
+                ))
+                .unwrap();
+            repo.create_rule_content(NewRuleContent::new(
+                rule.id.clone(),
+                "en".to_string(),
+                title.to_string(),
+                None,
+            ))
+            .unwrap();
+            ids.insert(number, rule.id);
+        }
+
+        for (text, difficulty, rules) in [
+            ("Contact before the catch?", "intermediate", vec!["17.2.1"]),
+            ("What is a foul?", "beginner", vec!["17", "17.2"]),
+            ("When does play stop?", "beginner", vec!["10.1"]),
+            ("Who calls fouls?", "advanced", vec![]),
+        ] {
+            repo.create_quiz_question_complete(&QuizQuestionData::new(
+                rule_set.id.clone(),
+                version.id.clone(),
+                text.to_string(),
+                "Because.".to_string(),
+                difficulty.to_string(),
+                vec![QuizAnswerData {
+                    answer_text: "Yes".to_string(),
+                    is_correct: true,
+                }],
+                rules
+                    .into_iter()
+                    .map(|number| ids[number].clone())
+                    .collect(),
+            ))
+            .unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn test_sections_count_questions_in_their_subtree() {
+        let repo = seeded_repo();
+        let scope = QuizScope::current(&repo, "indoor").unwrap().unwrap();
+
+        let sections: Vec<(String, String, usize)> = scope
+            .sections(&repo, "en")
+            .unwrap()
+            .into_iter()
+            .map(|section| (section.number, section.title, section.question_count))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                ("2".to_string(), "Playing Field".to_string(), 0),
+                ("10".to_string(), "The Check".to_string(), 1),
+                ("17".to_string(), "Fouls".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_focused_session_only_draws_from_its_sections() {
+        let repo = seeded_repo();
+        let scope = QuizScope::current(&repo, "indoor").unwrap().unwrap();
+        let session = scope
+            .start_session(&repo, "en", QuizMode::Practice, None)
+            .unwrap();
+        let fouls = scope.sections(&repo, "en").unwrap().pop().unwrap();
+
+        let focused = scope
+            .clone()
+            .focus_session(&repo, &session.id, std::slice::from_ref(&fouls.rule_id))
+            .unwrap();
+        assert_eq!(focused.total_questions(&repo).unwrap(), 2);
+        let record = repo.get_quiz_session(&session.id).unwrap().unwrap();
+        assert_eq!(record.topic_rule_ids(), vec![fouls.rule_id.clone()]);
+
+        // The stored focus applies to later requests
+        let resumed = QuizScope::for_session(&repo, "indoor", &record)
+            .unwrap()
+            .unwrap();
+        for _ in 0..2 {
+            let next = resumed.next_question(&repo, &session.id).unwrap().unwrap();
+            assert!(
+                ["Contact before the catch?", "What is a foul?"]
+                    .contains(&next.question.question_text.as_str())
+            );
+            resumed
+                .submit_answer(
+                    &repo,
+                    &session.id,
+                    &next.question.id,
+                    &next.answers[0].id,
+                    None,
+                )
+                .unwrap()
+                .unwrap();
+        }
+        assert!(resumed.next_question(&repo, &session.id).unwrap().is_none());
+        assert_eq!(resumed.questions_seen(&repo, &session.id).unwrap(), 2);
+
+        // Unknown rule ids are dropped, leaving the whole rule set
+        let all = scope
+            .focus_session(&repo, &session.id, &["not-a-rule".to_string()])
+            .unwrap();
+        assert!(all.focus.is_none());
+        assert_eq!(all.total_questions(&repo).unwrap(), 4);
+        let record = repo.get_quiz_session(&session.id).unwrap().unwrap();
+        assert_eq!(record.topic_rule_ids, None);
+    }
+
+    #[test]
+    fn test_difficulty_modes_pick_new_questions_by_level() {
+        let repo = seeded_repo();
+        let scope = QuizScope::current(&repo, "indoor").unwrap().unwrap();
+        let session = scope
+            .start_session(&repo, "en", QuizMode::Practice, None)
+            .unwrap();
+
+        let advanced = scope
+            .clone()
+            .set_session_difficulty(
+                &repo,
+                &session.id,
+                DifficultyMode::Single(Difficulty::Advanced),
+            )
+            .unwrap();
+        assert_eq!(advanced.total_questions(&repo).unwrap(), 1);
+        let record = repo.get_quiz_session(&session.id).unwrap().unwrap();
+        assert_eq!(
+            record.difficulty_mode,
+            DifficultyMode::Single(Difficulty::Advanced)
+        );
+
+        // Progressive sessions answer both beginner questions before moving on,
+        // as two correct answers are not a full streak
+        let progressive = scope
+            .clone()
+            .set_session_difficulty(&repo, &session.id, DifficultyMode::Progressive)
+            .unwrap();
+        let mut levels = Vec::new();
+        while let Some(next) = progressive.next_question(&repo, &session.id).unwrap() {
+            levels.push(next.question.difficulty_level.clone());
+            progressive
+                .submit_answer(
+                    &repo,
+                    &session.id,
+                    &next.question.id,
+                    &next.answers[0].id,
+                    None,
+                )
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(
+            levels,
+            vec!["beginner", "beginner", "intermediate", "advanced"]
+        );
+
+        // A mix weighting every level 0 counts as all levels
+        let unweighted = scope
+            .set_session_difficulty(
+                &repo,
+                &session.id,
+                DifficultyMode::Mix {
+                    beginner: 0,
+                    intermediate: 0,
+                    advanced: 0,
+                },
+            )
+            .unwrap();
+        assert_eq!(unweighted.difficulty, DifficultyMode::All);
+    }
+}
@@ -1,15 +1,35 @@
-use axum::{extract::FromRequestParts, http::request::Parts};
-use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
-use tracing::debug;
+use axum::{
+    extract::{FromRef, FromRequestParts, Path, Request, State},
+    http::{HeaderMap, StatusCode, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::{Cookie, Key, PrivateCookieJar, SameSite};
+use sha2::{Digest, Sha512};
+use tracing::{debug, error};
+
+use crate::{
+    config::SecurityConfig,
+    learner::{self, LearnerCookies},
+    models::{NewQuizSessionRecord, QuizMode, QuizSessionRecord},
+    quiz_engine::QuizScope,
+    repository::RuleRepository,
+};
 
 const QUIZ_SESSION_COOKIE_NAME: &str = "quiz_session";
 
-/// QuizSession provides quiz session context to handlers
-/// Simple wrapper around session ID for consistent access
-#[derive(Debug)]
+/// The request's quiz session.
+///
+/// Resolved once per request by [`quiz_session_middleware`], which stores it in the
+/// request extensions; the extractor and the session cookie both use that one id.
+#[derive(Debug, Clone)]
 pub struct QuizSession {
     /// The unique session identifier
     pub session_id: String,
+    /// Rule set, version, language and mode the session was started with
+    pub record: QuizSessionRecord,
+    /// Not the session the cookie names, so the response has to set the cookie
+    pub is_new: bool,
 }
 
 impl QuizSession {
@@ -18,120 +38,660 @@ impl QuizSession {
         &self.session_id
     }
 
-    /// Create a new quiz session with generated UUID
-    pub fn new() -> Self {
+    /// Get the session's stored metadata
+    pub fn record(&self) -> &QuizSessionRecord {
+        &self.record
+    }
+
+    fn from_record(record: QuizSessionRecord, is_new: bool) -> Self {
         Self {
-            session_id: uuid::Uuid::now_v7().to_string(),
+            session_id: record.id.clone(),
+            record,
+            is_new,
         }
     }
+}
 
-    /// Create from existing session ID
-    fn from_session_id(session_id: String) -> Self {
-        Self { session_id }
-    }
+/// Reads and writes the `quiz_session` cookie.
+///
+/// The session id is encrypted and authenticated with a key derived from
+/// `security.jwt_secret`, so visitors can neither read nor pick a session id; a
+/// tampered cookie simply doesn't decrypt and starts a new session.
+#[derive(Clone)]
+pub struct QuizCookies {
+    key: Key,
+    secure: bool,
+    max_age: time::Duration,
 }
 
-impl Default for QuizSession {
-    fn default() -> Self {
-        Self::new()
+/// Session id named by the request's quiz cookie, for routes outside
+/// [`quiz_session_middleware`] that must not start a session
+#[derive(Debug, Clone)]
+pub struct QuizCookieSessionId(pub Option<String>);
+
+impl<S> FromRequestParts<S> for QuizCookieSessionId
+where
+    S: Send + Sync,
+    QuizCookies: FromRef<S>,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(
+            QuizCookies::from_ref(state).session_id(&parts.headers),
+        ))
     }
 }
 
-/// Create a simple session cookie (no encryption needed - just tracking)
-pub fn create_quiz_session_cookie(session_id: String) -> Cookie<'static> {
-    Cookie::build((QUIZ_SESSION_COOKIE_NAME, session_id))
-        .http_only(true)
-        .secure(false) // Allow HTTP for development
-        .same_site(SameSite::Lax)
-        .max_age(time::Duration::hours(24)) // 24 hour session
-        .path("/")
-        .build()
+/// Key for an encrypted cookie, derived from `jwt_secret` separately for each purpose
+/// and from the JWT signing key, which uses the secret as is
+pub(crate) fn cookie_key(security: &SecurityConfig, purpose: &str) -> Key {
+    let mut hasher = Sha512::new();
+    hasher.update(format!("regelator {purpose} cookie\0").as_bytes());
+    hasher.update(security.jwt_secret.as_bytes());
+    Key::from(hasher.finalize().as_slice())
 }
 
-/// Clear the quiz session cookie
-pub fn clear_quiz_session_cookie() -> Cookie<'static> {
-    Cookie::build((QUIZ_SESSION_COOKIE_NAME, ""))
-        .http_only(true)
-        .secure(false)
-        .same_site(SameSite::Lax)
-        .max_age(time::Duration::seconds(0))
-        .path("/")
-        .build()
+impl QuizCookies {
+    pub fn new(security: &SecurityConfig) -> Self {
+        Self {
+            key: cookie_key(security, "quiz session"),
+            secure: security.quiz_cookie.secure,
+            max_age: time::Duration::hours(security.quiz_cookie.max_age_hours.into()),
+        }
+    }
+
+    /// Session id from the request's cookie, if it decrypts
+    pub fn session_id(&self, headers: &HeaderMap) -> Option<String> {
+        PrivateCookieJar::from_headers(headers, self.key.clone())
+            .get(QUIZ_SESSION_COOKIE_NAME)
+            .map(|cookie| cookie.value().to_string())
+    }
+
+    /// Cookie jar setting the encrypted cookie for this session
+    pub fn set(&self, session_id: String) -> PrivateCookieJar {
+        let cookie = Cookie::build((QUIZ_SESSION_COOKIE_NAME, session_id))
+            .http_only(true)
+            .secure(self.secure)
+            .same_site(SameSite::Lax)
+            .max_age(self.max_age)
+            .path("/")
+            .build();
+        PrivateCookieJar::new(self.key.clone()).add(cookie)
+    }
+}
+
+/// End the session and start a fresh one in the same quiz, keeping its learner,
+/// sections and difficulty mode. An exam is replaced by a practice session.
+///
+/// Used when a visitor clears their data, so the old id is never accepted again.
+pub fn rotate_session(
+    repository: &RuleRepository,
+    session: &QuizSession,
+) -> color_eyre::Result<QuizSessionRecord> {
+    let old = session.record();
+    repository.end_quiz_session(&old.id)?;
+    let record = repository.create_quiz_session(
+        NewQuizSessionRecord::new(
+            old.rule_set_id.clone(),
+            old.version_id.clone(),
+            old.language.clone(),
+            match old.mode {
+                QuizMode::Exam => QuizMode::Practice,
+                mode => mode,
+            },
+        )
+        .for_learner(old.learner_id.clone())
+        .with_topics(old.topic_rule_ids.clone())
+        .with_difficulty_mode(old.difficulty_mode),
+    )?;
+    debug!("Rotated quiz session {} to {}", old.id, record.id);
+    Ok(record)
 }
 
 impl<S> FromRequestParts<S> for QuizSession
 where
     S: Send + Sync,
 {
-    type Rejection = (); // Never reject - always provide a session
+    type Rejection = (StatusCode, &'static str);
 
-    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        // Extract cookie jar
-        let jar = match CookieJar::from_request_parts(parts, state).await {
-            Ok(jar) => jar,
-            Err(_) => return Ok(QuizSession::new()), // Create new session if no jar
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<QuizSession>()
+            .cloned()
+            .ok_or_else(|| {
+                error!("QuizSession extracted on a route without quiz_session_middleware");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+            })
+    }
+}
+
+/// Resume the session named by the cookie, or start one in the quiz of this route.
+///
+/// A signed-in learner adopts the cookie's anonymous session and then continues their
+/// own session in this rule set. Sessions owned by a learner are never resumed from
+/// the cookie alone, so signing out leaves nothing readable behind. After an exam the
+/// cookie's anonymous visitor continues the practice session they started it from.
+/// An anonymous session is only resumed in its own rule set; another quiz starts a
+/// new one.
+fn resolve_session(
+    repository: &RuleRepository,
+    cookie_session_id: Option<&str>,
+    learner_id: Option<&str>,
+    language: &str,
+    rule_set_slug: &str,
+) -> color_eyre::Result<Option<QuizSession>> {
+    let mut cookie_session = match cookie_session_id {
+        Some(session_id) => repository
+            .get_quiz_session(session_id)?
+            .filter(|record| record.learner_id.is_none()),
+        None => None,
+    };
+    // A finished exam hands back to the practice session it was started from
+    let mut is_new = false;
+    if let Some(record) = &cookie_session
+        && record.ended_at.is_some()
+    {
+        let practice_session_id = match record.mode {
+            QuizMode::Exam => repository
+                .get_quiz_exam(&record.id)?
+                .and_then(|exam| exam.practice_session_id),
+            _ => None,
+        };
+        cookie_session = match practice_session_id {
+            Some(session_id) => repository
+                .get_quiz_session(&session_id)?
+                .filter(|record| record.ended_at.is_none() && record.learner_id.is_none()),
+            None => None,
         };
+        is_new = cookie_session.is_some();
+    }
 
-        // Get existing session or create new one
-        if let Some(cookie) = jar.get(QUIZ_SESSION_COOKIE_NAME) {
-            let session_id = cookie.value().to_string();
-            if !session_id.is_empty() && uuid::Uuid::parse_str(&session_id).is_ok() {
-                debug!("Found existing quiz session: {}", session_id);
-                Ok(QuizSession::from_session_id(session_id))
-            } else {
-                debug!("Invalid session ID, creating new session");
-                Ok(QuizSession::new()) // Invalid session ID, create new one
-            }
-        } else {
-            debug!("No session cookie found, creating new session");
-            Ok(QuizSession::new()) // No session cookie, create new one
+    let Some(scope) = QuizScope::current(repository, rule_set_slug)? else {
+        return Ok(None);
+    };
+
+    let Some(learner_id) = learner_id else {
+        if let Some(record) =
+            cookie_session.filter(|record| record.rule_set_id == scope.rule_set.id)
+        {
+            debug!("Found existing quiz session: {}", record.id);
+            return Ok(Some(QuizSession::from_record(record, is_new)));
         }
+        let record = scope.start_session(repository, language, QuizMode::Practice, None)?;
+        debug!("Started new quiz session: {}", record.id);
+        return Ok(Some(QuizSession::from_record(record, true)));
+    };
+
+    if let Some(record) = cookie_session {
+        learner::adopt_session(repository, learner_id, &record)?;
+    }
+    if let Some(record) = repository.get_open_learner_session(learner_id, &scope.rule_set.id)? {
+        debug!("Found learner quiz session: {}", record.id);
+        return Ok(Some(QuizSession::from_record(record, false)));
     }
+    let record = scope.start_session(repository, language, QuizMode::Practice, Some(learner_id))?;
+    debug!("Started new learner quiz session: {}", record.id);
+    Ok(Some(QuizSession::from_record(record, true)))
 }
 
-/// Simple middleware function compatible with `from_fn`
-/// Automatically sets session cookies for quiz routes if not present
-pub async fn quiz_session_middleware(
-    req: axum::extract::Request,
-    next: axum::middleware::Next,
-) -> axum::response::Response {
-    // Extract cookie jar from request
-    let (mut parts, body) = req.into_parts();
-    let jar = match CookieJar::from_request_parts(&mut parts, &()).await {
-        Ok(jar) => jar,
-        Err(_) => {
-            debug!("Could not extract cookie jar, proceeding without session");
-            let req = axum::extract::Request::from_parts(parts, body);
-            return next.run(req).await;
+/// Whether the handler already set or cleared the quiz session cookie itself
+fn sets_quiz_cookie(response: &Response) -> bool {
+    let prefix = format!("{QUIZ_SESSION_COOKIE_NAME}=");
+    response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .any(|value| value.as_bytes().starts_with(prefix.as_bytes()))
+}
+
+/// Middleware for the `/{language}/quiz/{rule_set_slug}` routes.
+///
+/// Resolves the [`QuizSession`] before the handler runs and sets the cookie for a
+/// session started by this request.
+pub async fn quiz_session_middleware<S>(
+    State(state): State<S>,
+    Path((language, rule_set_slug)): Path<(String, String)>,
+    mut req: Request,
+    next: Next,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    RuleRepository: FromRef<S>,
+    QuizCookies: FromRef<S>,
+    LearnerCookies: FromRef<S>,
+{
+    let repository = RuleRepository::from_ref(&state);
+    let cookies = QuizCookies::from_ref(&state);
+    let cookie_session_id = cookies.session_id(req.headers());
+    let learner_id = LearnerCookies::from_ref(&state).learner_id(req.headers());
+    let session = match resolve_session(
+        &repository,
+        cookie_session_id.as_deref(),
+        learner_id.as_deref(),
+        &language,
+        &rule_set_slug,
+    ) {
+        Ok(Some(session)) => session,
+        Ok(None) => return (StatusCode::NOT_FOUND, "Quiz not found").into_response(),
+        Err(e) => {
+            error!("Failed to resolve quiz session: {:?}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
         }
     };
+    req.extensions_mut().insert(session.clone());
+
+    let response = next.run(req).await;
+
+    if session.is_new && !sets_quiz_cookie(&response) {
+        debug!("Setting new quiz session cookie: {}", session.session_id);
+        return (cookies.set(session.session_id), response).into_response();
+    }
+
+    response
+}
 
-    // Check if quiz session cookie already exists and is valid
-    let needs_cookie = if let Some(cookie) = jar.get(QUIZ_SESSION_COOKIE_NAME) {
-        let session_id = cookie.value();
-        session_id.is_empty() || uuid::Uuid::parse_str(session_id).is_err()
-    } else {
-        true
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::QuizCookieConfig;
+    use crate::test_support::{self, repository_with_current_version};
+    use axum::{
+        Router,
+        body::Body,
+        routing::{get, post},
     };
+    use tower::ServiceExt;
 
-    // Reconstruct request and call next handler
-    let req = axum::extract::Request::from_parts(parts, body);
-    let mut response = next.run(req).await;
+    #[derive(Clone, FromRef)]
+    struct TestState {
+        repository: RuleRepository,
+        cookies: QuizCookies,
+        learner_cookies: LearnerCookies,
+    }
 
-    // Add session cookie to response if needed
-    if needs_cookie {
-        let new_session_id = uuid::Uuid::now_v7().to_string();
-        let cookie = create_quiz_session_cookie(new_session_id.clone());
+    fn quiz_security() -> SecurityConfig {
+        SecurityConfig {
+            session_duration_hours: 2,
+            jwt_secret: "test-secret-that-is-long-enough-for-validation".to_string(),
+            login_throttle: Default::default(),
+            quiz_cookie: Default::default(),
+            oidc: None,
+            certificate_key: None,
+        }
+    }
 
-        debug!("Setting new quiz session cookie: {}", new_session_id);
+    fn learner_cookies() -> LearnerCookies {
+        LearnerCookies::new(&quiz_security(), &Default::default())
+    }
+
+    /// Value a browser stores for the cookie this jar sets; `get` would decrypt it
+    fn encrypted_value(jar: PrivateCookieJar, name: &str) -> String {
+        let response = (jar, ()).into_response();
+        response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .map(|value| Cookie::parse(value.to_str().unwrap().to_string()).unwrap())
+            .find(|cookie| cookie.name() == name)
+            .unwrap()
+            .value()
+            .to_string()
+    }
 
-        // Convert cookie to header value and add to response
-        if let Ok(cookie_header) = cookie.to_string().parse::<axum::http::HeaderValue>() {
-            response
-                .headers_mut()
-                .append(axum::http::header::SET_COOKIE, cookie_header);
+    /// Encrypted learner cookie value signing in this learner
+    fn signed_in(learner_id: &str) -> String {
+        encrypted_value(
+            learner_cookies().sign_in(learner_id.to_string()),
+            "learner_session",
+        )
+    }
+
+    fn quiz_cookies(secure: bool, max_age_hours: u32) -> QuizCookies {
+        QuizCookies::new(&SecurityConfig {
+            quiz_cookie: QuizCookieConfig {
+                secure,
+                max_age_hours,
+            },
+            ..quiz_security()
+        })
+    }
+
+    /// Echoes the session id the handler sees; `/clear` also rotates the session
+    fn app(repository: RuleRepository, cookies: QuizCookies) -> Router {
+        let state = TestState {
+            repository,
+            cookies,
+            learner_cookies: learner_cookies(),
+        };
+        Router::new()
+            .route(
+                "/{language}/quiz/{rule_set_slug}",
+                get(|session: QuizSession| async move { session.session_id }),
+            )
+            .route(
+                "/{language}/quiz/{rule_set_slug}/clear",
+                post(
+                    |State(state): State<TestState>, session: QuizSession| async move {
+                        let record = rotate_session(&state.repository, &session).unwrap();
+                        (state.cookies.set(record.id), session.session_id)
+                    },
+                ),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                quiz_session_middleware::<TestState>,
+            ))
+            .with_state(state)
+    }
+
+    /// Session id seen by the handler and the raw `quiz_session` cookies set by the response
+    async fn visit(
+        app: &Router,
+        uri: &str,
+        cookie: Option<&str>,
+    ) -> (String, Vec<Cookie<'static>>) {
+        visit_as(app, uri, cookie, None).await
+    }
+
+    /// Like [`visit`], optionally signed in with an encrypted learner cookie value
+    async fn visit_as(
+        app: &Router,
+        uri: &str,
+        cookie: Option<&str>,
+        learner_cookie: Option<&str>,
+    ) -> (String, Vec<Cookie<'static>>) {
+        // `/clear` changes state, so like in the app it only takes POSTs
+        let method = if uri.ends_with("/clear") {
+            axum::http::Method::POST
+        } else {
+            axum::http::Method::GET
+        };
+        let mut request = axum::http::Request::builder().method(method).uri(uri);
+        let cookies: Vec<String> = [
+            cookie.map(|value| format!("{QUIZ_SESSION_COOKIE_NAME}={value}")),
+            learner_cookie.map(|value| format!("learner_session={value}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !cookies.is_empty() {
+            request = request.header(header::COOKIE, cookies.join("; "));
         }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let cookies = response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .map(|value| Cookie::parse(value.to_str().unwrap().to_string()).unwrap())
+            .filter(|cookie| cookie.name() == QUIZ_SESSION_COOKIE_NAME)
+            .collect();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (String::from_utf8(body.to_vec()).unwrap(), cookies)
     }
 
-    response
+    /// Session id a browser sending this cookie back would resume
+    fn decrypt(cookies: &QuizCookies, cookie: &Cookie) -> Option<String> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            format!("{QUIZ_SESSION_COOKIE_NAME}={}", cookie.value())
+                .parse()
+                .unwrap(),
+        );
+        cookies.session_id(&headers)
+    }
+
+    #[tokio::test]
+    async fn test_cookie_session_is_not_resumed_in_another_rule_set() {
+        let (repo, indoor, _) = repository_with_current_version();
+        let (beach, _) = test_support::rule_set_with_current_version(&repo, "Beach", "beach");
+        let cookies = quiz_cookies(true, 24);
+        let app = app(repo.clone(), cookies.clone());
+
+        let (indoor_session, set) = visit(&app, "/en/quiz/indoor", None).await;
+        let indoor_cookie = set[0].value().to_string();
+
+        // The same cookie on another quiz starts a session in that rule set
+        let (beach_session, set) = visit(&app, "/en/quiz/beach", Some(&indoor_cookie)).await;
+        assert_ne!(beach_session, indoor_session);
+        assert_eq!(set.len(), 1);
+        assert_eq!(decrypt(&cookies, &set[0]), Some(beach_session.clone()));
+        let record = repo.get_quiz_session(&beach_session).unwrap().unwrap();
+        assert_eq!(record.rule_set_id, beach.id);
+
+        // The first session is left as it was
+        let record = repo.get_quiz_session(&indoor_session).unwrap().unwrap();
+        assert_eq!(record.rule_set_id, indoor.id);
+        assert!(record.ended_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_new_session_cookie_matches_handler_session() {
+        let (repo, ..) = repository_with_current_version();
+        let cookies = quiz_cookies(true, 24);
+        let app = app(repo.clone(), cookies.clone());
+
+        let (session_id, set) = visit(&app, "/de/quiz/indoor", None).await;
+        assert_eq!(set.len(), 1);
+        assert_ne!(set[0].value(), session_id, "the id must not be readable");
+        assert_eq!(decrypt(&cookies, &set[0]), Some(session_id.clone()));
+
+        let record = repo.get_quiz_session(&session_id).unwrap().unwrap();
+        assert_eq!(record.language, "de");
+        assert_eq!(record.mode, QuizMode::Practice);
+        assert!(record.ended_at.is_none());
+
+        // The cookie resumes the same session without setting it again
+        let (resumed, set_again) = visit(&app, "/de/quiz/indoor", Some(set[0].value())).await;
+        assert_eq!(resumed, session_id);
+        assert!(set_again.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forged_or_tampered_cookies_start_a_new_session() {
+        let (repo, ..) = repository_with_current_version();
+        let app = app(repo.clone(), quiz_cookies(true, 24));
+        let (session_id, set) = visit(&app, "/en/quiz/indoor", None).await;
+
+        // A bare id of someone else's session is not accepted
+        let (other, _) = visit(&app, "/en/quiz/indoor", Some(&session_id)).await;
+        assert_ne!(other, session_id);
+
+        let mut tampered = set[0].value().to_string();
+        let last = tampered.pop().unwrap();
+        tampered.push(if last == 'A' { 'B' } else { 'A' });
+        let (other, _) = visit(&app, "/en/quiz/indoor", Some(&tampered)).await;
+        assert_ne!(other, session_id);
+
+        // Cookies encrypted under a different secret don't decrypt either
+        let foreign = QuizCookies::new(&SecurityConfig {
+            jwt_secret: "another-secret-that-is-long-enough-for-validation".to_string(),
+            ..quiz_security()
+        })
+        .set(session_id.clone());
+        let foreign = encrypted_value(foreign, QUIZ_SESSION_COOKIE_NAME);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            format!("{QUIZ_SESSION_COOKIE_NAME}={foreign}")
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(quiz_cookies(true, 24).session_id(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn test_ended_sessions_are_replaced() {
+        let (repo, ..) = repository_with_current_version();
+        let cookies = quiz_cookies(true, 24);
+        let app = app(repo.clone(), cookies.clone());
+        let (session_id, set) = visit(&app, "/en/quiz/indoor", None).await;
+
+        repo.end_quiz_session(&session_id).unwrap();
+        let (replacement, set_again) = visit(&app, "/en/quiz/indoor", Some(set[0].value())).await;
+        assert_ne!(replacement, session_id);
+        assert_eq!(decrypt(&cookies, &set_again[0]), Some(replacement));
+    }
+
+    #[tokio::test]
+    async fn test_finished_exam_returns_to_practice_session() {
+        let (repo, ..) = repository_with_current_version();
+        let cookies = quiz_cookies(true, 24);
+        let app = app(repo.clone(), cookies.clone());
+        let (practice_id, _) = visit(&app, "/en/quiz/indoor", None).await;
+        let practice = repo.get_quiz_session(&practice_id).unwrap().unwrap();
+
+        let exam_session = repo
+            .create_quiz_session(NewQuizSessionRecord::new(
+                practice.rule_set_id.clone(),
+                practice.version_id.clone(),
+                "en".to_string(),
+                QuizMode::Exam,
+            ))
+            .unwrap();
+        let now = chrono::Utc::now().naive_utc();
+        repo.create_quiz_exam(
+            &crate::models::QuizExam {
+                session_id: exam_session.id.clone(),
+                practice_session_id: Some(practice_id.clone()),
+                question_count: 1,
+                pass_percent: 80,
+                started_at: now,
+                ends_at: now + chrono::Duration::minutes(30),
+                finished_at: None,
+                correct_count: None,
+                passed: None,
+            },
+            &[],
+        )
+        .unwrap();
+        let exam_cookie = encrypted_value(cookies.set(exam_session.id.clone()), "quiz_session");
+
+        let (during, set) = visit(&app, "/en/quiz/indoor", Some(&exam_cookie)).await;
+        assert_eq!(during, exam_session.id);
+        assert!(set.is_empty());
+
+        repo.finish_quiz_exam(&exam_session.id, now, 1, true)
+            .unwrap();
+        let (after, set) = visit(&app, "/en/quiz/indoor", Some(&exam_cookie)).await;
+        assert_eq!(after, practice_id);
+        assert_eq!(decrypt(&cookies, &set[0]), Some(practice_id));
+    }
+
+    #[tokio::test]
+    async fn test_clear_rotates_the_session() {
+        let (repo, ..) = repository_with_current_version();
+        let cookies = quiz_cookies(true, 24);
+        let app = app(repo.clone(), cookies.clone());
+        let (session_id, set) = visit(&app, "/fr/quiz/indoor", None).await;
+
+        // The handler's cookie wins over the middleware's
+        let (cleared, set_again) = visit(&app, "/fr/quiz/indoor/clear", Some(set[0].value())).await;
+        assert_eq!(cleared, session_id);
+        assert_eq!(set_again.len(), 1);
+        let rotated = decrypt(&cookies, &set_again[0]).unwrap();
+        assert_ne!(rotated, session_id);
+
+        assert!(
+            repo.get_quiz_session(&session_id)
+                .unwrap()
+                .unwrap()
+                .ended_at
+                .is_some()
+        );
+        let record = repo.get_quiz_session(&rotated).unwrap().unwrap();
+        assert!(record.ended_at.is_none());
+        assert_eq!(record.language, "fr");
+
+        // The old cookie no longer resumes anything
+        let (after, _) = visit(&app, "/fr/quiz/indoor", Some(set[0].value())).await;
+        assert_ne!(after, session_id);
+    }
+
+    #[tokio::test]
+    async fn test_cookie_attributes_follow_config() {
+        let app = app(repository_with_current_version().0, quiz_cookies(false, 1));
+        let (_, set) = visit(&app, "/en/quiz/indoor", None).await;
+        assert_ne!(set[0].secure(), Some(true));
+        assert_eq!(set[0].max_age(), Some(time::Duration::hours(1)));
+        assert_eq!(set[0].http_only(), Some(true));
+
+        let app = app_with_defaults();
+        let (_, set) = visit(&app, "/en/quiz/indoor", None).await;
+        assert_eq!(set[0].secure(), Some(true));
+        assert_eq!(set[0].max_age(), Some(time::Duration::hours(24)));
+    }
+
+    fn app_with_defaults() -> Router {
+        app(
+            repository_with_current_version().0,
+            QuizCookies::new(&quiz_security()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_unknown_rule_set_is_not_found() {
+        let response = app_with_defaults()
+            .oneshot(
+                axum::http::Request::get("/en/quiz/missing")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_learner_adopts_cookie_session_and_resumes_it_anywhere() {
+        let (repo, ..) = repository_with_current_version();
+        let cookies = quiz_cookies(true, 24);
+        let app = app(repo.clone(), cookies.clone());
+        let learner = repo
+            .create_learner(crate::models::NewLearner::new(
+                "player@example.org".to_string(),
+            ))
+            .unwrap();
+        let learner_cookie = signed_in(&learner.id);
+
+        // Progress made anonymously on the phone becomes the learner's on sign-in
+        let (phone, set) = visit(&app, "/en/quiz/indoor", None).await;
+        let (resumed, _) = visit_as(
+            &app,
+            "/en/quiz/indoor",
+            Some(set[0].value()),
+            Some(&learner_cookie),
+        )
+        .await;
+        assert_eq!(resumed, phone);
+        let record = repo.get_quiz_session(&phone).unwrap().unwrap();
+        assert_eq!(record.learner_id.as_deref(), Some(learner.id.as_str()));
+
+        // Another device without a quiz cookie continues the same session
+        let (laptop, _) = visit_as(&app, "/en/quiz/indoor", None, Some(&learner_cookie)).await;
+        assert_eq!(laptop, phone);
+
+        // Signed out, the old cookie no longer reaches the learner's session
+        let (anonymous, _) = visit(&app, "/en/quiz/indoor", Some(set[0].value())).await;
+        assert_ne!(anonymous, phone);
+
+        // Clearing keeps the fresh session with the learner
+        let (_, set_again) =
+            visit_as(&app, "/en/quiz/indoor/clear", None, Some(&learner_cookie)).await;
+        let rotated = decrypt(&cookies, &set_again[0]).unwrap();
+        let record = repo.get_quiz_session(&rotated).unwrap().unwrap();
+        assert_eq!(record.learner_id.as_deref(), Some(learner.id.as_str()));
+        let (after, _) = visit_as(&app, "/en/quiz/indoor", None, Some(&learner_cookie)).await;
+        assert_eq!(after, rotated);
+    }
 }
@@ -0,0 +1,138 @@
+//! Bearer tokens for quiz sessions started through the JSON API.
+//!
+//! Starting a session through the API issues a random token, sent back in the
+//! `X-Quiz-Session` header. Only its hash is stored, like admin API tokens, so a
+//! session id seen anywhere else (in a cookie, a learner account or an exam) grants
+//! no access to the API. Tokens only work for the rule set they were issued for.
+
+use chrono::NaiveDateTime;
+use color_eyre::Result;
+use rand::Rng;
+use tracing::debug;
+
+use crate::{
+    api_token::hash_api_token,
+    models::{QuizApiToken, QuizMode, QuizSessionRecord},
+    repository::RuleRepository,
+};
+
+/// Marks a string as a quiz session token, e.g. for secret scanners
+pub const TOKEN_PREFIX: &str = "rglq_";
+
+/// Generate a new token: the prefix followed by 64 hex characters
+pub fn generate_quiz_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("{TOKEN_PREFIX}{hex}")
+}
+
+/// Issue the token of a session started through the API
+pub fn issue_quiz_token(
+    repository: &RuleRepository,
+    session: &QuizSessionRecord,
+    now: NaiveDateTime,
+) -> Result<String> {
+    let token = generate_quiz_token();
+    repository.create_quiz_api_token(&QuizApiToken {
+        token_hash: hash_api_token(&token),
+        session_id: session.id.clone(),
+        created_at: now,
+    })?;
+    debug!("Issued API token for quiz session {}", session.id);
+    Ok(token)
+}
+
+/// The session a token was issued for, or `None` if the token is unknown, or its
+/// session has ended, belongs to a learner, is for another rule set or is an exam.
+///
+/// Exams are only taken in the browser, where answers get no feedback until the end
+/// and the time limit is enforced.
+pub fn resolve_quiz_token(
+    repository: &RuleRepository,
+    token: &str,
+    rule_set_id: &str,
+) -> Result<Option<QuizSessionRecord>> {
+    Ok(repository
+        .get_quiz_api_token_session(&hash_api_token(token))?
+        .filter(|session| {
+            session.ended_at.is_none()
+                && session.learner_id.is_none()
+                && session.mode != QuizMode::Exam
+                && session.rule_set_id == rule_set_id
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NewLearner;
+    use crate::test_support::{self, repository_with_current_version};
+
+    fn now() -> NaiveDateTime {
+        chrono::Utc::now().naive_utc()
+    }
+
+    #[test]
+    fn test_only_issued_tokens_resolve() {
+        let (repo, indoor, version) = repository_with_current_version();
+        let session = |mode| test_support::quiz_session(&repo, &indoor, &version, mode);
+        let api = session(QuizMode::Practice);
+        let token = issue_quiz_token(&repo, &api, now()).unwrap();
+        assert!(token.starts_with(TOKEN_PREFIX));
+
+        let resolved = resolve_quiz_token(&repo, &token, &indoor.id).unwrap();
+        assert_eq!(resolved.map(|session| session.id), Some(api.id.clone()));
+
+        // Session ids are not tokens: neither the API session's own id nor that of a
+        // session named by a quiz cookie or of an exam
+        let cookie = session(QuizMode::Practice);
+        let exam = session(QuizMode::Exam);
+        for id in [&api.id, &cookie.id, &exam.id] {
+            assert!(resolve_quiz_token(&repo, id, &indoor.id).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_tokens_of_ended_learner_exam_and_other_rule_set_sessions_are_rejected() {
+        let (repo, indoor, version) = repository_with_current_version();
+        let (beach, _) = test_support::rule_set_with_current_version(&repo, "Beach", "beach");
+        let session = |mode| test_support::quiz_session(&repo, &indoor, &version, mode);
+
+        let api = session(QuizMode::Practice);
+        let token = issue_quiz_token(&repo, &api, now()).unwrap();
+        assert!(
+            resolve_quiz_token(&repo, &token, &beach.id)
+                .unwrap()
+                .is_none()
+        );
+
+        let learner = repo
+            .create_learner(NewLearner::new("alex@example.org".to_string()))
+            .unwrap();
+        let owned = session(QuizMode::Practice);
+        let owned_token = issue_quiz_token(&repo, &owned, now()).unwrap();
+        repo.set_quiz_session_learner(&owned.id, &learner.id)
+            .unwrap();
+        assert!(
+            resolve_quiz_token(&repo, &owned_token, &indoor.id)
+                .unwrap()
+                .is_none()
+        );
+
+        // Even with a token, an exam can't be answered through the API
+        let exam = session(QuizMode::Exam);
+        let exam_token = issue_quiz_token(&repo, &exam, now()).unwrap();
+        assert!(
+            resolve_quiz_token(&repo, &exam_token, &indoor.id)
+                .unwrap()
+                .is_none()
+        );
+
+        repo.end_quiz_session(&api.id).unwrap();
+        assert!(
+            resolve_quiz_token(&repo, &token, &indoor.id)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -215,6 +215,95 @@ impl RuleRepository {
         Ok(result)
     }
 
+    /// Get rule content in exactly the given language, without English fallback
+    pub fn find_rule_content_for_language(
+        &self,
+        rule_id_param: &str,
+        language_param: &str,
+    ) -> Result<Option<RuleContent>> {
+        use crate::schema::rule_content::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = rule_content
+            .filter(rule_id.eq(rule_id_param))
+            .filter(language.eq(language_param))
+            .select(RuleContent::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load rule content")?;
+
+        Ok(result)
+    }
+
+    /// Get all versions of a rule set, newest first
+    pub fn get_versions_for_rule_set(&self, rule_set_id_param: &str) -> Result<Vec<Version>> {
+        use crate::schema::versions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = versions
+            .filter(rule_set_id.eq(rule_set_id_param))
+            .select(Version::as_select())
+            .order(effective_from.desc())
+            .load(&mut conn)
+            .wrap_err("Failed to load versions")?;
+
+        Ok(results)
+    }
+
+    /// Get rule by number and version
+    pub fn get_rule_by_number(
+        &self,
+        rule_number: &str,
+        version_id_param: &str,
+    ) -> Result<Option<Rule>> {
+        use crate::schema::rules::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = rules
+            .filter(number.eq(rule_number))
+            .filter(version_id.eq(version_id_param))
+            .select(Rule::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load rule by number")?;
+
+        Ok(result)
+    }
+
+    /// Get the languages that have rule content in a version, sorted by code
+    pub fn get_languages_for_version(&self, version_id_param: &str) -> Result<Vec<String>> {
+        use crate::schema::rule_content::dsl as content_dsl;
+        use crate::schema::rules::dsl as rules_dsl;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = content_dsl::rule_content
+            .inner_join(rules_dsl::rules.on(rules_dsl::id.eq(content_dsl::rule_id)))
+            .filter(rules_dsl::version_id.eq(version_id_param))
+            .select(content_dsl::language)
+            .distinct()
+            .order(content_dsl::language.asc())
+            .load(&mut conn)
+            .wrap_err("Failed to load languages for version")?;
+
+        Ok(results)
+    }
+
     /// Get child rules for a parent rule
     pub fn get_child_rules(&self, parent_id: &str) -> Result<Vec<Rule>> {
         use crate::schema::rules::dsl::*;
@@ -437,6 +526,56 @@ impl RuleRepository {
         Ok(result)
     }
 
+    /// Find a glossary term by slug, regardless of which languages it has content in
+    pub fn find_glossary_term(
+        &self,
+        rule_set_id_param: &str,
+        version_id_param: &str,
+        slug_param: &str,
+    ) -> Result<Option<GlossaryTerm>> {
+        use crate::schema::glossary_terms::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = glossary_terms
+            .filter(rule_set_id.eq(rule_set_id_param))
+            .filter(version_id.eq(version_id_param))
+            .filter(slug.eq(slug_param))
+            .select(GlossaryTerm::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to find glossary term")?;
+
+        Ok(result)
+    }
+
+    /// Get glossary content for a term in exactly the given language
+    pub fn find_glossary_content_for_language(
+        &self,
+        term_id_param: &str,
+        language_param: &str,
+    ) -> Result<Option<GlossaryContent>> {
+        use crate::schema::glossary_content::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = glossary_content
+            .filter(term_id.eq(term_id_param))
+            .filter(language.eq(language_param))
+            .select(GlossaryContent::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load glossary content")?;
+
+        Ok(result)
+    }
+
     // Quiz repository methods
 
     /// Create a complete quiz question with answers and rule links in a transaction
@@ -489,7 +628,8 @@ impl RuleRepository {
         Ok(result)
     }
 
-    /// Get quiz questions for a rule set and version
+    /// Get the active quiz questions for a rule set and version; drafts and archived
+    /// questions are never asked
     pub fn get_quiz_questions(
         &self,
         rule_set_id_param: &str,
@@ -505,6 +645,7 @@ impl RuleRepository {
         let results = quiz_questions
             .filter(rule_set_id.eq(rule_set_id_param))
             .filter(version_id.eq(version_id_param))
+            .filter(status.eq(QuestionStatus::Active))
             .select(QuizQuestion::as_select())
             .load(&mut conn)
             .wrap_err("Failed to load quiz questions")?;
@@ -512,7 +653,7 @@ impl RuleRepository {
         Ok(results)
     }
 
-    /// Get quiz questions that haven't been attempted in the current session
+    /// Get active quiz questions that haven't been attempted in the current session
     pub fn get_unattempted_questions_for_session(
         &self,
         session_id_param: &str,
@@ -535,251 +676,2137 @@ impl RuleRepository {
             .load(&mut conn)
             .wrap_err("Failed to load attempted question IDs")?;
 
-        // Get questions not in the attempted list
-        let results = qq_dsl::quiz_questions
-            .filter(qq_dsl::rule_set_id.eq(rule_set_id_param))
-            .filter(qq_dsl::version_id.eq(version_id_param))
-            .filter(qq_dsl::id.ne_all(attempted_question_ids))
-            .select(QuizQuestion::as_select())
-            .load(&mut conn)
-            .wrap_err("Failed to load unattempted quiz questions")?;
+        // Get questions not in the attempted list
+        let results = qq_dsl::quiz_questions
+            .filter(qq_dsl::rule_set_id.eq(rule_set_id_param))
+            .filter(qq_dsl::version_id.eq(version_id_param))
+            .filter(qq_dsl::status.eq(QuestionStatus::Active))
+            .filter(qq_dsl::id.ne_all(attempted_question_ids))
+            .select(QuizQuestion::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load unattempted quiz questions")?;
+
+        Ok(results)
+    }
+
+    /// Get a specific quiz question by ID
+    pub fn get_quiz_question_by_id(&self, question_id_param: &str) -> Result<Option<QuizQuestion>> {
+        use crate::schema::quiz_questions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = quiz_questions
+            .filter(id.eq(question_id_param))
+            .select(QuizQuestion::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load quiz question")?;
+
+        Ok(result)
+    }
+
+    /// Get quiz answers for a question
+    pub fn get_quiz_answers(&self, question_id_param: &str) -> Result<Vec<QuizAnswer>> {
+        use crate::schema::quiz_answers::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = quiz_answers
+            .filter(question_id.eq(question_id_param))
+            .order(sort_order.asc())
+            .select(QuizAnswer::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load quiz answers")?;
+
+        Ok(results)
+    }
+
+    /// Get a single quiz answer by ID
+    pub fn get_quiz_answer_by_id(&self, answer_id_param: &str) -> Result<Option<QuizAnswer>> {
+        use crate::schema::quiz_answers::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = quiz_answers
+            .filter(id.eq(answer_id_param))
+            .select(QuizAnswer::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load quiz answer")?;
+
+        Ok(result)
+    }
+
+    /// Record a quiz attempt
+    pub fn create_quiz_attempt(&self, attempt: &NewQuizAttempt) -> Result<QuizAttempt> {
+        use crate::schema::quiz_attempts::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::insert_into(quiz_attempts)
+            .values(attempt)
+            .execute(&mut conn)
+            .wrap_err("Failed to create quiz attempt")?;
+
+        let result = quiz_attempts
+            .filter(id.eq(&attempt.id))
+            .select(QuizAttempt::as_select())
+            .first(&mut conn)
+            .wrap_err("Failed to load created quiz attempt")?;
+
+        Ok(result)
+    }
+
+    /// Get quiz attempts for a session
+    pub fn get_session_attempts(&self, session_id_param: &str) -> Result<Vec<QuizAttempt>> {
+        use crate::schema::quiz_attempts::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = quiz_attempts
+            .filter(session_id.eq(session_id_param))
+            .order(created_at.desc())
+            .select(QuizAttempt::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load session attempts")?;
+
+        Ok(results)
+    }
+
+    /// Get session statistics summary
+    pub fn get_session_statistics(&self, session_id_param: &str) -> Result<SessionStatistics> {
+        use crate::schema::quiz_attempts::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let attempts = quiz_attempts
+            .filter(session_id.eq(session_id_param))
+            .order(created_at.asc())
+            .select(QuizAttempt::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load session attempts")?;
+
+        let total_questions = attempts.len();
+        let questions_seen = attempts
+            .iter()
+            .map(|a| a.question_id.as_str())
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+        let correct_answers = attempts
+            .iter()
+            .filter(|a| a.is_correct == Some(true))
+            .count();
+        let accuracy_percentage = if total_questions > 0 {
+            (correct_answers as f32 / total_questions as f32 * 100.0).round() as u32
+        } else {
+            0
+        };
+
+        // Calculate current streak (consecutive correct answers from most recent)
+        let mut current_streak = 0;
+        for attempt in attempts.iter().rev() {
+            if attempt.is_correct == Some(true) {
+                current_streak += 1;
+            } else {
+                break;
+            }
+        }
+
+        Ok(SessionStatistics {
+            total_questions,
+            questions_seen,
+            correct_answers,
+            accuracy_percentage,
+            current_streak,
+        })
+    }
+
+    /// Get questions that were answered incorrectly in this session
+    pub fn get_session_missed_questions(
+        &self,
+        session_id_param: &str,
+    ) -> Result<Vec<(QuizQuestion, QuizAttempt)>> {
+        use crate::schema::quiz_attempts::dsl as qa_dsl;
+        use crate::schema::quiz_questions::dsl as qq_dsl;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = qq_dsl::quiz_questions
+            .inner_join(qa_dsl::quiz_attempts.on(qa_dsl::question_id.eq(qq_dsl::id)))
+            .filter(qa_dsl::session_id.eq(session_id_param))
+            .filter(qa_dsl::is_correct.eq(Some(false)))
+            .select((QuizQuestion::as_select(), QuizAttempt::as_select()))
+            .load(&mut conn)
+            .wrap_err("Failed to load missed questions")?;
+
+        Ok(results)
+    }
+
+    /// Clear all attempts for a session (for privacy)
+    pub fn clear_session_attempts(&self, session_id_param: &str) -> Result<()> {
+        use crate::schema::quiz_attempts::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::delete(quiz_attempts.filter(session_id.eq(session_id_param)))
+            .execute(&mut conn)
+            .wrap_err("Failed to clear session attempts")?;
+
+        Ok(())
+    }
+
+    /// Create a quiz session record
+    pub fn create_quiz_session(
+        &self,
+        new_session: NewQuizSessionRecord,
+    ) -> Result<QuizSessionRecord> {
+        use crate::schema::quiz_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::insert_into(quiz_sessions)
+            .values(&new_session)
+            .execute(&mut conn)
+            .wrap_err("Failed to create quiz session")?;
+
+        let created = quiz_sessions
+            .filter(id.eq(&new_session.id))
+            .select(QuizSessionRecord::as_select())
+            .first(&mut conn)
+            .wrap_err("Failed to retrieve created quiz session")?;
+
+        Ok(created)
+    }
+
+    /// Get a quiz session record by ID
+    pub fn get_quiz_session(&self, session_id_param: &str) -> Result<Option<QuizSessionRecord>> {
+        use crate::schema::quiz_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = quiz_sessions
+            .filter(id.eq(session_id_param))
+            .select(QuizSessionRecord::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load quiz session")?;
+
+        Ok(result)
+    }
+
+    /// Mark a quiz session as ended; ended sessions are not resumed
+    pub fn end_quiz_session(&self, session_id_param: &str) -> Result<()> {
+        use crate::schema::quiz_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::update(
+            quiz_sessions
+                .filter(id.eq(session_id_param))
+                .filter(ended_at.is_null()),
+        )
+        .set(ended_at.eq(chrono::Utc::now().naive_utc()))
+        .execute(&mut conn)
+        .wrap_err("Failed to end quiz session")?;
+
+        Ok(())
+    }
+
+    /// Spaced-repetition state of a question for a session, if it was answered before
+    pub fn get_quiz_review(
+        &self,
+        session_id_param: &str,
+        question_id_param: &str,
+    ) -> Result<Option<QuizReview>> {
+        use crate::schema::quiz_reviews::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = quiz_reviews
+            .filter(session_id.eq(session_id_param))
+            .filter(question_id.eq(question_id_param))
+            .select(QuizReview::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load quiz review")?;
+
+        Ok(result)
+    }
+
+    /// Insert or replace the spaced-repetition state of a question for a session
+    pub fn save_quiz_review(&self, review: &QuizReview) -> Result<()> {
+        use crate::schema::quiz_reviews::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::replace_into(quiz_reviews)
+            .values(review)
+            .execute(&mut conn)
+            .wrap_err("Failed to save quiz review")?;
+
+        Ok(())
+    }
+
+    /// Questions of a quiz whose review is due before `due_before`, most overdue first
+    pub fn get_due_review_questions(
+        &self,
+        session_id_param: &str,
+        rule_set_id_param: &str,
+        version_id_param: &str,
+        due_before: chrono::NaiveDateTime,
+    ) -> Result<Vec<QuizQuestion>> {
+        use crate::schema::quiz_questions::dsl as qq_dsl;
+        use crate::schema::quiz_reviews::dsl as qr_dsl;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = qr_dsl::quiz_reviews
+            .inner_join(qq_dsl::quiz_questions)
+            .filter(qr_dsl::session_id.eq(session_id_param))
+            .filter(qr_dsl::due_at.le(due_before))
+            .filter(qq_dsl::rule_set_id.eq(rule_set_id_param))
+            .filter(qq_dsl::version_id.eq(version_id_param))
+            .order(qr_dsl::due_at.asc())
+            .select(QuizQuestion::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load due review questions")?;
+
+        Ok(results)
+    }
+
+    /// When the session's next review in this quiz falls due, if any are scheduled
+    pub fn get_next_review_due(
+        &self,
+        session_id_param: &str,
+        rule_set_id_param: &str,
+        version_id_param: &str,
+    ) -> Result<Option<chrono::NaiveDateTime>> {
+        use crate::schema::quiz_questions::dsl as qq_dsl;
+        use crate::schema::quiz_reviews::dsl as qr_dsl;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = qr_dsl::quiz_reviews
+            .inner_join(qq_dsl::quiz_questions)
+            .filter(qr_dsl::session_id.eq(session_id_param))
+            .filter(qq_dsl::rule_set_id.eq(rule_set_id_param))
+            .filter(qq_dsl::version_id.eq(version_id_param))
+            .select(diesel::dsl::min(qr_dsl::due_at))
+            .first(&mut conn)
+            .wrap_err("Failed to load next review due date")?;
+
+        Ok(result)
+    }
+
+    /// All review states of a session
+    pub fn get_session_reviews(&self, session_id_param: &str) -> Result<Vec<QuizReview>> {
+        use crate::schema::quiz_reviews::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = quiz_reviews
+            .filter(session_id.eq(session_id_param))
+            .select(QuizReview::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load session reviews")?;
+
+        Ok(results)
+    }
+
+    /// `(question_id, rule_id)` pairs linking a quiz's questions to the rules they cover,
+    /// in the order they were linked
+    pub fn get_question_rule_links(
+        &self,
+        rule_set_id_param: &str,
+        version_id_param: &str,
+    ) -> Result<Vec<(String, String)>> {
+        use crate::schema::quiz_question_rules::dsl as qqr_dsl;
+        use crate::schema::quiz_questions::dsl as qq_dsl;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = qqr_dsl::quiz_question_rules
+            .inner_join(qq_dsl::quiz_questions)
+            .filter(qq_dsl::rule_set_id.eq(rule_set_id_param))
+            .filter(qq_dsl::version_id.eq(version_id_param))
+            .order(qqr_dsl::id.asc())
+            .select((qqr_dsl::question_id, qqr_dsl::rule_id))
+            .load(&mut conn)
+            .wrap_err("Failed to load question rule links")?;
+
+        Ok(results)
+    }
+
+    /// Focus a session on rule subtrees, or on the whole rule set with `None`
+    pub fn set_quiz_session_topics(
+        &self,
+        session_id_param: &str,
+        topic_rule_ids_param: Option<String>,
+    ) -> Result<()> {
+        use crate::schema::quiz_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::update(quiz_sessions.filter(id.eq(session_id_param)))
+            .set(topic_rule_ids.eq(topic_rule_ids_param))
+            .execute(&mut conn)
+            .wrap_err("Failed to set quiz session topics")?;
+
+        Ok(())
+    }
+
+    /// Set how a session picks question difficulties
+    pub fn set_quiz_session_difficulty_mode(
+        &self,
+        session_id_param: &str,
+        difficulty_mode_param: DifficultyMode,
+    ) -> Result<()> {
+        use crate::schema::quiz_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::update(quiz_sessions.filter(id.eq(session_id_param)))
+            .set(difficulty_mode.eq(difficulty_mode_param))
+            .execute(&mut conn)
+            .wrap_err("Failed to set quiz session difficulty mode")?;
+
+        Ok(())
+    }
+
+    /// Difficulty level and correctness of a session's answers, oldest first
+    pub fn get_session_answer_difficulties(
+        &self,
+        session_id_param: &str,
+    ) -> Result<Vec<(String, Option<bool>)>> {
+        use crate::schema::quiz_attempts::dsl as qa_dsl;
+        use crate::schema::quiz_questions::dsl as qq_dsl;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = qa_dsl::quiz_attempts
+            .inner_join(qq_dsl::quiz_questions)
+            .filter(qa_dsl::session_id.eq(session_id_param))
+            .order((qa_dsl::created_at.asc(), qa_dsl::id.asc()))
+            .select((qq_dsl::difficulty_level, qa_dsl::is_correct))
+            .load(&mut conn)
+            .wrap_err("Failed to load session answer difficulties")?;
+
+        Ok(results)
+    }
+
+    /// The learner's most recently started open session in a rule set
+    pub fn get_open_learner_session(
+        &self,
+        learner_id_param: &str,
+        rule_set_id_param: &str,
+    ) -> Result<Option<QuizSessionRecord>> {
+        use crate::schema::quiz_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = quiz_sessions
+            .filter(learner_id.eq(learner_id_param))
+            .filter(rule_set_id.eq(rule_set_id_param))
+            .filter(ended_at.is_null())
+            .order(started_at.desc())
+            .select(QuizSessionRecord::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load open learner session")?;
+
+        Ok(result)
+    }
+
+    /// All of a learner's quiz sessions, newest first
+    pub fn get_learner_sessions(&self, learner_id_param: &str) -> Result<Vec<QuizSessionRecord>> {
+        use crate::schema::quiz_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = quiz_sessions
+            .filter(learner_id.eq(learner_id_param))
+            .order(started_at.desc())
+            .select(QuizSessionRecord::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load learner sessions")?;
+
+        Ok(results)
+    }
+
+    /// Make an anonymous session belong to a learner
+    pub fn set_quiz_session_learner(
+        &self,
+        session_id_param: &str,
+        learner_id_param: &str,
+    ) -> Result<QuizSessionRecord> {
+        use crate::schema::quiz_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::update(quiz_sessions.filter(id.eq(session_id_param)))
+            .set(learner_id.eq(learner_id_param))
+            .execute(&mut conn)
+            .wrap_err("Failed to set quiz session learner")?;
+
+        let updated = quiz_sessions
+            .filter(id.eq(session_id_param))
+            .select(QuizSessionRecord::as_select())
+            .first(&mut conn)
+            .wrap_err("Failed to load updated quiz session")?;
+
+        Ok(updated)
+    }
+
+    /// Move one session's attempts and reviews into another and delete it.
+    ///
+    /// Where both sessions reviewed the same question, the more recent review wins.
+    pub fn merge_quiz_sessions(&self, from_session_id: &str, into_session_id: &str) -> Result<()> {
+        use crate::schema::{
+            quiz_api_tokens, quiz_attempts, quiz_reviews, quiz_sessions, quiz_transfer_codes,
+        };
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::update(
+                quiz_attempts::table.filter(quiz_attempts::session_id.eq(from_session_id)),
+            )
+            .set(quiz_attempts::session_id.eq(into_session_id))
+            .execute(conn)?;
+
+            let moved: Vec<QuizReview> = quiz_reviews::table
+                .filter(quiz_reviews::session_id.eq(from_session_id))
+                .select(QuizReview::as_select())
+                .load(conn)?;
+            for review in moved {
+                let existing: Option<QuizReview> = quiz_reviews::table
+                    .filter(quiz_reviews::session_id.eq(into_session_id))
+                    .filter(quiz_reviews::question_id.eq(&review.question_id))
+                    .select(QuizReview::as_select())
+                    .first(conn)
+                    .optional()?;
+                if existing
+                    .is_none_or(|existing| existing.last_reviewed_at < review.last_reviewed_at)
+                {
+                    diesel::replace_into(quiz_reviews::table)
+                        .values(&QuizReview {
+                            session_id: into_session_id.to_string(),
+                            ..review
+                        })
+                        .execute(conn)?;
+                }
+            }
+            diesel::delete(
+                quiz_reviews::table.filter(quiz_reviews::session_id.eq(from_session_id)),
+            )
+            .execute(conn)?;
+
+            diesel::delete(
+                quiz_transfer_codes::table
+                    .filter(quiz_transfer_codes::session_id.eq(from_session_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                quiz_api_tokens::table.filter(quiz_api_tokens::session_id.eq(from_session_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(quiz_sessions::table.filter(quiz_sessions::id.eq(from_session_id)))
+                .execute(conn)?;
+
+            Ok(())
+        })
+        .wrap_err("Failed to merge quiz sessions")?;
+
+        Ok(())
+    }
+
+    /// Create a learner account
+    pub fn create_learner(&self, new_learner: NewLearner) -> Result<Learner> {
+        use crate::schema::learners::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::insert_into(learners)
+            .values(&new_learner)
+            .execute(&mut conn)
+            .wrap_err("Failed to create learner")?;
+
+        let created = learners
+            .filter(id.eq(&new_learner.id))
+            .select(Learner::as_select())
+            .first(&mut conn)
+            .wrap_err("Failed to retrieve created learner")?;
+
+        Ok(created)
+    }
+
+    /// Get a learner account by ID
+    pub fn get_learner(&self, learner_id: &str) -> Result<Option<Learner>> {
+        use crate::schema::learners::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = learners
+            .filter(id.eq(learner_id))
+            .select(Learner::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load learner")?;
+
+        Ok(result)
+    }
+
+    /// Get a learner account by its (normalized) email address
+    pub fn get_learner_by_email(&self, email_param: &str) -> Result<Option<Learner>> {
+        use crate::schema::learners::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = learners
+            .filter(email.eq(email_param))
+            .select(Learner::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load learner by email")?;
+
+        Ok(result)
+    }
+
+    /// Record a successful sign-in
+    pub fn touch_learner_login(&self, learner_id: &str, now: chrono::NaiveDateTime) -> Result<()> {
+        use crate::schema::learners::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::update(learners.filter(id.eq(learner_id)))
+            .set(last_login_at.eq(now))
+            .execute(&mut conn)
+            .wrap_err("Failed to update learner login time")?;
+
+        Ok(())
+    }
+
+    /// Delete a learner with all their quiz sessions, attempts, reviews, exams, certificates
+    /// and sign-in links
+    pub fn delete_learner(&self, learner_id_param: &str) -> Result<()> {
+        use crate::schema::{
+            learner_login_links, learners, quiz_api_tokens, quiz_attempts, quiz_certificates,
+            quiz_exam_questions, quiz_exams, quiz_reviews, quiz_sessions, quiz_transfer_codes,
+        };
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let session_ids: Vec<String> = quiz_sessions::table
+                .filter(quiz_sessions::learner_id.eq(learner_id_param))
+                .select(quiz_sessions::id)
+                .load(conn)?;
+            diesel::delete(
+                quiz_attempts::table.filter(quiz_attempts::session_id.eq_any(&session_ids)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                quiz_reviews::table.filter(quiz_reviews::session_id.eq_any(&session_ids)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                quiz_transfer_codes::table
+                    .filter(quiz_transfer_codes::session_id.eq_any(&session_ids)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                quiz_api_tokens::table.filter(quiz_api_tokens::session_id.eq_any(&session_ids)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                quiz_certificates::table
+                    .filter(quiz_certificates::exam_session_id.eq_any(&session_ids)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                quiz_exam_questions::table
+                    .filter(quiz_exam_questions::session_id.eq_any(&session_ids)),
+            )
+            .execute(conn)?;
+            diesel::delete(quiz_exams::table.filter(quiz_exams::session_id.eq_any(&session_ids)))
+                .execute(conn)?;
+            diesel::delete(quiz_sessions::table.filter(quiz_sessions::id.eq_any(&session_ids)))
+                .execute(conn)?;
+
+            let email: Option<String> = learners::table
+                .filter(learners::id.eq(learner_id_param))
+                .select(learners::email)
+                .first(conn)
+                .optional()?;
+            if let Some(email) = email {
+                diesel::delete(
+                    learner_login_links::table.filter(learner_login_links::email.eq(email)),
+                )
+                .execute(conn)?;
+            }
+            diesel::delete(learners::table.filter(learners::id.eq(learner_id_param)))
+                .execute(conn)?;
+
+            Ok(())
+        })
+        .wrap_err("Failed to delete learner")?;
+
+        Ok(())
+    }
+
+    /// Store an emailed sign-in link
+    pub fn create_learner_login_link(&self, link: NewLearnerLoginLink) -> Result<()> {
+        use crate::schema::learner_login_links::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::insert_into(learner_login_links)
+            .values(&link)
+            .execute(&mut conn)
+            .wrap_err("Failed to create learner login link")?;
+
+        Ok(())
+    }
+
+    /// Number of sign-in links sent to an address since `since`
+    pub fn count_learner_login_links_since(
+        &self,
+        email_param: &str,
+        since: chrono::NaiveDateTime,
+    ) -> Result<i64> {
+        use crate::schema::learner_login_links::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let count = learner_login_links
+            .filter(email.eq(email_param))
+            .filter(created_at.gt(since))
+            .count()
+            .get_result(&mut conn)
+            .wrap_err("Failed to count learner login links")?;
+
+        Ok(count)
+    }
+
+    /// Mark an unused, unexpired sign-in link as used and return it.
+    ///
+    /// Returns `None` if there is no such link, so each link signs in at most once.
+    pub fn use_learner_login_link(
+        &self,
+        token_hash_param: &str,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Option<LearnerLoginLink>> {
+        use crate::schema::learner_login_links::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let updated = diesel::update(
+            learner_login_links
+                .filter(token_hash.eq(token_hash_param))
+                .filter(used_at.is_null())
+                .filter(expires_at.gt(now)),
+        )
+        .set(used_at.eq(now))
+        .execute(&mut conn)
+        .wrap_err("Failed to use learner login link")?;
+        if updated == 0 {
+            return Ok(None);
+        }
+
+        let link = learner_login_links
+            .filter(token_hash.eq(token_hash_param))
+            .select(LearnerLoginLink::as_select())
+            .first(&mut conn)
+            .wrap_err("Failed to load learner login link")?;
+
+        Ok(Some(link))
+    }
+
+    /// Store the hashed API token of a quiz session
+    pub fn create_quiz_api_token(&self, token: &QuizApiToken) -> Result<()> {
+        use crate::schema::quiz_api_tokens;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::insert_into(quiz_api_tokens::table)
+            .values(token)
+            .execute(&mut conn)
+            .wrap_err("Failed to create quiz API token")?;
+
+        Ok(())
+    }
+
+    /// The quiz session an API token was issued for
+    pub fn get_quiz_api_token_session(
+        &self,
+        token_hash_param: &str,
+    ) -> Result<Option<QuizSessionRecord>> {
+        use crate::schema::{quiz_api_tokens, quiz_sessions};
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = quiz_api_tokens::table
+            .inner_join(quiz_sessions::table)
+            .filter(quiz_api_tokens::token_hash.eq(token_hash_param))
+            .select(QuizSessionRecord::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load quiz API token")?;
+
+        Ok(result)
+    }
+
+    /// Store a transfer code for a session, replacing the session's unused codes
+    pub fn create_quiz_transfer_code(&self, code: QuizTransferCode) -> Result<()> {
+        use crate::schema::quiz_transfer_codes::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(
+                quiz_transfer_codes
+                    .filter(session_id.eq(&code.session_id))
+                    .filter(used_at.is_null()),
+            )
+            .execute(conn)?;
+            diesel::insert_into(quiz_transfer_codes)
+                .values(&code)
+                .execute(conn)?;
+            Ok(())
+        })
+        .wrap_err("Failed to create quiz transfer code")?;
+
+        Ok(())
+    }
+
+    /// Mark an unused, unexpired transfer code as used and return it.
+    ///
+    /// Returns `None` if there is no such code, so each code is redeemed at most once.
+    pub fn use_quiz_transfer_code(
+        &self,
+        code_hash_param: &str,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Option<QuizTransferCode>> {
+        use crate::schema::quiz_transfer_codes::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let updated = diesel::update(
+            quiz_transfer_codes
+                .filter(code_hash.eq(code_hash_param))
+                .filter(used_at.is_null())
+                .filter(expires_at.gt(now)),
+        )
+        .set(used_at.eq(now))
+        .execute(&mut conn)
+        .wrap_err("Failed to use quiz transfer code")?;
+        if updated == 0 {
+            return Ok(None);
+        }
+
+        let code = quiz_transfer_codes
+            .filter(code_hash.eq(code_hash_param))
+            .select(QuizTransferCode::as_select())
+            .first(&mut conn)
+            .wrap_err("Failed to load quiz transfer code")?;
+
+        Ok(Some(code))
+    }
+
+    /// Store a new exam with its question draw
+    pub fn create_quiz_exam(&self, exam: &QuizExam, questions: &[QuizExamQuestion]) -> Result<()> {
+        use crate::schema::{quiz_exam_questions, quiz_exams};
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::insert_into(quiz_exams::table)
+                .values(exam)
+                .execute(conn)?;
+            diesel::insert_into(quiz_exam_questions::table)
+                .values(questions)
+                .execute(conn)?;
+            Ok(())
+        })
+        .wrap_err("Failed to create quiz exam")?;
+
+        Ok(())
+    }
+
+    /// The exam run in a quiz session, if it is an exam session
+    pub fn get_quiz_exam(&self, session_id_param: &str) -> Result<Option<QuizExam>> {
+        use crate::schema::quiz_exams::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = quiz_exams
+            .filter(session_id.eq(session_id_param))
+            .select(QuizExam::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load quiz exam")?;
+
+        Ok(result)
+    }
+
+    /// An exam's questions in the order they are served
+    pub fn get_quiz_exam_questions(&self, session_id_param: &str) -> Result<Vec<QuizExamQuestion>> {
+        use crate::schema::quiz_exam_questions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = quiz_exam_questions
+            .filter(session_id.eq(session_id_param))
+            .order(position.asc())
+            .select(QuizExamQuestion::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load quiz exam questions")?;
+
+        Ok(results)
+    }
+
+    /// Record an exam's result and end its session; returns `false` if it was
+    /// already finished
+    pub fn finish_quiz_exam(
+        &self,
+        session_id_param: &str,
+        finished_at_param: chrono::NaiveDateTime,
+        correct_count_param: i32,
+        passed_param: bool,
+    ) -> Result<bool> {
+        use crate::schema::{quiz_exams, quiz_sessions};
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let finished = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                let updated = diesel::update(
+                    quiz_exams::table
+                        .filter(quiz_exams::session_id.eq(session_id_param))
+                        .filter(quiz_exams::finished_at.is_null()),
+                )
+                .set((
+                    quiz_exams::finished_at.eq(finished_at_param),
+                    quiz_exams::correct_count.eq(correct_count_param),
+                    quiz_exams::passed.eq(passed_param),
+                ))
+                .execute(conn)?;
+                diesel::update(
+                    quiz_sessions::table
+                        .filter(quiz_sessions::id.eq(session_id_param))
+                        .filter(quiz_sessions::ended_at.is_null()),
+                )
+                .set(quiz_sessions::ended_at.eq(finished_at_param))
+                .execute(conn)?;
+                Ok(updated > 0)
+            })
+            .wrap_err("Failed to finish quiz exam")?;
+
+        Ok(finished)
+    }
+
+    /// Store a newly issued certificate
+    pub fn create_quiz_certificate(&self, certificate: &QuizCertificate) -> Result<()> {
+        use crate::schema::quiz_certificates;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::insert_into(quiz_certificates::table)
+            .values(certificate)
+            .execute(&mut conn)
+            .wrap_err("Failed to create quiz certificate")?;
+
+        Ok(())
+    }
+
+    pub fn get_quiz_certificate(&self, certificate_id: &str) -> Result<Option<QuizCertificate>> {
+        use crate::schema::quiz_certificates::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = quiz_certificates
+            .filter(id.eq(certificate_id))
+            .select(QuizCertificate::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load quiz certificate")?;
+
+        Ok(result)
+    }
+
+    /// The certificate issued for an exam, if any
+    pub fn get_exam_certificate(&self, session_id_param: &str) -> Result<Option<QuizCertificate>> {
+        use crate::schema::quiz_certificates::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = quiz_certificates
+            .filter(exam_session_id.eq(session_id_param))
+            .select(QuizCertificate::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load exam certificate")?;
+
+        Ok(result)
+    }
+
+    /// Certificates, newest first, optionally only those whose holder name or id
+    /// contains `search`
+    pub fn get_quiz_certificates(&self, search: Option<&str>) -> Result<Vec<QuizCertificate>> {
+        use crate::schema::quiz_certificates::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let mut query = quiz_certificates.into_boxed();
+        if let Some(search) = search {
+            let search_pattern = format!("%{search}%");
+            query = query.filter(
+                holder_name
+                    .like(search_pattern.clone())
+                    .or(id.like(search_pattern)),
+            );
+        }
+
+        let results = query
+            .order(issued_at.desc())
+            .select(QuizCertificate::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load quiz certificates")?;
+
+        Ok(results)
+    }
+
+    /// Revoke a certificate; returns it as it was before, or `None` if it doesn't
+    /// exist or is already revoked
+    pub fn revoke_quiz_certificate(
+        &self,
+        certificate_id: &str,
+        revoked_at_param: chrono::NaiveDateTime,
+        reason: Option<&str>,
+    ) -> Result<Option<QuizCertificate>> {
+        use crate::schema::quiz_certificates::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let certificate = quiz_certificates
+            .filter(id.eq(certificate_id))
+            .filter(revoked_at.is_null())
+            .select(QuizCertificate::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load quiz certificate")?;
+
+        if certificate.is_some() {
+            diesel::update(quiz_certificates.filter(id.eq(certificate_id)))
+                .set((
+                    revoked_at.eq(revoked_at_param),
+                    revocation_reason.eq(reason),
+                ))
+                .execute(&mut conn)
+                .wrap_err("Failed to revoke quiz certificate")?;
+        }
+
+        Ok(certificate)
+    }
+
+    // Admin authentication methods
+
+    /// Find admin by username
+    pub fn find_admin_by_username(&self, username_param: &str) -> Result<Option<Admin>> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = admins
+            .filter(username.eq(username_param))
+            .filter(is_active.eq(true))
+            .select(Admin::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to find admin by username")?;
+
+        Ok(result)
+    }
+
+    /// Update admin last login timestamp
+    pub fn update_admin_last_login(&self, admin_id: &str) -> Result<()> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::update(admins.filter(id.eq(admin_id)))
+            .set(last_login.eq(chrono::Utc::now().naive_utc()))
+            .execute(&mut conn)
+            .wrap_err("Failed to update admin last login")?;
+
+        Ok(())
+    }
+
+    /// Update admin password hash (requires current password hash for verification)
+    pub fn update_admin_password(
+        &self,
+        admin_id: &str,
+        current_password_hash: &str,
+        new_password_hash: &str,
+    ) -> Result<()> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        // Update only if the current password hash matches (prevents unauthorized changes)
+        let rows_affected = diesel::update(
+            admins.filter(id.eq(admin_id).and(password_hash.eq(current_password_hash))),
+        )
+        .set(password_hash.eq(new_password_hash))
+        .execute(&mut conn)
+        .wrap_err("Failed to update admin password")?;
+
+        if rows_affected == 0 {
+            return Err(color_eyre::eyre::eyre!(
+                "Password update failed - current password incorrect or admin not found"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Create a new admin account
+    pub fn create_admin(&self, new_admin: NewAdmin) -> Result<Admin> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::insert_into(admins)
+            .values(&new_admin)
+            .execute(&mut conn)
+            .wrap_err("Failed to insert admin")?;
+
+        let result = admins
+            .filter(id.eq(&new_admin.id))
+            .select(Admin::as_select())
+            .first(&mut conn)
+            .wrap_err("Failed to load created admin")?;
+
+        Ok(result)
+    }
+
+    /// Get all admin accounts, including deactivated ones
+    pub fn get_admins(&self) -> Result<Vec<Admin>> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = admins
+            .select(Admin::as_select())
+            .order(username.asc())
+            .load(&mut conn)
+            .wrap_err("Failed to load admins")?;
+
+        Ok(results)
+    }
+
+    /// Get an admin by ID regardless of active status
+    pub fn get_admin_by_id(&self, admin_id: &str) -> Result<Option<Admin>> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = admins
+            .filter(id.eq(admin_id))
+            .select(Admin::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load admin")?;
+
+        Ok(result)
+    }
+
+    /// Get an admin by username regardless of active status
+    pub fn get_admin_by_username(&self, username_param: &str) -> Result<Option<Admin>> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = admins
+            .filter(username.eq(username_param))
+            .select(Admin::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load admin by username")?;
+
+        Ok(result)
+    }
+
+    /// Get an active admin by email, compared case-insensitively
+    pub fn find_admin_by_email(&self, email_param: &str) -> Result<Option<Admin>> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = admins
+            .filter(email.eq(email_param.trim().to_lowercase()))
+            .filter(is_active.eq(true))
+            .select(Admin::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load admin by email")?;
+
+        Ok(result)
+    }
+
+    /// Get an active admin by identity provider subject
+    pub fn find_admin_by_oidc_subject(&self, subject: &str) -> Result<Option<Admin>> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = admins
+            .filter(oidc_subject.eq(subject))
+            .filter(is_active.eq(true))
+            .select(Admin::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load admin by subject")?;
+
+        Ok(result)
+    }
+
+    /// Set the identity provider email and subject an admin can log in with
+    pub fn set_admin_identity(
+        &self,
+        admin_id: &str,
+        new_email: Option<&str>,
+        new_subject: Option<&str>,
+    ) -> Result<()> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let rows_affected = diesel::update(admins.filter(id.eq(admin_id)))
+            .set((
+                email.eq(new_email.map(|value| value.trim().to_lowercase())),
+                oidc_subject.eq(new_subject),
+            ))
+            .execute(&mut conn)
+            .wrap_err("Failed to update admin identity")?;
+
+        if rows_affected == 0 {
+            return Err(color_eyre::eyre::eyre!("Admin not found: {}", admin_id));
+        }
+
+        Ok(())
+    }
+
+    /// Activate or deactivate an admin account; inactive admins cannot log in
+    pub fn set_admin_active(&self, admin_id: &str, active: bool) -> Result<()> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let rows_affected = diesel::update(admins.filter(id.eq(admin_id)))
+            .set(is_active.eq(active))
+            .execute(&mut conn)
+            .wrap_err("Failed to update admin status")?;
+
+        if rows_affected == 0 {
+            return Err(color_eyre::eyre::eyre!("Admin not found: {}", admin_id));
+        }
+
+        Ok(())
+    }
+
+    /// Change the role of an admin account
+    pub fn set_admin_role(&self, admin_id: &str, new_role: AdminRole) -> Result<()> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let rows_affected = diesel::update(admins.filter(id.eq(admin_id)))
+            .set(role.eq(new_role))
+            .execute(&mut conn)
+            .wrap_err("Failed to update admin role")?;
+
+        if rows_affected == 0 {
+            return Err(color_eyre::eyre::eyre!("Admin not found: {}", admin_id));
+        }
+
+        Ok(())
+    }
+
+    /// Replace an admin's password hash without knowing the current password
+    pub fn reset_admin_password(&self, admin_id: &str, new_password_hash: &str) -> Result<()> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let rows_affected = diesel::update(admins.filter(id.eq(admin_id)))
+            .set(password_hash.eq(new_password_hash))
+            .execute(&mut conn)
+            .wrap_err("Failed to reset admin password")?;
+
+        if rows_affected == 0 {
+            return Err(color_eyre::eyre::eyre!("Admin not found: {}", admin_id));
+        }
+
+        Ok(())
+    }
+
+    // Admin Session Methods
+
+    /// Store a new admin session and drop sessions that have expired
+    pub fn create_admin_session(&self, new_session: NewAdminSession) -> Result<AdminSession> {
+        use crate::schema::admin_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::delete(admin_sessions.filter(expires_at.lt(chrono::Utc::now().naive_utc())))
+            .execute(&mut conn)
+            .wrap_err("Failed to delete expired admin sessions")?;
+
+        diesel::insert_into(admin_sessions)
+            .values(&new_session)
+            .execute(&mut conn)
+            .wrap_err("Failed to create admin session")?;
+
+        let result = admin_sessions
+            .filter(id.eq(&new_session.id))
+            .select(AdminSession::as_select())
+            .first(&mut conn)
+            .wrap_err("Failed to load created admin session")?;
+
+        Ok(result)
+    }
+
+    /// Get a session that is neither revoked nor expired, with its admin if still active
+    pub fn get_valid_admin_session(
+        &self,
+        session_id: &str,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Option<(AdminSession, Admin)>> {
+        use crate::schema::admin_sessions::dsl::*;
+        use crate::schema::admins;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = admin_sessions
+            .inner_join(admins::table)
+            .filter(id.eq(session_id))
+            .filter(revoked_at.is_null())
+            .filter(expires_at.gt(now))
+            .filter(admins::is_active.eq(true))
+            .select((AdminSession::as_select(), Admin::as_select()))
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load admin session")?;
+
+        Ok(result)
+    }
+
+    /// Record activity on a session, at most once a minute to limit writes
+    pub fn touch_admin_session(&self, session_id: &str, now: chrono::NaiveDateTime) -> Result<()> {
+        use crate::schema::admin_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::update(
+            admin_sessions
+                .filter(id.eq(session_id))
+                .filter(last_seen_at.lt(now - chrono::Duration::minutes(1))),
+        )
+        .set(last_seen_at.eq(now))
+        .execute(&mut conn)
+        .wrap_err("Failed to update admin session")?;
+
+        Ok(())
+    }
+
+    /// Sessions of an admin that are neither revoked nor expired, most recently used first
+    pub fn get_active_admin_sessions(
+        &self,
+        admin_id_param: &str,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<AdminSession>> {
+        use crate::schema::admin_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = admin_sessions
+            .filter(admin_id.eq(admin_id_param))
+            .filter(revoked_at.is_null())
+            .filter(expires_at.gt(now))
+            .order(last_seen_at.desc())
+            .select(AdminSession::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load admin sessions")?;
+
+        Ok(results)
+    }
+
+    /// Revoke one session of an admin; returns whether it was active
+    pub fn revoke_admin_session(&self, admin_id_param: &str, session_id: &str) -> Result<bool> {
+        use crate::schema::admin_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let rows_affected = diesel::update(
+            admin_sessions
+                .filter(id.eq(session_id))
+                .filter(admin_id.eq(admin_id_param))
+                .filter(revoked_at.is_null()),
+        )
+        .set(revoked_at.eq(chrono::Utc::now().naive_utc()))
+        .execute(&mut conn)
+        .wrap_err("Failed to revoke admin session")?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Revoke every session of an admin, optionally keeping one; returns how many were revoked
+    pub fn revoke_admin_sessions(
+        &self,
+        admin_id_param: &str,
+        keep_session_id: Option<&str>,
+    ) -> Result<usize> {
+        use crate::schema::admin_sessions::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let rows_affected = diesel::update(
+            admin_sessions
+                .filter(admin_id.eq(admin_id_param))
+                .filter(revoked_at.is_null())
+                .filter(id.ne(keep_session_id.unwrap_or_default())),
+        )
+        .set(revoked_at.eq(chrono::Utc::now().naive_utc()))
+        .execute(&mut conn)
+        .wrap_err("Failed to revoke admin sessions")?;
+
+        Ok(rows_affected)
+    }
+
+    // API Token Methods
+
+    /// Store a new API token
+    pub fn create_api_token(&self, new_token: NewAdminApiToken) -> Result<AdminApiToken> {
+        use crate::schema::admin_api_tokens::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::insert_into(admin_api_tokens)
+            .values(&new_token)
+            .execute(&mut conn)
+            .wrap_err("Failed to create API token")?;
+
+        let result = admin_api_tokens
+            .filter(id.eq(&new_token.id))
+            .select(AdminApiToken::as_select())
+            .first(&mut conn)
+            .wrap_err("Failed to load created API token")?;
+
+        Ok(result)
+    }
+
+    /// Get an unrevoked token by hash, with its admin if still active
+    pub fn get_valid_api_token(&self, hash: &str) -> Result<Option<(AdminApiToken, Admin)>> {
+        use crate::schema::admin_api_tokens::dsl::*;
+        use crate::schema::admins;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let result = admin_api_tokens
+            .inner_join(admins::table)
+            .filter(token_hash.eq(hash))
+            .filter(revoked_at.is_null())
+            .filter(admins::is_active.eq(true))
+            .select((AdminApiToken::as_select(), Admin::as_select()))
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load API token")?;
+
+        Ok(result)
+    }
+
+    /// Record that a token was used, at most once a minute to limit writes
+    pub fn touch_api_token(&self, token_id: &str, now: chrono::NaiveDateTime) -> Result<()> {
+        use crate::schema::admin_api_tokens::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let stale = now - chrono::Duration::minutes(1);
+        diesel::update(
+            admin_api_tokens
+                .filter(id.eq(token_id))
+                .filter(last_used_at.is_null().or(last_used_at.lt(stale))),
+        )
+        .set(last_used_at.eq(now))
+        .execute(&mut conn)
+        .wrap_err("Failed to update API token")?;
+
+        Ok(())
+    }
+
+    /// Unrevoked tokens of an admin, newest first
+    pub fn get_admin_api_tokens(&self, admin_id_param: &str) -> Result<Vec<AdminApiToken>> {
+        use crate::schema::admin_api_tokens::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = admin_api_tokens
+            .filter(admin_id.eq(admin_id_param))
+            .filter(revoked_at.is_null())
+            .order(created_at.desc())
+            .select(AdminApiToken::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load API tokens")?;
+
+        Ok(results)
+    }
+
+    /// Revoke one of an admin's tokens; returns the token if it was active
+    pub fn revoke_api_token(
+        &self,
+        admin_id_param: &str,
+        token_id: &str,
+    ) -> Result<Option<AdminApiToken>> {
+        use crate::schema::admin_api_tokens::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let token = admin_api_tokens
+            .filter(id.eq(token_id))
+            .filter(admin_id.eq(admin_id_param))
+            .filter(revoked_at.is_null())
+            .select(AdminApiToken::as_select())
+            .first(&mut conn)
+            .optional()
+            .wrap_err("Failed to load API token")?;
+
+        if token.is_some() {
+            diesel::update(admin_api_tokens.filter(id.eq(token_id)))
+                .set(revoked_at.eq(chrono::Utc::now().naive_utc()))
+                .execute(&mut conn)
+                .wrap_err("Failed to revoke API token")?;
+        }
+
+        Ok(token)
+    }
+
+    // Two-Factor Methods
+
+    /// Store a TOTP secret that is not yet confirmed (or clear it with `None`)
+    pub fn set_admin_totp_secret(&self, admin_id: &str, secret: Option<&str>) -> Result<()> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::update(admins.filter(id.eq(admin_id)))
+            .set((
+                totp_secret.eq(secret),
+                totp_enabled_at.eq(None::<chrono::NaiveDateTime>),
+                totp_last_step.eq(None::<i64>),
+            ))
+            .execute(&mut conn)
+            .wrap_err("Failed to store TOTP secret")?;
+
+        Ok(())
+    }
+
+    /// Confirm the stored TOTP secret and remember the step it was confirmed with
+    pub fn enable_admin_totp(&self, admin_id: &str, step: i64) -> Result<()> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        diesel::update(admins.filter(id.eq(admin_id)))
+            .set((
+                totp_enabled_at.eq(chrono::Utc::now().naive_utc()),
+                totp_last_step.eq(step),
+            ))
+            .execute(&mut conn)
+            .wrap_err("Failed to enable TOTP")?;
+
+        Ok(())
+    }
+
+    /// Remember the last accepted TOTP step so codes cannot be replayed.
+    /// Returns false if a newer or equal step was already used.
+    pub fn record_admin_totp_step(&self, admin_id: &str, step: i64) -> Result<bool> {
+        use crate::schema::admins::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let rows_affected = diesel::update(
+            admins
+                .filter(id.eq(admin_id))
+                .filter(totp_last_step.is_null().or(totp_last_step.lt(step))),
+        )
+        .set(totp_last_step.eq(step))
+        .execute(&mut conn)
+        .wrap_err("Failed to record TOTP step")?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Remove an admin's second factor and recovery codes
+    pub fn disable_admin_two_factor(&self, admin_id_param: &str) -> Result<()> {
+        use crate::schema::{admin_recovery_codes, admins};
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::update(admins::table.filter(admins::id.eq(admin_id_param)))
+                .set((
+                    admins::totp_secret.eq(None::<String>),
+                    admins::totp_enabled_at.eq(None::<chrono::NaiveDateTime>),
+                    admins::totp_last_step.eq(None::<i64>),
+                ))
+                .execute(conn)?;
+            diesel::delete(
+                admin_recovery_codes::table
+                    .filter(admin_recovery_codes::admin_id.eq(admin_id_param)),
+            )
+            .execute(conn)?;
+            Ok(())
+        })
+        .wrap_err("Failed to disable two-factor authentication")?;
+
+        Ok(())
+    }
+
+    /// Replace all recovery codes of an admin with new hashed codes
+    pub fn replace_recovery_codes(
+        &self,
+        admin_id_param: &str,
+        code_hashes: &[String],
+    ) -> Result<()> {
+        use crate::schema::admin_recovery_codes::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let new_codes: Vec<NewAdminRecoveryCode> = code_hashes
+            .iter()
+            .map(|hash| NewAdminRecoveryCode::new(admin_id_param.to_string(), hash.clone()))
+            .collect();
 
-        Ok(results)
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(admin_recovery_codes.filter(admin_id.eq(admin_id_param)))
+                .execute(conn)?;
+            diesel::insert_into(admin_recovery_codes)
+                .values(&new_codes)
+                .execute(conn)?;
+            Ok(())
+        })
+        .wrap_err("Failed to replace recovery codes")?;
+
+        Ok(())
     }
 
-    /// Get a specific quiz question by ID
-    pub fn get_quiz_question_by_id(&self, question_id_param: &str) -> Result<Option<QuizQuestion>> {
-        use crate::schema::quiz_questions::dsl::*;
+    /// Mark an unused recovery code as used; returns whether one matched
+    pub fn use_recovery_code(&self, admin_id_param: &str, code_hash_param: &str) -> Result<bool> {
+        use crate::schema::admin_recovery_codes::dsl::*;
 
         let mut conn = self
             .pool
             .get()
             .wrap_err("Failed to get database connection")?;
 
-        let result = quiz_questions
-            .filter(id.eq(question_id_param))
-            .select(QuizQuestion::as_select())
-            .first(&mut conn)
-            .optional()
-            .wrap_err("Failed to load quiz question")?;
+        let rows_affected = diesel::update(
+            admin_recovery_codes
+                .filter(admin_id.eq(admin_id_param))
+                .filter(code_hash.eq(code_hash_param))
+                .filter(used_at.is_null()),
+        )
+        .set(used_at.eq(chrono::Utc::now().naive_utc()))
+        .execute(&mut conn)
+        .wrap_err("Failed to use recovery code")?;
 
-        Ok(result)
+        Ok(rows_affected > 0)
     }
 
-    /// Get quiz answers for a question
-    pub fn get_quiz_answers(&self, question_id_param: &str) -> Result<Vec<QuizAnswer>> {
-        use crate::schema::quiz_answers::dsl::*;
+    /// Count recovery codes an admin has not used yet
+    pub fn count_unused_recovery_codes(&self, admin_id_param: &str) -> Result<i64> {
+        use crate::schema::admin_recovery_codes::dsl::*;
 
         let mut conn = self
             .pool
             .get()
             .wrap_err("Failed to get database connection")?;
 
-        let results = quiz_answers
-            .filter(question_id.eq(question_id_param))
-            .order(sort_order.asc())
-            .select(QuizAnswer::as_select())
-            .load(&mut conn)
-            .wrap_err("Failed to load quiz answers")?;
+        let count = admin_recovery_codes
+            .filter(admin_id.eq(admin_id_param))
+            .filter(used_at.is_null())
+            .count()
+            .get_result(&mut conn)
+            .wrap_err("Failed to count recovery codes")?;
 
-        Ok(results)
+        Ok(count)
     }
 
-    /// Record a quiz attempt
-    pub fn create_quiz_attempt(&self, attempt: &NewQuizAttempt) -> Result<QuizAttempt> {
-        use crate::schema::quiz_attempts::dsl::*;
+    // Settings Methods
+
+    /// Get a site-wide setting
+    pub fn get_setting(&self, key_param: &str) -> Result<Option<String>> {
+        use crate::schema::settings::dsl::*;
 
         let mut conn = self
             .pool
             .get()
             .wrap_err("Failed to get database connection")?;
 
-        diesel::insert_into(quiz_attempts)
-            .values(attempt)
-            .execute(&mut conn)
-            .wrap_err("Failed to create quiz attempt")?;
-
-        let result = quiz_attempts
-            .filter(id.eq(&attempt.id))
-            .select(QuizAttempt::as_select())
+        let result = settings
+            .filter(key.eq(key_param))
+            .select(value)
             .first(&mut conn)
-            .wrap_err("Failed to load created quiz attempt")?;
+            .optional()
+            .wrap_err("Failed to load setting")?;
 
         Ok(result)
     }
 
-    /// Get quiz attempts for a session
-    pub fn get_session_attempts(&self, session_id_param: &str) -> Result<Vec<QuizAttempt>> {
-        use crate::schema::quiz_attempts::dsl::*;
+    /// Insert or update a site-wide setting
+    pub fn set_setting(&self, key_param: &str, value_param: &str) -> Result<()> {
+        use crate::schema::settings::dsl::*;
 
         let mut conn = self
             .pool
             .get()
             .wrap_err("Failed to get database connection")?;
 
-        let results = quiz_attempts
-            .filter(session_id.eq(session_id_param))
-            .order(created_at.desc())
-            .select(QuizAttempt::as_select())
-            .load(&mut conn)
-            .wrap_err("Failed to load session attempts")?;
+        diesel::replace_into(settings)
+            .values((
+                key.eq(key_param),
+                value.eq(value_param),
+                updated_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(&mut conn)
+            .wrap_err("Failed to save setting")?;
 
-        Ok(results)
+        Ok(())
     }
 
-    /// Get session statistics summary
-    pub fn get_session_statistics(&self, session_id_param: &str) -> Result<SessionStatistics> {
-        use crate::schema::quiz_attempts::dsl::*;
+    /// Whether every admin must use a second factor
+    pub fn is_two_factor_required(&self) -> Result<bool> {
+        Ok(self
+            .get_setting(crate::two_factor::REQUIRE_TWO_FACTOR_SETTING)?
+            .is_some_and(|value| value == "true"))
+    }
+
+    // Audit Log Methods
+
+    /// Record an admin action in the audit log
+    pub fn record_audit_event(&self, entry: NewAuditLogEntry) -> Result<()> {
+        use crate::schema::admin_audit_log::dsl::*;
 
         let mut conn = self
             .pool
             .get()
             .wrap_err("Failed to get database connection")?;
 
-        let attempts = quiz_attempts
-            .filter(session_id.eq(session_id_param))
-            .order(created_at.asc())
-            .select(QuizAttempt::as_select())
-            .load(&mut conn)
-            .wrap_err("Failed to load session attempts")?;
-
-        let total_questions = attempts.len();
-        let correct_answers = attempts
-            .iter()
-            .filter(|a| a.is_correct == Some(true))
-            .count();
-        let accuracy_percentage = if total_questions > 0 {
-            (correct_answers as f32 / total_questions as f32 * 100.0).round() as u32
-        } else {
-            0
-        };
-
-        // Calculate current streak (consecutive correct answers from most recent)
-        let mut current_streak = 0;
-        for attempt in attempts.iter().rev() {
-            if attempt.is_correct == Some(true) {
-                current_streak += 1;
-            } else {
-                break;
-            }
-        }
+        diesel::insert_into(admin_audit_log)
+            .values(&entry)
+            .execute(&mut conn)
+            .wrap_err("Failed to record audit event")?;
 
-        Ok(SessionStatistics {
-            total_questions,
-            correct_answers,
-            accuracy_percentage,
-            current_streak,
-        })
+        Ok(())
     }
 
-    /// Get questions that were answered incorrectly in this session
-    pub fn get_session_missed_questions(
+    /// Get audit log entries matching the filter, newest first
+    pub fn get_audit_log(
         &self,
-        session_id_param: &str,
-    ) -> Result<Vec<(QuizQuestion, QuizAttempt)>> {
-        use crate::schema::quiz_attempts::dsl as qa_dsl;
-        use crate::schema::quiz_questions::dsl as qq_dsl;
+        filter: &AuditLogFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditLogEntry>> {
+        use crate::schema::admin_audit_log::dsl::*;
 
         let mut conn = self
             .pool
             .get()
             .wrap_err("Failed to get database connection")?;
 
-        let results = qq_dsl::quiz_questions
-            .inner_join(qa_dsl::quiz_attempts.on(qa_dsl::question_id.eq(qq_dsl::id)))
-            .filter(qa_dsl::session_id.eq(session_id_param))
-            .filter(qa_dsl::is_correct.eq(Some(false)))
-            .select((QuizQuestion::as_select(), QuizAttempt::as_select()))
+        let mut query = admin_audit_log.into_boxed();
+
+        if let Some(actor_val) = &filter.actor {
+            query = query.filter(actor.eq(actor_val));
+        }
+        if let Some(action_val) = filter.action {
+            query = query.filter(action.eq(action_val));
+        }
+        if let Some(entity_type_val) = &filter.entity_type {
+            query = query.filter(entity_type.eq(entity_type_val));
+        }
+        if let Some(entity_id_val) = &filter.entity_id {
+            query = query.filter(entity_id.eq(entity_id_val));
+        }
+        if let Some(start) = filter.start_date {
+            let start_datetime = start.and_hms_opt(0, 0, 0).unwrap();
+            query = query.filter(created_at.ge(start_datetime));
+        }
+        if let Some(end) = filter.end_date {
+            let next_day = end + chrono::Duration::days(1);
+            let end_datetime = next_day.and_hms_opt(0, 0, 0).unwrap();
+            query = query.filter(created_at.lt(end_datetime));
+        }
+
+        let results = query
+            .order((created_at.desc(), id.desc()))
+            .limit(limit)
+            .offset(offset)
+            .select(AuditLogEntry::as_select())
             .load(&mut conn)
-            .wrap_err("Failed to load missed questions")?;
+            .wrap_err("Failed to load audit log")?;
 
         Ok(results)
     }
 
-    /// Clear all attempts for a session (for privacy)
-    pub fn clear_session_attempts(&self, session_id_param: &str) -> Result<()> {
-        use crate::schema::quiz_attempts::dsl::*;
+    /// Get the distinct actors that appear in the audit log
+    pub fn get_audit_log_actors(&self) -> Result<Vec<String>> {
+        use crate::schema::admin_audit_log::dsl::*;
 
         let mut conn = self
             .pool
             .get()
             .wrap_err("Failed to get database connection")?;
 
-        diesel::delete(quiz_attempts.filter(session_id.eq(session_id_param)))
-            .execute(&mut conn)
-            .wrap_err("Failed to clear session attempts")?;
+        let results = admin_audit_log
+            .select(actor)
+            .distinct()
+            .order(actor.asc())
+            .load(&mut conn)
+            .wrap_err("Failed to load audit log actors")?;
 
-        Ok(())
+        Ok(results)
     }
 
-    // Admin authentication methods
+    // Login Throttle Methods
 
-    /// Find admin by username
-    pub fn find_admin_by_username(&self, username_param: &str) -> Result<Option<Admin>> {
-        use crate::schema::admins::dsl::*;
+    /// Get failed login tracking for a username or IP
+    pub fn get_login_throttle(
+        &self,
+        scope_param: ThrottleScope,
+        subject_param: &str,
+    ) -> Result<Option<LoginThrottle>> {
+        use crate::schema::login_throttles::dsl::*;
 
         let mut conn = self
             .pool
             .get()
             .wrap_err("Failed to get database connection")?;
 
-        let result = admins
-            .filter(username.eq(username_param))
-            .filter(is_active.eq(true))
-            .select(Admin::as_select())
+        let result = login_throttles
+            .filter(scope.eq(scope_param))
+            .filter(subject.eq(subject_param))
+            .select(LoginThrottle::as_select())
             .first(&mut conn)
             .optional()
-            .wrap_err("Failed to find admin by username")?;
+            .wrap_err("Failed to load login throttle")?;
 
         Ok(result)
     }
 
-    /// Update admin last login timestamp
-    pub fn update_admin_last_login(&self, admin_id: &str) -> Result<()> {
-        use crate::schema::admins::dsl::*;
+    /// Count a failed login for a username or IP. The read and the write share
+    /// an immediate transaction so concurrent failures are never lost.
+    pub fn record_login_failure(
+        &self,
+        throttle_config: &crate::config::LoginThrottleConfig,
+        scope_param: ThrottleScope,
+        subject_param: &str,
+        now: chrono::NaiveDateTime,
+    ) -> Result<(LoginThrottle, bool)> {
+        use crate::schema::login_throttles::dsl::*;
 
         let mut conn = self
             .pool
             .get()
             .wrap_err("Failed to get database connection")?;
 
-        diesel::update(admins.filter(id.eq(admin_id)))
-            .set(last_login.eq(chrono::Utc::now().naive_utc()))
-            .execute(&mut conn)
-            .wrap_err("Failed to update admin last login")?;
+        conn.immediate_transaction(|conn| {
+            let previous = login_throttles
+                .filter(scope.eq(scope_param))
+                .filter(subject.eq(subject_param))
+                .select(LoginThrottle::as_select())
+                .first(conn)
+                .optional()
+                .wrap_err("Failed to load login throttle")?;
 
-        Ok(())
+            let (throttle, newly_locked) =
+                throttle_config.record_failure(previous, scope_param, subject_param, now);
+
+            diesel::replace_into(login_throttles)
+                .values(&throttle)
+                .execute(conn)
+                .wrap_err("Failed to save login throttle")?;
+
+            Ok((throttle, newly_locked))
+        })
     }
 
-    /// Update admin password hash (requires current password hash for verification)
-    pub fn update_admin_password(
+    /// Forget failed logins for a username or IP; returns whether a record existed
+    pub fn clear_login_throttle(
         &self,
-        admin_id: &str,
-        current_password_hash: &str,
-        new_password_hash: &str,
-    ) -> Result<()> {
-        use crate::schema::admins::dsl::*;
+        scope_param: ThrottleScope,
+        subject_param: &str,
+    ) -> Result<bool> {
+        use crate::schema::login_throttles::dsl::*;
 
         let mut conn = self
             .pool
             .get()
             .wrap_err("Failed to get database connection")?;
 
-        // Update only if the current password hash matches (prevents unauthorized changes)
-        let rows_affected = diesel::update(
-            admins.filter(id.eq(admin_id).and(password_hash.eq(current_password_hash))),
+        let deleted = diesel::delete(
+            login_throttles
+                .filter(scope.eq(scope_param))
+                .filter(subject.eq(subject_param)),
         )
-        .set(password_hash.eq(new_password_hash))
         .execute(&mut conn)
-        .wrap_err("Failed to update admin password")?;
+        .wrap_err("Failed to clear login throttle")?;
 
-        if rows_affected == 0 {
-            return Err(color_eyre::eyre::eyre!(
-                "Password update failed - current password incorrect or admin not found"
-            ));
-        }
+        Ok(deleted > 0)
+    }
 
-        Ok(())
+    /// Get lockouts still in force at `now`, ending soonest first
+    pub fn get_active_login_lockouts(
+        &self,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<LoginThrottle>> {
+        use crate::schema::login_throttles::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let results = login_throttles
+            .filter(locked_until.gt(now))
+            .order(locked_until.asc())
+            .select(LoginThrottle::as_select())
+            .load(&mut conn)
+            .wrap_err("Failed to load login lockouts")?;
+
+        Ok(results)
     }
 
     // Question Management Methods
@@ -1202,6 +3229,84 @@ impl RuleRepository {
         })
     }
 
+    /// Attempts segmented by the quiz mode and difficulty mode of their sessions
+    pub fn get_mode_statistics(
+        &self,
+        start_date: Option<chrono::NaiveDate>,
+        end_date: Option<chrono::NaiveDate>,
+    ) -> Result<Vec<crate::models::ModeStatistics>> {
+        use crate::schema::quiz_attempts::dsl as qa_dsl;
+        use crate::schema::quiz_sessions::dsl as qs_dsl;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let mut attempts_query = qa_dsl::quiz_attempts.into_boxed();
+        if let Some(start) = start_date {
+            let start_datetime = start.and_hms_opt(0, 0, 0).unwrap();
+            attempts_query = attempts_query.filter(qa_dsl::created_at.ge(start_datetime));
+        }
+        if let Some(end) = end_date {
+            let next_day = end + chrono::Duration::days(1);
+            let end_datetime = next_day.and_hms_opt(0, 0, 0).unwrap();
+            attempts_query = attempts_query.filter(qa_dsl::created_at.lt(end_datetime));
+        }
+        let attempts: Vec<(String, Option<bool>)> = attempts_query
+            .select((qa_dsl::session_id, qa_dsl::is_correct))
+            .load(&mut conn)
+            .wrap_err("Failed to load attempts")?;
+
+        let session_ids: std::collections::HashSet<&String> =
+            attempts.iter().map(|(session, _)| session).collect();
+        let session_modes: std::collections::HashMap<String, (QuizMode, DifficultyMode)> =
+            qs_dsl::quiz_sessions
+                .filter(qs_dsl::id.eq_any(session_ids))
+                .select((qs_dsl::id, qs_dsl::mode, qs_dsl::difficulty_mode))
+                .load::<(String, QuizMode, DifficultyMode)>(&mut conn)
+                .wrap_err("Failed to load session modes")?
+                .into_iter()
+                .map(|(session, mode, difficulty_mode)| (session, (mode, difficulty_mode)))
+                .collect();
+
+        // (mode, difficulty mode) -> (sessions, attempts, correct attempts)
+        let mut groups: std::collections::BTreeMap<
+            (String, String),
+            (std::collections::HashSet<String>, usize, usize),
+        > = std::collections::BTreeMap::new();
+        for (session, is_correct) in attempts {
+            // Sessions deleted since, e.g. with a learner account, no longer count
+            let Some((mode, difficulty_mode)) = session_modes.get(&session) else {
+                continue;
+            };
+            let group = groups
+                .entry((mode.as_str().to_string(), difficulty_mode.to_string()))
+                .or_default();
+            group.0.insert(session);
+            group.1 += 1;
+            if is_correct == Some(true) {
+                group.2 += 1;
+            }
+        }
+
+        Ok(groups
+            .into_iter()
+            .map(
+                |((mode, difficulty_mode), (sessions, attempts, correct_attempts))| {
+                    crate::models::ModeStatistics {
+                        mode,
+                        difficulty_mode,
+                        sessions: sessions.len(),
+                        attempts,
+                        correct_attempts,
+                        success_rate: correct_attempts as f64 / attempts as f64 * 100.0,
+                    }
+                },
+            )
+            .collect())
+    }
+
     /// Get detailed statistics for a specific question
     pub fn get_question_detail_statistics(
         &self,
@@ -1256,6 +3361,9 @@ impl RuleRepository {
         } else {
             0.0
         };
+        let response_times = crate::models::quiz::ResponseTimePercentiles::from_times(
+            attempts.iter().filter_map(|a| a.response_time_ms).collect(),
+        );
 
         // Get answer distribution
         let answer_distribution =
@@ -1330,6 +3438,7 @@ impl RuleRepository {
             correct_attempts,
             success_rate,
             most_common_wrong_answer,
+            response_times,
             recent_attempts: recent_attempts_structured,
         }))
     }
@@ -1407,6 +3516,42 @@ impl RuleRepository {
         Ok(distribution)
     }
 
+    /// Response time percentiles of a question's timed attempts in the date range
+    pub fn get_response_time_percentiles(
+        &self,
+        question_id_param: &str,
+        start_date: Option<chrono::NaiveDate>,
+        end_date: Option<chrono::NaiveDate>,
+    ) -> Result<Option<crate::models::quiz::ResponseTimePercentiles>> {
+        use crate::schema::quiz_attempts::dsl::*;
+
+        let mut conn = self
+            .pool
+            .get()
+            .wrap_err("Failed to get database connection")?;
+
+        let mut query = quiz_attempts
+            .filter(question_id.eq(question_id_param))
+            .filter(response_time_ms.is_not_null())
+            .into_boxed();
+        if let Some(start) = start_date {
+            query = query.filter(created_at.ge(start.and_hms_opt(0, 0, 0).unwrap()));
+        }
+        if let Some(end) = end_date {
+            let next_day = end + chrono::Duration::days(1);
+            query = query.filter(created_at.lt(next_day.and_hms_opt(0, 0, 0).unwrap()));
+        }
+
+        let times = query
+            .select(response_time_ms.assume_not_null())
+            .load::<i32>(&mut conn)
+            .wrap_err("Failed to load response times")?;
+
+        Ok(crate::models::quiz::ResponseTimePercentiles::from_times(
+            times,
+        ))
+    }
+
     /// Get questions with selection data for CSV export
     pub fn get_questions_with_selection_data_for_export(
         &self,
@@ -1461,6 +3606,11 @@ impl RuleRepository {
                 total_attempts: stat.total_attempts,
                 correct_attempts: stat.correct_attempts,
                 success_rate_percent: stat.success_rate * 100.0,
+                response_times: self.get_response_time_percentiles(
+                    &question.id,
+                    start_date,
+                    end_date,
+                )?,
                 answers: export_answers,
                 created_at: question.created_at,
                 updated_at: question.updated_at,
@@ -1562,7 +3712,7 @@ impl RuleRepository {
             })
             .collect();
 
-        daily_attempts.sort_by(|a, b| a.date.cmp(&b.date));
+        daily_attempts.sort_by_key(|a| a.date);
         Ok(daily_attempts)
     }
 
@@ -0,0 +1,164 @@
+//! Response times for HTML quiz answers.
+//!
+//! Rendering a question adds a signed stamp of when it was served to the answer
+//! form; the submit handler checks the signature and stores the elapsed time with
+//! the attempt. The stamp is bound to the session and question, so it can't be
+//! reused for another question, and implausible times are dropped rather than
+//! skewing the statistics.
+
+use chrono::{DateTime, Utc};
+use color_eyre::{Result, eyre::WrapErr};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+
+use crate::config::SecurityConfig;
+
+/// Faster answers are treated as accidental or scripted submits
+pub const MIN_RESPONSE_MS: i64 = 500;
+
+/// Slower answers mean the learner left the question open and came back later
+pub const MAX_RESPONSE_MS: i64 = 10 * 60 * 1000;
+
+const SERVED_PURPOSE: &str = "quiz question served";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ServedClaims {
+    sid: String,
+    question_id: String,
+    purpose: String,
+    /// Milliseconds since the Unix epoch
+    served_at: i64,
+}
+
+/// Signs and checks question-served stamps with `security.jwt_secret`
+#[derive(Clone)]
+pub struct QuestionTimer {
+    jwt_secret: String,
+}
+
+impl QuestionTimer {
+    pub fn new(security: &SecurityConfig) -> Self {
+        Self {
+            jwt_secret: security.jwt_secret.clone(),
+        }
+    }
+
+    /// Stamp for the answer form of a question served to the session at `served_at`
+    pub fn stamp(
+        &self,
+        session_id: &str,
+        question_id: &str,
+        served_at: DateTime<Utc>,
+    ) -> Result<String> {
+        let claims = ServedClaims {
+            sid: session_id.to_string(),
+            question_id: question_id.to_string(),
+            purpose: SERVED_PURPOSE.to_string(),
+            served_at: served_at.timestamp_millis(),
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.jwt_secret.as_ref()),
+        )
+        .wrap_err("Failed to sign question stamp")
+    }
+
+    /// Milliseconds between serving and answering the question, or `None` if the stamp
+    /// is missing, forged, for another session or question, or the time is implausible
+    pub fn response_time_ms(
+        &self,
+        stamp: Option<&str>,
+        session_id: &str,
+        question_id: &str,
+        answered_at: DateTime<Utc>,
+    ) -> Option<i32> {
+        // The stamp carries no expiry; the plausibility window bounds its age
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.required_spec_claims.clear();
+        validation.validate_exp = false;
+
+        let claims = decode::<ServedClaims>(
+            stamp?,
+            &DecodingKey::from_secret(self.jwt_secret.as_ref()),
+            &validation,
+        )
+        .ok()?
+        .claims;
+        if claims.purpose != SERVED_PURPOSE
+            || claims.sid != session_id
+            || claims.question_id != question_id
+        {
+            return None;
+        }
+
+        let elapsed = answered_at.timestamp_millis() - claims.served_at;
+        (MIN_RESPONSE_MS..=MAX_RESPONSE_MS)
+            .contains(&elapsed)
+            .then_some(elapsed as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn timer(secret: &str) -> QuestionTimer {
+        QuestionTimer {
+            jwt_secret: secret.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_elapsed_time_is_measured_from_the_stamp() {
+        let timer = timer("secret");
+        let served_at = Utc::now();
+        let stamp = timer.stamp("session", "question", served_at).unwrap();
+
+        let answered_at = served_at + Duration::milliseconds(4200);
+        assert_eq!(
+            timer.response_time_ms(Some(&stamp), "session", "question", answered_at),
+            Some(4200)
+        );
+        // Bound to the session and question it was served for
+        assert_eq!(
+            timer.response_time_ms(Some(&stamp), "other", "question", answered_at),
+            None
+        );
+        assert_eq!(
+            timer.response_time_ms(Some(&stamp), "session", "other", answered_at),
+            None
+        );
+        assert_eq!(
+            timer.response_time_ms(None, "session", "question", answered_at),
+            None
+        );
+    }
+
+    #[test]
+    fn test_forged_stamps_and_implausible_times_are_ignored() {
+        let served_at = Utc::now();
+        let forged = timer("other secret")
+            .stamp("session", "question", served_at)
+            .unwrap();
+        let timer = timer("secret");
+        let in_time = served_at + Duration::seconds(5);
+        assert_eq!(
+            timer.response_time_ms(Some(&forged), "session", "question", in_time),
+            None
+        );
+
+        let stamp = timer.stamp("session", "question", served_at).unwrap();
+        for answered_at in [
+            served_at - Duration::seconds(1),
+            served_at + Duration::milliseconds(MIN_RESPONSE_MS - 1),
+            served_at + Duration::milliseconds(MAX_RESPONSE_MS + 1),
+        ] {
+            assert_eq!(
+                timer.response_time_ms(Some(&stamp), "session", "question", answered_at),
+                None
+            );
+        }
+    }
+}
@@ -1,5 +1,56 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    admin_api_tokens (id) {
+        id -> Text,
+        admin_id -> Text,
+        name -> Text,
+        token_hash -> Text,
+        token_hint -> Text,
+        scope -> Text,
+        created_at -> Timestamp,
+        last_used_at -> Nullable<Timestamp>,
+        revoked_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    admin_audit_log (id) {
+        id -> Text,
+        admin_id -> Nullable<Text>,
+        actor -> Text,
+        action -> Text,
+        entity_type -> Text,
+        entity_id -> Nullable<Text>,
+        before_json -> Nullable<Text>,
+        after_json -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    admin_recovery_codes (id) {
+        id -> Text,
+        admin_id -> Text,
+        code_hash -> Text,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    admin_sessions (id) {
+        id -> Text,
+        admin_id -> Text,
+        created_at -> Timestamp,
+        last_seen_at -> Timestamp,
+        expires_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        ip_address -> Nullable<Text>,
+        user_agent -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     admins (id) {
         id -> Text,
@@ -9,6 +60,12 @@ diesel::table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         last_login -> Nullable<Timestamp>,
+        role -> Text,
+        totp_secret -> Nullable<Text>,
+        totp_enabled_at -> Nullable<Timestamp>,
+        totp_last_step -> Nullable<BigInt>,
+        email -> Nullable<Text>,
+        oidc_subject -> Nullable<Text>,
     }
 }
 
@@ -35,6 +92,35 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    learner_login_links (token_hash) {
+        token_hash -> Text,
+        email -> Text,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    learners (id) {
+        id -> Text,
+        email -> Text,
+        created_at -> Timestamp,
+        last_login_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    login_throttles (scope, subject) {
+        scope -> Text,
+        subject -> Text,
+        failed_attempts -> Integer,
+        last_failed_at -> Timestamp,
+        locked_until -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     quiz_answers (id) {
         id -> Text,
@@ -47,6 +133,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    quiz_api_tokens (token_hash) {
+        token_hash -> Text,
+        session_id -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     quiz_attempts (id) {
         id -> Text,
@@ -59,6 +153,43 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    quiz_certificates (id) {
+        id -> Text,
+        exam_session_id -> Text,
+        holder_name -> Text,
+        rule_set_name -> Text,
+        version_name -> Text,
+        score_percent -> Integer,
+        passed_at -> Timestamp,
+        issued_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        revocation_reason -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    quiz_exam_questions (session_id, position) {
+        session_id -> Text,
+        position -> Integer,
+        question_id -> Text,
+    }
+}
+
+diesel::table! {
+    quiz_exams (session_id) {
+        session_id -> Text,
+        practice_session_id -> Nullable<Text>,
+        question_count -> Integer,
+        pass_percent -> Integer,
+        started_at -> Timestamp,
+        ends_at -> Timestamp,
+        finished_at -> Nullable<Timestamp>,
+        correct_count -> Nullable<Integer>,
+        passed -> Nullable<Bool>,
+    }
+}
+
 diesel::table! {
     quiz_question_rules (id) {
         id -> Text,
@@ -82,6 +213,43 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    quiz_reviews (session_id, question_id) {
+        session_id -> Text,
+        question_id -> Text,
+        repetitions -> Integer,
+        interval_days -> Integer,
+        ease_factor -> Double,
+        due_at -> Timestamp,
+        last_reviewed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    quiz_sessions (id) {
+        id -> Text,
+        rule_set_id -> Text,
+        version_id -> Text,
+        language -> Text,
+        mode -> Text,
+        started_at -> Timestamp,
+        ended_at -> Nullable<Timestamp>,
+        learner_id -> Nullable<Text>,
+        topic_rule_ids -> Nullable<Text>,
+        difficulty_mode -> Text,
+    }
+}
+
+diesel::table! {
+    quiz_transfer_codes (code_hash) {
+        code_hash -> Text,
+        session_id -> Text,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     rule_content (id) {
         id -> Text,
@@ -118,6 +286,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    settings (key) {
+        key -> Text,
+        value -> Text,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     versions (id) {
         id -> Text,
@@ -131,31 +307,60 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(admin_api_tokens -> admins (admin_id));
+diesel::joinable!(admin_recovery_codes -> admins (admin_id));
+diesel::joinable!(admin_sessions -> admins (admin_id));
 diesel::joinable!(glossary_content -> glossary_terms (term_id));
 diesel::joinable!(glossary_terms -> rule_sets (rule_set_id));
 diesel::joinable!(glossary_terms -> versions (version_id));
 diesel::joinable!(quiz_answers -> quiz_questions (question_id));
+diesel::joinable!(quiz_api_tokens -> quiz_sessions (session_id));
 diesel::joinable!(quiz_attempts -> quiz_answers (selected_answer_id));
 diesel::joinable!(quiz_attempts -> quiz_questions (question_id));
+diesel::joinable!(quiz_certificates -> quiz_exams (exam_session_id));
+diesel::joinable!(quiz_exam_questions -> quiz_exams (session_id));
+diesel::joinable!(quiz_exam_questions -> quiz_questions (question_id));
+diesel::joinable!(quiz_exams -> quiz_sessions (session_id));
 diesel::joinable!(quiz_question_rules -> quiz_questions (question_id));
 diesel::joinable!(quiz_question_rules -> rule_content (rule_id));
 diesel::joinable!(quiz_questions -> rule_sets (rule_set_id));
 diesel::joinable!(quiz_questions -> versions (version_id));
+diesel::joinable!(quiz_reviews -> quiz_questions (question_id));
+diesel::joinable!(quiz_reviews -> quiz_sessions (session_id));
+diesel::joinable!(quiz_sessions -> learners (learner_id));
+diesel::joinable!(quiz_sessions -> rule_sets (rule_set_id));
+diesel::joinable!(quiz_sessions -> versions (version_id));
+diesel::joinable!(quiz_transfer_codes -> quiz_sessions (session_id));
 diesel::joinable!(rule_content -> rules (rule_id));
 diesel::joinable!(rules -> rule_sets (rule_set_id));
 diesel::joinable!(rules -> versions (version_id));
 diesel::joinable!(versions -> rule_sets (rule_set_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    admin_api_tokens,
+    admin_audit_log,
+    admin_recovery_codes,
+    admin_sessions,
     admins,
     glossary_content,
     glossary_terms,
+    learner_login_links,
+    learners,
+    login_throttles,
     quiz_answers,
+    quiz_api_tokens,
     quiz_attempts,
+    quiz_certificates,
+    quiz_exam_questions,
+    quiz_exams,
     quiz_question_rules,
     quiz_questions,
+    quiz_reviews,
+    quiz_sessions,
+    quiz_transfer_codes,
     rule_content,
     rule_sets,
     rules,
+    settings,
     versions,
 );
@@ -0,0 +1,118 @@
+//! SM-2 scheduling for quiz questions.
+//!
+//! Every answer grades the question for that learner session and pushes its next
+//! review further out the more often it was answered correctly in a row; a wrong
+//! answer starts the question over. The policy is pure; the repository stores the
+//! state and the quiz engine serves due reviews before new questions.
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::models::QuizReview;
+
+/// Ease factor of a question that has not been reviewed yet
+pub const INITIAL_EASE_FACTOR: f64 = 2.5;
+/// Lower bound keeping hard questions from being scheduled every day forever
+pub const MIN_EASE_FACTOR: f64 = 1.3;
+
+/// How well a question was recalled, on SM-2's 0–5 scale
+pub fn quality(is_correct: bool) -> u8 {
+    if is_correct { 4 } else { 1 }
+}
+
+/// Review state after answering with `quality`, starting from `previous` if the
+/// question was reviewed before
+pub fn review(
+    previous: Option<&QuizReview>,
+    session_id: &str,
+    question_id: &str,
+    quality: u8,
+    now: NaiveDateTime,
+) -> QuizReview {
+    let quality = quality.min(5);
+    let (repetitions, interval_days, ease_factor) = previous
+        .map_or((0, 0, INITIAL_EASE_FACTOR), |review| {
+            (review.repetitions, review.interval_days, review.ease_factor)
+        });
+
+    let (repetitions, interval_days) = if quality >= 3 {
+        let interval_days = match repetitions {
+            0 => 1,
+            1 => 6,
+            _ => (f64::from(interval_days) * ease_factor).round() as i32,
+        };
+        (repetitions + 1, interval_days)
+    } else {
+        (0, 1)
+    };
+
+    let miss = f64::from(5 - quality);
+    let ease_factor = (ease_factor + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE_FACTOR);
+
+    QuizReview {
+        session_id: session_id.to_string(),
+        question_id: question_id.to_string(),
+        repetitions,
+        interval_days,
+        ease_factor,
+        due_at: now + Duration::days(interval_days.into()),
+        last_reviewed_at: now,
+    }
+}
+
+/// End of the UTC day containing `now`; reviews due before it are "due today"
+pub fn end_of_day(now: NaiveDateTime) -> NaiveDateTime {
+    (now.date() + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2025, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_correct_answers_space_reviews_out() {
+        let first = review(None, "s", "q", quality(true), at(1, 10));
+        assert_eq!((first.repetitions, first.interval_days), (1, 1));
+        assert_eq!(first.due_at, at(2, 10));
+        assert_eq!(first.ease_factor, INITIAL_EASE_FACTOR);
+
+        let second = review(Some(&first), "s", "q", quality(true), at(2, 10));
+        assert_eq!((second.repetitions, second.interval_days), (2, 6));
+
+        let third = review(Some(&second), "s", "q", quality(true), at(8, 10));
+        assert_eq!((third.repetitions, third.interval_days), (3, 15));
+        assert_eq!(third.due_at, at(23, 10));
+    }
+
+    #[test]
+    fn test_wrong_answer_starts_over_and_makes_question_harder() {
+        let learned = review(None, "s", "q", 5, at(1, 10));
+        let learned = review(Some(&learned), "s", "q", 5, at(2, 10));
+        assert!(learned.ease_factor > INITIAL_EASE_FACTOR);
+
+        let lapsed = review(Some(&learned), "s", "q", quality(false), at(8, 10));
+        assert_eq!((lapsed.repetitions, lapsed.interval_days), (0, 1));
+        assert_eq!(lapsed.due_at, at(9, 10));
+        assert!(lapsed.ease_factor < learned.ease_factor);
+
+        let mut review_state = lapsed;
+        for _ in 0..10 {
+            review_state = review(Some(&review_state), "s", "q", 0, at(9, 10));
+        }
+        assert_eq!(review_state.ease_factor, MIN_EASE_FACTOR);
+    }
+
+    #[test]
+    fn test_end_of_day() {
+        assert_eq!(end_of_day(at(1, 0)), at(2, 0));
+        assert_eq!(end_of_day(at(1, 23)), at(2, 0));
+    }
+}
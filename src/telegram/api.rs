@@ -0,0 +1,210 @@
+//! Minimal Telegram Bot API client: only the methods and fields the bot uses.
+
+use std::time::Duration;
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+/// Incoming update from `getUpdates` or a webhook
+#[derive(Debug, Clone, Deserialize)]
+pub struct Update {
+    pub update_id: i64,
+    #[serde(default)]
+    pub message: Option<Message>,
+    #[serde(default)]
+    pub callback_query: Option<CallbackQuery>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Message {
+    pub message_id: i64,
+    pub chat: Chat,
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Chat {
+    pub id: i64,
+}
+
+/// Press of an inline keyboard button
+#[derive(Debug, Clone, Deserialize)]
+pub struct CallbackQuery {
+    pub id: String,
+    #[serde(default)]
+    pub message: Option<Message>,
+    #[serde(default)]
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct InlineKeyboardButton {
+    pub text: String,
+    pub callback_data: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct InlineKeyboardMarkup {
+    pub inline_keyboard: Vec<Vec<InlineKeyboardButton>>,
+}
+
+impl InlineKeyboardMarkup {
+    /// One button per row, for answer options that may be long
+    pub fn column(buttons: impl IntoIterator<Item = InlineKeyboardButton>) -> Self {
+        Self {
+            inline_keyboard: buttons.into_iter().map(|button| vec![button]).collect(),
+        }
+    }
+}
+
+/// Text message in Telegram's HTML formatting
+#[derive(Debug, Clone, Serialize)]
+pub struct SendMessage {
+    pub chat_id: i64,
+    pub text: String,
+    pub parse_mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_markup: Option<InlineKeyboardMarkup>,
+}
+
+impl SendMessage {
+    pub fn html(chat_id: i64, text: String) -> Self {
+        Self {
+            chat_id,
+            text,
+            parse_mode: "HTML",
+            reply_markup: None,
+        }
+    }
+
+    pub fn with_keyboard(mut self, keyboard: InlineKeyboardMarkup) -> Self {
+        self.reply_markup = Some(keyboard);
+        self
+    }
+}
+
+/// Envelope around every Bot API response
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+/// Bot API client for one bot token
+#[derive(Debug, Clone)]
+pub struct BotApi {
+    http: reqwest::Client,
+    /// `{api_base_url}/bot{token}`; method names are appended
+    endpoint: String,
+}
+
+impl BotApi {
+    /// `poll_timeout` is the longest a `getUpdates` call may block on the server
+    pub fn new(api_base_url: &str, bot_token: &str, poll_timeout: Duration) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(poll_timeout + Duration::from_secs(10))
+            .build()
+            .wrap_err("Failed to build Telegram HTTP client")?;
+        Ok(Self {
+            http,
+            endpoint: format!("{}/bot{bot_token}", api_base_url.trim_end_matches('/')),
+        })
+    }
+
+    async fn call<P: Serialize + ?Sized, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &P,
+    ) -> Result<R> {
+        let response: ApiResponse<R> = self
+            .http
+            .post(format!("{}/{method}", self.endpoint))
+            .json(params)
+            .send()
+            .await
+            .wrap_err_with(|| format!("Telegram {method} request failed"))?
+            .json()
+            .await
+            .wrap_err_with(|| format!("Invalid Telegram {method} response"))?;
+
+        match response {
+            ApiResponse {
+                ok: true,
+                result: Some(result),
+                ..
+            } => Ok(result),
+            ApiResponse { description, .. } => Err(eyre!(
+                "Telegram {method} failed: {}",
+                description.unwrap_or_else(|| "no description".to_string())
+            )),
+        }
+    }
+
+    /// Long poll for updates after `offset`
+    pub async fn get_updates(&self, offset: i64, timeout_seconds: u64) -> Result<Vec<Update>> {
+        self.call(
+            "getUpdates",
+            &serde_json::json!({
+                "offset": offset,
+                "timeout": timeout_seconds,
+                "allowed_updates": ["message", "callback_query"],
+            }),
+        )
+        .await
+    }
+
+    pub async fn send_message(&self, message: &SendMessage) -> Result<()> {
+        self.call::<_, serde_json::Value>("sendMessage", message)
+            .await
+            .map(drop)
+    }
+
+    /// Stop the loading indicator on a pressed button
+    pub async fn answer_callback_query(&self, callback_query_id: &str) -> Result<()> {
+        self.call::<_, bool>(
+            "answerCallbackQuery",
+            &serde_json::json!({ "callback_query_id": callback_query_id }),
+        )
+        .await
+        .map(drop)
+    }
+
+    /// Remove the inline keyboard from a message so it cannot be pressed twice
+    pub async fn remove_keyboard(&self, chat_id: i64, message_id: i64) -> Result<()> {
+        self.call::<_, serde_json::Value>(
+            "editMessageReplyMarkup",
+            &serde_json::json!({
+                "chat_id": chat_id,
+                "message_id": message_id,
+                "reply_markup": InlineKeyboardMarkup::default(),
+            }),
+        )
+        .await
+        .map(drop)
+    }
+
+    pub async fn set_webhook(&self, url: &str, secret_token: Option<&str>) -> Result<()> {
+        self.call::<_, bool>(
+            "setWebhook",
+            &serde_json::json!({
+                "url": url,
+                "secret_token": secret_token,
+                "allowed_updates": ["message", "callback_query"],
+            }),
+        )
+        .await
+        .map(drop)
+    }
+
+    /// `getUpdates` is refused while a webhook is registered
+    pub async fn delete_webhook(&self) -> Result<()> {
+        self.call::<_, bool>("deleteWebhook", &serde_json::json!({}))
+            .await
+            .map(drop)
+    }
+}
@@ -0,0 +1,490 @@
+//! Long-poll and webhook loops feeding updates to the chat engine.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use color_eyre::{Result, eyre::WrapErr};
+use tracing::{info, instrument, warn};
+
+use super::{
+    api::{BotApi, InlineKeyboardButton, InlineKeyboardMarkup, SendMessage, Update},
+    format::{LinkTargets, markdown_to_telegram_html},
+};
+use crate::{
+    chat::{ChatEngine, ChatInput, FollowUp, conversation_session_id},
+    config::{TelegramConfig, TelegramWebhookConfig},
+    repository::RuleRepository,
+};
+
+const SECRET_TOKEN_HEADER: &str = "x-telegram-bot-api-secret-token";
+
+/// Quiz session id for a Telegram chat
+pub fn chat_session_id(chat_id: i64) -> String {
+    conversation_session_id("telegram", &chat_id.to_string())
+}
+
+/// Adapts Telegram updates to the [`ChatEngine`]
+pub struct TelegramBot {
+    api: BotApi,
+    engine: ChatEngine,
+    config: TelegramConfig,
+    links: Option<LinkTargets>,
+    /// What each chat's last response expects; lost on restart, when buttons still work
+    follow_ups: Mutex<HashMap<i64, FollowUp>>,
+}
+
+impl TelegramBot {
+    pub fn new(config: TelegramConfig, repo: RuleRepository) -> Result<Self> {
+        let api = BotApi::new(
+            &config.api_base_url,
+            &config.bot_token,
+            Duration::from_secs(config.poll_timeout_seconds),
+        )?;
+        let links = config
+            .site_url
+            .as_deref()
+            .map(|site_url| LinkTargets::for_site(site_url, &config.language, &config.rule_set));
+        Ok(Self {
+            api,
+            engine: ChatEngine::new(repo, config.rule_set.clone(), config.language.clone()),
+            config,
+            links,
+            follow_ups: Mutex::default(),
+        })
+    }
+
+    /// Serve updates until the process is stopped
+    pub async fn run(self) -> Result<()> {
+        match self.config.webhook.clone() {
+            Some(webhook) => self.run_webhook(webhook).await,
+            None => {
+                self.api.delete_webhook().await?;
+                info!(rule_set = %self.config.rule_set, "Polling Telegram for updates");
+                let mut offset = 0;
+                loop {
+                    match self.poll_once(offset).await {
+                        Ok(next_offset) => offset = next_offset,
+                        Err(error) => {
+                            warn!("Polling Telegram failed: {error:?}");
+                            tokio::time::sleep(Duration::from_secs(5)).await;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handle one batch of updates and return the offset for the next poll
+    pub async fn poll_once(&self, offset: i64) -> Result<i64> {
+        let updates = self
+            .api
+            .get_updates(offset, self.config.poll_timeout_seconds)
+            .await?;
+        let mut next_offset = offset;
+        for update in updates {
+            next_offset = next_offset.max(update.update_id + 1);
+            self.handle_update_logged(update).await;
+        }
+        Ok(next_offset)
+    }
+
+    async fn run_webhook(self, webhook: TelegramWebhookConfig) -> Result<()> {
+        let path = url::Url::parse(&webhook.url)
+            .wrap_err("Invalid telegram.webhook.url")?
+            .path()
+            .to_string();
+        self.api
+            .set_webhook(&webhook.url, webhook.secret_token.as_deref())
+            .await?;
+
+        let listener = tokio::net::TcpListener::bind(&webhook.listen)
+            .await
+            .wrap_err_with(|| format!("Failed to bind webhook listener to {}", webhook.listen))?;
+        info!(listen = %webhook.listen, path, "Receiving Telegram updates by webhook");
+        axum::serve(listener, Arc::new(self).webhook_router(&path))
+            .await
+            .wrap_err("Webhook listener failed")
+    }
+
+    /// Router accepting Bot API updates posted to `path`
+    pub fn webhook_router(self: Arc<Self>, path: &str) -> Router {
+        Router::new()
+            .route(path, post(receive_update))
+            .with_state(self)
+    }
+
+    /// Errors are logged rather than returned: a failing update must not stop the bot,
+    /// and Telegram would only redeliver it
+    async fn handle_update_logged(&self, update: Update) {
+        let update_id = update.update_id;
+        if let Err(error) = self.handle_update(update).await {
+            warn!(update_id, "Failed to handle Telegram update: {error:?}");
+        }
+    }
+
+    #[instrument(skip(self, update), fields(update_id = update.update_id))]
+    pub async fn handle_update(&self, update: Update) -> Result<()> {
+        if let Some(query) = update.callback_query {
+            self.api.answer_callback_query(&query.id).await?;
+            let (Some(message), Some(action)) = (query.message, query.data) else {
+                return Ok(());
+            };
+            // Buttons only work once; the response brings new ones
+            self.api
+                .remove_keyboard(message.chat.id, message.message_id)
+                .await?;
+            return self
+                .respond(message.chat.id, ChatInput::Button(action))
+                .await;
+        }
+
+        let Some(message) = update.message else {
+            return Ok(());
+        };
+        let Some(text) = message.text else {
+            return Ok(());
+        };
+        self.respond(message.chat.id, ChatInput::Text(text)).await
+    }
+
+    async fn respond(&self, chat_id: i64, input: ChatInput) -> Result<()> {
+        let follow_up = self
+            .follow_ups
+            .lock()
+            .expect("follow-up lock poisoned")
+            .remove(&chat_id)
+            .unwrap_or_default();
+        let response = self
+            .engine
+            .handle(&chat_session_id(chat_id), &input, &follow_up)?;
+
+        let mut message = SendMessage::html(
+            chat_id,
+            markdown_to_telegram_html(&response.text, self.links.as_ref()),
+        );
+        if !response.buttons.is_empty() {
+            message = message.with_keyboard(InlineKeyboardMarkup::column(
+                response
+                    .buttons
+                    .into_iter()
+                    .map(|button| InlineKeyboardButton {
+                        text: button.label,
+                        callback_data: button.action,
+                    }),
+            ));
+        }
+        if response.follow_up != FollowUp::None {
+            self.follow_ups
+                .lock()
+                .expect("follow-up lock poisoned")
+                .insert(chat_id, response.follow_up);
+        }
+        self.api.send_message(&message).await
+    }
+}
+
+async fn receive_update(
+    State(bot): State<Arc<TelegramBot>>,
+    headers: HeaderMap,
+    Json(update): Json<Update>,
+) -> StatusCode {
+    if let Some(webhook) = &bot.config.webhook
+        && let Some(expected) = &webhook.secret_token
+        && headers
+            .get(SECRET_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            != Some(expected.as_str())
+    {
+        return StatusCode::UNAUTHORIZED;
+    }
+    bot.handle_update_logged(update).await;
+    StatusCode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::tests::seeded_repo;
+    use axum::{body::Body, extract::Path, http::Request};
+    use tower::ServiceExt;
+
+    /// Bot API calls received by the mock server and updates it hands out
+    #[derive(Default)]
+    struct MockBotApi {
+        calls: Vec<(String, serde_json::Value)>,
+        updates: Vec<serde_json::Value>,
+    }
+
+    type Shared = Arc<Mutex<MockBotApi>>;
+
+    async fn bot_method(
+        State(mock): State<Shared>,
+        Path((bot, method)): Path<(String, String)>,
+        Json(params): Json<serde_json::Value>,
+    ) -> Json<serde_json::Value> {
+        assert_eq!(bot, "bottest-token");
+        let mut mock = mock.lock().unwrap();
+        let result = if method == "getUpdates" {
+            let offset = params["offset"].as_i64().unwrap();
+            serde_json::Value::from(
+                mock.updates
+                    .iter()
+                    .filter(|update| update["update_id"].as_i64().unwrap() >= offset)
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            )
+        } else {
+            serde_json::Value::Bool(true)
+        };
+        mock.calls.push((method, params));
+        Json(serde_json::json!({ "ok": true, "result": result }))
+    }
+
+    async fn start_mock_bot_api() -> (Shared, String) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let mock = Shared::default();
+        let app = Router::new()
+            .route("/{bot}/{method}", post(bot_method))
+            .with_state(mock.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (mock, base_url)
+    }
+
+    fn bot(
+        base_url: &str,
+        repo: RuleRepository,
+        webhook: Option<TelegramWebhookConfig>,
+    ) -> TelegramBot {
+        TelegramBot::new(
+            TelegramConfig {
+                bot_token: "test-token".to_string(),
+                api_base_url: base_url.to_string(),
+                rule_set: "indoor".to_string(),
+                language: "en".to_string(),
+                site_url: Some("https://rules.example.org".to_string()),
+                poll_timeout_seconds: 0,
+                webhook,
+            },
+            repo,
+        )
+        .unwrap()
+    }
+
+    fn text_update(update_id: i64, chat_id: i64, text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "update_id": update_id,
+            "message": { "message_id": update_id, "chat": { "id": chat_id, "type": "private" }, "text": text },
+        })
+    }
+
+    fn callback_update(update_id: i64, chat_id: i64, data: &str) -> Update {
+        serde_json::from_value(serde_json::json!({
+            "update_id": update_id,
+            "callback_query": {
+                "id": format!("callback-{update_id}"),
+                "message": { "message_id": 1, "chat": { "id": chat_id } },
+                "data": data,
+            },
+        }))
+        .unwrap()
+    }
+
+    /// Parameters of the last message sent
+    fn last_sent(mock: &Shared) -> serde_json::Value {
+        mock.lock()
+            .unwrap()
+            .calls
+            .iter()
+            .rev()
+            .find(|(method, _)| method == "sendMessage")
+            .map(|(_, params)| params.clone())
+            .expect("a message was sent")
+    }
+
+    #[tokio::test]
+    async fn test_rule_lookup_is_rendered_as_html() {
+        let (mock, base_url) = start_mock_bot_api().await;
+        let bot = bot(&base_url, seeded_repo(), None);
+
+        bot.handle_update(serde_json::from_value(text_update(1, 7, "/rule 17.5")).unwrap())
+            .await
+            .unwrap();
+        let sent = last_sent(&mock);
+        assert_eq!(sent["chat_id"], 7);
+        assert_eq!(sent["parse_mode"], "HTML");
+        assert_eq!(
+            sent["text"],
+            "<b>Rule 17.5</b>\n\nThe <a href=\"https://rules.example.org/en/rules/indoor/definitions#marker\">marker</a> \
+             counts to ten &amp; may not <i>straddle</i> the \
+             <a href=\"https://rules.example.org/en/rules/indoor/definitions#pivot\">pivot</a>."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quiz_records_attempts_for_chat_session() {
+        let (mock, base_url) = start_mock_bot_api().await;
+        let repo = seeded_repo();
+        let bot = bot(&base_url, repo.clone(), None);
+
+        bot.handle_update(serde_json::from_value(text_update(1, 7, "/quiz")).unwrap())
+            .await
+            .unwrap();
+        let question = last_sent(&mock);
+        assert_eq!(
+            question["text"],
+            "Who calls fouls?\n\n1. The players\n2. A referee"
+        );
+        let buttons = question["reply_markup"]["inline_keyboard"]
+            .as_array()
+            .unwrap();
+        assert_eq!(buttons.len(), 2);
+        let wrong = buttons
+            .iter()
+            .find(|row| row[0]["text"] == "A referee")
+            .unwrap()[0]["callback_data"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        bot.handle_update(callback_update(2, 7, &wrong))
+            .await
+            .unwrap();
+        let feedback = last_sent(&mock);
+        assert_eq!(
+            feedback["text"],
+            "❌ <b>Not quite.</b> Correct: The players\n\nPlayers referee themselves."
+        );
+        assert_eq!(
+            feedback["reply_markup"]["inline_keyboard"][0][0]["text"],
+            "Next question"
+        );
+        let methods: Vec<String> = mock
+            .lock()
+            .unwrap()
+            .calls
+            .iter()
+            .map(|(method, _)| method.clone())
+            .collect();
+        assert!(methods.contains(&"answerCallbackQuery".to_string()));
+        assert!(methods.contains(&"editMessageReplyMarkup".to_string()));
+
+        let attempts = repo.get_session_attempts(&chat_session_id(7)).unwrap();
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].is_correct, Some(false));
+        assert!(
+            repo.get_session_attempts(&chat_session_id(8))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_typed_answer_uses_chat_follow_up() {
+        let (mock, base_url) = start_mock_bot_api().await;
+        let repo = seeded_repo();
+        let bot = bot(&base_url, repo.clone(), None);
+
+        bot.handle_update(serde_json::from_value(text_update(1, 7, "/quiz")).unwrap())
+            .await
+            .unwrap();
+        // Another chat's reply is not an answer to chat 7's question
+        bot.handle_update(serde_json::from_value(text_update(2, 8, "1")).unwrap())
+            .await
+            .unwrap();
+        assert!(
+            last_sent(&mock)["text"]
+                .as_str()
+                .unwrap()
+                .starts_with("I did not understand that.")
+        );
+
+        bot.handle_update(serde_json::from_value(text_update(3, 7, "1")).unwrap())
+            .await
+            .unwrap();
+        assert!(
+            last_sent(&mock)["text"]
+                .as_str()
+                .unwrap()
+                .starts_with("✅ <b>Correct!</b>")
+        );
+        assert_eq!(
+            repo.get_session_attempts(&chat_session_id(7))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_long_poll_advances_offset() {
+        let (mock, base_url) = start_mock_bot_api().await;
+        mock.lock().unwrap().updates =
+            vec![text_update(10, 7, "/help"), text_update(11, 7, "hello")];
+        let bot = bot(&base_url, seeded_repo(), None);
+
+        assert_eq!(bot.poll_once(0).await.unwrap(), 12);
+        let sent = mock
+            .lock()
+            .unwrap()
+            .calls
+            .iter()
+            .filter(|(method, _)| method == "sendMessage")
+            .count();
+        assert_eq!(sent, 2);
+        assert!(
+            last_sent(&mock)["text"]
+                .as_str()
+                .unwrap()
+                .starts_with("I did not understand that.")
+        );
+
+        // Nothing new after the offset
+        assert_eq!(bot.poll_once(12).await.unwrap(), 12);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_checks_secret_token() {
+        let (mock, base_url) = start_mock_bot_api().await;
+        let bot = Arc::new(bot(
+            &base_url,
+            seeded_repo(),
+            Some(TelegramWebhookConfig {
+                url: "https://bot.example.org/telegram/webhook".to_string(),
+                listen: "127.0.0.1:0".to_string(),
+                secret_token: Some("webhook-secret".to_string()),
+            }),
+        ));
+        let app = bot.webhook_router("/telegram/webhook");
+
+        let post = |secret: &str| {
+            Request::post("/telegram/webhook")
+                .header("content-type", "application/json")
+                .header(SECRET_TOKEN_HEADER, secret)
+                .body(Body::from(text_update(1, 7, "/rule 17.5").to_string()))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(post("wrong")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(mock.lock().unwrap().calls.is_empty());
+
+        let response = app.oneshot(post("webhook-secret")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(
+            last_sent(&mock)["text"]
+                .as_str()
+                .unwrap()
+                .starts_with("<b>Rule 17.5</b>")
+        );
+    }
+}
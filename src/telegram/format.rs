@@ -0,0 +1,183 @@
+//! Rule Markdown to Telegram's HTML subset.
+//!
+//! Telegram only understands a handful of inline tags, so block structure is
+//! flattened to line breaks and bullets. Custom `rule:` and `definition:` links
+//! point at the web app when a site URL is configured and become plain text
+//! otherwise.
+
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+/// Where `rule:{slug}` and `definition:{slug}` links lead
+#[derive(Debug, Clone)]
+pub struct LinkTargets {
+    pub rule_prefix: String,
+    pub definition_prefix: String,
+}
+
+impl LinkTargets {
+    /// Links into the web app's rule pages for one rule set and language
+    pub fn for_site(site_url: &str, language: &str, rule_set_slug: &str) -> Self {
+        let base = format!(
+            "{}/{language}/rules/{rule_set_slug}",
+            site_url.trim_end_matches('/')
+        );
+        Self {
+            rule_prefix: format!("{base}/"),
+            definition_prefix: format!("{base}/definitions#"),
+        }
+    }
+
+    fn resolve(&self, dest_url: &str) -> Option<String> {
+        if let Some(slug) = dest_url.strip_prefix("rule:") {
+            return Some(format!("{}{slug}", self.rule_prefix));
+        }
+        if let Some(slug) = dest_url.strip_prefix("definition:") {
+            return Some(format!("{}{slug}", self.definition_prefix));
+        }
+        None
+    }
+}
+
+/// Escape text for Telegram's HTML parse mode
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render Markdown as Telegram HTML
+pub fn markdown_to_telegram_html(markdown: &str, links: Option<&LinkTargets>) -> String {
+    let mut out = String::new();
+    // Next number per open list, `None` for bullet lists
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    // Whether each open link emitted an `<a>` tag
+    let mut open_links: Vec<bool> = Vec::new();
+
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Start(Tag::Strong) => out.push_str("<b>"),
+            Event::End(TagEnd::Strong) => out.push_str("</b>"),
+            Event::Start(Tag::Emphasis) => out.push_str("<i>"),
+            Event::End(TagEnd::Emphasis) => out.push_str("</i>"),
+            Event::Start(Tag::Strikethrough) => out.push_str("<s>"),
+            Event::End(TagEnd::Strikethrough) => out.push_str("</s>"),
+            Event::Start(Tag::Heading { .. }) => out.push_str("<b>"),
+            Event::End(TagEnd::Heading(_)) => out.push_str("</b>\n\n"),
+            Event::End(TagEnd::Paragraph) => {
+                // Paragraphs inside list items are separated by the item itself
+                out.push_str(if lists.is_empty() { "\n\n" } else { "\n" });
+            }
+            Event::Start(Tag::BlockQuote(_)) => out.push_str("<blockquote>"),
+            Event::End(TagEnd::BlockQuote(_)) => {
+                trim_trailing_newlines(&mut out);
+                out.push_str("</blockquote>\n\n");
+            }
+            Event::Start(Tag::CodeBlock(_)) => out.push_str("<pre>"),
+            Event::End(TagEnd::CodeBlock) => {
+                trim_trailing_newlines(&mut out);
+                out.push_str("</pre>\n\n");
+            }
+            Event::Start(Tag::List(start)) => {
+                if !lists.is_empty() {
+                    trim_trailing_newlines(&mut out);
+                    out.push('\n');
+                }
+                lists.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+                if lists.is_empty() {
+                    out.push('\n');
+                }
+            }
+            Event::Start(Tag::Item) => {
+                out.push_str(&"  ".repeat(lists.len().saturating_sub(1)));
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        out.push_str(&format!("{number}. "));
+                        *number += 1;
+                    }
+                    _ => out.push_str("• "),
+                }
+            }
+            Event::End(TagEnd::Item) => {
+                trim_trailing_newlines(&mut out);
+                out.push('\n');
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                let href = links
+                    .and_then(|links| links.resolve(&dest_url))
+                    .or_else(|| {
+                        (dest_url.starts_with("https://") || dest_url.starts_with("http://"))
+                            .then(|| dest_url.to_string())
+                    });
+                match href {
+                    Some(href) => {
+                        out.push_str(&format!("<a href=\"{}\">", escape_html(&href)));
+                        open_links.push(true);
+                    }
+                    None => open_links.push(false),
+                }
+            }
+            Event::End(TagEnd::Link) if open_links.pop() == Some(true) => out.push_str("</a>"),
+            Event::Text(text) => out.push_str(&escape_html(&text)),
+            Event::Code(code) => out.push_str(&format!("<code>{}</code>", escape_html(&code))),
+            Event::Html(html) | Event::InlineHtml(html) => out.push_str(&escape_html(&html)),
+            Event::SoftBreak => out.push(' '),
+            Event::HardBreak => out.push('\n'),
+            Event::Rule => out.push_str("——————\n\n"),
+            _ => {}
+        }
+    }
+
+    out.trim_end().to_string()
+}
+
+fn trim_trailing_newlines(out: &mut String) {
+    while out.ends_with('\n') {
+        out.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_formatting_and_escaping() {
+        assert_eq!(
+            markdown_to_telegram_html("A **pivot** is *not* a `<travel>` & so on.", None),
+            "A <b>pivot</b> is <i>not</i> a <code>&lt;travel&gt;</code> &amp; so on."
+        );
+    }
+
+    #[test]
+    fn test_blocks_become_line_breaks() {
+        let markdown =
+            "First paragraph\ncontinues.\n\nSecond:\n\n1. one\n2. two\n   - nested\n\nAfter.";
+        assert_eq!(
+            markdown_to_telegram_html(markdown, None),
+            "First paragraph continues.\n\nSecond:\n\n1. one\n2. two\n  • nested\n\nAfter."
+        );
+    }
+
+    #[test]
+    fn test_custom_links() {
+        let markdown = "See [Rule 15](rule:fouls) and [pivot](definition:pivot-foot) or [WFDF](https://wfdf.sport).";
+
+        let links = LinkTargets::for_site("https://rules.example.org/", "en", "wfdf-ultimate");
+        assert_eq!(
+            markdown_to_telegram_html(markdown, Some(&links)),
+            "See <a href=\"https://rules.example.org/en/rules/wfdf-ultimate/fouls\">Rule 15</a> and \
+             <a href=\"https://rules.example.org/en/rules/wfdf-ultimate/definitions#pivot-foot\">pivot</a> or \
+             <a href=\"https://wfdf.sport\">WFDF</a>."
+        );
+
+        // Without a site to link to, custom links keep only their text
+        assert_eq!(
+            markdown_to_telegram_html(markdown, None),
+            "See Rule 15 and pivot or <a href=\"https://wfdf.sport\">WFDF</a>."
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Telegram bot for rule lookups and quizzes.
+//!
+//! Runs as the `regelator-telegram` binary next to the web server and reads the
+//! same database. Commands are answered by the [`crate::chat`] engine; this module
+//! only talks to the Bot API and renders the engine's Markdown as Telegram HTML.
+//! Each chat quizzes under its own session id, so its attempts show up in the quiz
+//! statistics like those from the web quiz.
+
+mod api;
+mod bot;
+mod format;
+
+pub use api::{
+    BotApi, CallbackQuery, Chat, InlineKeyboardButton, InlineKeyboardMarkup, Message, SendMessage,
+    Update,
+};
+pub use bot::{TelegramBot, chat_session_id};
+pub use format::{LinkTargets, escape_html, markdown_to_telegram_html};
@@ -0,0 +1,104 @@
+//! Fixtures shared by the unit tests.
+//!
+//! Also built with the `test-support` feature, which the server binary's tests
+//! enable through a dev-dependency on this crate.
+
+use diesel::r2d2::{ConnectionManager, Pool};
+
+use crate::config::{
+    Config, DatabaseConfig, ExamConfig, LearnerConfig, LoggingConfig, LoginThrottleConfig,
+    QuizCookieConfig, SecurityConfig, ServerConfig,
+};
+use crate::models::{
+    NewQuizSessionRecord, NewRuleSet, NewVersion, QuizMode, QuizSessionRecord, RuleSet, Version,
+};
+use crate::repository::RuleRepository;
+
+/// An empty in-memory database with all migrations applied
+pub fn repository() -> RuleRepository {
+    let pool = Pool::builder()
+        .max_size(1)
+        .build(ConnectionManager::new(":memory:"))
+        .unwrap();
+    crate::db::run_pending_migrations(&pool).unwrap();
+    RuleRepository::new(pool)
+}
+
+/// Add a rule set with a current version `2025`
+pub fn rule_set_with_current_version(
+    repository: &RuleRepository,
+    name: &str,
+    slug: &str,
+) -> (RuleSet, Version) {
+    let rule_set = repository
+        .create_rule_set(NewRuleSet::new(name.to_string(), slug.to_string(), None))
+        .unwrap();
+    let version = repository
+        .create_version(NewVersion::new(
+            rule_set.id.clone(),
+            "2025".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            None,
+            None,
+            true,
+        ))
+        .unwrap();
+    (rule_set, version)
+}
+
+/// An in-memory database with the rule set "Indoor" (`indoor`) and its current
+/// version `2025`, which most fixtures add their rules and questions to
+pub fn repository_with_current_version() -> (RuleRepository, RuleSet, Version) {
+    let repository = repository();
+    let (rule_set, version) = rule_set_with_current_version(&repository, "Indoor", "indoor");
+    (repository, rule_set, version)
+}
+
+/// Start an anonymous English quiz session
+pub fn quiz_session(
+    repository: &RuleRepository,
+    rule_set: &RuleSet,
+    version: &Version,
+    mode: QuizMode,
+) -> QuizSessionRecord {
+    repository
+        .create_quiz_session(NewQuizSessionRecord::new(
+            rule_set.id.clone(),
+            version.id.clone(),
+            "en".to_string(),
+            mode,
+        ))
+        .unwrap()
+}
+
+/// Configuration with a valid JWT secret and defaults everywhere else
+pub fn config() -> Config {
+    Config {
+        server: ServerConfig {
+            host: "127.0.0.1".to_string(),
+            port: 8000,
+            shutdown_timeout_seconds: 30,
+            trust_forwarded_for: false,
+        },
+        database: DatabaseConfig {
+            url: ":memory:".to_string(),
+        },
+        security: SecurityConfig {
+            session_duration_hours: 1,
+            jwt_secret: "test-secret-that-is-long-enough-for-validation".to_string(),
+            login_throttle: LoginThrottleConfig::default(),
+            quiz_cookie: QuizCookieConfig::default(),
+            oidc: None,
+            certificate_key: None,
+        },
+        logging: LoggingConfig {
+            level: tracing::Level::INFO,
+            format: "tree".to_string(),
+            enable_colors: false,
+        },
+        learners: LearnerConfig::default(),
+        exam: ExamConfig::default(),
+        email: None,
+        telegram: None,
+    }
+}
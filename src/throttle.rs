@@ -0,0 +1,235 @@
+//! Backoff and lockout policy for failed admin logins.
+//!
+//! Failures are counted per username and per client IP. Failed transfer code
+//! redemptions are counted per client IP with the same policy. Each failure doubles the
+//! delay before the next attempt is accepted, and too many failures lock the
+//! subject out for a while. The policy is pure; the repository stores the state.
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::config::LoginThrottleConfig;
+use crate::models::{LoginThrottle, ThrottleScope};
+
+/// Whether a login attempt may proceed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleDecision {
+    Allowed,
+    /// Too soon after the last failure
+    Delayed {
+        retry_after: Duration,
+    },
+    LockedOut {
+        until: NaiveDateTime,
+    },
+}
+
+impl LoginThrottleConfig {
+    fn max_attempts(&self, scope: ThrottleScope) -> i32 {
+        let max = match scope {
+            ThrottleScope::Username => self.max_failed_attempts,
+            ThrottleScope::Ip | ThrottleScope::TransferIp => self.max_failed_attempts_per_ip,
+        };
+        max.max(1) as i32
+    }
+
+    /// Delay required after `failed_attempts` consecutive failures
+    fn delay_after(&self, failed_attempts: i32) -> Duration {
+        let exponent = (failed_attempts - 1).clamp(0, 30) as u32;
+        let delay = self
+            .base_delay_seconds
+            .saturating_mul(2u64.saturating_pow(exponent))
+            .min(self.max_delay_seconds);
+        Duration::seconds(delay as i64)
+    }
+
+    fn is_stale(&self, throttle: &LoginThrottle, now: NaiveDateTime) -> bool {
+        !throttle.is_locked(now)
+            && now - throttle.last_failed_at > Duration::minutes(self.reset_after_minutes as i64)
+    }
+
+    /// Decide whether an attempt is allowed given the stored failures
+    pub fn check(&self, throttle: Option<&LoginThrottle>, now: NaiveDateTime) -> ThrottleDecision {
+        let Some(throttle) = throttle else {
+            return ThrottleDecision::Allowed;
+        };
+
+        if let Some(until) = throttle.locked_until
+            && until > now
+        {
+            return ThrottleDecision::LockedOut { until };
+        }
+
+        if self.is_stale(throttle, now) {
+            return ThrottleDecision::Allowed;
+        }
+
+        let ready_at = throttle.last_failed_at + self.delay_after(throttle.failed_attempts);
+        if ready_at > now {
+            ThrottleDecision::Delayed {
+                retry_after: ready_at - now,
+            }
+        } else {
+            ThrottleDecision::Allowed
+        }
+    }
+
+    /// Count a failed attempt. Returns the updated state and whether this
+    /// failure started a new lockout.
+    pub fn record_failure(
+        &self,
+        throttle: Option<LoginThrottle>,
+        scope: ThrottleScope,
+        subject: &str,
+        now: NaiveDateTime,
+    ) -> (LoginThrottle, bool) {
+        let previous = throttle.filter(|throttle| !self.is_stale(throttle, now));
+        let was_locked = previous.as_ref().is_some_and(|t| t.is_locked(now));
+        let failed_attempts = previous.as_ref().map_or(0, |t| t.failed_attempts) + 1;
+
+        let locked_until = if failed_attempts >= self.max_attempts(scope) {
+            Some(now + Duration::minutes(self.lockout_minutes as i64))
+        } else {
+            None
+        };
+
+        let updated = LoginThrottle {
+            scope,
+            subject: subject.to_string(),
+            failed_attempts,
+            last_failed_at: now,
+            locked_until,
+        };
+        let newly_locked = locked_until.is_some() && !was_locked;
+        (updated, newly_locked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LoginThrottleConfig {
+        LoginThrottleConfig {
+            max_failed_attempts: 3,
+            max_failed_attempts_per_ip: 10,
+            base_delay_seconds: 2,
+            max_delay_seconds: 5,
+            lockout_minutes: 15,
+            reset_after_minutes: 60,
+        }
+    }
+
+    fn at(seconds: i64) -> NaiveDateTime {
+        chrono::DateTime::from_timestamp(1_750_000_000 + seconds, 0)
+            .unwrap()
+            .naive_utc()
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let config = config();
+        let (first, locked) = config.record_failure(None, ThrottleScope::Username, "alice", at(0));
+        assert!(!locked);
+        assert_eq!(
+            config.check(Some(&first), at(1)),
+            ThrottleDecision::Delayed {
+                retry_after: Duration::seconds(1)
+            }
+        );
+        assert_eq!(config.check(Some(&first), at(2)), ThrottleDecision::Allowed);
+
+        let (second, _) =
+            config.record_failure(Some(first), ThrottleScope::Username, "alice", at(2));
+        assert_eq!(second.failed_attempts, 2);
+        assert_eq!(
+            config.check(Some(&second), at(3)),
+            ThrottleDecision::Delayed {
+                retry_after: Duration::seconds(3)
+            }
+        );
+        assert_eq!(config.delay_after(10), Duration::seconds(5));
+    }
+
+    #[test]
+    fn test_lockout_after_max_attempts() {
+        let config = config();
+        let mut throttle = None;
+        let mut lockouts = 0;
+        for attempt in 0..3 {
+            let (updated, locked) =
+                config.record_failure(throttle, ThrottleScope::Username, "alice", at(attempt * 10));
+            lockouts += locked as usize;
+            throttle = Some(updated);
+        }
+        let throttle = throttle.unwrap();
+
+        assert_eq!(lockouts, 1);
+        assert_eq!(
+            config.check(Some(&throttle), at(60)),
+            ThrottleDecision::LockedOut {
+                until: at(20 + 15 * 60)
+            }
+        );
+        assert_eq!(
+            config.check(Some(&throttle), at(20 + 15 * 60)),
+            ThrottleDecision::Allowed
+        );
+    }
+
+    #[test]
+    fn test_concurrent_failures_are_all_counted() {
+        let config = config();
+        let repository = crate::test_support::repository();
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    repository
+                        .record_login_failure(&config, ThrottleScope::Ip, "127.0.0.1", at(0))
+                        .unwrap();
+                });
+            }
+        });
+
+        let throttle = repository
+            .get_login_throttle(ThrottleScope::Ip, "127.0.0.1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(throttle.failed_attempts, 8);
+    }
+
+    #[test]
+    fn test_ip_scope_uses_own_limit() {
+        let config = config();
+        let (throttle, locked) = config.record_failure(
+            Some(LoginThrottle {
+                scope: ThrottleScope::Ip,
+                subject: "127.0.0.1".to_string(),
+                failed_attempts: 5,
+                last_failed_at: at(0),
+                locked_until: None,
+            }),
+            ThrottleScope::Ip,
+            "127.0.0.1",
+            at(10),
+        );
+        assert!(!locked);
+        assert_eq!(throttle.failed_attempts, 6);
+    }
+
+    #[test]
+    fn test_old_failures_are_forgotten() {
+        let config = config();
+        let (throttle, _) = config.record_failure(None, ThrottleScope::Username, "alice", at(0));
+        let (throttle, _) =
+            config.record_failure(Some(throttle), ThrottleScope::Username, "alice", at(10));
+
+        let later = at(10 + 61 * 60);
+        assert_eq!(
+            config.check(Some(&throttle), later),
+            ThrottleDecision::Allowed
+        );
+        let (throttle, _) =
+            config.record_failure(Some(throttle), ThrottleScope::Username, "alice", later);
+        assert_eq!(throttle.failed_attempts, 1);
+    }
+}
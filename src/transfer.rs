@@ -0,0 +1,316 @@
+//! Transfer codes that continue an anonymous quiz session on another device.
+//!
+//! A visitor asks for a short code (also shown as a QR code linking to the
+//! transfer page) and types it on their other device, whose quiz cookie then
+//! names the same session. Codes expire after a few minutes, work once and are
+//! stored hashed; nothing about the visitor is recorded. Progress the other
+//! device already made is merged in, as when a learner signs in. Failed
+//! redemptions are throttled per client IP like failed admin logins, so codes
+//! can't be guessed.
+
+use std::net::IpAddr;
+
+use chrono::{Duration, NaiveDateTime};
+use color_eyre::Result;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use crate::{
+    config::LoginThrottleConfig,
+    models::{QuizMode, QuizSessionRecord, QuizTransferCode, ThrottleScope},
+    repository::RuleRepository,
+    throttle::ThrottleDecision,
+};
+
+/// Minutes a transfer code can be redeemed for
+pub const TRANSFER_CODE_MINUTES: i64 = 10;
+
+/// Code characters, without easily confused ones like 0/O and 1/I/L
+const TRANSFER_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+/// Outcome of redeeming a code from a client
+#[derive(Debug, Clone)]
+pub enum Redemption {
+    /// The session the device should continue
+    Continued(QuizSessionRecord),
+    /// The code is unknown, used, expired or can't be continued here
+    Rejected,
+    /// Too soon after a failed attempt from the client's address; the code was not checked
+    Delayed { retry_after: Duration },
+    /// Too many failed attempts from the client's address; the code was not checked
+    LockedOut { until: NaiveDateTime },
+}
+
+/// A freshly issued code, shown to the visitor once
+#[derive(Debug, Clone)]
+pub struct IssuedTransferCode {
+    /// Formatted as `XXXX-XXXX`
+    pub code: String,
+    pub expires_at: NaiveDateTime,
+}
+
+/// Generate a random code formatted as `XXXX-XXXX`
+pub fn generate_transfer_code() -> String {
+    let mut rng = rand::rng();
+    let chars: String = (0..8)
+        .map(|_| TRANSFER_ALPHABET[rng.random_range(0..TRANSFER_ALPHABET.len())] as char)
+        .collect();
+    format!("{}-{}", &chars[..4], &chars[4..])
+}
+
+/// Uppercase the code and drop whitespace and dashes, as people type it
+pub fn normalize_transfer_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+fn hash_transfer_code(code: &str) -> String {
+    Sha256::digest(normalize_transfer_code(code).as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Issue a code for an anonymous session, replacing its earlier unused codes
+pub fn issue_transfer_code(
+    repository: &RuleRepository,
+    session: &QuizSessionRecord,
+    now: NaiveDateTime,
+) -> Result<IssuedTransferCode> {
+    let code = generate_transfer_code();
+    let expires_at = now + Duration::minutes(TRANSFER_CODE_MINUTES);
+    repository.create_quiz_transfer_code(QuizTransferCode {
+        code_hash: hash_transfer_code(&code),
+        session_id: session.id.clone(),
+        created_at: now,
+        expires_at,
+        used_at: None,
+    })?;
+    debug!("Issued transfer code for quiz session {}", session.id);
+    Ok(IssuedTransferCode { code, expires_at })
+}
+
+/// Redeem a code on the device whose current session is `current`.
+///
+/// Returns the session the device should continue, or `None` if the code is unknown,
+/// used or expired, or its session has ended, belongs to a learner, is an exam or is for
+/// another rule set. The device's anonymous progress is merged into that session.
+pub fn redeem_transfer_code(
+    repository: &RuleRepository,
+    code: &str,
+    current: &QuizSessionRecord,
+    now: NaiveDateTime,
+) -> Result<Option<QuizSessionRecord>> {
+    let Some(transfer) = repository.use_quiz_transfer_code(&hash_transfer_code(code), now)? else {
+        return Ok(None);
+    };
+    let Some(target) = repository
+        .get_quiz_session(&transfer.session_id)?
+        .filter(|target| {
+            target.ended_at.is_none()
+                && target.learner_id.is_none()
+                && target.mode != QuizMode::Exam
+                && target.rule_set_id == current.rule_set_id
+        })
+    else {
+        return Ok(None);
+    };
+
+    if target.id != current.id && current.learner_id.is_none() {
+        if target.version_id == current.version_id && target.mode == current.mode {
+            repository.merge_quiz_sessions(&current.id, &target.id)?;
+            debug!("Merged quiz session {} into {}", current.id, target.id);
+        } else {
+            repository.end_quiz_session(&current.id)?;
+        }
+    }
+    Ok(Some(target))
+}
+
+/// Redeem a code as [`redeem_transfer_code`] does, counting failures against the
+/// client's IP with the admin login policy. Clients without a known address are
+/// not throttled.
+pub fn redeem_transfer_code_from(
+    repository: &RuleRepository,
+    throttle_config: &LoginThrottleConfig,
+    client_ip: Option<IpAddr>,
+    code: &str,
+    current: &QuizSessionRecord,
+    now: NaiveDateTime,
+) -> Result<Redemption> {
+    let subject = client_ip.map(|ip| ip.to_string());
+    if let Some(subject) = &subject {
+        let throttle = repository.get_login_throttle(ThrottleScope::TransferIp, subject)?;
+        match throttle_config.check(throttle.as_ref(), now) {
+            ThrottleDecision::Allowed => {}
+            ThrottleDecision::Delayed { retry_after } => {
+                warn!(client_ip = %subject, "Transfer code redemption rejected by backoff");
+                return Ok(Redemption::Delayed { retry_after });
+            }
+            ThrottleDecision::LockedOut { until } => {
+                warn!(client_ip = %subject, locked_until = %until, "Transfer code redemption rejected by lockout");
+                return Ok(Redemption::LockedOut { until });
+            }
+        }
+    }
+
+    if let Some(target) = redeem_transfer_code(repository, code, current, now)? {
+        return Ok(Redemption::Continued(target));
+    }
+
+    if let Some(subject) = &subject {
+        let (throttle, newly_locked) = repository.record_login_failure(
+            throttle_config,
+            ThrottleScope::TransferIp,
+            subject,
+            now,
+        )?;
+        if newly_locked {
+            warn!(
+                client_ip = %subject,
+                failed_attempts = throttle.failed_attempts,
+                locked_until = ?throttle.locked_until,
+                "Transfer code redemption locked out after repeated failures"
+            );
+        }
+    }
+    Ok(Redemption::Rejected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{RuleSet, Version};
+    use crate::test_support::{self, repository_with_current_version};
+
+    fn session(repo: &RuleRepository, rule_set: &RuleSet, version: &Version) -> QuizSessionRecord {
+        test_support::quiz_session(repo, rule_set, version, QuizMode::Practice)
+    }
+
+    fn now() -> NaiveDateTime {
+        chrono::Utc::now().naive_utc()
+    }
+
+    #[test]
+    fn test_codes_are_typeable() {
+        let code = generate_transfer_code();
+        assert_eq!(code.len(), 9);
+        assert_eq!(&code[4..5], "-");
+        assert!(
+            code.bytes()
+                .filter(|byte| *byte != b'-')
+                .all(|byte| TRANSFER_ALPHABET.contains(&byte))
+        );
+        assert_eq!(normalize_transfer_code(" abcd-efgh "), "ABCDEFGH");
+    }
+
+    #[test]
+    fn test_redeeming_continues_the_session_once() {
+        let (repo, indoor, version) = repository_with_current_version();
+        let phone = session(&repo, &indoor, &version);
+        let laptop = session(&repo, &indoor, &version);
+
+        let issued = issue_transfer_code(&repo, &phone, now()).unwrap();
+        let redeemed = redeem_transfer_code(&repo, &issued.code.to_lowercase(), &laptop, now())
+            .unwrap()
+            .unwrap();
+        assert_eq!(redeemed.id, phone.id);
+        // The laptop's own session was merged into the phone's
+        assert!(repo.get_quiz_session(&laptop.id).unwrap().is_none());
+
+        assert!(
+            redeem_transfer_code(&repo, &issued.code, &phone, now())
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_expired_replaced_and_foreign_codes_are_rejected() {
+        let (repo, indoor, version) = repository_with_current_version();
+        let phone = session(&repo, &indoor, &version);
+        let laptop = session(&repo, &indoor, &version);
+
+        let expired = issue_transfer_code(&repo, &phone, now()).unwrap();
+        let later = expired.expires_at + Duration::seconds(1);
+        assert!(
+            redeem_transfer_code(&repo, &expired.code, &laptop, later)
+                .unwrap()
+                .is_none()
+        );
+
+        let replaced = issue_transfer_code(&repo, &phone, now()).unwrap();
+        let current = issue_transfer_code(&repo, &phone, now()).unwrap();
+        assert!(
+            redeem_transfer_code(&repo, &replaced.code, &laptop, now())
+                .unwrap()
+                .is_none()
+        );
+
+        let (beach_rules, beach_version) =
+            test_support::rule_set_with_current_version(&repo, "Beach", "beach");
+        let beach = session(&repo, &beach_rules, &beach_version);
+        assert!(
+            redeem_transfer_code(&repo, &current.code, &beach, now())
+                .unwrap()
+                .is_none()
+        );
+        assert!(repo.get_quiz_session(&beach.id).unwrap().is_some());
+        assert!(repo.get_quiz_session(&laptop.id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_repeated_failures_lock_out_the_client_ip() {
+        let (repo, indoor, version) = repository_with_current_version();
+        let phone = session(&repo, &indoor, &version);
+        let laptop = session(&repo, &indoor, &version);
+        let throttle_config = LoginThrottleConfig {
+            max_failed_attempts_per_ip: 3,
+            base_delay_seconds: 1,
+            max_delay_seconds: 1,
+            ..LoginThrottleConfig::default()
+        };
+        let guesser: IpAddr = "203.0.113.7".parse().unwrap();
+        let start = now();
+        let redeem = |ip, code: &str, seconds| {
+            redeem_transfer_code_from(
+                &repo,
+                &throttle_config,
+                ip,
+                code,
+                &laptop,
+                start + Duration::seconds(seconds),
+            )
+            .unwrap()
+        };
+
+        for attempt in 0..3 {
+            assert!(matches!(
+                redeem(Some(guesser), "AAAA-AAAA", attempt * 10),
+                Redemption::Rejected
+            ));
+        }
+
+        // Locked out: even a valid code is refused, and stays usable
+        let issued = issue_transfer_code(&repo, &phone, start).unwrap();
+        assert!(matches!(
+            redeem(Some(guesser), &issued.code, 30),
+            Redemption::LockedOut { .. }
+        ));
+        let other: IpAddr = "198.51.100.2".parse().unwrap();
+        assert!(matches!(
+            redeem(Some(other), &issued.code, 30),
+            Redemption::Continued(session) if session.id == phone.id
+        ));
+
+        // Admin logins from the address are counted separately
+        assert!(
+            repo.get_login_throttle(ThrottleScope::Ip, &guesser.to_string())
+                .unwrap()
+                .is_none()
+        );
+    }
+}